@@ -1,13 +1,21 @@
 use std::fs::{create_dir_all, read_dir, read_to_string};
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 use pulldown_cmark::{Event, HeadingLevel, Parser, Tag};
 use pulldown_cmark_to_cmark::cmark_resume;
+use regex::Regex;
 use time::macros::format_description;
 
+use crate::config::Mode;
+use crate::model::{normalize_status, section_matches};
+use crate::profile;
+use crate::reservations::Reservations;
+use crate::state::State;
+
 // format the current date
 pub(crate) fn now() -> Result<String> {
     let now = time::OffsetDateTime::now_utc();
@@ -15,23 +23,296 @@ pub(crate) fn now() -> Result<String> {
     Ok(x)
 }
 
+// whether generated artifacts leave out the time they were made, set with --deterministic
+static DETERMINISTIC: OnceLock<bool> = OnceLock::new();
+
+pub(crate) fn set_deterministic() {
+    let _ = DETERMINISTIC.set(true);
+}
+
+// whether commands may change the ADRs, set with --read-only
+static READ_ONLY: OnceLock<bool> = OnceLock::new();
+
+pub(crate) fn set_read_only() {
+    let _ = READ_ONLY.set(true);
+}
+
+pub(crate) fn is_read_only() -> bool {
+    READ_ONLY.get().copied().unwrap_or_default()
+}
+
+// whether files can be created in the directory. A directory that doesn't exist yet is left for
+// the command to create
+pub(crate) fn is_writable(dir: &Path) -> bool {
+    !dir.is_dir()
+        || tempfile::Builder::new()
+            .prefix(".adrs-")
+            .tempfile_in(dir)
+            .is_ok()
+}
+
+// fail before a command that changes the ADRs starts, rather than part way through, if adrs is
+// running read-only or the ADR directory can't be written, e.g. on a read-only docs volume
+pub(crate) fn ensure_writable(command: &str) -> Result<()> {
+    if is_read_only() {
+        anyhow::bail!(
+            "`adrs {}` changes the ADRs, which --read-only doesn't allow",
+            command
+        );
+    }
+    let dir = ADR_DIR
+        .get()
+        .cloned()
+        .or_else(|| read_adr_dir_file().ok())
+        .unwrap_or_else(|| PathBuf::from("doc/adr"));
+    if !is_writable(&dir) {
+        anyhow::bail!(
+            "The ADR directory {} is read-only, so `adrs {}` can't change it. Commands that only \
+             read the ADRs, such as list, search, export and doctor, still work",
+            dir.display(),
+            command
+        );
+    }
+    Ok(())
+}
+
+// whether generated artifacts should come out the same however often they are regenerated,
+// because of --deterministic or because SOURCE_DATE_EPOCH pins their time
+pub(crate) fn is_deterministic() -> bool {
+    DETERMINISTIC.get().copied().unwrap_or_default()
+        || std::env::var_os("SOURCE_DATE_EPOCH").is_some()
+}
+
+// the time to stamp generated artifacts with. SOURCE_DATE_EPOCH pins it, as in reproducible
+// builds, and with --deterministic but no SOURCE_DATE_EPOCH artifacts aren't stamped at all
+pub(crate) fn artifact_time() -> Result<Option<time::OffsetDateTime>> {
+    if let Ok(epoch) = std::env::var("SOURCE_DATE_EPOCH") {
+        let seconds = epoch.trim().parse::<i64>().with_context(|| {
+            format!(
+                "Invalid SOURCE_DATE_EPOCH '{}', expected seconds since 1970-01-01",
+                epoch
+            )
+        })?;
+        return Ok(Some(time::OffsetDateTime::from_unix_timestamp(seconds)?));
+    }
+    if DETERMINISTIC.get().copied().unwrap_or_default() {
+        return Ok(None);
+    }
+    Ok(Some(time::OffsetDateTime::now_utc()))
+}
+
+// the date to stamp generated artifacts with, as YYYY-MM-DD
+pub(crate) fn artifact_date() -> Result<Option<String>> {
+    artifact_time()?
+        .map(|time| Ok(time.format(format_description!("[year]-[month]-[day]"))?))
+        .transpose()
+}
+
+// parse a date as written in an ADR
+pub(crate) fn parse_date(date: &str) -> Result<time::Date> {
+    time::Date::parse(date.trim(), format_description!("[year]-[month]-[day]"))
+        .map_err(|_| anyhow::anyhow!("invalid date '{}', expected YYYY-MM-DD", date.trim()))
+}
+
+// split an ADR into its YAML frontmatter block (including the delimiters) and its markdown body
+pub(crate) fn split_frontmatter(markdown: &str) -> (&str, &str) {
+    if let Some(rest) = markdown.strip_prefix("---\n") {
+        if let Some(end) = rest.find("\n---\n") {
+            let split = 4 + end + 5;
+            return markdown.split_at(split);
+        }
+    }
+    ("", markdown)
+}
+
+// a new immutable identifier for an ADR, which unlike its number never changes
+pub(crate) fn new_adr_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+// prepend the metadata block used by nextgen mode to a rendered ADR, assigning it an id
+pub(crate) fn with_frontmatter(mode: Mode, rendered: String) -> String {
+    match mode {
+        Mode::Compatible => rendered,
+        Mode::Nextgen => format!("---\nid: {}\ntags: []\n---\n\n{}", new_adr_id(), rendered),
+    }
+}
+
+// get a field from the YAML frontmatter block
+pub(crate) fn get_frontmatter_field(markdown: &str, key: &str) -> Option<String> {
+    let (frontmatter, _) = split_frontmatter(markdown);
+    frontmatter
+        .lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+        .map(|value| value.trim().trim_matches('"').to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// get a list from the YAML frontmatter block, written either as `[a, b]` or as one `- item` per
+// line
+pub(crate) fn get_frontmatter_list(markdown: &str, key: &str) -> Vec<String> {
+    let (frontmatter, _) = split_frontmatter(markdown);
+    let mut lines = frontmatter.lines();
+    let Some(value) = lines.find_map(|line| line.strip_prefix(key)?.strip_prefix(':')) else {
+        return Vec::new();
+    };
+    let unquote = |item: &str| item.trim().trim_matches(['"', '\'']).to_string();
+    let value = value.trim();
+    let items = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        Some(items) => items.split(',').map(unquote).collect::<Vec<_>>(),
+        None if value.is_empty() => lines
+            .map_while(|line| line.trim_start().strip_prefix("- "))
+            .map(unquote)
+            .collect(),
+        None => vec![unquote(value)],
+    };
+    items.into_iter().filter(|item| !item.is_empty()).collect()
+}
+
+// free text quoted for the frontmatter, where get_frontmatter_field unquotes it again. Double
+// quotes inside become single quotes, as nothing unescapes them
+pub(crate) fn quote_frontmatter_value(value: &str) -> String {
+    format!("\"{}\"", value.trim().replace('"', "'"))
+}
+
+// set a field in the YAML frontmatter block. ADRs without frontmatter are left unchanged, so
+// compatible mode ADRs stay plain markdown
+pub(crate) fn set_frontmatter_field(markdown: &str, key: &str, value: &str) -> String {
+    let (frontmatter, body) = split_frontmatter(markdown);
+    if frontmatter.is_empty() {
+        return markdown.to_string();
+    }
+    let field = format!("{}: {}", key, value);
+    let mut lines = frontmatter.lines().collect::<Vec<_>>();
+    let existing = lines.iter().position(|line| {
+        line.strip_prefix(key)
+            .is_some_and(|rest| rest.starts_with(':'))
+    });
+    match existing {
+        Some(i) => lines[i] = &field,
+        None => lines.insert(lines.len() - 1, &field),
+    }
+    format!("{}\n{}", lines.join("\n"), body)
+}
+
+// remove a single line field from the YAML frontmatter block, if it is there
+pub(crate) fn remove_frontmatter_field(markdown: &str, key: &str) -> String {
+    let (frontmatter, body) = split_frontmatter(markdown);
+    let lines = frontmatter
+        .lines()
+        .filter(|line| {
+            !line
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.starts_with(':'))
+        })
+        .collect::<Vec<_>>();
+    if frontmatter.is_empty() {
+        return markdown.to_string();
+    }
+    format!("{}\n{}", lines.join("\n"), body)
+}
+
+// split a comma separated `Tags:` line into its tags
+pub(crate) fn split_tags(line: &str) -> Vec<String> {
+    line.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// set the `Tags:` line that compatible mode ADRs keep under the title and date. ADRs with
+// frontmatter keep their tags there and are left unchanged, as are ADRs without any tags
+pub(crate) fn set_tags_line(markdown: &str, tags: &[String]) -> String {
+    let (frontmatter, _) = split_frontmatter(markdown);
+    if !frontmatter.is_empty() || tags.is_empty() {
+        return markdown.to_string();
+    }
+    let field = format!("Tags: {}", tags.join(", "));
+    let mut lines = markdown.lines().collect::<Vec<_>>();
+    let preamble = lines
+        .iter()
+        .position(|line| line.starts_with("## "))
+        .unwrap_or(lines.len());
+    let existing = lines[..preamble]
+        .iter()
+        .position(|line| line.starts_with("Tags:"));
+    let after = lines[..preamble]
+        .iter()
+        .rposition(|line| line.starts_with("Date:") || line.starts_with("# "));
+    match (existing, after) {
+        (Some(i), _) => lines[i] = &field,
+        (None, Some(i)) => lines.splice(i + 1..i + 1, ["", &field]).for_each(drop),
+        (None, None) => return markdown.to_string(),
+    }
+    let mut out = lines.join("\n");
+    if markdown.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+// the frontmatter field recording when an ADR entered a status
+pub(crate) fn status_date_field(status: &str) -> Option<&'static str> {
+    match status.to_lowercase().as_str() {
+        "proposed" => Some("proposed_date"),
+        "accepted" | "rejected" | "withdrawn" => Some("decided_date"),
+        "implemented" => Some("implemented_date"),
+        _ => None,
+    }
+}
+
+// record the date an ADR entered a status in its frontmatter
+pub(crate) fn record_status_date(markdown: &str, status: &str, date: &str) -> String {
+    match status_date_field(status) {
+        Some(field) => set_frontmatter_field(markdown, field, date),
+        None => markdown.to_string(),
+    }
+}
+
+// derive the reverse description of a link, e.g. "Amends" becomes "Amended by"
+pub(crate) fn reverse_link_kind(kind: &str) -> String {
+    let known = [
+        ("Amends", "Amended by"),
+        ("Clarifies", "Clarified by"),
+        ("Extends", "Extended by"),
+        ("Supersedes", "Superseded by"),
+        ("Depends on", "Required by"),
+        ("Relates to", "Relates to"),
+    ];
+    for (forward, reverse) in known {
+        if kind.eq_ignore_ascii_case(forward) {
+            return reverse.to_string();
+        }
+        if kind.eq_ignore_ascii_case(reverse) {
+            return forward.to_string();
+        }
+    }
+    match kind.strip_suffix(" by") {
+        Some(forward) => forward.to_string(),
+        None => format!("{} by", kind),
+    }
+}
+
+// the lowercase words of the text joined with hyphens, for file names
+pub(crate) fn slugify(text: &str) -> String {
+    text.split_terminator(|c| char::is_ascii_whitespace(&c) || char::is_ascii_punctuation(&c))
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+        .to_lowercase()
+}
+
 // format the ADR path
 pub(crate) fn format_adr_path(adr_dir: &Path, sequence: i32, title: &str) -> PathBuf {
-    Path::new(adr_dir).join(format!(
-        "{:0>4}-{}.md",
-        sequence,
-        title
-            .split_terminator(|c| char::is_ascii_whitespace(&c) || char::is_ascii_punctuation(&c))
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<&str>>()
-            .join("-")
-            .to_lowercase()
-    ))
+    Path::new(adr_dir).join(format!("{:0>4}-{}.md", sequence, slugify(title)))
 }
 
 // find the adr file that best matches the given string
 pub(crate) fn find_adr<P: AsRef<Path>>(path: P, s: &str) -> Result<PathBuf> {
-    if s.chars().all(char::is_numeric) {
+    if uuid::Uuid::parse_str(s).is_ok() {
+        find_adr_by_id(path.as_ref(), s)
+    } else if s.chars().all(char::is_numeric) {
         let n = s.parse::<i32>().expect("Invalid ADR number");
         find_adr_by_number(path.as_ref(), n)
     } else {
@@ -65,6 +346,22 @@ pub(crate) fn find_adr_by_str(path: &Path, s: &str) -> Result<PathBuf> {
     Ok(first.0.clone())
 }
 
+// find the ADR, active or archived, with the given id in its frontmatter
+pub(crate) fn find_adr_by_id(path: &Path, id: &str) -> Result<PathBuf> {
+    for adr in list_adrs(path)?
+        .into_iter()
+        .chain(list_archived_adrs(path)?)
+    {
+        let markdown = std::fs::read_to_string(&adr)?;
+        if get_frontmatter_field(&markdown, "id")
+            .is_some_and(|found| found.eq_ignore_ascii_case(id))
+        {
+            return Ok(adr);
+        }
+    }
+    Err(anyhow::anyhow!("No ADR found with id {}", id))
+}
+
 // takes the top level directory and a number to match and returns the best matching filename
 pub(crate) fn find_adr_by_number(path: &Path, n: i32) -> Result<PathBuf> {
     let target = path.join(format!("{:0>4}-", n));
@@ -86,6 +383,7 @@ pub(crate) fn find_adr_by_number(path: &Path, n: i32) -> Result<PathBuf> {
 
 // returns a sorted list of all the ADRs in the directory
 pub(crate) fn list_adrs(path: &Path) -> Result<Vec<PathBuf>> {
+    let _span = profile::span("discovery");
     let mut adrs = read_dir(path)?
         .map(|entry| entry.unwrap().path())
         .filter(|filename| {
@@ -103,20 +401,29 @@ pub(crate) fn list_adrs(path: &Path) -> Result<Vec<PathBuf>> {
     Ok(adrs)
 }
 
+// returns the number of the ADR from its filename
+pub(crate) fn get_number(path: &Path) -> Option<i32> {
+    let filename = path.file_name()?.to_str()?;
+    let digits = filename
+        .split_once('-')
+        .map_or(filename, |(digits, _)| digits);
+    digits.parse().ok()
+}
+
 // returns the title of the ADR
 pub(crate) fn get_title(path: &Path) -> Result<String> {
+    let _span = profile::span("parsing");
     let markdown = std::fs::read_to_string(path)?;
-    let parser = Parser::new(&markdown);
+    let (_, markdown) = split_frontmatter(&markdown);
+    let parser = Parser::new(markdown);
     let mut in_title = false;
     for event in parser {
         match event {
             Event::Start(Tag::Heading(HeadingLevel::H1, _, _)) => {
                 in_title = true;
             }
-            Event::Text(text) => {
-                if in_title {
-                    return Ok(text.to_string());
-                }
+            Event::Text(text) if in_title => {
+                return Ok(text.to_string());
             }
             _ => {}
         }
@@ -126,26 +433,71 @@ pub(crate) fn get_title(path: &Path) -> Result<String> {
 
 // get the statuses of the ADR
 pub(crate) fn get_status(path: &Path) -> Result<Vec<String>> {
-    let markdown = std::fs::read_to_string(path)?;
-    let parser = Parser::new(&markdown).into_offset_iter();
+    let _span = profile::span("parsing");
+    let input = std::fs::read_to_string(path)?;
+    let (_, markdown) = split_frontmatter(&input);
+    let parser = Parser::new(markdown).into_offset_iter();
     let mut in_status = false;
+    let mut found = false;
     let mut buf = String::new();
     for (event, offset) in parser {
         match event {
             Event::Start(Tag::Heading(HeadingLevel::H2, _, _)) => {
                 in_status = markdown[offset].starts_with("## Status");
+                found |= in_status;
             }
-            Event::Start(Tag::Paragraph) => {
-                if in_status {
-                    buf += &markdown[offset];
-                }
+            Event::Start(Tag::Paragraph) if in_status => {
+                buf += &markdown[offset];
             }
             _ => {}
         }
     }
+    // MADR keeps the status in the frontmatter instead of a section
+    if !found {
+        return Ok(get_frontmatter_field(&input, "status")
+            .into_iter()
+            .collect());
+    }
     Ok(buf.lines().map(|s| s.to_string()).collect())
 }
 
+// get the date of the ADR from the Date: line before its first section
+pub(crate) fn get_date(path: &Path) -> Result<Option<String>> {
+    let _span = profile::span("parsing");
+    let markdown = std::fs::read_to_string(path)?;
+    let (_, markdown) = split_frontmatter(&markdown);
+    Ok(markdown
+        .lines()
+        .take_while(|line| !line.starts_with("## "))
+        .find_map(|line| line.trim().strip_prefix("Date:"))
+        .map(|date| date.trim().to_string())
+        .filter(|date| !date.is_empty()))
+}
+
+// get the current status of the ADR: the last status line that isn't a link, or Superseded when the
+// ADR has been superseded by another
+pub(crate) fn get_current_status(path: &Path) -> Result<Option<String>> {
+    let status = get_status(path)?;
+    if status.iter().any(|s| s.starts_with("Superseded by")) {
+        return Ok(Some("Superseded".to_string()));
+    }
+    Ok(status
+        .iter()
+        .map(|s| s.trim())
+        .rfind(|s| !s.is_empty() && !s.contains("]("))
+        .map(normalize_status))
+}
+
+// returns a sorted list of the ADRs that have been moved to the archive subdirectory
+pub(crate) fn list_archived_adrs(path: &Path) -> Result<Vec<PathBuf>> {
+    let archive = path.join("archive");
+    if archive.is_dir() {
+        list_adrs(&archive)
+    } else {
+        Ok(Vec::new())
+    }
+}
+
 // get only the statuses that are links
 pub(crate) fn get_links(path: &Path) -> Result<Vec<(String, String, String)>> {
     let status = get_status(path)?;
@@ -173,19 +525,22 @@ pub(crate) fn get_links(path: &Path) -> Result<Vec<(String, String, String)>> {
     Ok(links)
 }
 
-// append the status to the ADR
-pub(crate) fn append_status(path: &Path, status: &str) -> Result<()> {
-    let markdown_input = std::fs::read_to_string(path)?;
-    let mut buf = String::with_capacity(markdown_input.len() + status.len() + 2);
+// append the status to the ADR markdown
+pub(crate) fn append_status_markdown(input: &str, status: &str) -> Result<String> {
+    let (frontmatter, markdown_input) = split_frontmatter(input);
+    let mut buf = String::with_capacity(input.len() + status.len() + 2);
+    if !frontmatter.is_empty() {
+        buf = buf + frontmatter + "\n";
+    }
 
     let mut state = None;
     let mut in_status = false;
-    for (event, offset) in Parser::new(&markdown_input).into_offset_iter() {
+    for (event, offset) in Parser::new(markdown_input).into_offset_iter() {
         match event {
-            Event::End(Tag::Heading(HeadingLevel::H2, _, _)) => {
-                if markdown_input[offset].starts_with("## Status") {
-                    in_status = true;
-                }
+            Event::End(Tag::Heading(HeadingLevel::H2, _, _))
+                if markdown_input[offset].starts_with("## Status") =>
+            {
+                in_status = true;
             }
             Event::End(Tag::Paragraph) => {
                 if in_status {
@@ -200,19 +555,119 @@ pub(crate) fn append_status(path: &Path, status: &str) -> Result<()> {
     if let Some(state) = state {
         state.finalize(&mut buf)?;
     }
+    buf.truncate(buf.trim_end().len());
+    buf.push('\n');
+    Ok(buf)
+}
 
-    std::fs::write(path, buf)?;
-    Ok(())
+// replace the status of the ADR markdown, keeping the links in the status section
+pub(crate) fn set_status_markdown(input: &str, status: &str) -> Result<String> {
+    let (frontmatter, markdown) = split_frontmatter(input);
+    let mut buf = String::with_capacity(input.len() + status.len());
+    buf += frontmatter;
+
+    let mut found = false;
+    let mut in_status = false;
+    for line in markdown.split_inclusive('\n') {
+        if line.starts_with("## ") {
+            in_status = line.trim_end() == "## Status";
+            buf += line;
+            if in_status {
+                found = true;
+                buf = buf + "\n" + status + "\n\n";
+            }
+        } else if in_status {
+            let line = line.trim();
+            if line.contains("](") {
+                buf = buf + line + "\n\n";
+            }
+        } else {
+            buf += line;
+        }
+    }
+    if !found {
+        // MADR keeps the status in the frontmatter, in lowercase
+        return match get_frontmatter_field(input, "status") {
+            Some(current) if current.starts_with(char::is_lowercase) => Ok(set_frontmatter_field(
+                input,
+                "status",
+                &status.to_lowercase(),
+            )),
+            Some(_) => Ok(set_frontmatter_field(input, "status", status)),
+            None => Err(anyhow::anyhow!("No status section found")),
+        };
+    }
+    buf.truncate(buf.trim_end().len());
+    buf.push('\n');
+    Ok(buf)
+}
+
+// set a link in the status section, replacing any link of the same kind to the same file so that
+// its title is current. Superseding drops the status itself too, as a superseded ADR has only
+// its "Superseded by" link
+pub(crate) fn set_link_markdown(
+    input: &str,
+    kind: &str,
+    title: &str,
+    filename: &str,
+    drop_status: bool,
+) -> Result<String> {
+    let (frontmatter, markdown) = split_frontmatter(input);
+    let mut buf = String::with_capacity(input.len() + title.len() + filename.len());
+    buf += frontmatter;
+
+    let link = Regex::new(r"^(?<kind>.+?)\s*\[[^\]]*\]\((?<dest>[^)\s]+)\)$").unwrap();
+    let same_link = |line: &str| {
+        link.captures(line).is_some_and(|caps| {
+            caps["kind"].eq_ignore_ascii_case(kind)
+                && caps["dest"].rsplit('/').next() == Some(filename)
+        })
+    };
+    let mut found = false;
+    let mut in_status = false;
+    for line in markdown.split_inclusive('\n') {
+        if line.starts_with("## ") {
+            if in_status {
+                buf = buf + kind + " [" + title + "](" + filename + ")\n\n";
+            }
+            in_status = line.trim_end() == "## Status";
+            buf += line;
+            if in_status {
+                found = true;
+                buf += "\n";
+            }
+        } else if in_status {
+            let line = line.trim();
+            let is_link = line.contains("](");
+            if !line.is_empty() && !same_link(line) && (is_link || !drop_status) {
+                buf = buf + line + "\n\n";
+            }
+        } else {
+            buf += line;
+        }
+    }
+    if !found {
+        return Err(anyhow::anyhow!("No status section found"));
+    }
+    if in_status {
+        buf = buf + kind + " [" + title + "](" + filename + ")\n";
+    }
+    buf.truncate(buf.trim_end().len());
+    buf.push('\n');
+    Ok(buf)
 }
 
-// remove a status from the ADR
-pub(crate) fn remove_status(path: &Path, status: &str) -> Result<()> {
-    let markdown_input = std::fs::read_to_string(path)?;
-    let mut buf = String::with_capacity(markdown_input.len() + status.len() + 2);
+// remove a status from the ADR markdown
+pub(crate) fn remove_status_markdown(input: &str, status: &str) -> Result<String> {
+    let (frontmatter, markdown_input) = split_frontmatter(input);
+    let mut buf = String::with_capacity(input.len() + status.len() + 2);
+    if !frontmatter.is_empty() {
+        buf = buf + frontmatter + "\n";
+    }
 
     let mut state = None;
     let mut in_status = false;
-    for (event, offset) in Parser::new(&markdown_input).into_offset_iter() {
+    for (event, offset) in Parser::new(markdown_input).into_offset_iter() {
         match event {
             Event::End(Tag::Heading(HeadingLevel::H2, _, _)) => {
                 in_status = markdown_input[offset].starts_with("## Status");
@@ -230,31 +685,149 @@ pub(crate) fn remove_status(path: &Path, status: &str) -> Result<()> {
     if let Some(state) = state {
         state.finalize(&mut buf)?;
     }
+    buf.truncate(buf.trim_end().len());
+    buf.push('\n');
+    Ok(buf)
+}
 
-    std::fs::write(path, buf)?;
-    Ok(())
+// replace the body of the section with the heading, adding the section after the `after` section
+// if the ADR doesn't have it, or at the end if it has neither
+pub(crate) fn set_section_markdown(input: &str, heading: &str, body: &str, after: &str) -> String {
+    let (frontmatter, markdown) = split_frontmatter(input);
+    // an empty section keeps just its heading
+    let body = if body.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n\n", body)
+    };
+    let section = format!("## {}\n\n{}", heading, body);
+    let is_heading = |line: &str, name: &str| {
+        line.strip_prefix("## ")
+            .is_some_and(|h| section_matches(h.trim(), name))
+    };
+
+    let mut buf = String::with_capacity(input.len() + section.len());
+    buf += frontmatter;
+    let mut found = markdown.lines().any(|line| is_heading(line, heading));
+    let mut in_section = false;
+    let mut in_after = false;
+    for line in markdown.split_inclusive('\n') {
+        if line.starts_with("## ") {
+            if in_after && !found {
+                buf += &section;
+                found = true;
+            }
+            in_after = is_heading(line, after);
+            in_section = is_heading(line, heading);
+            buf += line;
+            if in_section {
+                buf = buf + "\n" + &body;
+            }
+        } else if !in_section {
+            buf += line;
+        }
+    }
+    buf.truncate(buf.trim_end().len());
+    if !found {
+        buf = buf + "\n\n" + section.trim_end();
+    }
+    buf.push('\n');
+    buf
 }
 
-// read the .adr-dir file
+// format a path with forward slashes, as used in .adr-dir and in markdown links on every platform
+pub(crate) fn to_forward_slashes(path: &Path) -> String {
+    path.to_str().unwrap().replace('\\', "/")
+}
+
+// read the .adr-dir file, which may have been written with Windows path separators
 pub(crate) fn read_adr_dir_file() -> Result<PathBuf> {
     let dir = read_to_string(".adr-dir")?;
-    Ok(PathBuf::from(dir.trim()))
+    Ok(PathBuf::from(dir.trim().replace('\\', "/")))
+}
+
+// the ADR directory given with --dir, which takes the place of .adr-dir for the whole run
+static ADR_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+// use the directory as the ADR directory for the rest of the run, without any discovery
+pub(crate) fn set_adr_dir(dir: PathBuf) {
+    let _ = ADR_DIR.set(dir);
+}
+
+// the ADR directory given with --dir or --scope, or else the one recorded in .adr-dir
+pub(crate) fn configured_adr_dir() -> Result<PathBuf> {
+    match ADR_DIR.get() {
+        Some(dir) => Ok(dir.clone()),
+        None => read_adr_dir_file(),
+    }
+}
+
+// the scope given with --scope, which keeps its numbering apart from the other scopes
+static SCOPE: OnceLock<String> = OnceLock::new();
+
+// work on the scope for the rest of the run
+pub(crate) fn set_scope(scope: String) {
+    let _ = SCOPE.set(scope);
+}
+
+pub(crate) fn current_scope() -> Option<&'static str> {
+    SCOPE.get().map(String::as_str)
 }
 
 // find the ADR directory, defaulting to "doc/adr" and creating it if it doesn't exist
 pub(crate) fn find_adr_dir() -> Result<PathBuf> {
+    let _span = profile::span("discovery");
+    if let Some(dir) = ADR_DIR.get() {
+        if !dir.is_dir() {
+            anyhow::bail!("{} isn't a directory", dir.display());
+        }
+        return Ok(dir.clone());
+    }
     match read_adr_dir_file() {
         Ok(dir) => Ok(dir),
+        // nothing may be created when running read-only
+        _ if is_read_only() => Ok(PathBuf::from("doc/adr")),
         _ => {
             create_dir_all("doc/adr")?;
             Ok(PathBuf::from("doc/adr"))
         }
     }
 }
-// get the next ADR number
+// find the root of the enclosing git repository, if there is one
+pub(crate) fn find_repo_root() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+// the root of the project being worked on: the enclosing git repository, or outside one the
+// working directory
+pub(crate) fn find_project_root() -> Option<PathBuf> {
+    find_repo_root().or_else(|| std::env::current_dir().ok())
+}
+
+// the highest ADR number ever allocated, including archived ADRs and ADRs that have since been
+// deleted
+pub(crate) fn highest_adr_number(path: impl AsRef<Path>) -> Result<i32> {
+    let path = path.as_ref();
+    let adrs = list_adrs(path)?
+        .into_iter()
+        .chain(list_archived_adrs(path)?)
+        .collect::<Vec<_>>();
+    let highest = adrs.iter().filter_map(|adr| get_number(adr)).max();
+    Ok(highest.unwrap_or(0).max(State::load()?.high_water_mark))
+}
+
+// get the next ADR number. Numbers are never reused, and numbers reserved by another team are
+// skipped
 pub(crate) fn next_adr_number(path: impl AsRef<Path>) -> Result<i32> {
-    let adrs = list_adrs(path.as_ref())?;
-    Ok(adrs.len() as i32 + 1)
+    let reservations = Reservations::load()?;
+    let mut number = highest_adr_number(path)? + 1;
+    while reservations.is_reserved(number) {
+        number += 1;
+    }
+    Ok(number)
 }
 
 #[cfg(test)]
@@ -388,6 +961,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_number() {
+        assert_eq!(get_number(Path::new("doc/adr/0001-some-title.md")), Some(1));
+        assert_eq!(get_number(Path::new("0042-answer.md")), Some(42));
+        assert_eq!(get_number(Path::new("doc/adr/README.md")), None);
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_get_title() {
@@ -438,6 +1018,38 @@ mod tests {
             .is_empty());
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn test_get_current_status() {
+        let temp = TempDir::new().unwrap();
+        std::env::set_current_dir(temp.path()).unwrap();
+
+        temp.child("doc/adr/0001-some-title.md")
+            .write_str("# 1. Some title\n\n## Status\n\nProposed\n\nAccepted\n\nAmends [2. Other](0002-other.md)\n\n")
+            .unwrap();
+        temp.child("doc/adr/0002-other.md")
+            .write_str(
+                "# 2. Other\n\n## Status\n\nAccepted\n\nSuperseded by [3. New](0003-new.md)\n\n",
+            )
+            .unwrap();
+        temp.child("doc/adr/0003-new.md")
+            .write_str("# 3. New\n\n## Context\n\n")
+            .unwrap();
+
+        assert_eq!(
+            get_current_status(Path::new("doc/adr/0001-some-title.md")).unwrap(),
+            Some("Accepted".to_string())
+        );
+        assert_eq!(
+            get_current_status(Path::new("doc/adr/0002-other.md")).unwrap(),
+            Some("Superseded".to_string())
+        );
+        assert_eq!(
+            get_current_status(Path::new("doc/adr/0003-new.md")).unwrap(),
+            None
+        );
+    }
+
     #[test]
     #[serial_test::serial]
     fn get_links() {
@@ -474,13 +1086,198 @@ mod tests {
             .write_str("# 1. Some title\n\n## Status\n\nAccepted\n\n")
             .unwrap();
 
-        append_status(Path::new("doc/adr/0001-some-title.md"), "Rejected")
-            .expect("Failed to append status");
+        let markdown = read_to_string("doc/adr/0001-some-title.md").unwrap();
+        std::fs::write(
+            "doc/adr/0001-some-title.md",
+            append_status_markdown(&markdown, "Rejected").expect("Failed to append status"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_status(Path::new("doc/adr/0001-some-title.md")).unwrap(),
+            vec!["Accepted", "Rejected"]
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_append_status_frontmatter() {
+        let temp = TempDir::new().unwrap();
+        std::env::set_current_dir(temp.path()).unwrap();
+
+        temp.child("doc/adr/0001-some-title.md")
+            .write_str("---\ntags: []\n---\n\n# 1. Some title\n\n## Status\n\nAccepted\n\n")
+            .unwrap();
+
+        let markdown = read_to_string("doc/adr/0001-some-title.md").unwrap();
+        std::fs::write(
+            "doc/adr/0001-some-title.md",
+            append_status_markdown(&markdown, "Rejected").expect("Failed to append status"),
+        )
+        .unwrap();
 
         assert_eq!(
             get_status(Path::new("doc/adr/0001-some-title.md")).unwrap(),
             vec!["Accepted", "Rejected"]
         );
+        assert_eq!(
+            get_title(Path::new("doc/adr/0001-some-title.md")).unwrap(),
+            "1. Some title"
+        );
+        assert!(read_to_string("doc/adr/0001-some-title.md")
+            .unwrap()
+            .starts_with("---\ntags: []\n---\n\n# 1. Some title\n"));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_get_date() {
+        let temp = TempDir::new().unwrap();
+        std::env::set_current_dir(temp.path()).unwrap();
+
+        let markdown = "# 1. Some title\n\nDate: 2024-02-29\n\n## Status\n\nAccepted\n";
+        temp.child("doc/adr/0001-some-title.md")
+            .write_str(markdown)
+            .unwrap();
+        let path = Path::new("doc/adr/0001-some-title.md");
+        assert_eq!(get_date(path).unwrap().as_deref(), Some("2024-02-29"));
+
+        // status updates leave the date alone
+        std::fs::write(path, append_status_markdown(markdown, "Rejected").unwrap()).unwrap();
+        assert_eq!(get_date(path).unwrap().as_deref(), Some("2024-02-29"));
+
+        temp.child("doc/adr/0001-some-title.md")
+            .write_str("# 1. Some title\n\n## Status\n\nDate: 2024-01-01\n")
+            .unwrap();
+        assert_eq!(get_date(path).unwrap(), None);
+
+        assert!(parse_date("2024-02-29").is_ok());
+        assert!(parse_date("2023-02-29").is_err());
+        assert!(parse_date("29/02/2024").is_err());
+    }
+
+    #[test]
+    fn test_set_section_markdown() {
+        let markdown =
+            "# 1. X\n\n## Context and Problem Statement\n\nWhy.\n\n## Decision Outcome\n\nThis.\n";
+        let added = set_section_markdown(
+            markdown,
+            "Decision Drivers",
+            "* Cost",
+            "Context and Problem Statement",
+        );
+        assert_eq!(added, "# 1. X\n\n## Context and Problem Statement\n\nWhy.\n\n## Decision Drivers\n\n* Cost\n\n## Decision Outcome\n\nThis.\n");
+        assert_eq!(
+            set_section_markdown(&added, "Decision Drivers", "", "Context"),
+            "# 1. X\n\n## Context and Problem Statement\n\nWhy.\n\n## Decision Drivers\n\n## Decision Outcome\n\nThis.\n"
+        );
+        assert_eq!(
+            set_section_markdown("# 1. X\n", "Decision Drivers", "* Cost", "Context"),
+            "# 1. X\n\n## Decision Drivers\n\n* Cost\n"
+        );
+    }
+
+    #[test]
+    fn test_set_link_markdown() {
+        let markdown = "# 3. X\n\n## Status\n\nAccepted\n\nAmends [1. One](0001-one.md)\n\nSuperseded by [4. Old title](0004-y.md)\n\n## Context\n\nWhy.\n";
+        assert_eq!(
+            set_link_markdown(markdown, "Superseded by", "4. Y", "0004-y.md", true).unwrap(),
+            "# 3. X\n\n## Status\n\nAmends [1. One](0001-one.md)\n\nSuperseded by [4. Y](0004-y.md)\n\n## Context\n\nWhy.\n"
+        );
+        assert_eq!(
+            set_link_markdown(
+                "# 4. Y\n\n## Status\n\nAccepted\n",
+                "Supersedes",
+                "3. X",
+                "0003-x.md",
+                false
+            )
+            .unwrap(),
+            "# 4. Y\n\n## Status\n\nAccepted\n\nSupersedes [3. X](0003-x.md)\n"
+        );
+        assert!(set_link_markdown("# 4. Y\n", "Supersedes", "3. X", "0003-x.md", false).is_err());
+    }
+
+    #[test]
+    fn test_set_status_markdown() {
+        let markdown = "---\ntags: []\n---\n\n# 1. Some title\n\n## Status\n\nProposed\n\nAmends [2. Other](0002-other.md)\n\n## Context\n\nWhy.\n";
+        assert_eq!(
+            set_status_markdown(markdown, "Accepted").unwrap(),
+            "---\ntags: []\n---\n\n# 1. Some title\n\n## Status\n\nAccepted\n\nAmends [2. Other](0002-other.md)\n\n## Context\n\nWhy.\n"
+        );
+        assert!(set_status_markdown("# 1. No status\n", "Accepted").is_err());
+    }
+
+    #[test]
+    fn test_frontmatter_fields() {
+        let markdown = "---\ntags: []\n---\n\n# 1. Some title\n";
+        let updated = record_status_date(markdown, "accepted", "2024-01-02");
+        assert_eq!(
+            updated,
+            "---\ntags: []\ndecided_date: 2024-01-02\n---\n\n# 1. Some title\n"
+        );
+        assert_eq!(
+            get_frontmatter_field(&updated, "decided_date").as_deref(),
+            Some("2024-01-02")
+        );
+        let updated = set_frontmatter_field(&updated, "decided_date", "2024-01-03");
+        assert_eq!(
+            get_frontmatter_field(&updated, "decided_date").as_deref(),
+            Some("2024-01-03")
+        );
+        assert_eq!(
+            get_frontmatter_field(&updated, "tags").as_deref(),
+            Some("[]")
+        );
+
+        // compatible mode ADRs have no frontmatter to record dates in
+        assert_eq!(
+            record_status_date("# 1. Some title\n", "Accepted", "2024-01-02"),
+            "# 1. Some title\n"
+        );
+        assert_eq!(
+            record_status_date(markdown, "Deprecated", "2024-01-02"),
+            markdown
+        );
+    }
+
+    #[test]
+    fn test_set_tags_line() {
+        let tags = vec!["api".to_string(), "security".to_string()];
+        assert_eq!(
+            set_tags_line("# 1. X\n\nDate: 2024-01-01\n\n## Status\n", &tags),
+            "# 1. X\n\nDate: 2024-01-01\n\nTags: api, security\n\n## Status\n"
+        );
+        assert_eq!(
+            set_tags_line("# 1. X\n\nTags: old\n\n## Status\n", &tags),
+            "# 1. X\n\nTags: api, security\n\n## Status\n"
+        );
+        let nextgen = "---\ntags: []\n---\n\n# 1. X\n";
+        assert_eq!(set_tags_line(nextgen, &tags), nextgen);
+        assert_eq!(set_tags_line("# 1. X\n", &[]), "# 1. X\n");
+    }
+
+    #[test]
+    fn test_reverse_link_kind() {
+        assert_eq!(reverse_link_kind("Amends"), "Amended by");
+        assert_eq!(reverse_link_kind("amended by"), "Amends");
+        assert_eq!(reverse_link_kind("Depends on"), "Required by");
+        assert_eq!(reverse_link_kind("Relates to"), "Relates to");
+        assert_eq!(reverse_link_kind("Inspired"), "Inspired by");
+        assert_eq!(reverse_link_kind("Inspired by"), "Inspired");
+    }
+
+    #[test]
+    fn test_split_frontmatter() {
+        assert_eq!(
+            split_frontmatter("---\ntags: []\n---\n# 1. Title\n"),
+            ("---\ntags: []\n---\n", "# 1. Title\n")
+        );
+        assert_eq!(split_frontmatter("# 1. Title\n"), ("", "# 1. Title\n"));
+        assert_eq!(
+            split_frontmatter("---\nunterminated\n"),
+            ("", "---\nunterminated\n")
+        );
     }
 
     #[test]
@@ -499,7 +1296,12 @@ mod tests {
             get_status(Path::new("doc/adr/0001-some-title.md")).unwrap(),
             vec!["Accepted", "Rejected"]
         );
-        assert!(remove_status(Path::new("doc/adr/0001-some-title.md"), "Rejected").is_ok());
+        let markdown = read_to_string("doc/adr/0001-some-title.md").unwrap();
+        std::fs::write(
+            "doc/adr/0001-some-title.md",
+            remove_status_markdown(&markdown, "Rejected").expect("Failed to remove status"),
+        )
+        .unwrap();
 
         assert_eq!(
             get_status(Path::new("doc/adr/0001-some-title.md")).unwrap(),
@@ -516,6 +1318,10 @@ mod tests {
         temp.child(".adr-dir").write_str("doc/adr\n").unwrap();
 
         assert_eq!(read_adr_dir_file().unwrap(), Path::new("doc/adr"));
+
+        temp.child(".adr-dir").write_str("doc\\adr\r\n").unwrap();
+
+        assert_eq!(read_adr_dir_file().unwrap(), Path::new("doc/adr"));
     }
 
     #[test]
@@ -544,5 +1350,16 @@ mod tests {
         temp.child("doc/adr/garbage.md").touch().unwrap();
 
         assert_eq!(next_adr_number("doc/adr").unwrap(), 3);
+
+        temp.child(".adrs/reservations.toml")
+            .write_str("[[reservation]]\nnumber = 3\nowner = \"josh\"\ndate = \"2024-01-01\"\n")
+            .unwrap();
+        assert_eq!(next_adr_number("doc/adr").unwrap(), 4);
+
+        // a deleted ADR's number isn't reused
+        temp.child(".adrs/state.toml")
+            .write_str("high_water_mark = 7\n")
+            .unwrap();
+        assert_eq!(next_adr_number("doc/adr").unwrap(), 8);
     }
 }
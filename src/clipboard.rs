@@ -0,0 +1,64 @@
+use std::process::Command;
+
+use anyhow::Result;
+
+// the commands that print the clipboard, tried in order until one succeeds
+#[cfg(target_os = "macos")]
+static PASTE_COMMANDS: &[&[&str]] = &[&["pbpaste"]];
+#[cfg(windows)]
+static PASTE_COMMANDS: &[&[&str]] =
+    &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard -Raw"]];
+#[cfg(not(any(target_os = "macos", windows)))]
+static PASTE_COMMANDS: &[&[&str]] = &[
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-o"],
+    &["xsel", "--clipboard", "--output"],
+];
+
+// read the text on the clipboard with the platform's paste command
+pub(crate) fn read_clipboard() -> Result<String> {
+    for command in PASTE_COMMANDS {
+        let Ok(output) = Command::new(command[0]).args(&command[1..]).output() else {
+            continue;
+        };
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+        }
+    }
+    let programs = PASTE_COMMANDS
+        .iter()
+        .map(|command| command[0])
+        .collect::<Vec<_>>();
+    anyhow::bail!(
+        "Unable to read the clipboard, tried {}",
+        programs.join(", ")
+    )
+}
+
+// split a draft into the text of its first heading, if it starts with one, and the rest
+pub(crate) fn split_draft_title(draft: &str) -> (Option<String>, &str) {
+    let trimmed = draft.trim_start();
+    let (first, rest) = trimmed.split_once('\n').unwrap_or((trimmed, ""));
+    match first.strip_prefix("# ") {
+        Some(title) if !title.trim().is_empty() => (Some(title.trim().to_string()), rest),
+        _ => (None, draft),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_draft_title() {
+        assert_eq!(
+            split_draft_title("\n# Use Postgres\n\n## Context\n"),
+            (Some("Use Postgres".to_string()), "\n## Context\n")
+        );
+        assert_eq!(
+            split_draft_title("## Context\n\nWhy\n"),
+            (None, "## Context\n\nWhy\n")
+        );
+        assert_eq!(split_draft_title("# \n"), (None, "# \n"));
+    }
+}
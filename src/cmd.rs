@@ -1,7 +1,38 @@
+pub mod backup;
+pub mod completions;
 pub mod config;
+pub mod confirm;
+pub mod discover;
+pub mod doctor;
+pub mod drivers;
 pub mod edit;
+pub mod export;
+pub mod fix_links;
+pub mod fmt;
 pub mod generate;
+pub mod implement;
+pub mod import;
+pub mod info;
 pub mod init;
+pub mod last;
 pub mod link;
 pub mod list;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 pub mod new;
+pub mod next_number;
+pub mod option;
+pub mod order;
+pub mod publish;
+pub mod replace;
+pub mod report_issue;
+pub mod reserve;
+pub mod retire;
+pub mod search;
+pub mod show;
+pub mod stats;
+pub mod status;
+pub mod todos;
+pub mod upgrade;
+pub mod validate;
+pub mod verify_export;
@@ -0,0 +1,168 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use time::macros::format_description;
+
+use crate::adr::{artifact_time, find_adr_dir, is_deterministic, list_adrs, list_archived_adrs};
+use crate::config::CONFIG_FILE;
+use crate::json_adr::export_adrs;
+
+// the JSON-ADR export in the backup, which is written for other tools and isn't restored
+static EXPORT_FILE: &str = "adrs.json";
+// the directory of project state, such as the high water mark and the reservations
+static STATE_DIR: &str = ".adrs";
+
+#[derive(Debug, Args)]
+pub(crate) struct BackupArgs {
+    /// The directory to write the backup to
+    #[arg(short, long, default_value = ".")]
+    output: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct RestoreBackupArgs {
+    /// The backup to restore, as written by `adrs backup`
+    archive: PathBuf,
+    /// Overwrite existing files
+    #[arg(long, default_value_t = false)]
+    overwrite: bool,
+}
+
+// the ADR directory as a path in the backup, which is relative to the project root
+fn archive_path(adr_dir: &Path, root: &Path) -> Result<PathBuf> {
+    if adr_dir.is_relative() {
+        return Ok(adr_dir.to_path_buf());
+    }
+    adr_dir
+        .strip_prefix(root)
+        .map(Path::to_path_buf)
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "{} isn't inside the current directory, so it can't be backed up",
+                adr_dir.display()
+            )
+        })
+}
+
+// the name of the backup, timestamped so that backups sort by when they were taken
+fn backup_name() -> Result<String> {
+    let Some(time) = artifact_time()? else {
+        return Ok("adrs-backup.tar.gz".to_string());
+    };
+    let timestamp = time.format(format_description!(
+        "[year][month][day]-[hour][minute][second]"
+    ))?;
+    Ok(format!("adrs-backup-{}.tar.gz", timestamp))
+}
+
+// bundle the ADR directory, the configuration, the project state and a JSON-ADR export. The
+// cache, the journal and the audit log are per user, so they aren't included
+pub(crate) fn run(args: &BackupArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let mut paths = list_adrs(&adr_dir)?;
+    paths.extend(list_archived_adrs(&adr_dir)?);
+    let export = export_adrs(&adr_dir, &paths)?.to_json()?;
+
+    std::fs::create_dir_all(&args.output)?;
+    let path = args.output.join(backup_name()?);
+    let file =
+        File::create(&path).with_context(|| format!("Unable to create {}", path.display()))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    if is_deterministic() {
+        // leave out the owners and modification times of the files
+        archive.mode(tar::HeaderMode::Deterministic);
+    }
+    archive.append_dir_all(archive_path(&adr_dir, &std::env::current_dir()?)?, &adr_dir)?;
+    for file in [".adr-dir", CONFIG_FILE] {
+        if Path::new(file).is_file() {
+            archive.append_path(file)?;
+        }
+    }
+    if Path::new(STATE_DIR).is_dir() {
+        archive.append_dir_all(STATE_DIR, STATE_DIR)?;
+    }
+    let mut header = tar::Header::new_gnu();
+    header.set_size(export.len() as u64);
+    header.set_mode(0o644);
+    let mtime = artifact_time()?.map_or(0, |time| time.unix_timestamp());
+    header.set_mtime(mtime.max(0) as u64);
+    archive.append_data(&mut header, EXPORT_FILE, export.as_bytes())?;
+    archive
+        .into_inner()?
+        .finish()
+        .with_context(|| format!("Unable to write {}", path.display()))?;
+
+    println!("Backed up {} ADR(s) to {}", paths.len(), path.display());
+    Ok(())
+}
+
+fn open_backup(path: &Path) -> Result<tar::Archive<GzDecoder<File>>> {
+    let file = File::open(path).with_context(|| format!("Unable to open {}", path.display()))?;
+    Ok(tar::Archive::new(GzDecoder::new(file)))
+}
+
+// restore the backup into the current directory, refusing to replace files unless told to
+pub(crate) fn run_restore(args: &RestoreBackupArgs) -> Result<()> {
+    let mut files = Vec::new();
+    for entry in open_backup(&args.archive)?.entries()? {
+        let entry = entry.with_context(|| format!("Unable to read {}", args.archive.display()))?;
+        if entry.header().entry_type().is_file() {
+            files.push(entry.path()?.into_owned());
+        }
+    }
+    if !files.iter().any(|file| file == Path::new(EXPORT_FILE)) {
+        anyhow::bail!("{} isn't an adrs backup", args.archive.display());
+    }
+    let existing = files
+        .iter()
+        .filter(|file| file.as_path() != Path::new(EXPORT_FILE) && file.exists())
+        .map(|file| file.display().to_string())
+        .collect::<Vec<_>>();
+    if !existing.is_empty() && !args.overwrite {
+        anyhow::bail!(
+            "Files already exist: {}. Use the --overwrite flag to overwrite them.",
+            existing.join(", ")
+        );
+    }
+
+    let mut restored = 0;
+    for entry in open_backup(&args.archive)?.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.as_ref() == Path::new(EXPORT_FILE) {
+            continue;
+        }
+        // unpack_in refuses paths that would escape the current directory
+        if entry.unpack_in(".")? && entry.header().entry_type().is_file() {
+            restored += 1;
+        }
+    }
+    println!(
+        "Restored {} file(s) from {}",
+        restored,
+        args.archive.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_path() {
+        assert_eq!(
+            archive_path(Path::new("doc/adr"), Path::new("/project")).unwrap(),
+            PathBuf::from("doc/adr")
+        );
+        assert_eq!(
+            archive_path(Path::new("/project/decisions"), Path::new("/project")).unwrap(),
+            PathBuf::from("decisions")
+        );
+        assert!(archive_path(Path::new("/elsewhere/adr"), Path::new("/project")).is_err());
+    }
+}
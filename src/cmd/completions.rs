@@ -0,0 +1,63 @@
+use anyhow::Result;
+use clap::{Args, Command, ValueEnum};
+use clap_complete::{generate, Shell};
+use clap_complete_nushell::Nushell;
+
+use crate::config::load_config;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum CompletionShell {
+    Bash,
+    Elvish,
+    Fish,
+    Nushell,
+    Powershell,
+    Zsh,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct CompletionsArgs {
+    /// The shell to generate completions for
+    shell: CompletionShell,
+}
+
+// add a subcommand for each configured alias so that aliases complete like the commands they expand to
+fn with_aliases(mut cmd: Command) -> Result<Command> {
+    // a broken configuration has already been warned about, and completes without aliases
+    let Ok(config) = load_config() else {
+        return Ok(cmd);
+    };
+    for (name, expansion) in &config.alias {
+        if cmd.find_subcommand(name).is_some() {
+            continue;
+        }
+        let about = format!("Alias for `{}`", expansion);
+        let target = expansion
+            .split_whitespace()
+            .next()
+            .and_then(|target| cmd.find_subcommand(target))
+            .cloned();
+        let alias = match target {
+            Some(target) => target.name(name.clone()).about(about),
+            None => Command::new(name.clone()).about(about),
+        };
+        cmd = cmd.subcommand(alias);
+    }
+    Ok(cmd)
+}
+
+pub(crate) fn run(args: &CompletionsArgs, cmd: Command) -> Result<()> {
+    let mut cmd = with_aliases(cmd)?;
+    let name = cmd.get_name().to_string();
+    let mut out = std::io::stdout();
+
+    match args.shell {
+        CompletionShell::Bash => generate(Shell::Bash, &mut cmd, name, &mut out),
+        CompletionShell::Elvish => generate(Shell::Elvish, &mut cmd, name, &mut out),
+        CompletionShell::Fish => generate(Shell::Fish, &mut cmd, name, &mut out),
+        CompletionShell::Nushell => generate(Nushell, &mut cmd, name, &mut out),
+        CompletionShell::Powershell => generate(Shell::PowerShell, &mut cmd, name, &mut out),
+        CompletionShell::Zsh => generate(Shell::Zsh, &mut cmd, name, &mut out),
+    }
+    Ok(())
+}
@@ -1,12 +1,32 @@
+use std::path::PathBuf;
+
 use anyhow::Result;
 use clap::Args;
 
 use crate::adr::read_adr_dir_file;
+use crate::paths;
 
 #[derive(Debug, Args)]
-pub(crate) struct ConfigArgs {}
+pub(crate) struct ConfigArgs {
+    /// Show the locations of the global configuration, cache and state files
+    #[arg(long, default_value_t = false)]
+    paths: bool,
+}
+
+pub(crate) fn run(args: &ConfigArgs) -> Result<()> {
+    if args.paths {
+        let show = |name: &str, path: Option<PathBuf>| match path {
+            Some(path) => println!("{}={}", name, path.display()),
+            None => println!("{}=", name),
+        };
+        show("adrs_global_config", paths::global_config_file());
+        show("adrs_cache_dir", paths::cache_dir());
+        show("adrs_state_dir", paths::state_dir());
+        show("adrs_journal", paths::journal_file());
+        show("adrs_audit_log", paths::audit_log_file());
+        return Ok(());
+    }
 
-pub(crate) fn run(_args: &ConfigArgs) -> Result<()> {
     println!(
         "adrs_bin_dir={}",
         std::env::current_exe().unwrap().parent().unwrap().display()
@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{
+    find_adr, find_adr_dir, list_adrs, now, quote_frontmatter_value, remove_frontmatter_field,
+    set_frontmatter_field, split_frontmatter,
+};
+use crate::cmd::fmt::with_formatted_frontmatter;
+use crate::config::load_config;
+use crate::model::{Adr, ConfirmationResult};
+use crate::transaction::Transaction;
+
+#[derive(Debug, Args)]
+pub(crate) struct ConfirmArgs {
+    /// The Architectural Decision Record number or file name match
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    adr: Option<String>,
+    /// The outcome of checking that the decision is followed
+    #[arg(
+        long,
+        value_enum,
+        required_unless_present = "run",
+        conflicts_with = "run"
+    )]
+    result: Option<ConfirmationResult>,
+    /// What was checked, or what was found, e.g. a link to the CI run
+    #[arg(long)]
+    note: Option<String>,
+    /// Run the ADR's check command, declared as confirmation_command in the frontmatter or as a
+    /// sh code block in its Confirmation section, and record whether it succeeded
+    #[arg(long, default_value_t = false)]
+    run: bool,
+    /// Run the check commands of every accepted or implemented ADR that has one
+    #[arg(long, default_value_t = false, requires = "run")]
+    all: bool,
+}
+
+// record the outcome in the ADR's frontmatter
+fn record(path: &Path, result: ConfirmationResult, note: Option<&str>, date: &str) -> Result<()> {
+    let config = load_config().context("Unable to load configuration")?;
+    let mut tx = Transaction::new();
+    tx.update(path, |markdown| {
+        let mut markdown = set_frontmatter_field(markdown, "confirmation_result", result.as_str());
+        markdown = set_frontmatter_field(&markdown, "confirmed_date", date);
+        // the note belongs to the run it was given with, so a run without one clears it
+        markdown = match note {
+            Some(note) => set_frontmatter_field(
+                &markdown,
+                "confirmation_note",
+                &quote_frontmatter_value(note),
+            ),
+            None => remove_frontmatter_field(&markdown, "confirmation_note"),
+        };
+        Ok(with_formatted_frontmatter(
+            &markdown,
+            &config.fmt.frontmatter_order,
+        ))
+    })?;
+    tx.commit()
+}
+
+// whether the ADR has frontmatter to record the outcome in
+fn has_frontmatter(path: &Path) -> Result<bool> {
+    let markdown = std::fs::read_to_string(path)
+        .with_context(|| format!("Unable to read {}", path.display()))?;
+    Ok(!split_frontmatter(&markdown).0.is_empty())
+}
+
+// run the check command in a shell from the current directory, with its output passed through
+fn run_check(command: &str) -> Result<(ConfirmationResult, String)> {
+    let status = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).status()
+    } else {
+        Command::new("sh").args(["-c", command]).status()
+    }
+    .with_context(|| format!("Unable to run {}", command))?;
+    let result = if status.success() {
+        ConfirmationResult::Pass
+    } else {
+        ConfirmationResult::Fail
+    };
+    Ok((result, format!("`{}` {}", command, status)))
+}
+
+// run the check commands of the ADRs, recording the outcome of each in the ADRs that have
+// frontmatter, and fail if any check failed
+fn run_checks(adr_dir: &Path, paths: &[PathBuf], note: Option<&str>) -> Result<()> {
+    let date = now()?;
+    let mut failed = 0;
+    for path in paths {
+        let adr = Adr::from_path(adr_dir, path)?;
+        let Some(command) = adr.check_command() else {
+            continue;
+        };
+        let (result, outcome) = run_check(&command)?;
+        if result == ConfirmationResult::Fail {
+            failed += 1;
+        }
+        if has_frontmatter(path)? {
+            record(path, result, Some(note.unwrap_or(&outcome)), &date)?;
+        }
+        println!(
+            "{}: confirmation={} ({})",
+            path.display(),
+            result.as_str(),
+            outcome
+        );
+    }
+    if failed > 0 {
+        anyhow::bail!("{} confirmation(s) failed", failed);
+    }
+    Ok(())
+}
+
+pub(crate) fn run(args: &ConfirmArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+
+    if args.all {
+        let mut paths = Vec::new();
+        for path in list_adrs(&adr_dir)? {
+            if Adr::from_path(&adr_dir, &path)?.is_in_force() {
+                paths.push(path);
+            }
+        }
+        return run_checks(&adr_dir, &paths, args.note.as_deref());
+    }
+
+    let path = find_adr(&adr_dir, args.adr.as_deref().unwrap_or_default())?;
+    if args.run {
+        if Adr::from_path(&adr_dir, &path)?.check_command().is_none() {
+            anyhow::bail!(
+                "{} doesn't declare a check command, as confirmation_command in its frontmatter or a sh code block in its Confirmation section",
+                path.display()
+            );
+        }
+        return run_checks(&adr_dir, &[path], args.note.as_deref());
+    }
+
+    if !has_frontmatter(&path)? {
+        anyhow::bail!(
+            "{} has no frontmatter, confirmation tracking needs nextgen mode",
+            path.display()
+        );
+    }
+    if Adr::from_path(&adr_dir, &path)?.confirmation().is_none() {
+        eprintln!(
+            "Warning: {} has no Confirmation section saying how the decision is checked",
+            path.display()
+        );
+    }
+    let result = args
+        .result
+        .context("The result is required without --run")?;
+    record(&path, result, args.note.as_deref(), &now()?)?;
+
+    println!("{}: confirmation={}", path.display(), result.as_str());
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use regex::Regex;
+
+use crate::adr::{read_adr_dir_file, slugify, to_forward_slashes};
+use crate::config::{load_config, read_config_table, Config, CONFIG_FILE};
+use crate::prompt::prompt_bool;
+use crate::transaction::Transaction;
+
+#[derive(Debug, Args)]
+pub(crate) struct DiscoverArgs {
+    /// Register the directories that aren't configured yet as scopes, without asking
+    #[arg(long, default_value_t = false)]
+    register: bool,
+    /// How many NNNN-title.md files a directory needs to count as a collection of ADRs
+    #[arg(long, default_value_t = 2, value_name = "N")]
+    min: usize,
+}
+
+// directories that hold dependencies or build output rather than documentation
+static SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build"];
+
+// the path components that say a directory holds decisions, but not whose
+static GENERIC_NAMES: &[&str] = &[
+    "doc",
+    "docs",
+    "documentation",
+    "adr",
+    "adrs",
+    "decisions",
+    "decision-records",
+    "architecture",
+    "architecture-decisions",
+    "design",
+    "records",
+];
+
+// a directory that looks like a collection of ADRs
+#[derive(Debug)]
+struct Candidate {
+    dir: PathBuf,
+    count: usize,
+    // the first and last numbers of the ADRs
+    range: (i32, i32),
+}
+
+// the directories under the root with at least min files named like ADRs, by path
+fn find_candidates(root: &Path, min: usize) -> Result<Vec<Candidate>> {
+    let numbered = Regex::new(r"^(\d{3,4})-.+\.md$").unwrap();
+    let mut dirs: BTreeMap<PathBuf, Vec<i32>> = BTreeMap::new();
+    let walker = walkdir::WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || !(name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()))
+        });
+    for entry in walker {
+        let entry = entry.context("Unable to read directory")?;
+        let name = entry.file_name().to_string_lossy();
+        let Some(caps) = numbered.captures(&name) else {
+            continue;
+        };
+        if entry.file_type().is_file() {
+            let dir = entry.path().parent().unwrap_or(root);
+            let dir = match dir.strip_prefix(root).unwrap_or(dir) {
+                dir if dir.as_os_str().is_empty() => PathBuf::from("."),
+                dir => dir.to_path_buf(),
+            };
+            dirs.entry(dir).or_default().push(caps[1].parse()?);
+        }
+    }
+    let mut candidates = Vec::new();
+    for (dir, numbers) in &dirs {
+        if numbers.len() < min {
+            continue;
+        }
+        // archived ADRs belong to the collection above them
+        if dir.file_name().is_some_and(|name| name == "archive")
+            && dir
+                .parent()
+                .and_then(|parent| dirs.get(parent))
+                .is_some_and(|numbers| numbers.len() >= min)
+        {
+            continue;
+        }
+        candidates.push(Candidate {
+            dir: dir.clone(),
+            count: numbers.len(),
+            range: (
+                *numbers.iter().min().unwrap(),
+                *numbers.iter().max().unwrap(),
+            ),
+        });
+    }
+    Ok(candidates)
+}
+
+// a scope name for the directory, from the nearest component that isn't a generic name for
+// documentation, e.g. payments for services/payments/doc/adr
+fn scope_name(dir: &Path) -> String {
+    dir.components()
+        .rev()
+        .map(|component| slugify(&component.as_os_str().to_string_lossy()))
+        .find(|name| !name.is_empty() && !GENERIC_NAMES.contains(&name.as_str()))
+        .unwrap_or_else(|| "main".to_string())
+}
+
+// add the scopes to the project configuration file, creating it if there isn't one
+fn register_scopes(scopes: &[(String, PathBuf)]) -> Result<()> {
+    let path = Path::new(CONFIG_FILE);
+    let mut table = if path.exists() {
+        read_config_table(path)?
+    } else {
+        toml::Table::new()
+    };
+    let configured = table
+        .entry("scopes")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+        .with_context(|| format!("[scopes] in {} isn't a table", CONFIG_FILE))?;
+    for (name, dir) in scopes {
+        configured.insert(name.clone(), toml::Value::String(to_forward_slashes(dir)));
+    }
+    let config: Config = table
+        .try_into()
+        .context("Invalid configuration after adding the scopes")?;
+    let contents = toml::to_string_pretty(&config).context("Unable to serialize configuration")?;
+    let mut tx = Transaction::new();
+    tx.write(path, contents);
+    tx.commit()
+}
+
+pub(crate) fn run(args: &DiscoverArgs) -> Result<()> {
+    let config = load_config().context("Unable to load configuration")?;
+    let root = std::env::current_dir().context("Unable to read the current directory")?;
+    let adr_dir = read_adr_dir_file().ok();
+    let candidates = find_candidates(&root, args.min)?;
+    if candidates.is_empty() {
+        println!("No directories of ADRs found");
+        return Ok(());
+    }
+
+    let mut names = config.scopes.keys().cloned().collect::<Vec<_>>();
+    let mut unregistered = Vec::new();
+    for candidate in &candidates {
+        let registered = config
+            .scopes
+            .iter()
+            .find(|(_, dir)| dir.as_path() == candidate.dir);
+        let state = if adr_dir.as_deref() == Some(candidate.dir.as_path()) {
+            "the ADR directory".to_string()
+        } else if let Some((name, _)) = registered {
+            format!("scope {}", name)
+        } else {
+            // names stay unique among the configured and suggested scopes
+            let base = scope_name(&candidate.dir);
+            let mut name = base.clone();
+            let mut n = 1;
+            while names.contains(&name) {
+                n += 1;
+                name = format!("{}-{}", base, n);
+            }
+            names.push(name.clone());
+            unregistered.push((name.clone(), candidate.dir.clone()));
+            format!("not registered, as scope {}", name)
+        };
+        println!(
+            "{}: {} ADR(s), {:04} to {:04}, {}",
+            to_forward_slashes(&candidate.dir),
+            candidate.count,
+            candidate.range.0,
+            candidate.range.1,
+            state
+        );
+    }
+    if unregistered.is_empty() {
+        return Ok(());
+    }
+
+    let register = args.register
+        || (std::io::stdin().is_terminal()
+            && prompt_bool(
+                &format!("Register {} directories as scopes", unregistered.len()),
+                false,
+            )?);
+    if !register {
+        println!(
+            "Run again with --register to add them to [scopes] in {}",
+            CONFIG_FILE
+        );
+        return Ok(());
+    }
+    register_scopes(&unregistered)?;
+    for (name, dir) in &unregistered {
+        println!("Registered scope {} for {}", name, to_forward_slashes(dir));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_name() {
+        assert_eq!(
+            scope_name(Path::new("services/payments/doc/adr")),
+            "payments"
+        );
+        assert_eq!(
+            scope_name(Path::new("Mobile App/docs/decisions")),
+            "mobile-app"
+        );
+        assert_eq!(scope_name(Path::new("doc/adr")), "main");
+    }
+}
@@ -0,0 +1,865 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::adr::{
+    find_adr_dir, get_date, get_links, get_number, get_status, get_title, list_adrs, parse_date,
+    set_status_markdown, split_frontmatter,
+};
+use crate::config::{load_config, Config, Mode, Reconcile};
+use crate::history::status_history;
+use crate::manifest::{read_manifest, ManifestRepo};
+use crate::model::{section_matches, split_sections, Adr, ConfirmationResult};
+use crate::reservations::Reservations;
+use crate::sarif::SarifLog;
+use crate::transaction::Transaction;
+
+// the lint rules checked when the configuration doesn't choose any
+pub(crate) static DEFAULT_RULES: &[&str] = &[
+    "title",
+    "status",
+    "date",
+    "links",
+    "status-links",
+    "confirmation",
+];
+
+#[derive(Debug, Args)]
+pub(crate) struct DoctorArgs {
+    /// Also check that the repository can still be used with the original adr-tools scripts.
+    /// The same check runs when the "adr-tools" lint rule is configured
+    #[arg(long, default_value_t = false)]
+    adr_tools: bool,
+    /// How to report the problems found
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    format: ReportFormat,
+    /// Check every repository listed in this TOML manifest in parallel, as `[[repos]]` tables
+    /// with a `path` and an optional `name`, and report on them together
+    #[arg(long, value_name = "FILE")]
+    manifest: Option<PathBuf>,
+    /// Reconcile ADRs whose status and links contradict each other before checking, trusting
+    /// whichever side lint.reconcile in the configuration prefers
+    #[arg(long, default_value_t = false)]
+    fix: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct DoctorReport<'a> {
+    errors: usize,
+    warnings: usize,
+    diagnostics: &'a [Diagnostic],
+}
+
+// a diagnostic as reported by doctor in another repository
+#[derive(Debug, Deserialize, Serialize)]
+struct RepoDiagnostic {
+    severity: Severity,
+    rule: String,
+    path: Option<PathBuf>,
+    message: String,
+}
+
+// the outcome of checking one repository in the manifest
+#[derive(Debug, Default, Serialize)]
+struct RepoReport {
+    name: String,
+    path: PathBuf,
+    errors: usize,
+    warnings: usize,
+    diagnostics: Vec<RepoDiagnostic>,
+    // why the repository couldn't be checked at all
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestReport {
+    repos: Vec<RepoReport>,
+    errors: usize,
+    warnings: usize,
+    // repositories that couldn't be checked
+    failures: usize,
+}
+
+/// How diagnostics are reported
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ReportFormat {
+    /// One line per problem
+    #[default]
+    Text,
+    /// A JSON document for tools
+    Json,
+    /// A SARIF log, for GitHub code scanning and other CI dashboards
+    Sarif,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+// a problem found by one of the checks
+#[derive(Debug, Serialize)]
+pub(crate) struct Diagnostic {
+    pub(crate) severity: Severity,
+    pub(crate) rule: &'static str,
+    pub(crate) path: Option<PathBuf>,
+    pub(crate) message: String,
+}
+
+impl Diagnostic {
+    pub(crate) fn new(
+        severity: Severity,
+        rule: &'static str,
+        path: Option<&Path>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity,
+            rule,
+            path: path.map(Path::to_path_buf),
+            message: message.into(),
+        }
+    }
+
+    // the diagnostic as a SARIF finding
+    pub(crate) fn finding(&self) -> (Severity, &str, Option<&Path>, &str) {
+        (
+            self.severity,
+            self.rule,
+            self.path.as_deref(),
+            &self.message,
+        )
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}[{}]: ", self.severity, self.rule)?;
+        if let Some(path) = &self.path {
+            write!(f, "{}: ", path.display())?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+// two ADRs must never share a number
+fn check_numbering(adrs: &[PathBuf], diagnostics: &mut Vec<Diagnostic>) {
+    let mut numbers: BTreeMap<i32, Vec<&PathBuf>> = BTreeMap::new();
+    for adr in adrs {
+        if let Some(number) = get_number(adr) {
+            numbers.entry(number).or_default().push(adr);
+        }
+    }
+    for (number, paths) in numbers.into_iter().filter(|(_, paths)| paths.len() > 1) {
+        for path in paths {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                "numbering",
+                Some(path),
+                format!("ADR number {} is used more than once", number),
+            ));
+        }
+    }
+}
+
+// reserved numbers must be unique and not yet used by an ADR
+fn check_reservations(adrs: &[PathBuf], diagnostics: &mut Vec<Diagnostic>) {
+    let path = Reservations::path();
+    let reservations = match Reservations::load() {
+        Ok(reservations) => reservations,
+        Err(e) => {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                "reservations",
+                path.as_deref(),
+                format!("{:#}", e),
+            ));
+            return;
+        }
+    };
+
+    let mut seen = Vec::new();
+    for reservation in &reservations.reservations {
+        if seen.contains(&reservation.number) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                "reservations",
+                path.as_deref(),
+                format!(
+                    "ADR number {} is reserved more than once",
+                    reservation.number
+                ),
+            ));
+        }
+        seen.push(reservation.number);
+
+        if let Some(adr) = adrs
+            .iter()
+            .find(|adr| get_number(adr) == Some(reservation.number))
+        {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                "reservations",
+                Some(adr),
+                format!(
+                    "ADR number {} is reserved by {} but already in use",
+                    reservation.number, reservation.owner
+                ),
+            ));
+        }
+    }
+}
+
+// ADRs that aren't linked to or from any other ADR, and groups of linked ADRs that aren't linked
+// to the rest. Unlinked decisions often point to a missed supersession or a duplicate topic
+fn check_connectivity(adrs: &[PathBuf], rules: &[&str], diagnostics: &mut Vec<Diagnostic>) {
+    let numbered = adrs
+        .iter()
+        .filter_map(|adr| Some((get_number(adr)?, adr)))
+        .collect::<BTreeMap<_, _>>();
+    if numbered.len() < 2 {
+        return;
+    }
+    let mut neighbours: BTreeMap<i32, BTreeSet<i32>> = BTreeMap::new();
+    for (number, adr) in &numbered {
+        neighbours.entry(*number).or_default();
+        for (_, _, target) in get_links(adr).unwrap_or_default() {
+            let Some(target) = get_number(Path::new(&target)) else {
+                continue;
+            };
+            if target != *number && numbered.contains_key(&target) {
+                neighbours.entry(*number).or_default().insert(target);
+                neighbours.entry(target).or_default().insert(*number);
+            }
+        }
+    }
+
+    let mut clusters: Vec<Vec<i32>> = Vec::new();
+    let mut seen = BTreeSet::new();
+    for number in neighbours.keys() {
+        if !seen.insert(*number) {
+            continue;
+        }
+        let mut cluster = vec![*number];
+        let mut i = 0;
+        while i < cluster.len() {
+            for next in &neighbours[&cluster[i]] {
+                if seen.insert(*next) {
+                    cluster.push(*next);
+                }
+            }
+            i += 1;
+        }
+        cluster.sort();
+        clusters.push(cluster);
+    }
+
+    if rules.contains(&"islands") {
+        for cluster in clusters.iter().filter(|cluster| cluster.len() == 1) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Info,
+                "islands",
+                Some(numbered[&cluster[0]]),
+                "not linked to or from any other ADR",
+            ));
+        }
+    }
+    let mut linked = clusters
+        .into_iter()
+        .filter(|cluster| cluster.len() > 1)
+        .collect::<Vec<_>>();
+    if rules.contains(&"clusters") && linked.len() > 1 {
+        // the largest group is the main body of decisions, the first written winning a tie
+        linked.sort_by_key(|cluster| (std::cmp::Reverse(cluster.len()), cluster[0]));
+        for cluster in &linked[1..] {
+            let numbers = cluster
+                .iter()
+                .map(i32::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            diagnostics.push(Diagnostic::new(
+                Severity::Info,
+                "clusters",
+                Some(numbered[&cluster[0]]),
+                format!(
+                    "ADRs {} are linked to each other but not to the other ADRs",
+                    numbers
+                ),
+            ));
+        }
+    }
+}
+
+// the repository must stay usable by the adr-tools shell scripts: .adr-dir pointing at the ADR
+// directory, NNNN-title.md file names, a `# N. Title` first line, no frontmatter in compatible
+// mode and status links in the form adr-tools writes and reads
+fn check_adr_tools(adrs: &[PathBuf], mode: Mode, diagnostics: &mut Vec<Diagnostic>) {
+    let mut report = |severity, path: Option<&Path>, message: String| {
+        diagnostics.push(Diagnostic::new(severity, "adr-tools", path, message))
+    };
+    // without .adr-dir both tools use doc/adr
+    match std::fs::read_to_string(".adr-dir") {
+        Err(_) => {}
+        Ok(contents) if contents.contains('\\') => report(
+            Severity::Error,
+            None,
+            ".adr-dir uses backslashes, which adr-tools doesn't understand".to_string(),
+        ),
+        Ok(contents) if Path::new(contents.trim()).is_absolute() => report(
+            Severity::Warning,
+            None,
+            ".adr-dir is an absolute path, which only works on this machine".to_string(),
+        ),
+        Ok(_) => {}
+    }
+
+    let name = Regex::new(r"^\d{4}-[^/\\]+\.md$").unwrap();
+    let link = Regex::new(r"^[^\[]+ \[\d+\. [^\]]+\]\(\d{4}-[^)/]+\.md\)$").unwrap();
+    for adr in adrs {
+        let filename = adr.file_name().unwrap_or_default().to_string_lossy();
+        if !name.is_match(&filename) {
+            report(
+                Severity::Warning,
+                Some(adr),
+                "file name doesn't follow the NNNN-title.md naming".to_string(),
+            );
+        }
+        let Ok(markdown) = std::fs::read_to_string(adr) else {
+            continue;
+        };
+        let (frontmatter, body) = split_frontmatter(&markdown);
+        if mode == Mode::Compatible && !frontmatter.is_empty() {
+            report(
+                Severity::Error,
+                Some(adr),
+                "frontmatter in compatible mode, adr-tools would show it as text".to_string(),
+            );
+        }
+        let number = get_number(adr).unwrap_or_default();
+        let first = body.lines().find(|line| !line.trim().is_empty());
+        if !first.is_some_and(|line| line.starts_with(&format!("# {}. ", number))) {
+            report(
+                Severity::Warning,
+                Some(adr),
+                format!("the title line doesn't start with '# {}. '", number),
+            );
+        }
+        let (_, sections) = split_sections(body);
+        for (_, status) in sections
+            .iter()
+            .filter(|(heading, _)| section_matches(heading, "Status"))
+        {
+            for line in status.lines().map(str::trim) {
+                if line.contains("](") && !link.is_match(line) {
+                    report(
+                        Severity::Warning,
+                        Some(adr),
+                        format!("'{}' isn't a link adr-tools can follow", line),
+                    );
+                }
+            }
+        }
+    }
+}
+
+// raise or lower the severity of the rules configured in [lint.severity]
+pub(crate) fn apply_severities(config: &Config, diagnostics: &mut [Diagnostic]) {
+    for diagnostic in diagnostics {
+        if let Some(severity) = config.lint.severity.get(diagnostic.rule) {
+            diagnostic.severity = *severity;
+        }
+    }
+}
+
+// the lint rules chosen in the configuration, or the defaults
+pub(crate) fn lint_rules(config: &Config) -> Vec<&str> {
+    if config.lint.rules.is_empty() {
+        DEFAULT_RULES.to_vec()
+    } else {
+        config.lint.rules.iter().map(String::as_str).collect()
+    }
+}
+
+// the counts of errors and warnings
+pub(crate) fn count(diagnostics: &[Diagnostic]) -> (usize, usize) {
+    let count = |severity| {
+        diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    };
+    (count(Severity::Error), count(Severity::Warning))
+}
+
+// how an ADR's status contradicts its links
+#[derive(Debug, PartialEq, Eq)]
+enum StatusConflict {
+    // a status other than Superseded alongside a "Superseded by" link
+    SupersededLink(String),
+    // Superseded, without a link to the ADR that supersedes it
+    NoSupersedingLink,
+}
+
+impl fmt::Display for StatusConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StatusConflict::SupersededLink(status) => write!(
+                f,
+                "status is {} but a 'Superseded by' link says it has been superseded",
+                status
+            ),
+            StatusConflict::NoSupersedingLink => write!(
+                f,
+                "status is Superseded but no 'Superseded by' link says which ADR supersedes it"
+            ),
+        }
+    }
+}
+
+fn status_conflict(adr: &Adr) -> Option<StatusConflict> {
+    let superseded_by = adr.links.iter().any(|link| link.kind == "Superseded by");
+    let status = adr.status.as_deref()?;
+    let superseded = status.eq_ignore_ascii_case("Superseded");
+    if superseded_by && !superseded {
+        Some(StatusConflict::SupersededLink(status.to_string()))
+    } else if !superseded_by && superseded {
+        Some(StatusConflict::NoSupersedingLink)
+    } else {
+        None
+    }
+}
+
+// drop the lines of the status section that `remove` matches
+fn remove_status_lines(markdown: &str, remove: impl Fn(&str) -> bool) -> String {
+    let mut in_status = false;
+    // the blank line after a removed paragraph goes with it
+    let mut removed = false;
+    let mut out = String::with_capacity(markdown.len());
+    for line in markdown.split_inclusive('\n') {
+        if line.starts_with("## ") {
+            in_status = line.trim_end() == "## Status";
+        } else if in_status && !line.trim().is_empty() && remove(line.trim()) {
+            removed = true;
+            continue;
+        } else if removed && line.trim().is_empty() {
+            removed = false;
+            continue;
+        }
+        removed = false;
+        out += line;
+    }
+    out
+}
+
+// reconcile the ADRs whose status and links contradict each other, returning what was done
+fn fix_status_conflicts(adrs: &[PathBuf], reconcile: Reconcile) -> Result<Vec<String>> {
+    let mut fixes = Vec::new();
+    let mut tx = Transaction::new();
+    for path in adrs {
+        let markdown = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        let Some(conflict) = Adr::parse(path, &markdown)
+            .ok()
+            .and_then(|adr| status_conflict(&adr))
+        else {
+            continue;
+        };
+        match (conflict, reconcile) {
+            (StatusConflict::SupersededLink(status), Reconcile::Links) => {
+                tx.update(path, |markdown| {
+                    Ok(remove_status_lines(markdown, |line| line == status))
+                })?;
+                fixes.push(format!(
+                    "Fixed {}: removed status {}, as it has been superseded",
+                    path.display(),
+                    status
+                ));
+            }
+            (StatusConflict::SupersededLink(status), Reconcile::Status) => {
+                tx.update(path, |markdown| {
+                    Ok(remove_status_lines(markdown, |line| {
+                        line.starts_with("Superseded by ")
+                    }))
+                })?;
+                fixes.push(format!(
+                    "Fixed {}: removed the 'Superseded by' link, as its status is {}",
+                    path.display(),
+                    status
+                ));
+            }
+            (StatusConflict::NoSupersedingLink, Reconcile::Links) => {
+                // the status it had before it was marked superseded
+                let previous = status_history(path)
+                    .into_iter()
+                    .rev()
+                    .map(|(_, status)| status)
+                    .find(|status| status != "Superseded");
+                match previous {
+                    Some(previous) => {
+                        tx.update(path, |markdown| set_status_markdown(markdown, &previous))?;
+                        fixes.push(format!(
+                            "Fixed {}: restored status {}, as no ADR supersedes it",
+                            path.display(),
+                            previous
+                        ));
+                    }
+                    None => fixes.push(format!(
+                        "Unable to fix {}: no earlier status in its history",
+                        path.display()
+                    )),
+                }
+            }
+            (StatusConflict::NoSupersedingLink, Reconcile::Status) => fixes.push(format!(
+                "Unable to fix {}: link it to the ADR that supersedes it with `adrs status --by`",
+                path.display()
+            )),
+        }
+    }
+    tx.commit()?;
+    Ok(fixes)
+}
+
+// the configured lint rules for a single ADR
+pub(crate) fn check_adr(adr: &Path, rules: &[&str], diagnostics: &mut Vec<Diagnostic>) {
+    if rules.contains(&"title") && get_title(adr).is_err() {
+        diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            "title",
+            Some(adr),
+            "missing title",
+        ));
+    }
+    if rules.contains(&"status")
+        && get_status(adr).map_or(true, |status| status.iter().all(|s| s.trim().is_empty()))
+    {
+        diagnostics.push(Diagnostic::new(
+            Severity::Warning,
+            "status",
+            Some(adr),
+            "missing status",
+        ));
+    }
+    if rules.contains(&"date") {
+        match get_date(adr) {
+            Ok(None) => diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                "date",
+                Some(adr),
+                "missing date",
+            )),
+            Ok(Some(date)) => {
+                if let Err(e) = parse_date(&date) {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Error,
+                        "date",
+                        Some(adr),
+                        e.to_string(),
+                    ));
+                }
+            }
+            Err(_) => {}
+        }
+    }
+    if rules.contains(&"status-links") {
+        if let Some(conflict) = std::fs::read_to_string(adr)
+            .ok()
+            .and_then(|markdown| Adr::parse(adr, &markdown).ok())
+            .and_then(|parsed| status_conflict(&parsed))
+        {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                "status-links",
+                Some(adr),
+                conflict.to_string(),
+            ));
+        }
+    }
+    if rules.contains(&"confirmation") {
+        if let Some(parsed) = std::fs::read_to_string(adr)
+            .ok()
+            .and_then(|markdown| Adr::parse(adr, &markdown).ok())
+        {
+            if parsed.is_unconfirmed() {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Info,
+                    "confirmation",
+                    Some(adr),
+                    "has a Confirmation section but has never been confirmed",
+                ));
+            } else if parsed.confirmation_result == Some(ConfirmationResult::Fail) {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Warning,
+                    "confirmation",
+                    Some(adr),
+                    format!(
+                        "the last confirmation failed{}",
+                        parsed
+                            .confirmed_date
+                            .map(|date| format!(" on {}", date))
+                            .unwrap_or_default()
+                    ),
+                ));
+            }
+        }
+    }
+    if rules.contains(&"links") {
+        for (kind, _title, target) in get_links(adr).unwrap_or_default() {
+            if !adr.with_file_name(&target).exists() {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    "links",
+                    Some(adr),
+                    format!("'{}' links to missing file {}", kind, target),
+                ));
+            }
+        }
+    }
+}
+
+// run every check over the ADRs in the directory
+pub(crate) fn diagnose(adr_dir: &Path, rules: &[&str], mode: Mode) -> Result<Vec<Diagnostic>> {
+    let adrs = list_adrs(adr_dir)?;
+    let mut diagnostics = Vec::new();
+    check_numbering(&adrs, &mut diagnostics);
+    check_reservations(&adrs, &mut diagnostics);
+    check_connectivity(&adrs, rules, &mut diagnostics);
+    if rules.contains(&"adr-tools") {
+        check_adr_tools(&adrs, mode, &mut diagnostics);
+    }
+    for adr in &adrs {
+        check_adr(adr, rules, &mut diagnostics);
+    }
+    Ok(diagnostics)
+}
+
+// the line summarizing the diagnostics
+fn summary(errors: usize, warnings: usize, infos: usize) -> String {
+    if errors + warnings + infos == 0 {
+        "No problems found".to_string()
+    } else if infos > 0 {
+        format!(
+            "{} error(s), {} warning(s), {} info",
+            errors, warnings, infos
+        )
+    } else {
+        format!("{} error(s), {} warning(s)", errors, warnings)
+    }
+}
+
+// run doctor in the repository as a separate process, so that each one is read with its own
+// .adr-dir and configuration
+fn check_repo(repo: &ManifestRepo, adr_tools: bool, fix: bool) -> RepoReport {
+    let path = repo.path.clone();
+    let mut report = RepoReport {
+        name: repo.name.clone(),
+        path: path.clone(),
+        ..Default::default()
+    };
+    // doctor would otherwise create doc/adr in a repository without ADRs
+    if repo.adr_dir().is_none() {
+        report.failure = Some("no ADR directory found".to_string());
+        return report;
+    }
+
+    let mut command = std::process::Command::new(
+        std::env::current_exe().unwrap_or_else(|_| PathBuf::from("adrs")),
+    );
+    command
+        .args(["doctor", "--format", "json"])
+        .current_dir(&path);
+    if adr_tools {
+        command.arg("--adr-tools");
+    }
+    if fix {
+        command.arg("--fix");
+    }
+    let output = match command.output() {
+        Ok(output) => output,
+        Err(e) => {
+            report.failure = Some(format!("unable to run doctor: {}", e));
+            return report;
+        }
+    };
+
+    #[derive(Deserialize)]
+    struct Checked {
+        errors: usize,
+        warnings: usize,
+        diagnostics: Vec<RepoDiagnostic>,
+    }
+    match serde_json::from_slice::<Checked>(&output.stdout) {
+        Ok(checked) => {
+            report.errors = checked.errors;
+            report.warnings = checked.warnings;
+            report.diagnostics = checked.diagnostics;
+        }
+        Err(_) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            report.failure = Some(stderr.trim().trim_start_matches("Error: ").to_string());
+        }
+    }
+    report
+}
+
+// check every repository in the manifest at once and report on them together
+fn run_manifest(manifest_path: &Path, args: &DoctorArgs) -> Result<()> {
+    let manifest = read_manifest(manifest_path)?;
+    let repos = std::thread::scope(|scope| {
+        let handles = manifest
+            .iter()
+            .map(|repo| scope.spawn(|| check_repo(repo, args.adr_tools, args.fix)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("doctor thread panicked"))
+            .collect::<Vec<_>>()
+    });
+    let report = ManifestReport {
+        errors: repos.iter().map(|repo| repo.errors).sum(),
+        warnings: repos.iter().map(|repo| repo.warnings).sum(),
+        failures: repos.iter().filter(|repo| repo.failure.is_some()).count(),
+        repos,
+    };
+
+    match args.format {
+        ReportFormat::Text => {
+            for repo in &report.repos {
+                println!("== {} ({}) ==", repo.name, repo.path.display());
+                for d in &repo.diagnostics {
+                    match &d.path {
+                        Some(path) => println!(
+                            "{}[{}]: {}: {}",
+                            d.severity,
+                            d.rule,
+                            path.display(),
+                            d.message
+                        ),
+                        None => println!("{}[{}]: {}", d.severity, d.rule, d.message),
+                    }
+                }
+                match &repo.failure {
+                    Some(failure) => println!("Unable to check: {}", failure),
+                    None => {
+                        let infos = repo.diagnostics.len() - repo.errors - repo.warnings;
+                        println!("{}", summary(repo.errors, repo.warnings, infos));
+                    }
+                }
+                println!();
+            }
+            println!(
+                "{} repositories: {} error(s), {} warning(s), {} unable to check",
+                report.repos.len(),
+                report.errors,
+                report.warnings,
+                report.failures
+            );
+        }
+        ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        ReportFormat::Sarif => {
+            // the paths are reported from where doctor was run, through each repository
+            let paths = report
+                .repos
+                .iter()
+                .flat_map(|repo| {
+                    repo.diagnostics
+                        .iter()
+                        .map(|d| d.path.as_ref().map(|path| repo.path.join(path)))
+                })
+                .collect::<Vec<_>>();
+            let findings = report
+                .repos
+                .iter()
+                .flat_map(|repo| &repo.diagnostics)
+                .zip(&paths)
+                .map(|(d, path)| {
+                    (
+                        d.severity,
+                        d.rule.as_str(),
+                        path.as_deref(),
+                        d.message.as_str(),
+                    )
+                });
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&SarifLog::new(findings))?
+            );
+        }
+    }
+    if report.errors > 0 || report.failures > 0 {
+        anyhow::bail!(
+            "Found {} error(s) and {} repositories that couldn't be checked",
+            report.errors,
+            report.failures
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn run(args: &DoctorArgs) -> Result<()> {
+    if let Some(manifest) = &args.manifest {
+        return run_manifest(manifest, args);
+    }
+    let config = load_config().context("Unable to load configuration")?;
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let mut rules = lint_rules(&config);
+    if args.adr_tools && !rules.contains(&"adr-tools") {
+        rules.push("adr-tools");
+    }
+    if args.fix {
+        for fix in fix_status_conflicts(&list_adrs(&adr_dir)?, config.lint.reconcile)? {
+            match args.format {
+                ReportFormat::Text => println!("{}", fix),
+                // keep the report on stdout parseable
+                _ => eprintln!("{}", fix),
+            }
+        }
+    }
+    let mut diagnostics = diagnose(&adr_dir, &rules, config.mode)?;
+    apply_severities(&config, &mut diagnostics);
+
+    let (errors, warnings) = count(&diagnostics);
+    match args.format {
+        ReportFormat::Text => {
+            for diagnostic in &diagnostics {
+                println!("{}", diagnostic);
+            }
+            let infos = diagnostics.len() - errors - warnings;
+            println!("{}", summary(errors, warnings, infos));
+        }
+        ReportFormat::Json => {
+            let report = DoctorReport {
+                errors,
+                warnings,
+                diagnostics: &diagnostics,
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        ReportFormat::Sarif => {
+            let log = SarifLog::new(diagnostics.iter().map(Diagnostic::finding));
+            println!("{}", serde_json::to_string_pretty(&log)?);
+        }
+    }
+    if errors > 0 {
+        anyhow::bail!("Found {} error(s)", errors);
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+
+use crate::adr::{find_adr, find_adr_dir, set_section_markdown};
+use crate::model::{bullet_list, Adr, DECISION_DRIVERS};
+use crate::transaction::Transaction;
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum DriversCommands {
+    /// Add a decision driver to an Architectural Decision Record
+    Add(DriverArgs),
+    /// Remove a decision driver from an Architectural Decision Record
+    Remove(DriverArgs),
+    /// List the decision drivers of an Architectural Decision Record
+    List(ListDriversArgs),
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct DriverArgs {
+    /// The Architectural Decision Record number or file name match
+    adr: String,
+    /// The decision driver, e.g. "Team expertise"
+    driver: String,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct ListDriversArgs {
+    /// The Architectural Decision Record number or file name match
+    adr: String,
+}
+
+// write the drivers as the bullets of the Decision Drivers section, which MADR puts after the
+// context
+fn update_drivers(adr: &str, change: impl Fn(&mut Vec<String>) -> Result<()>) -> Result<PathBuf> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let path = find_adr(&adr_dir, adr)?;
+    let mut drivers = Adr::from_path(&adr_dir, &path)?.decision_drivers();
+    change(&mut drivers)?;
+
+    let mut tx = Transaction::new();
+    tx.update(&path, |markdown| {
+        Ok(set_section_markdown(
+            markdown,
+            DECISION_DRIVERS,
+            &bullet_list(&drivers),
+            "Context",
+        ))
+    })?;
+    tx.commit()?;
+    Ok(path)
+}
+
+pub(crate) fn run(args: &DriversCommands) -> Result<()> {
+    match args {
+        DriversCommands::Add(args) => {
+            let driver = args.driver.trim();
+            if driver.is_empty() {
+                anyhow::bail!("The decision driver can't be empty");
+            }
+            let path = update_drivers(&args.adr, |drivers| {
+                if drivers.iter().any(|d| d.eq_ignore_ascii_case(driver)) {
+                    anyhow::bail!(
+                        "ADR {} already has the decision driver {}",
+                        args.adr,
+                        driver
+                    );
+                }
+                drivers.push(driver.to_string());
+                Ok(())
+            })?;
+            println!("{}: added decision driver {}", path.display(), driver);
+        }
+        DriversCommands::Remove(args) => {
+            let path = update_drivers(&args.adr, |drivers| {
+                let Some(i) = drivers
+                    .iter()
+                    .position(|d| d.eq_ignore_ascii_case(args.driver.trim()))
+                else {
+                    anyhow::bail!(
+                        "ADR {} has no decision driver {}",
+                        args.adr,
+                        args.driver.trim()
+                    );
+                };
+                drivers.remove(i);
+                Ok(())
+            })?;
+            println!(
+                "{}: removed decision driver {}",
+                path.display(),
+                args.driver.trim()
+            );
+        }
+        DriversCommands::List(args) => {
+            let adr_dir = find_adr_dir().context("No ADR directory found")?;
+            let path = find_adr(&adr_dir, &args.adr)?;
+            for driver in Adr::from_path(&adr_dir, &path)?.decision_drivers() {
+                println!("{}", driver);
+            }
+        }
+    }
+    Ok(())
+}
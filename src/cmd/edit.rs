@@ -2,14 +2,31 @@ use std::{fs::read_to_string, path::Path};
 
 use anyhow::{Context, Result};
 use clap::Args;
-use edit::edit;
 
-use crate::adr::{find_adr, find_adr_dir};
+use crate::adr::{find_adr, find_adr_dir, set_section_markdown, split_frontmatter};
+use crate::editor::edit_text;
+use crate::model::{section_matches, split_sections};
 
 #[derive(Debug, Args)]
 pub(crate) struct EditArgs {
     /// The number of the ADR to edit
     name: String,
+    /// Only edit the body of this section, e.g. decision, which also finds MADR's "Decision
+    /// Outcome"
+    #[arg(long)]
+    section: Option<String>,
+}
+
+// edit just the body of the section, whatever the ADR's format calls it
+fn edit_section(content: &str, name: &str) -> Result<String> {
+    let (_, markdown) = split_frontmatter(content);
+    let (_, sections) = split_sections(markdown);
+    let (heading, body) = sections
+        .into_iter()
+        .find(|(heading, _)| section_matches(heading, name))
+        .with_context(|| format!("No {} section found", name))?;
+    let edited = edit_text(body.trim())?;
+    Ok(set_section_markdown(content, &heading, edited.trim(), ""))
 }
 
 pub(crate) fn run(args: &EditArgs) -> Result<()> {
@@ -17,7 +34,10 @@ pub(crate) fn run(args: &EditArgs) -> Result<()> {
 
     let adr = find_adr(Path::new(&adr_dir), &args.name)?;
     let content = read_to_string(adr.clone())?;
-    let edited = edit(content)?;
+    let edited = match &args.section {
+        Some(section) => edit_section(&content, section)?,
+        None => edit_text(&content)?,
+    };
 
     std::fs::write(adr.as_path(), edited)?;
 
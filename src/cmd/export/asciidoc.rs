@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
+
+use crate::adr::{find_adr_dir, list_adrs, list_archived_adrs, split_frontmatter};
+use crate::cmd::export::{page_link, page_names};
+use crate::model::Adr;
+use crate::shortcodes::ShortcodeIndex;
+
+#[derive(Debug, Args)]
+pub(crate) struct AsciidocArgs {
+    /// The directory to write the .adoc files to
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Overwrite an existing directory
+    #[arg(long, default_value_t = false)]
+    overwrite: bool,
+    /// The title of the index
+    #[arg(long, default_value = "Architecture Decision Records")]
+    title: String,
+}
+
+// convert the ADR's markdown to AsciiDoc, pointing links to other ADRs at their pages with xref
+fn to_asciidoc(markdown: &str, from: &Path, pages: &BTreeMap<PathBuf, String>) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut out = String::new();
+    // whether each list being written is ordered, innermost last
+    let mut lists: Vec<bool> = Vec::new();
+    // where the text of each open link or image starts, and where it goes
+    let mut links: Vec<(usize, String)> = Vec::new();
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::Heading(level, _, _)) => {
+                out.push_str(&"=".repeat(level as usize));
+                out.push(' ');
+            }
+            Event::End(Tag::Heading(..)) => out.push_str("\n\n"),
+            Event::End(Tag::Paragraph) if lists.is_empty() => out.push_str("\n\n"),
+            Event::End(Tag::Paragraph) => out.push('\n'),
+            Event::Start(Tag::BlockQuote) => out.push_str("____\n"),
+            Event::End(Tag::BlockQuote) => {
+                out.truncate(out.trim_end().len());
+                out.push_str("\n____\n\n");
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                if let CodeBlockKind::Fenced(lang) = kind {
+                    if !lang.is_empty() {
+                        let _ = writeln!(out, "[source,{}]", lang);
+                    }
+                }
+                out.push_str("----\n");
+            }
+            Event::End(Tag::CodeBlock(_)) => out.push_str("----\n\n"),
+            Event::Start(Tag::List(start)) => {
+                if !lists.is_empty() && !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                lists.push(start.is_some());
+            }
+            Event::End(Tag::List(_)) => {
+                lists.pop();
+                if lists.is_empty() {
+                    out.push('\n');
+                }
+            }
+            Event::Start(Tag::Item) => {
+                let marker = if lists.last() == Some(&true) {
+                    "."
+                } else {
+                    "*"
+                };
+                out.push_str(&marker.repeat(lists.len()));
+                out.push(' ');
+            }
+            Event::End(Tag::Item) if !out.ends_with('\n') => out.push('\n'),
+            Event::Start(Tag::Emphasis) | Event::End(Tag::Emphasis) => out.push('_'),
+            Event::Start(Tag::Strong) | Event::End(Tag::Strong) => out.push('*'),
+            Event::Start(Tag::Strikethrough) => out.push_str("[line-through]#"),
+            Event::End(Tag::Strikethrough) => out.push('#'),
+            Event::Start(Tag::Link(_, dest, _)) => {
+                let target = match page_link(&dest, from, pages) {
+                    Some(page) => format!("xref:{}", page),
+                    None => format!("link:{}", dest),
+                };
+                links.push((out.len(), target));
+            }
+            Event::Start(Tag::Image(_, dest, _)) => {
+                links.push((out.len(), format!("image:{}", dest)));
+            }
+            Event::End(Tag::Link(..)) | Event::End(Tag::Image(..)) => {
+                if let Some((start, target)) = links.pop() {
+                    let text = out.split_off(start);
+                    let _ = write!(out, "{}[{}]", target, text.replace(']', "\\]"));
+                }
+            }
+            Event::Start(Tag::Table(_)) => out.push_str("|===\n"),
+            Event::End(Tag::Table(_)) => out.push_str("|===\n\n"),
+            Event::End(Tag::TableHead) => out.push_str("\n\n"),
+            Event::End(Tag::TableRow) => out.push('\n'),
+            Event::Start(Tag::TableCell) => out.push('|'),
+            Event::End(Tag::TableCell) => out.push(' '),
+            Event::Text(text) | Event::Html(text) => out.push_str(&text),
+            Event::Code(code) => {
+                let _ = write!(out, "`+{}+`", code);
+            }
+            Event::SoftBreak => out.push('\n'),
+            Event::HardBreak => out.push_str(" +\n"),
+            Event::Rule => out.push_str("'''\n\n"),
+            Event::TaskListMarker(done) => out.push_str(if done { "[x] " } else { "[ ] " }),
+            _ => {}
+        }
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+// the document attributes that describe the ADR, for Antora and Asciidoctor to use
+fn document_attributes(adr: &Adr) -> String {
+    let mut attributes = format!(":adr-number: {}\n", adr.number);
+    if let Some(status) = adr.current_status() {
+        let _ = writeln!(attributes, ":adr-status: {}", status);
+    }
+    if let Some(date) = &adr.date {
+        let _ = writeln!(attributes, ":revdate: {}", date);
+    }
+    if !adr.tags.is_empty() {
+        let _ = writeln!(attributes, ":keywords: {}", adr.tags.join(", "));
+    }
+    attributes
+}
+
+// the ADR as an AsciiDoc document, with its attributes in the header under the title
+fn adr_document(adr: &Adr, body: String) -> String {
+    let attributes = document_attributes(adr);
+    match body.split_once('\n') {
+        Some((title, rest)) if title.starts_with("= ") => {
+            format!("{}\n{}{}", title, attributes, rest)
+        }
+        _ => format!("{}\n{}", attributes, body),
+    }
+}
+
+pub(crate) fn run_asciidoc(args: &AsciidocArgs) -> Result<()> {
+    if args.output.exists() && !args.overwrite {
+        anyhow::bail!(
+            "Directory already exists: {}. Use the --overwrite flag to overwrite it.",
+            args.output.display()
+        );
+    }
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let mut paths = list_adrs(&adr_dir)?;
+    paths.extend(list_archived_adrs(&adr_dir)?);
+    let index = ShortcodeIndex::load(&adr_dir)?;
+    let pages = page_names(&adr_dir, &paths, "adoc");
+
+    create_dir_all(&args.output)?;
+    let mut contents = format!("= {}\n\n", args.title);
+    for path in &paths {
+        let markdown = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        let adr = Adr::from_path(&adr_dir, path)?;
+        let relative = adr.relative_path();
+        let (_, body) = split_frontmatter(&markdown);
+        let body = to_asciidoc(&index.expand(body, &relative), &relative, &pages);
+        let page = &pages[&relative];
+        std::fs::write(args.output.join(page), adr_document(&adr, body))?;
+
+        let _ = write!(contents, "* xref:{}[{}. {}]", page, adr.number, adr.title);
+        match adr.current_status() {
+            Some(status) => {
+                let _ = writeln!(contents, " ({})", status);
+            }
+            None => contents.push('\n'),
+        }
+    }
+    std::fs::write(args.output.join("index.adoc"), contents)?;
+    println!("Wrote {} ADR(s) to {}", paths.len(), args.output.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_asciidoc() {
+        let pages = BTreeMap::from([(
+            PathBuf::from("0001-use-mysql.md"),
+            "0001-use-mysql.adoc".to_string(),
+        )]);
+        let markdown = "# 2. Tune MySQL\n\n## Status\n\nAmends [1. Use MySQL](0001-use-mysql.md)\n\n## Decision\n\nWe *will* use `innodb` and **[the docs](https://dev.mysql.com)**:\n\n* one\n  1. nested\n* two\n\n```sql\nSET x = 1;\n```\n";
+        assert_eq!(
+            to_asciidoc(markdown, Path::new("0002-tune-mysql.md"), &pages),
+            "= 2. Tune MySQL\n\n== Status\n\nAmends xref:0001-use-mysql.adoc[1. Use MySQL]\n\n== Decision\n\nWe _will_ use `+innodb+` and *link:https://dev.mysql.com[the docs]*:\n\n* one\n.. nested\n* two\n\n[source,sql]\n----\nSET x = 1;\n----\n"
+        );
+    }
+
+    #[test]
+    fn test_adr_document() {
+        let adr = Adr::parse(
+            Path::new("0002-tune-mysql.md"),
+            "---\ntags: [database]\n---\n\n# 2. Tune MySQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n",
+        )
+        .unwrap();
+        assert_eq!(
+            adr_document(&adr, "= 2. Tune MySQL\n\nDate: 2024-01-01\n".to_string()),
+            "= 2. Tune MySQL\n:adr-number: 2\n:adr-status: Accepted\n:revdate: 2024-01-01\n:keywords: database\n\nDate: 2024-01-01\n"
+        );
+    }
+}
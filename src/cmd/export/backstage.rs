@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::adr::{find_adr_dir, list_adrs, list_archived_adrs, parse_date, split_frontmatter};
+use crate::cmd::export::{page_link, page_names};
+use crate::model::Adr;
+
+// the annotation the Backstage ADR plugin reads the location of an entity's ADRs from
+static ADR_LOCATION_ANNOTATION: &str = "backstage.io/adr-location";
+// the directory the ADRs are written to, next to catalog-info.yaml
+static ADR_LOCATION: &str = "docs/adrs";
+
+#[derive(Debug, Args)]
+pub(crate) struct BackstageArgs {
+    /// The directory to write catalog-info.yaml and the ADRs to
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Overwrite an existing directory
+    #[arg(long, default_value_t = false)]
+    overwrite: bool,
+    /// The name of the catalog entity, which defaults to the name of the current directory
+    #[arg(long)]
+    name: Option<String>,
+    /// The owner of the catalog entity
+    #[arg(long, default_value = "unknown")]
+    owner: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CatalogInfo {
+    api_version: &'static str,
+    kind: &'static str,
+    metadata: CatalogMetadata,
+    spec: CatalogSpec,
+}
+
+#[derive(Debug, Serialize)]
+struct CatalogMetadata {
+    name: String,
+    annotations: BTreeMap<&'static str, &'static str>,
+}
+
+#[derive(Debug, Serialize)]
+struct CatalogSpec {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    lifecycle: &'static str,
+    owner: String,
+}
+
+// the front matter the plugin reads the status and date from, as in MADR
+#[derive(Debug, Serialize)]
+struct BackstageFrontMatter {
+    status: String,
+    date: String,
+}
+
+// the reasons the plugin wouldn't list the ADR with its title, status and date. The plugin only
+// picks up files named NNNN-title.md, takes the title from the first heading and parses the date
+// as yyyy-MM-dd
+fn check(adr: &Adr, page: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    if !Regex::new(r"^\d{4}-[^/]+\.md$").unwrap().is_match(page) {
+        problems.push("the file name doesn't start with a four digit number".to_string());
+    }
+    if adr.title.trim().is_empty() {
+        problems.push("missing title".to_string());
+    }
+    match adr.date.as_deref() {
+        None => problems.push("missing date".to_string()),
+        Some(date) if parse_date(date).is_err() => {
+            problems.push(format!("the date '{}' isn't in YYYY-MM-DD form", date))
+        }
+        Some(_) => {}
+    }
+    if adr.current_status().is_none() {
+        problems.push("missing status".to_string());
+    }
+    problems
+}
+
+// the ADR with the plugin's front matter in place of its own, and links to other ADRs pointing
+// at their files in the flat ADR location
+fn backstage_page(
+    adr: &Adr,
+    markdown: &str,
+    from: &Path,
+    pages: &BTreeMap<PathBuf, String>,
+) -> Result<String> {
+    let front_matter = BackstageFrontMatter {
+        status: adr.current_status().unwrap_or_default().to_lowercase(),
+        date: adr.date.clone().unwrap_or_default(),
+    };
+    let front_matter =
+        serde_yaml::to_string(&front_matter).context("Unable to serialize the front matter")?;
+    let (_, body) = split_frontmatter(markdown);
+    let link = Regex::new(r"\]\(([^)\s]+)\)").unwrap();
+    let body = link.replace_all(body, |captures: &regex::Captures| {
+        match page_link(&captures[1], from, pages) {
+            Some(page) => format!("]({})", page),
+            None => captures[0].to_string(),
+        }
+    });
+    Ok(format!("---\n{}---\n\n{}\n", front_matter, body.trim()))
+}
+
+pub(crate) fn run_backstage(args: &BackstageArgs) -> Result<()> {
+    if args.output.exists() && !args.overwrite {
+        anyhow::bail!(
+            "Directory already exists: {}. Use the --overwrite flag to overwrite it.",
+            args.output.display()
+        );
+    }
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let mut paths = list_adrs(&adr_dir)?;
+    paths.extend(list_archived_adrs(&adr_dir)?);
+    // the plugin reads a single directory, so archived ADRs are written alongside the others
+    let pages = page_names(&adr_dir, &paths, "md");
+
+    let mut adrs = Vec::new();
+    let mut problems = 0;
+    for path in &paths {
+        let markdown = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        let adr = Adr::from_path(&adr_dir, path)?;
+        let relative = adr.relative_path();
+        for problem in check(&adr, &pages[&relative]) {
+            eprintln!("{}: {}", path.display(), problem);
+            problems += 1;
+        }
+        adrs.push((adr, markdown, relative));
+    }
+    if problems > 0 {
+        anyhow::bail!(
+            "Found {} problem(s) that would stop the Backstage ADR plugin from reading the ADRs",
+            problems
+        );
+    }
+
+    let name = match &args.name {
+        Some(name) => name.clone(),
+        None => std::env::current_dir()?
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "adrs".to_string()),
+    };
+    let catalog_info = CatalogInfo {
+        api_version: "backstage.io/v1alpha1",
+        kind: "Component",
+        metadata: CatalogMetadata {
+            name,
+            annotations: BTreeMap::from([(ADR_LOCATION_ANNOTATION, ADR_LOCATION)]),
+        },
+        spec: CatalogSpec {
+            kind: "service",
+            lifecycle: "production",
+            owner: args.owner.clone(),
+        },
+    };
+    let location = args.output.join(ADR_LOCATION);
+    create_dir_all(&location)?;
+    std::fs::write(
+        args.output.join("catalog-info.yaml"),
+        serde_yaml::to_string(&catalog_info).context("Unable to serialize catalog-info.yaml")?,
+    )?;
+    for (adr, markdown, relative) in &adrs {
+        std::fs::write(
+            location.join(&pages[relative]),
+            backstage_page(adr, markdown, relative, &pages)?,
+        )?;
+    }
+    println!("Wrote {} ADR(s) to {}", adrs.len(), location.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let adr = Adr::parse(
+            Path::new("0002-use-mysql.md"),
+            "# 2. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n",
+        )
+        .unwrap();
+        assert!(check(&adr, "0002-use-mysql.md").is_empty());
+        let adr = Adr::parse(
+            Path::new("use-mysql.md"),
+            "# 2. Use MySQL\n\nDate: 1 Jan 2024\n\n## Status\n\n",
+        )
+        .unwrap();
+        assert_eq!(
+            check(&adr, "use-mysql.md"),
+            [
+                "the file name doesn't start with a four digit number",
+                "the date '1 Jan 2024' isn't in YYYY-MM-DD form",
+                "missing status"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backstage_page() {
+        let markdown = "# 2. Tune MySQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nAmends [1. Use MySQL](archive/0001-use-mysql.md)\n";
+        let adr = Adr::parse(Path::new("0002-tune-mysql.md"), markdown).unwrap();
+        let pages = BTreeMap::from([(
+            PathBuf::from("archive/0001-use-mysql.md"),
+            "0001-use-mysql.md".to_string(),
+        )]);
+        assert_eq!(
+            backstage_page(&adr, markdown, Path::new("0002-tune-mysql.md"), &pages).unwrap(),
+            "---\nstatus: accepted\ndate: 2024-01-01\n---\n\n# 2. Tune MySQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nAmends [1. Use MySQL](0001-use-mysql.md)\n"
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use pulldown_cmark::escape::escape_html;
+use pulldown_cmark::{html, Event, Options, Parser, Tag};
+use serde::Serialize;
+
+use crate::adr::{find_adr_dir, list_adrs, list_archived_adrs, slugify, split_frontmatter};
+use crate::cmd::export::{page_link, page_names};
+use crate::model::{section_matches, split_sections, Adr};
+use crate::shortcodes::ShortcodeIndex;
+
+// the colour of the status macro for each status. Confluence has Grey, Red, Yellow, Green, Blue
+// and Purple
+static STATUS_COLOURS: &[(&str, &str)] = &[
+    ("Proposed", "Yellow"),
+    ("Accepted", "Green"),
+    ("Implemented", "Blue"),
+    ("Rejected", "Red"),
+    ("Withdrawn", "Grey"),
+    ("Deprecated", "Grey"),
+    ("Superseded", "Purple"),
+    ("Retired", "Grey"),
+];
+
+// the macro each section's body is wrapped in, so that the decision stands out and the long
+// comparison of options is folded away
+static SECTION_MACROS: &[(&str, &str)] = &[
+    ("Decision", "info"),
+    ("Pros and Cons of the Options", "expand"),
+];
+
+#[derive(Debug, Args)]
+pub(crate) struct ConfluenceArgs {
+    /// The directory to write the pages and manifest to
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Overwrite an existing directory
+    #[arg(long, default_value_t = false)]
+    overwrite: bool,
+}
+
+// a page for an upload script, in manifest.json
+#[derive(Debug, Serialize)]
+struct ManifestPage {
+    title: String,
+    file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    labels: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ConfluenceManifest {
+    pages: Vec<ManifestPage>,
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::new();
+    let _ = escape_html(&mut out, text);
+    out
+}
+
+// a link to the Confluence page with the title, at the anchor if there is one
+fn page_link_markup(title: &str, anchor: Option<&str>, text: &str) -> String {
+    let anchor = anchor
+        .map(|anchor| format!(" ac:anchor=\"{}\"", escape(anchor)))
+        .unwrap_or_default();
+    format!(
+        "<ac:link{}><ri:page ri:content-title=\"{}\" /><ac:plain-text-link-body><![CDATA[{}]]></ac:plain-text-link-body></ac:link>",
+        anchor,
+        escape(title),
+        text.replace("]]>", "]]]]><![CDATA[>")
+    )
+}
+
+// the status macro, a lozenge coloured by the status
+fn status_macro(status: &str) -> String {
+    let colour = STATUS_COLOURS
+        .iter()
+        .find(|(s, _)| s.eq_ignore_ascii_case(status))
+        .map_or("Grey", |(_, colour)| colour);
+    format!(
+        "<ac:structured-macro ac:name=\"status\"><ac:parameter ac:name=\"colour\">{}</ac:parameter><ac:parameter ac:name=\"title\">{}</ac:parameter></ac:structured-macro>",
+        colour,
+        escape(status)
+    )
+}
+
+// render markdown to storage format XHTML, with links to other ADRs as links to their pages
+fn render_storage(markdown: &str, from: &Path, titles: &BTreeMap<PathBuf, String>) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut events = Vec::new();
+    // the page of an open link to another ADR, and its text so far
+    let mut open_link: Option<(String, String)> = None;
+    for event in Parser::new_ext(markdown, options) {
+        match (&mut open_link, event) {
+            (None, Event::Start(Tag::Link(kind, dest, title))) => {
+                match page_link(&dest, from, titles) {
+                    Some(page) => open_link = Some((page, String::new())),
+                    None => events.push(Event::Start(Tag::Link(kind, dest, title))),
+                }
+            }
+            (Some((_, text)), Event::Text(t) | Event::Code(t)) => text.push_str(&t),
+            (Some(_), Event::End(Tag::Link(..))) => {
+                let (page, text) = open_link.take().unwrap();
+                let (title, anchor) = match page.split_once('#') {
+                    Some((title, anchor)) => (title, Some(anchor)),
+                    None => (page.as_str(), None),
+                };
+                events.push(Event::Html(page_link_markup(title, anchor, &text).into()));
+            }
+            (Some(_), _) => {}
+            (None, event) => events.push(event),
+        }
+    }
+    let mut out = String::new();
+    html::push_html(&mut out, events.into_iter());
+    out
+}
+
+// the page properties at the top of the page: the status, date, deciders and links
+fn page_properties(adr: &Adr, titles: &BTreeMap<i32, String>) -> String {
+    let mut rows = Vec::new();
+    if let Some(status) = adr.current_status() {
+        rows.push(("Status", status_macro(&status)));
+    }
+    if let Some(date) = &adr.date {
+        rows.push(("Date", escape(date)));
+    }
+    if !adr.deciders.is_empty() {
+        rows.push(("Deciders", escape(&adr.deciders.join(", "))));
+    }
+    let links = adr
+        .links
+        .iter()
+        .map(|link| match titles.get(&link.target) {
+            Some(title) => format!(
+                "{} {}",
+                escape(&link.kind),
+                page_link_markup(title, None, title)
+            ),
+            None => format!("{} ADR {}", escape(&link.kind), link.target),
+        })
+        .collect::<Vec<_>>();
+    if !links.is_empty() {
+        rows.push(("Links", links.join("<br />")));
+    }
+
+    let mut out = String::from(
+        "<ac:structured-macro ac:name=\"details\"><ac:rich-text-body><table><tbody>\n",
+    );
+    for (name, value) in rows {
+        let _ = writeln!(out, "<tr><th>{}</th><td>{}</td></tr>", name, value);
+    }
+    out.push_str("</tbody></table></ac:rich-text-body></ac:structured-macro>\n");
+    out
+}
+
+// the ADR as a Confluence page body. The title is the page's own title and the status is in the
+// page properties, so the body starts with the first section after the status
+fn storage_page(
+    adr: &Adr,
+    markdown: &str,
+    titles_by_path: &BTreeMap<PathBuf, String>,
+    titles_by_number: &BTreeMap<i32, String>,
+) -> String {
+    let from = adr.relative_path();
+    let mut out = page_properties(adr, titles_by_number);
+    let (_, sections) = split_sections(markdown);
+    for (heading, body) in sections {
+        if section_matches(&heading, "Status") {
+            continue;
+        }
+        let _ = writeln!(out, "<h2>{}</h2>", escape(&heading));
+        let body = render_storage(body, &from, titles_by_path);
+        match SECTION_MACROS
+            .iter()
+            .find(|(name, _)| section_matches(&heading, name))
+        {
+            Some((_, "expand")) => {
+                let _ = write!(
+                    out,
+                    "<ac:structured-macro ac:name=\"expand\"><ac:parameter ac:name=\"title\">{}</ac:parameter><ac:rich-text-body>\n{}</ac:rich-text-body></ac:structured-macro>\n",
+                    escape(&heading),
+                    body
+                );
+            }
+            Some((_, name)) => {
+                let _ = write!(
+                    out,
+                    "<ac:structured-macro ac:name=\"{}\"><ac:rich-text-body>\n{}</ac:rich-text-body></ac:structured-macro>\n",
+                    name, body
+                );
+            }
+            None => out.push_str(&body),
+        }
+    }
+    out
+}
+
+pub(crate) fn run_confluence(args: &ConfluenceArgs) -> Result<()> {
+    if args.output.exists() && !args.overwrite {
+        anyhow::bail!(
+            "Directory already exists: {}. Use the --overwrite flag to overwrite it.",
+            args.output.display()
+        );
+    }
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let mut paths = list_adrs(&adr_dir)?;
+    paths.extend(list_archived_adrs(&adr_dir)?);
+    let index = ShortcodeIndex::load(&adr_dir)?;
+    let files = page_names(&adr_dir, &paths, "xhtml");
+
+    let adrs = paths
+        .iter()
+        .map(|path| Adr::from_path(&adr_dir, path))
+        .collect::<Result<Vec<_>>>()?;
+    // pages are linked to by title, which is unique because it starts with the number
+    let title = |adr: &Adr| format!("{}. {}", adr.number, adr.title);
+    let titles_by_path = adrs
+        .iter()
+        .map(|adr| (adr.relative_path(), title(adr)))
+        .collect::<BTreeMap<_, _>>();
+    let titles_by_number = adrs
+        .iter()
+        .map(|adr| (adr.number, title(adr)))
+        .collect::<BTreeMap<_, _>>();
+
+    create_dir_all(&args.output)?;
+    let mut manifest = ConfluenceManifest { pages: Vec::new() };
+    for (path, adr) in paths.iter().zip(&adrs) {
+        let markdown = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        let relative = adr.relative_path();
+        let (_, body) = split_frontmatter(&markdown);
+        let body = index.expand(body, &relative);
+        let file = &files[&relative];
+        std::fs::write(
+            args.output.join(file),
+            storage_page(adr, &body, &titles_by_path, &titles_by_number),
+        )?;
+        manifest.pages.push(ManifestPage {
+            title: title(adr),
+            file: file.clone(),
+            status: adr.current_status(),
+            // Confluence labels can't contain spaces
+            labels: adr.tags.iter().map(|tag| slugify(tag)).collect(),
+        });
+    }
+    std::fs::write(
+        args.output.join("manifest.json"),
+        serde_json::to_string_pretty(&manifest)? + "\n",
+    )?;
+    println!(
+        "Wrote {} page(s) to {}",
+        manifest.pages.len(),
+        args.output.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_page() {
+        let adr = Adr::parse(
+            Path::new("0002-use-postgres.md"),
+            "# 2. Use Postgres\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nAmends [1. Use a database](0001-use-a-database.md)\n\n## Context and Problem Statement\n\nSee [1. Use a database](0001-use-a-database.md#context) & more.\n\n## Decision Outcome\n\nPostgres.\n",
+        )
+        .unwrap();
+        let titles_by_path = BTreeMap::from([(
+            PathBuf::from("0001-use-a-database.md"),
+            "1. Use a database".to_string(),
+        )]);
+        let titles_by_number = BTreeMap::from([(1, "1. Use a database".to_string())]);
+        let markdown = "# 2. Use Postgres\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nAmends [1. Use a database](0001-use-a-database.md)\n\n## Context and Problem Statement\n\nSee [1. Use a database](0001-use-a-database.md#context) & more.\n\n## Decision Outcome\n\nPostgres.\n";
+        assert_eq!(
+            storage_page(&adr, markdown, &titles_by_path, &titles_by_number),
+            "<ac:structured-macro ac:name=\"details\"><ac:rich-text-body><table><tbody>\n\
+             <tr><th>Status</th><td><ac:structured-macro ac:name=\"status\"><ac:parameter ac:name=\"colour\">Green</ac:parameter><ac:parameter ac:name=\"title\">Accepted</ac:parameter></ac:structured-macro></td></tr>\n\
+             <tr><th>Date</th><td>2024-01-01</td></tr>\n\
+             <tr><th>Links</th><td>Amends <ac:link><ri:page ri:content-title=\"1. Use a database\" /><ac:plain-text-link-body><![CDATA[1. Use a database]]></ac:plain-text-link-body></ac:link></td></tr>\n\
+             </tbody></table></ac:rich-text-body></ac:structured-macro>\n\
+             <h2>Context and Problem Statement</h2>\n\
+             <p>See <ac:link ac:anchor=\"context\"><ri:page ri:content-title=\"1. Use a database\" /><ac:plain-text-link-body><![CDATA[1. Use a database]]></ac:plain-text-link-body></ac:link> &amp; more.</p>\n\
+             <h2>Decision Outcome</h2>\n\
+             <ac:structured-macro ac:name=\"info\"><ac:rich-text-body>\n<p>Postgres.</p>\n</ac:rich-text-body></ac:structured-macro>\n"
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{find_adr_dir, list_adrs, list_archived_adrs};
+use crate::json_adr::{export_adrs, JsonAdr};
+
+#[derive(Debug, Args)]
+pub(crate) struct CsvArgs {
+    /// Write the export to a file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+// the columns of the export, in order
+static CSV_HEADER: &[&str] = &[
+    "number", "title", "status", "date", "tags", "deciders", "links",
+];
+
+// one row per ADR. Lists are joined with "; " so that each stays in a single cell, and links are
+// summarized as the kind and number of each, e.g. "Amends 1; Supersedes 3"
+pub(crate) fn export_csv(adrs: &[JsonAdr]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(CSV_HEADER)?;
+    for adr in adrs {
+        let links = adr
+            .links
+            .iter()
+            .map(|link| format!("{} {}", link.kind, link.target))
+            .collect::<Vec<_>>();
+        writer.write_record([
+            adr.number.to_string(),
+            adr.title.clone(),
+            adr.status.clone().unwrap_or_default(),
+            adr.date.clone().unwrap_or_default(),
+            adr.tags.join("; "),
+            adr.deciders.join("; "),
+            links.join("; "),
+        ])?;
+    }
+    let bytes = writer
+        .into_inner()
+        .context("Unable to write the CSV export")?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+pub(crate) fn run_csv(args: &CsvArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let mut adrs = list_adrs(&adr_dir)?;
+    adrs.extend(list_archived_adrs(&adr_dir)?);
+
+    let csv = export_csv(&export_adrs(&adr_dir, &adrs)?.adrs)?;
+    match &args.output {
+        Some(path) => std::fs::write(path, csv)
+            .with_context(|| format!("Unable to write {}", path.display()))?,
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::model::Adr;
+
+    #[test]
+    fn test_export_csv() {
+        let adr = Adr::parse(
+            Path::new("0002-use-mysql.md"),
+            "---\ntags: [database, ops]\ndeciders: [Ada, Grace]\n---\n\n# 2. Use MySQL, for now\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nAmends [1. Record decisions](0001-record-decisions.md)\n",
+        )
+        .unwrap();
+        assert_eq!(
+            export_csv(&[JsonAdr::from(&adr)]).unwrap(),
+            "number,title,status,date,tags,deciders,links\n2,\"Use MySQL, for now\",Accepted,2024-01-01,database; ops,Ada; Grace,Amends 1\n"
+        );
+    }
+}
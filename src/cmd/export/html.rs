@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use pulldown_cmark::{CowStr, Event, Parser, Tag};
+use serde::Serialize;
+use tinytemplate::TinyTemplate;
+
+use crate::adr::{find_adr_dir, list_adrs, list_archived_adrs, slugify, split_frontmatter};
+use crate::cmd::export::{page_link, page_names};
+use crate::config::load_config;
+use crate::model::Adr;
+use crate::render::{options, renderer, Renderer};
+use crate::shortcodes::ShortcodeIndex;
+
+static HTML_LAYOUT_TEMPLATE: &str = include_str!("../../../templates/html/layout.html");
+static HTML_LIST_TEMPLATE: &str = include_str!("../../../templates/html/list.html");
+static HTML_ADR_TEMPLATE: &str = include_str!("../../../templates/html/adr.html");
+static HTML_STYLE: &str = include_str!("../../../templates/html/style.css");
+
+#[derive(Debug, Args)]
+pub(crate) struct HtmlArgs {
+    /// The directory to write the site to
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Overwrite an existing directory
+    #[arg(long, default_value_t = false)]
+    overwrite: bool,
+    /// The title of the site
+    #[arg(long, default_value = "Architecture Decision Records")]
+    title: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TagContext {
+    name: String,
+    href: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AdrContext {
+    number: i32,
+    title: String,
+    href: String,
+    // empty when the ADR has no status
+    status: String,
+    status_class: String,
+    date: String,
+    tags: Vec<TagContext>,
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ListContext<'a> {
+    heading: String,
+    adrs: Vec<&'a AdrContext>,
+}
+
+#[derive(Debug, Serialize)]
+struct LayoutContext {
+    title: String,
+    site_title: String,
+    // the way back to the top of the site from the page
+    root: String,
+    body: String,
+}
+
+// render the ADR's markdown to HTML, pointing links to other ADRs at their pages
+fn render_markdown(
+    renderer: &dyn Renderer,
+    markdown: &str,
+    from: &Path,
+    pages: &BTreeMap<PathBuf, String>,
+) -> Result<String> {
+    let events = Parser::new_ext(markdown, options()).map(|event| match event {
+        Event::Start(Tag::Link(kind, dest, title)) => match page_link(&dest, from, pages) {
+            Some(page) => Event::Start(Tag::Link(kind, CowStr::from(page), title)),
+            None => Event::Start(Tag::Link(kind, dest, title)),
+        },
+        event => event,
+    });
+    renderer.html(events.collect())
+}
+
+// render a page of the site in the shared layout
+fn render_page(
+    tt: &TinyTemplate,
+    site_title: &str,
+    title: &str,
+    root: &str,
+    body: String,
+) -> Result<String> {
+    let context = LayoutContext {
+        title: title.to_string(),
+        site_title: site_title.to_string(),
+        root: root.to_string(),
+        body,
+    };
+    tt.render("layout", &context)
+        .context("Unable to render the HTML layout template")
+}
+
+// the tags of an ADR as links to their pages
+fn tag_links(tags: &[String]) -> Vec<TagContext> {
+    tags.iter()
+        .map(|tag| TagContext {
+            name: tag.clone(),
+            href: format!("tags/{}.html", slugify(tag)),
+        })
+        .collect()
+}
+
+pub(crate) fn run_html(args: &HtmlArgs) -> Result<()> {
+    if args.output.exists() && !args.overwrite {
+        anyhow::bail!(
+            "Directory already exists: {}. Use the --overwrite flag to overwrite it.",
+            args.output.display()
+        );
+    }
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let count = write_site(&adr_dir, &args.output, &args.title)?;
+    println!("Wrote {} ADR page(s) to {}", count, args.output.display());
+    Ok(())
+}
+
+// write the site for the ADRs, including the archived ones, to the directory, returning the
+// number of ADR pages
+pub(crate) fn write_site(adr_dir: &Path, output: &Path, site_title: &str) -> Result<usize> {
+    let mut paths = list_adrs(adr_dir)?;
+    paths.extend(list_archived_adrs(adr_dir)?);
+    let index = ShortcodeIndex::load(adr_dir)?;
+
+    let pages = page_names(adr_dir, &paths, "html");
+    let config = load_config().context("Unable to load configuration")?;
+    let renderer = renderer(&config.render);
+
+    let mut tt = TinyTemplate::new();
+    tt.add_template("layout", HTML_LAYOUT_TEMPLATE)?;
+    tt.add_template("list", HTML_LIST_TEMPLATE)?;
+    tt.add_template("adr", HTML_ADR_TEMPLATE)?;
+
+    let mut adrs = Vec::new();
+    for path in &paths {
+        let markdown = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        let adr = Adr::from_path(adr_dir, path)?;
+        let relative = adr.relative_path();
+        let (_, body) = split_frontmatter(&markdown);
+        let status = adr.current_status().unwrap_or_default();
+        adrs.push(AdrContext {
+            number: adr.number,
+            title: format!("{}. {}", adr.number, adr.title),
+            href: pages[&relative].clone(),
+            status_class: slugify(&status),
+            status,
+            date: adr.date.clone().unwrap_or_default(),
+            tags: tag_links(&adr.tags),
+            content: render_markdown(
+                renderer.as_ref(),
+                &index.expand(body, &relative),
+                &relative,
+                &pages,
+            )?,
+        });
+    }
+
+    create_dir_all(output.join("tags"))?;
+    std::fs::write(output.join("style.css"), HTML_STYLE)?;
+    for adr in &adrs {
+        let body = tt
+            .render("adr", adr)
+            .context("Unable to render the HTML ADR template")?;
+        let page = render_page(&tt, site_title, &adr.title, "", body)?;
+        std::fs::write(output.join(&adr.href), page)?;
+    }
+
+    let list = ListContext {
+        heading: site_title.to_string(),
+        adrs: adrs.iter().collect(),
+    };
+    let body = tt
+        .render("list", &list)
+        .context("Unable to render the HTML list template")?;
+    let page = render_page(&tt, site_title, site_title, "", body)?;
+    std::fs::write(output.join("index.html"), page)?;
+
+    // a page for each tag, listing the ADRs with it
+    let mut tags: BTreeMap<&str, Vec<&AdrContext>> = BTreeMap::new();
+    for adr in &adrs {
+        for tag in &adr.tags {
+            tags.entry(&tag.name).or_default().push(adr);
+        }
+    }
+    for (tag, tagged) in tags {
+        // the pages are a directory down, so links to the ADRs and other tags climb back up
+        let tagged = tagged
+            .into_iter()
+            .map(|adr| {
+                let mut adr = adr.clone();
+                adr.href = format!("../{}", adr.href);
+                for tag in &mut adr.tags {
+                    tag.href = format!("../{}", tag.href);
+                }
+                adr
+            })
+            .collect::<Vec<_>>();
+        let list = ListContext {
+            heading: format!("Tagged {}", tag),
+            adrs: tagged.iter().collect(),
+        };
+        let body = tt
+            .render("list", &list)
+            .context("Unable to render the HTML list template")?;
+        let page = render_page(&tt, site_title, &list.heading, "../", body)?;
+        std::fs::write(
+            output.join("tags").join(format!("{}.html", slugify(tag))),
+            page,
+        )?;
+    }
+    Ok(adrs.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::BuiltinRenderer;
+
+    #[test]
+    fn test_render_markdown_links() {
+        let pages = BTreeMap::from([
+            (
+                PathBuf::from("0001-use-mysql.md"),
+                "0001-use-mysql.html".to_string(),
+            ),
+            (
+                PathBuf::from("archive/0002-old.md"),
+                "0002-old.html".to_string(),
+            ),
+        ]);
+        assert_eq!(
+            render_markdown(
+                &BuiltinRenderer,
+                "Amends [1. Use MySQL](0001-use-mysql.md#decision), see [the docs](https://example.com/0001-use-mysql.md)",
+                Path::new("0003-tune.md"),
+                &pages
+            )
+            .unwrap(),
+            "<p>Amends <a href=\"0001-use-mysql.html#decision\">1. Use MySQL</a>, see <a href=\"https://example.com/0001-use-mysql.md\">the docs</a></p>\n"
+        );
+        assert_eq!(
+            render_markdown(
+                &BuiltinRenderer,
+                "[1. Use MySQL](../0001-use-mysql.md)",
+                Path::new("archive/0002-old.md"),
+                &pages
+            )
+            .unwrap(),
+            "<p><a href=\"0001-use-mysql.html\">1. Use MySQL</a></p>\n"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::adr::{find_adr_dir, list_adrs, list_archived_adrs, split_frontmatter};
+use crate::cmd::export::page_link;
+use crate::model::{split_sections, Adr};
+use crate::shortcodes::ShortcodeIndex;
+
+#[derive(Debug, Args)]
+pub(crate) struct HugoArgs {
+    /// The content section to write the page bundles to, e.g. content/decisions
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Overwrite an existing directory
+    #[arg(long, default_value_t = false)]
+    overwrite: bool,
+    /// The title of the section
+    #[arg(long, default_value = "Architecture Decision Records")]
+    title: String,
+}
+
+// the front matter of an ADR's page. The status is a list so that it can be used as a taxonomy,
+// once the site declares `status` in its taxonomies
+#[derive(Debug, Serialize)]
+struct HugoFrontMatter {
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    // ADRs are listed in order by number
+    weight: i32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    status: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    deciders: Vec<String>,
+    adr_number: i32,
+}
+
+#[derive(Debug, Serialize)]
+struct SectionFrontMatter {
+    title: String,
+}
+
+// the markdown as a Hugo page in YAML front matter
+fn hugo_page<T: Serialize>(front_matter: &T, body: &str) -> Result<String> {
+    let front_matter =
+        serde_yaml::to_string(front_matter).context("Unable to serialize the front matter")?;
+    Ok(format!("---\n{}---\n{}", front_matter, body))
+}
+
+// the ADR's sections, without the title and date that are in the front matter, with links to
+// other ADRs pointing at their bundles through relref so that Hugo checks them
+fn hugo_body(markdown: &str, from: &Path, bundles: &BTreeMap<PathBuf, String>) -> String {
+    let (preamble, _) = split_sections(markdown);
+    let body = &markdown[preamble.len()..];
+    let link = Regex::new(r"\]\(([^)\s]+)\)").unwrap();
+    let body = link.replace_all(body, |captures: &regex::Captures| {
+        match page_link(&captures[1], from, bundles) {
+            Some(bundle) => format!("]({{{{< relref \"{}\" >}}}})", bundle),
+            None => captures[0].to_string(),
+        }
+    });
+    format!("\n{}\n", body.trim())
+}
+
+pub(crate) fn run_hugo(args: &HugoArgs) -> Result<()> {
+    if args.output.exists() && !args.overwrite {
+        anyhow::bail!(
+            "Directory already exists: {}. Use the --overwrite flag to overwrite it.",
+            args.output.display()
+        );
+    }
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let mut paths = list_adrs(&adr_dir)?;
+    paths.extend(list_archived_adrs(&adr_dir)?);
+    let index = ShortcodeIndex::load(&adr_dir)?;
+
+    // each ADR is a leaf bundle named after its file, so that images can sit next to it
+    let bundles = paths
+        .iter()
+        .map(|path| {
+            let relative = path.strip_prefix(&adr_dir).unwrap_or(path).to_path_buf();
+            let bundle = relative.file_stem().unwrap_or_default().to_string_lossy();
+            (relative.clone(), bundle.into_owned())
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    create_dir_all(&args.output)?;
+    let section = SectionFrontMatter {
+        title: args.title.clone(),
+    };
+    std::fs::write(args.output.join("_index.md"), hugo_page(&section, "")?)?;
+    for path in &paths {
+        let markdown = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        let adr = Adr::from_path(&adr_dir, path)?;
+        let relative = adr.relative_path();
+        let (_, body) = split_frontmatter(&markdown);
+        let body = hugo_body(&index.expand(body, &relative), &relative, &bundles);
+        let front_matter = HugoFrontMatter {
+            title: format!("{}. {}", adr.number, adr.title),
+            date: adr.date.clone(),
+            weight: adr.number,
+            tags: adr.tags.clone(),
+            status: adr.current_status().into_iter().collect(),
+            deciders: adr.deciders.clone(),
+            adr_number: adr.number,
+        };
+        let bundle = args.output.join(&bundles[&relative]);
+        create_dir_all(&bundle)?;
+        std::fs::write(bundle.join("index.md"), hugo_page(&front_matter, &body)?)?;
+    }
+    println!("Wrote {} ADR(s) to {}", paths.len(), args.output.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hugo_body() {
+        let bundles = BTreeMap::from([(
+            PathBuf::from("0001-use-mysql.md"),
+            "0001-use-mysql".to_string(),
+        )]);
+        assert_eq!(
+            hugo_body(
+                "# 2. Tune MySQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nAmends [1. Use MySQL](0001-use-mysql.md#decision)\n\nSee [the docs](https://dev.mysql.com).\n",
+                Path::new("0002-tune-mysql.md"),
+                &bundles
+            ),
+            "\n## Status\n\nAccepted\n\nAmends [1. Use MySQL]({{< relref \"0001-use-mysql#decision\" >}})\n\nSee [the docs](https://dev.mysql.com).\n"
+        );
+    }
+}
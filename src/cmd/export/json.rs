@@ -0,0 +1,218 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{
+    artifact_date, find_adr, find_adr_dir, find_repo_root, list_adrs, list_archived_adrs,
+    to_forward_slashes,
+};
+use crate::config::{load_config, CONFIG_FILE};
+use crate::git::{head_commit, is_dirty, user_name};
+use crate::history::time_in_status;
+use crate::json_adr::{export_adrs, JsonAdrBulkExport, JsonAdrProvenance, JSON_ADR_SCHEMA};
+use crate::model::Adr;
+use crate::shortcodes::ShortcodeIndex;
+
+#[derive(Debug, Args)]
+pub(crate) struct JsonArgs {
+    /// Export only this Architectural Decision Record, by number, id or file name match
+    adr: Option<String>,
+    /// Write the export to a file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Record where each ADR can be viewed as its source_uri, by joining its path in the
+    /// repository to this URL. A `{sha}` placeholder is replaced with the current commit, so that
+    /// the links are permalinks, e.g. https://github.com/org/repo/blob/{sha}
+    #[arg(long, value_name = "URL")]
+    base_url: Option<String>,
+    /// Expand `{{adr N}}` shortcodes in the sections into links to the ADRs they refer to
+    #[arg(long, default_value_t = false)]
+    expand_shortcodes: bool,
+    /// Include the days each ADR spent in the statuses it has left, from the frontmatter dates or
+    /// the git history
+    #[arg(long, default_value_t = false)]
+    time_in_status: bool,
+    /// Embed the JSON-ADR JSON Schema in the export, so it can be validated without fetching it
+    #[arg(long, default_value_t = false)]
+    schema_embed: bool,
+    /// Write the JSON-ADR JSON Schema to schema.json next to the output file
+    #[arg(long, default_value_t = false, requires = "output")]
+    schema_sidecar: bool,
+    /// Record where the export came from: the git commit, whether the working tree was dirty, who
+    /// exported it and when
+    #[arg(long, default_value_t = false)]
+    provenance: bool,
+    /// Sign the output file with gpg, writing a detached ASCII armored signature next to it
+    #[arg(long, default_value_t = false, requires = "output")]
+    sign: bool,
+    /// The gpg key to sign with, instead of the default key
+    #[arg(long, value_name = "KEY", requires = "sign")]
+    signing_key: Option<String>,
+    /// Export the ADRs of every configured scope together, recording the scope of each
+    #[arg(long, default_value_t = false, conflicts_with = "adr")]
+    all_scopes: bool,
+}
+
+// who is running the export, from git or the environment
+fn exporting_user() -> Option<String> {
+    user_name()
+        .filter(|name| !name.is_empty())
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+}
+
+// write a detached signature for the file to FILE.asc with gpg
+fn sign_file(path: &Path, key: Option<&str>) -> Result<PathBuf> {
+    let mut signature = path.as_os_str().to_owned();
+    signature.push(".asc");
+    let signature = PathBuf::from(signature);
+    let mut gpg = Command::new("gpg");
+    gpg.args(["--batch", "--yes", "--armor", "--detach-sign", "--output"])
+        .arg(&signature);
+    if let Some(key) = key {
+        gpg.args(["--local-user", key]);
+    }
+    let status = gpg
+        .arg(path)
+        .status()
+        .context("Unable to run gpg to sign the export")?;
+    if !status.success() {
+        anyhow::bail!("gpg was unable to sign {}", path.display());
+    }
+    Ok(signature)
+}
+
+// the base URL without a trailing slash, with any {sha} placeholder filled in
+pub(crate) fn expand_base_url(base_url: &str) -> Result<String> {
+    let base_url = base_url.trim_end_matches('/');
+    if !base_url.contains("{sha}") {
+        return Ok(base_url.to_string());
+    }
+    let sha = head_commit().context("Unable to find the current commit for {sha}")?;
+    Ok(base_url.replace("{sha}", &sha))
+}
+
+// the path of the ADR directory within the repository, with a trailing slash
+pub(crate) fn repo_prefix(adr_dir: &Path) -> String {
+    let relative = find_repo_root().and_then(|root| {
+        let root = std::fs::canonicalize(root).ok()?;
+        let adr_dir = std::fs::canonicalize(adr_dir).ok()?;
+        Some(adr_dir.strip_prefix(root).ok()?.to_path_buf())
+    });
+    match relative {
+        Some(relative) if relative.as_os_str().is_empty() => String::new(),
+        Some(relative) => format!("{}/", to_forward_slashes(&relative)),
+        None => format!("{}/", to_forward_slashes(adr_dir)),
+    }
+}
+
+// the ADRs to export: the one asked for, or every ADR including the archived ones
+pub(crate) fn selected_adrs(adr_dir: &Path, adr: Option<&str>) -> Result<Vec<PathBuf>> {
+    if let Some(adr) = adr {
+        return Ok(vec![find_adr(adr_dir, adr)?]);
+    }
+    let mut adrs = list_adrs(adr_dir)?;
+    adrs.extend(list_archived_adrs(adr_dir)?);
+    Ok(adrs)
+}
+
+// expand the `{{adr N}}` shortcodes in the sections of every ADR in the export
+pub(crate) fn expand_export_shortcodes(
+    adr_dir: &Path,
+    export: &mut JsonAdrBulkExport,
+) -> Result<()> {
+    let index = ShortcodeIndex::load(adr_dir)?;
+    for json in &mut export.adrs {
+        let from = PathBuf::from(json.path.clone().unwrap_or_default());
+        let sections = [
+            &mut json.context,
+            &mut json.decision,
+            &mut json.consequences,
+        ];
+        for text in sections.into_iter().flatten() {
+            *text = index.expand(text, &from);
+        }
+        for section in &mut json.custom_sections {
+            section.content = index.expand(&section.content, &from);
+        }
+    }
+    Ok(())
+}
+
+// export the ADRs in the directory, with what the arguments add to each of them
+fn export_dir(args: &JsonArgs, adr_dir: &Path) -> Result<JsonAdrBulkExport> {
+    let adrs = selected_adrs(adr_dir, args.adr.as_deref())?;
+    let mut export = export_adrs(adr_dir, &adrs)?;
+    if let Some(base_url) = &args.base_url {
+        let base_url = expand_base_url(base_url)?;
+        let prefix = repo_prefix(adr_dir);
+        for json in &mut export.adrs {
+            if let Some(path) = &json.path {
+                json.source_uri = Some(format!("{}/{}{}", base_url, prefix, path));
+            }
+        }
+    }
+    if args.expand_shortcodes {
+        expand_export_shortcodes(adr_dir, &mut export)?;
+    }
+    if args.time_in_status {
+        for json in &mut export.adrs {
+            json.time_in_status = time_in_status(adr_dir, &Adr::from(&*json));
+        }
+    }
+    Ok(export)
+}
+
+pub(crate) fn run_json(args: &JsonArgs) -> Result<()> {
+    let mut export = if args.all_scopes {
+        let config = load_config().context("Unable to load configuration")?;
+        if config.scopes.is_empty() {
+            anyhow::bail!(
+                "No scopes are configured, add them to [scopes] in {}",
+                CONFIG_FILE
+            );
+        }
+        let mut export = JsonAdrBulkExport::new(Vec::new());
+        for (scope, adr_dir) in &config.scopes {
+            // a scope without any ADRs yet may not have its directory
+            if !adr_dir.is_dir() {
+                continue;
+            }
+            for mut json in export_dir(args, adr_dir)?.adrs {
+                json.scope = Some(scope.clone());
+                export.adrs.push(json);
+            }
+        }
+        export
+    } else {
+        export_dir(args, &find_adr_dir().context("No ADR directory found")?)?
+    };
+    if args.provenance {
+        export.provenance = Some(JsonAdrProvenance {
+            commit: head_commit(),
+            dirty: is_dirty(),
+            exported_by: exporting_user(),
+            exported_at: artifact_date()?,
+        });
+    }
+    if args.schema_embed {
+        export.schema = Some(serde_json::from_str(JSON_ADR_SCHEMA)?);
+    }
+    let json = export.to_json()?;
+    match &args.output {
+        Some(path) => std::fs::write(path, json + "\n")
+            .with_context(|| format!("Unable to write {}", path.display()))?,
+        None => println!("{}", json),
+    }
+    if let (true, Some(path)) = (args.schema_sidecar, &args.output) {
+        let schema = path.with_file_name("schema.json");
+        std::fs::write(&schema, JSON_ADR_SCHEMA)
+            .with_context(|| format!("Unable to write {}", schema.display()))?;
+    }
+    if let (true, Some(path)) = (args.sign, &args.output) {
+        sign_file(path, args.signing_key.as_deref())?;
+    }
+    Ok(())
+}
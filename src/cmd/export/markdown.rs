@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use regex::Regex;
+
+use crate::adr::{find_adr, find_adr_dir, split_frontmatter, to_forward_slashes};
+use crate::cmd::export::json::{expand_base_url, repo_prefix, selected_adrs};
+use crate::cmd::export::page_link;
+use crate::model::Adr;
+use crate::shortcodes::ShortcodeIndex;
+
+#[derive(Debug, Args)]
+pub(crate) struct MarkdownArgs {
+    /// The Architectural Decision Record number or file name match
+    adr: String,
+    /// Make the markdown stand on its own, e.g. for a PR description or an RFC: shortcodes are
+    /// expanded and links to other ADRs are named after their current titles, and made absolute
+    /// with --base-url or otherwise written as plain text
+    #[arg(long, default_value_t = false)]
+    standalone: bool,
+    /// Make relative links absolute by joining their path in the repository to this URL. A
+    /// `{sha}` placeholder is replaced with the current commit, e.g.
+    /// https://github.com/org/repo/blob/{sha}
+    #[arg(long, value_name = "URL", requires = "standalone")]
+    base_url: Option<String>,
+    /// Write the markdown to a file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+// the ADRs that links can point at, with the URL each can be viewed at if there is a base URL
+struct LinkTargets {
+    pages: BTreeMap<PathBuf, String>,
+    titles: BTreeMap<String, String>,
+    base_url: Option<String>,
+}
+
+// whether the link text refers to the ADR rather than being part of a sentence, i.e. it is a
+// numbered title, which may have gone stale, or the link's own destination
+fn names_adr(text: &str, dest: &str) -> bool {
+    text.trim().is_empty()
+        || text == dest
+        || Regex::new(r"^(ADR[- ]?)?\d+\b").unwrap().is_match(text)
+}
+
+// a link in the ADR at `from`, rewritten so that it still works wherever the markdown is pasted
+fn standalone_link(text: &str, dest: &str, from: &Path, targets: &LinkTargets) -> String {
+    let Some(page) = page_link(dest, from, &targets.pages) else {
+        // other relative links, e.g. to images, are made absolute if they can be
+        return match &targets.base_url {
+            Some(base_url) if !dest.contains("://") && !dest.starts_with('#') => {
+                let relative = from.parent().unwrap_or(Path::new("")).join(dest);
+                format!("[{}]({}{})", text, base_url, to_forward_slashes(&relative))
+            }
+            _ => format!("[{}]({})", text, dest),
+        };
+    };
+    let path = page.split('#').next().unwrap_or_default();
+    let text = match targets.titles.get(path) {
+        Some(title) if names_adr(text, dest) => title.as_str(),
+        _ => text,
+    };
+    match &targets.base_url {
+        Some(base_url) => format!("[{}]({}{})", text, base_url, page),
+        None => text.to_string(),
+    }
+}
+
+// the ADR's markdown as a single document with no links relative to the ADR directory
+fn standalone(markdown: &str, from: &Path, targets: &LinkTargets) -> String {
+    let link = Regex::new(r"\[([^\]]*)\]\(([^)\s]+)\)").unwrap();
+    link.replace_all(markdown, |captures: &regex::Captures| {
+        standalone_link(&captures[1], &captures[2], from, targets)
+    })
+    .into_owned()
+}
+
+pub(crate) fn run_markdown(args: &MarkdownArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let path = find_adr(&adr_dir, &args.adr)?;
+    let markdown = std::fs::read_to_string(&path)
+        .with_context(|| format!("Unable to read {}", path.display()))?;
+    let (_, body) = split_frontmatter(&markdown);
+    let mut out = body.trim_start().to_string();
+
+    if args.standalone {
+        let from = path.strip_prefix(&adr_dir).unwrap_or(&path).to_path_buf();
+        out = ShortcodeIndex::load(&adr_dir)?.expand(&out, &from);
+        let mut targets = LinkTargets {
+            pages: BTreeMap::new(),
+            titles: BTreeMap::new(),
+            base_url: None,
+        };
+        for path in selected_adrs(&adr_dir, None)? {
+            let adr = Adr::from_path(&adr_dir, &path)?;
+            let relative = to_forward_slashes(&adr.relative_path());
+            targets.pages.insert(adr.relative_path(), relative.clone());
+            targets
+                .titles
+                .insert(relative, format!("{}. {}", adr.number, adr.title));
+        }
+        if let Some(base_url) = &args.base_url {
+            targets.base_url = Some(format!(
+                "{}/{}",
+                expand_base_url(base_url)?,
+                repo_prefix(&adr_dir)
+            ));
+        }
+        out = standalone(&out, &from, &targets);
+    }
+
+    match &args.output {
+        Some(output) => std::fs::write(output, &out)
+            .with_context(|| format!("Unable to write {}", output.display()))?,
+        None => print!("{}", out),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(base_url: Option<&str>) -> LinkTargets {
+        LinkTargets {
+            pages: BTreeMap::from([(
+                PathBuf::from("archive/0001-use-mysql.md"),
+                "archive/0001-use-mysql.md".to_string(),
+            )]),
+            titles: BTreeMap::from([(
+                "archive/0001-use-mysql.md".to_string(),
+                "1. Use MySQL for storage".to_string(),
+            )]),
+            base_url: base_url.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_standalone() {
+        let markdown = "Amends [1. Use MySQL](archive/0001-use-mysql.md)\n\nSee [the old choice](archive/0001-use-mysql.md#decision) and ![diagram](diagram.png).\n";
+        let from = Path::new("0002-tune-mysql.md");
+        assert_eq!(
+            standalone(markdown, from, &targets(None)),
+            "Amends 1. Use MySQL for storage\n\nSee the old choice and ![diagram](diagram.png).\n"
+        );
+        assert_eq!(
+            standalone(
+                markdown,
+                from,
+                &targets(Some("https://example.com/blob/main/doc/adr/"))
+            ),
+            "Amends [1. Use MySQL for storage](https://example.com/blob/main/doc/adr/archive/0001-use-mysql.md)\n\nSee [the old choice](https://example.com/blob/main/doc/adr/archive/0001-use-mysql.md#decision) and ![diagram](https://example.com/blob/main/doc/adr/diagram.png).\n"
+        );
+    }
+}
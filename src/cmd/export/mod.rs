@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Result;
+use clap::Subcommand;
+
+pub mod asciidoc;
+pub mod backstage;
+pub mod confluence;
+pub mod csv;
+pub mod html;
+pub mod hugo;
+pub mod json;
+pub mod markdown;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod yaml;
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum ExportCommands {
+    /// Export the ADRs in JSON-ADR format
+    Json(json::JsonArgs),
+    /// Export the ADRs as CSV, one row per ADR, for spreadsheets
+    Csv(csv::CsvArgs),
+    /// Export the ADRs in the JSON-ADR structure, written as YAML
+    Yaml(yaml::YamlArgs),
+    /// Export the ADRs as a static HTML site, with an index, tag pages and links between ADRs
+    Html(html::HtmlArgs),
+    /// Export the ADRs as AsciiDoc, one .adoc file per ADR and an index, for Antora or Asciidoctor
+    Asciidoc(asciidoc::AsciidocArgs),
+    /// Export the ADRs as Confluence storage format pages, with a manifest for uploading them
+    Confluence(confluence::ConfluenceArgs),
+    /// Export the ADRs as Hugo page bundles, with front matter for titles, dates, tags and status
+    Hugo(hugo::HugoArgs),
+    /// Export the ADRs with a catalog-info.yaml for the Backstage ADR plugin, checking first that
+    /// the plugin can read their titles, dates and statuses
+    Backstage(backstage::BackstageArgs),
+    /// Export the ADRs to a SQLite database, with tables of ADRs, links, tags and people, for
+    /// reporting with SQL
+    #[cfg(feature = "sqlite")]
+    Sqlite(sqlite::SqliteArgs),
+    /// Export an ADR as markdown, without its frontmatter, optionally as a standalone document
+    /// with its links resolved for pasting into a PR description or an RFC
+    Markdown(markdown::MarkdownArgs),
+}
+
+// the page each ADR is written to by exporters that write a file per ADR, by the ADR's path
+// relative to the ADR directory. Archived ADRs are written alongside the others
+pub(crate) fn page_names(
+    adr_dir: &Path,
+    adrs: &[PathBuf],
+    extension: &str,
+) -> BTreeMap<PathBuf, String> {
+    adrs.iter()
+        .map(|path| {
+            let relative = path.strip_prefix(adr_dir).unwrap_or(path).to_path_buf();
+            let stem = relative.file_stem().unwrap_or_default().to_string_lossy();
+            let page = format!("{}.{}", stem, extension);
+            (relative, page)
+        })
+        .collect()
+}
+
+// the path without any `.` or `..` components, as far as it can be resolved
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+// the page, with any fragment, that a link in the ADR at `from` points at, if it is a link to
+// another exported ADR
+pub(crate) fn page_link(
+    dest: &str,
+    from: &Path,
+    pages: &BTreeMap<PathBuf, String>,
+) -> Option<String> {
+    let (file, fragment) = match dest.split_once('#') {
+        Some((file, fragment)) => (file, format!("#{}", fragment)),
+        None => (dest, String::new()),
+    };
+    if file.is_empty() || file.contains("://") {
+        return None;
+    }
+    let target = normalize(&from.parent().unwrap_or(Path::new("")).join(file));
+    pages
+        .get(&target)
+        .map(|page| format!("{}{}", page, fragment))
+}
+
+pub(crate) fn run(args: &ExportCommands) -> Result<()> {
+    match args {
+        ExportCommands::Json(args) => json::run_json(args),
+        ExportCommands::Csv(args) => csv::run_csv(args),
+        ExportCommands::Yaml(args) => yaml::run_yaml(args),
+        ExportCommands::Html(args) => html::run_html(args),
+        ExportCommands::Asciidoc(args) => asciidoc::run_asciidoc(args),
+        ExportCommands::Confluence(args) => confluence::run_confluence(args),
+        ExportCommands::Hugo(args) => hugo::run_hugo(args),
+        ExportCommands::Backstage(args) => backstage::run_backstage(args),
+        #[cfg(feature = "sqlite")]
+        ExportCommands::Sqlite(args) => sqlite::run_sqlite(args),
+        ExportCommands::Markdown(args) => markdown::run_markdown(args),
+    }
+}
@@ -0,0 +1,185 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use rusqlite::{params, Connection};
+
+use crate::adr::{find_adr_dir, list_adrs, list_archived_adrs, to_forward_slashes};
+use crate::model::Adr;
+
+// the tables of the export. Dates are kept as YYYY-MM-DD text so that SQLite's date functions
+// work on them, e.g. strftime('%Y', date)
+static SCHEMA: &str = "
+CREATE TABLE adrs (
+    number INTEGER NOT NULL,
+    id TEXT,
+    title TEXT NOT NULL,
+    status TEXT,
+    date TEXT,
+    proposed_date TEXT,
+    decided_date TEXT,
+    implemented_date TEXT,
+    implemented TEXT,
+    confirmation_result TEXT,
+    path TEXT NOT NULL,
+    archived INTEGER NOT NULL,
+    context TEXT,
+    decision TEXT,
+    consequences TEXT
+);
+CREATE TABLE links (
+    source INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    target INTEGER NOT NULL
+);
+CREATE TABLE tags (
+    adr INTEGER NOT NULL,
+    tag TEXT NOT NULL
+);
+CREATE TABLE people (
+    adr INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    role TEXT NOT NULL
+);
+CREATE INDEX links_target ON links (target);
+CREATE INDEX tags_tag ON tags (tag);
+";
+
+#[derive(Debug, Args)]
+pub(crate) struct SqliteArgs {
+    /// The database file to write, e.g. decisions.db
+    output: PathBuf,
+    /// Overwrite an existing database
+    #[arg(long, default_value_t = false)]
+    overwrite: bool,
+}
+
+// write the ADRs to the database, which must be empty
+fn write_adrs(conn: &mut Connection, adrs: &[(Adr, bool)]) -> Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute_batch(SCHEMA)?;
+    for (adr, archived) in adrs {
+        tx.execute(
+            "INSERT INTO adrs VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            params![
+                adr.number,
+                adr.id,
+                adr.title,
+                adr.current_status(),
+                adr.date,
+                adr.proposed_date,
+                adr.decided_date,
+                adr.implemented_date,
+                adr.implemented.map(|i| i.as_str()),
+                adr.confirmation_result.map(|r| r.as_str()),
+                to_forward_slashes(&adr.relative_path()),
+                archived,
+                adr.context,
+                adr.decision,
+                adr.consequences,
+            ],
+        )?;
+        for link in &adr.links {
+            tx.execute(
+                "INSERT INTO links VALUES (?1, ?2, ?3)",
+                params![adr.number, link.kind, link.target],
+            )?;
+        }
+        for tag in &adr.tags {
+            tx.execute("INSERT INTO tags VALUES (?1, ?2)", params![adr.number, tag])?;
+        }
+        for decider in &adr.deciders {
+            tx.execute(
+                "INSERT INTO people VALUES (?1, ?2, 'decider')",
+                params![adr.number, decider],
+            )?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+pub(crate) fn run_sqlite(args: &SqliteArgs) -> Result<()> {
+    if args.output.exists() {
+        if !args.overwrite {
+            anyhow::bail!(
+                "File already exists: {}. Use the --overwrite flag to overwrite it.",
+                args.output.display()
+            );
+        }
+        std::fs::remove_file(&args.output)
+            .with_context(|| format!("Unable to remove {}", args.output.display()))?;
+    }
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let mut adrs = Vec::new();
+    for path in list_adrs(&adr_dir)? {
+        adrs.push((Adr::from_path(&adr_dir, &path)?, false));
+    }
+    for path in list_archived_adrs(&adr_dir)? {
+        adrs.push((Adr::from_path(&adr_dir, &path)?, true));
+    }
+
+    let mut conn = Connection::open(&args.output)
+        .with_context(|| format!("Unable to create {}", args.output.display()))?;
+    write_adrs(&mut conn, &adrs)
+        .with_context(|| format!("Unable to write {}", args.output.display()))?;
+    println!("Wrote {} ADR(s) to {}", adrs.len(), args.output.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_write_adrs() {
+        let adrs = [
+            Adr::parse(
+                Path::new("0001-use-mysql.md"),
+                "---\ntags: [database]\ndeciders: [Ana, Bo]\n---\n\n# 1. Use MySQL\n\nDate: 2024-01-15\n\n## Status\n\nAccepted\n",
+            ),
+            Adr::parse(
+                Path::new("0002-tune-mysql.md"),
+                "# 2. Tune MySQL\n\nDate: 2024-05-02\n\n## Status\n\nProposed\n\nAmends [1. Use MySQL](0001-use-mysql.md)\n",
+            ),
+        ]
+        .into_iter()
+        .map(|adr| (adr.unwrap(), false))
+        .collect::<Vec<_>>();
+        let mut conn = Connection::open_in_memory().unwrap();
+        write_adrs(&mut conn, &adrs).unwrap();
+
+        // accepted decisions per quarter per tag
+        let rows = conn
+            .prepare(
+                "SELECT strftime('%Y', date) || '-Q' || ((strftime('%m', date) + 2) / 3), tag, count(*)
+                 FROM adrs JOIN tags ON tags.adr = adrs.number
+                 WHERE status = 'Accepted' GROUP BY 1, 2",
+            )
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<(String, String, i64)>>>()
+            .unwrap();
+        assert_eq!(rows, [("2024-Q1".to_string(), "database".to_string(), 1)]);
+
+        let (kind, target): (String, i32) = conn
+            .query_row(
+                "SELECT kind, target FROM links WHERE source = 2",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((kind.as_str(), target), ("Amends", 1));
+        let people: i64 = conn
+            .query_row(
+                "SELECT count(*) FROM people WHERE role = 'decider'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(people, 2);
+    }
+}
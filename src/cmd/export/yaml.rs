@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::find_adr_dir;
+use crate::cmd::export::json::{expand_export_shortcodes, selected_adrs};
+use crate::json_adr::export_adrs;
+
+#[derive(Debug, Args)]
+pub(crate) struct YamlArgs {
+    /// Export only this Architectural Decision Record, by number, id or file name match
+    adr: Option<String>,
+    /// Write the export to a file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Expand `{{adr N}}` shortcodes in the sections into links to the ADRs they refer to
+    #[arg(long, default_value_t = false)]
+    expand_shortcodes: bool,
+}
+
+pub(crate) fn run_yaml(args: &YamlArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let adrs = selected_adrs(&adr_dir, args.adr.as_deref())?;
+
+    let mut export = export_adrs(&adr_dir, &adrs)?;
+    if args.expand_shortcodes {
+        expand_export_shortcodes(&adr_dir, &mut export)?;
+    }
+    let yaml = export.to_yaml()?;
+    match &args.output {
+        Some(path) => std::fs::write(path, yaml)
+            .with_context(|| format!("Unable to write {}", path.display()))?,
+        None => print!("{}", yaml),
+    }
+    Ok(())
+}
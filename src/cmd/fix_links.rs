@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use regex::{Captures, Regex};
+
+use crate::adr::{find_adr_dir, get_number, get_title, list_adrs};
+use crate::model::AdrIndex;
+use crate::transaction::Transaction;
+
+#[derive(Debug, Args)]
+pub(crate) struct FixLinksArgs {
+    /// Report the links that would be fixed without changing any files
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+}
+
+// rewrite every link to another ADR so that its path and title match the ADR's current filename
+// and title, returning the updated markdown along with a description of each change. Only links
+// into the ADR directory are ADR links, a numbered file elsewhere such as a runbook is left alone
+pub(crate) fn fix_links_markdown(
+    markdown: &str,
+    adr_dir: &Path,
+    index: &AdrIndex,
+) -> (String, Vec<String>) {
+    let re = Regex::new(r"\[(?<text>[^\]]*)\]\((?<dest>[^)\s]+)\)").unwrap();
+    let canonical_adr_dir = adr_dir.canonicalize().ok();
+    let mut fixes = Vec::new();
+
+    let fixed = re.replace_all(markdown, |caps: &Captures| {
+        let text = &caps["text"];
+        let dest = &caps["dest"];
+        let original = caps[0].to_string();
+
+        if dest.contains("://") {
+            return original;
+        }
+        let (path, fragment) = match dest.split_once('#') {
+            Some((path, fragment)) => (path, format!("#{}", fragment)),
+            None => (dest, String::new()),
+        };
+        let (dir, filename) = match path.rsplit_once('/') {
+            Some((dir, filename)) => (format!("{}/", dir), filename),
+            None => (String::new(), path),
+        };
+        if !filename.ends_with(".md") {
+            return original;
+        }
+        if !dir.is_empty() {
+            let target_dir = adr_dir.join(&dir).canonicalize().ok();
+            if target_dir.is_none() || target_dir != canonical_adr_dir {
+                return original;
+            }
+        }
+        let Some(number) = get_number(filename.as_ref()) else {
+            return original;
+        };
+        let Some((current_filename, current_title)) = index.get(&number) else {
+            fixes.push(format!("unresolved link to ADR {}: {}", number, dest));
+            return original;
+        };
+
+        let text = match text.split_once(". ") {
+            Some((n, _)) if n.parse::<i32>().is_ok() && text != current_title => {
+                fixes.push(format!("{} -> {}", text, current_title));
+                current_title.as_str()
+            }
+            _ => text,
+        };
+        if filename != current_filename {
+            fixes.push(format!("{} -> {}", filename, current_filename));
+        }
+        format!("[{}]({}{}{})", text, dir, current_filename, fragment)
+    });
+    (fixed.into_owned(), fixes)
+}
+
+pub(crate) fn run(args: &FixLinksArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let adrs = list_adrs(&adr_dir)?;
+
+    let mut index = AdrIndex::new();
+    for adr in &adrs {
+        if let Some(number) = get_number(adr) {
+            let filename = adr.file_name().unwrap().to_str().unwrap().to_string();
+            index.insert(number, (filename, get_title(adr)?));
+        }
+    }
+
+    let mut tx = Transaction::new();
+    let mut fixed = 0;
+    for adr in &adrs {
+        let markdown = std::fs::read_to_string(adr)?;
+        let (updated, fixes) = fix_links_markdown(&markdown, &adr_dir, &index);
+        for fix in &fixes {
+            println!("{}: {}", adr.display(), fix);
+        }
+        if updated != markdown {
+            fixed += 1;
+            tx.write(PathBuf::from(adr), updated);
+        }
+    }
+
+    if !args.dry_run {
+        tx.commit()?;
+    }
+    println!(
+        "{} {} ADR{}",
+        if args.dry_run { "Would fix" } else { "Fixed" },
+        fixed,
+        if fixed == 1 { "" } else { "s" }
+    );
+    Ok(())
+}
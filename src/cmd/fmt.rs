@@ -0,0 +1,230 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use regex::Regex;
+
+use crate::adr::{find_adr, find_adr_dir, get_number, list_adrs, split_frontmatter};
+use crate::config::load_config;
+use crate::model::{index_adrs, section_matches, Adr, AdrIndex};
+use crate::transaction::Transaction;
+
+#[derive(Debug, Args)]
+pub(crate) struct FmtArgs {
+    /// The Architectural Decision Records to format, by number or file name match, or all of them
+    adrs: Vec<String>,
+    /// Report the ADRs that aren't formatted, failing if there are any, without changing any
+    /// files
+    #[arg(long, default_value_t = false)]
+    check: bool,
+}
+
+// order the frontmatter keys as configured, keeping keys the configuration doesn't mention in
+// their original order after the others. Lines that continue a value stay with its key
+fn format_frontmatter(frontmatter: &str, order: &[String]) -> String {
+    let inner = frontmatter
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.strip_suffix("\n---\n"))
+        .unwrap_or_default();
+    let mut entries: Vec<(&str, Vec<&str>)> = Vec::new();
+    for line in inner.lines().map(str::trim_end) {
+        let continues = line.is_empty() || line.starts_with([' ', '\t', '-', '#']);
+        match entries.last_mut() {
+            Some((_, lines)) if continues => lines.push(line),
+            _ => entries.push((
+                line.split(':').next().unwrap_or_default().trim(),
+                vec![line],
+            )),
+        }
+    }
+    entries.sort_by_key(|(key, _)| order.iter().position(|k| k == key).unwrap_or(order.len()));
+    let lines = entries
+        .into_iter()
+        .flat_map(|(_, lines)| lines)
+        .collect::<Vec<_>>();
+    format!("---\n{}\n---\n", lines.join("\n").trim_end())
+}
+
+// the ADR with its frontmatter formatted as `adrs fmt` formats it, for the commands that set
+// frontmatter fields. The body is left as it is
+pub(crate) fn with_formatted_frontmatter(markdown: &str, order: &[String]) -> String {
+    let (frontmatter, body) = split_frontmatter(markdown);
+    if frontmatter.is_empty() {
+        return markdown.to_string();
+    }
+    format!(
+        "{}\n{}",
+        format_frontmatter(frontmatter, order),
+        body.trim_start_matches('\n')
+    )
+}
+
+// format an ADR: the title is the only first level heading, headings and status lines are
+// separated by single blank lines, link lines read `Kind [N. Title](file)` and no line has
+// trailing whitespace. Fenced code is left as written
+pub(crate) fn format_markdown(markdown: &str, order: &[String], index: &AdrIndex) -> String {
+    let heading = Regex::new(r"^ {0,3}(?<hashes>#{1,6})[ \t]+(?<text>.*?)(?:[ \t]+#+)?$").unwrap();
+    let link = Regex::new(
+        r"^(?:[-*+][ \t]+)?(?<kind>[A-Za-z][^\[]*?):?[ \t]*\[(?<text>[^\]]*)\]\((?<dest>[^)\s]+)\)$",
+    )
+    .unwrap();
+    let (frontmatter, body) = split_frontmatter(markdown);
+
+    // None is a blank line, which collapses into the blank lines around it
+    let mut lines: Vec<Option<String>> = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut seen_heading = false;
+    let mut in_status = false;
+    for line in body.lines() {
+        if let Some(marker) = fence {
+            if line.trim_start().starts_with(marker) {
+                fence = None;
+            }
+            lines.push(Some(line.to_string()));
+            continue;
+        }
+        let line = line.trim_end();
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            lines.push(Some(line.to_string()));
+        } else if let Some(caps) = heading.captures(line) {
+            let text = &caps["text"];
+            let numbered = text
+                .split_once(". ")
+                .is_some_and(|(number, _)| number.parse::<i32>().is_ok());
+            let level = match caps["hashes"].len() {
+                // the title, even if it was written as a section
+                _ if !seen_heading && numbered => 1,
+                1 if seen_heading => 2,
+                level => level,
+            };
+            seen_heading = true;
+            in_status = level == 2 && section_matches(text, "Status");
+            lines.extend([None, Some(format!("{} {}", "#".repeat(level), text)), None]);
+        } else if line.is_empty() {
+            lines.push(None);
+        } else if in_status {
+            // each status and link is a paragraph of its own
+            let line = match link.captures(trimmed) {
+                Some(caps) => {
+                    let dest = &caps["dest"];
+                    let filename = dest.rsplit('/').next().unwrap_or(dest);
+                    match get_number(Path::new(filename)) {
+                        Some(number) => {
+                            let text = index.get(&number).map_or(&caps["text"], |(_, t)| t);
+                            format!("{} [{}]({})", caps["kind"].trim(), text, dest)
+                        }
+                        None => trimmed.to_string(),
+                    }
+                }
+                None => trimmed.to_string(),
+            };
+            lines.extend([None, Some(line), None]);
+        } else {
+            lines.push(Some(line.to_string()));
+        }
+    }
+
+    let mut out = String::with_capacity(markdown.len());
+    if !frontmatter.is_empty() {
+        out += &format_frontmatter(frontmatter, order);
+        out.push('\n');
+    }
+    let mut blank = true;
+    for line in lines {
+        match line {
+            Some(line) => {
+                out += &line;
+                out.push('\n');
+                blank = false;
+            }
+            None if !blank => {
+                out.push('\n');
+                blank = true;
+            }
+            None => {}
+        }
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+pub(crate) fn run(args: &FmtArgs) -> Result<()> {
+    let config = load_config().context("Unable to load configuration")?;
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let all = list_adrs(&adr_dir)?;
+    let mut adrs = Vec::new();
+    for path in &all {
+        adrs.push(Adr::from_path(&adr_dir, path)?);
+    }
+    let index = index_adrs(&adrs);
+    let paths = if args.adrs.is_empty() {
+        all
+    } else {
+        args.adrs
+            .iter()
+            .map(|name| find_adr(&adr_dir, name))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let mut tx = Transaction::new();
+    let mut unformatted = 0;
+    for path in &paths {
+        let markdown = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        let formatted = format_markdown(&markdown, &config.fmt.frontmatter_order, &index);
+        if formatted == markdown {
+            continue;
+        }
+        unformatted += 1;
+        if args.check {
+            println!("{} needs formatting", path.display());
+        } else {
+            println!("{}", path.display());
+            tx.write(path, formatted);
+        }
+    }
+    if args.check && unformatted > 0 {
+        anyhow::bail!("{} ADR(s) need formatting, run `adrs fmt`", unformatted);
+    }
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_frontmatter() {
+        let order = ["id", "status", "tags"].map(str::to_string);
+        assert_eq!(
+            format_frontmatter(
+                "---\ntags:\n  - db  \nextra: 1\nstatus: accepted\nid: 7\n---\n",
+                &order
+            ),
+            "---\nid: 7\nstatus: accepted\ntags:\n  - db\nextra: 1\n---\n"
+        );
+    }
+
+    #[test]
+    fn test_format_markdown() {
+        let mut index = AdrIndex::new();
+        index.insert(
+            1,
+            (
+                "0001-record-decisions.md".to_string(),
+                "1. Record decisions".to_string(),
+            ),
+        );
+        let markdown = "## 2. Use MySQL  \n\n\nDate: 2024-01-01\n# Status\nAccepted\n* Amends: [Record](0001-record-decisions.md)\n\n\n\n## Context ##\n\n```\nkeep  \n\n\nthis\n```\n\n";
+        assert_eq!(
+            format_markdown(markdown, &[], &index),
+            "# 2. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nAmends [1. Record decisions](0001-record-decisions.md)\n\n## Context\n\n```\nkeep  \n\n\nthis\n```\n"
+        );
+        // formatting is idempotent
+        let formatted = format_markdown(markdown, &[], &index);
+        assert_eq!(format_markdown(&formatted, &[], &index), formatted);
+    }
+}
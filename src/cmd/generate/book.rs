@@ -5,13 +5,22 @@ use std::{
 
 use anyhow::{Context, Result};
 use clap::Args;
+use regex::Regex;
 use serde::Serialize;
 use tinytemplate::TinyTemplate;
+use walkdir::WalkDir;
 
-use crate::adr::{find_adr_dir, get_title, list_adrs};
+use super::{relative_path, FilterArgs};
+use crate::adr::{find_adr_dir, get_current_status, get_title, slugify, to_forward_slashes};
+use crate::config::MarkerStyle;
+use crate::manifest::{read_manifest, ManifestRepo};
+use crate::markers::Markers;
+use crate::profile;
+use crate::shortcodes::ShortcodeIndex;
 
 static BOOK_TOML_TEMPLATE: &str = include_str!("../../../templates/book/book.toml");
 static BOOK_SUMMARY_TEMPLATE: &str = include_str!("../../../templates/book/SUMMARY.md");
+static BOOK_MULTI_SUMMARY_TEMPLATE: &str = include_str!("../../../templates/book/MULTI_SUMMARY.md");
 
 #[derive(Debug, Args)]
 pub(crate) struct BookArgs {
@@ -34,6 +43,18 @@ pub(crate) struct BookArgs {
     /// Author of the book
     #[clap(long, short)]
     author: Option<String>,
+    /// Fail if the existing book differs from what would be generated instead of writing it
+    #[clap(long, default_value_t = false)]
+    check: bool,
+    /// Build one book from every repository in this TOML manifest, as `[[repos]]` tables with a
+    /// `path` and an optional `name`, with a chapter of ADRs for each repository
+    #[clap(long, value_name = "MANIFEST")]
+    multi_repo: Option<PathBuf>,
+    /// Mark each ADR with its status, overriding markers.style in the configuration
+    #[clap(long, value_enum)]
+    markers: Option<MarkerStyle>,
+    #[clap(flatten)]
+    filter: FilterArgs,
 }
 
 #[derive(Debug, Serialize)]
@@ -48,16 +69,133 @@ struct SummaryContext {
     adrs: Vec<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct RepoChapterContext {
+    name: String,
+    readme: String,
+    adrs: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct MultiSummaryContext {
+    repos: Vec<RepoChapterContext>,
+}
+
+// a repository's chapter of a multi-repository book
+struct RepoChapter {
+    repo: ManifestRepo,
+    adr_dir: PathBuf,
+    // the directory of the chapter within src
+    dir: String,
+}
+
+// point links to ADRs in the other repositories of the book at their copies. The copy is at
+// `depth` directories below src, and links within the same repository still work as they are
+fn rewrite_cross_repo_links(
+    markdown: &str,
+    source_dir: &Path,
+    depth: usize,
+    chapter: &RepoChapter,
+    chapters: &[RepoChapter],
+) -> String {
+    let link = Regex::new(r"\]\(([^)\s]+)\)").unwrap();
+    link.replace_all(markdown, |captures: &regex::Captures| {
+        let target = &captures[1];
+        let (file, fragment) = match target.split_once('#') {
+            Some((file, fragment)) => (file, format!("#{}", fragment)),
+            None => (target, String::new()),
+        };
+        if file.is_empty() || file.contains("://") || file.starts_with('/') {
+            return captures[0].to_string();
+        }
+        let Ok(resolved) = std::fs::canonicalize(source_dir.join(file)) else {
+            return captures[0].to_string();
+        };
+        for other in chapters.iter().filter(|other| other.dir != chapter.dir) {
+            let Ok(other_dir) = std::fs::canonicalize(&other.adr_dir) else {
+                continue;
+            };
+            if let Ok(relative) = resolved.strip_prefix(&other_dir) {
+                return format!(
+                    "]({}{}/{}{})",
+                    "../".repeat(depth),
+                    other.dir,
+                    to_forward_slashes(relative),
+                    fragment
+                );
+            }
+        }
+        captures[0].to_string()
+    })
+    .into_owned()
+}
+
+// the files of a book directory that are generated, relative to the directory
+fn book_files(path: &Path) -> Vec<PathBuf> {
+    let mut files = WalkDir::new(path.join("src"))
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.path().strip_prefix(path).unwrap().to_path_buf())
+        .collect::<Vec<_>>();
+    if path.join("book.toml").is_file() {
+        files.push(PathBuf::from("book.toml"));
+    }
+    files.sort();
+    files
+}
+
+// generate the book in a temporary directory and compare it with the existing book
+fn check_book(args: &BookArgs) -> Result<()> {
+    let temp = tempfile::Builder::new()
+        .prefix("adrs-book-")
+        .tempdir()
+        .context("Unable to create a temporary directory")?;
+    write_book(args, temp.path())?;
+
+    let expected = book_files(temp.path());
+    let existing = book_files(&args.path);
+    let mut problems = Vec::new();
+    for file in &expected {
+        match std::fs::read(args.path.join(file)) {
+            Ok(contents) if contents == std::fs::read(temp.path().join(file))? => {}
+            Ok(_) => problems.push(format!("{} is out of date", to_forward_slashes(file))),
+            Err(_) => problems.push(format!("{} is missing", to_forward_slashes(file))),
+        }
+    }
+    for file in existing.iter().filter(|file| !expected.contains(file)) {
+        problems.push(format!(
+            "{} is no longer generated",
+            to_forward_slashes(file)
+        ));
+    }
+
+    if !problems.is_empty() {
+        for problem in &problems {
+            println!("{}: {}", args.path.display(), problem);
+        }
+        anyhow::bail!("{} is out of date", args.path.display());
+    }
+    println!("{} is up to date", args.path.display());
+    Ok(())
+}
+
 pub fn run_book(args: &BookArgs) -> Result<()> {
-    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    if args.check {
+        return check_book(args);
+    }
     if args.path.exists() && !args.overwrite {
         anyhow::bail!(
             "Directory already exists: {}. Use the --overwrite flat to overwrite it.",
             args.path.display()
         );
     }
+    write_book(args, &args.path)
+}
 
-    create_dir_all(args.path.as_path().join("src"))?;
+// write the book to the given directory
+fn write_book(args: &BookArgs, path: &Path) -> Result<()> {
+    create_dir_all(path.join("src"))?;
     let author = if let Some(author) = &args.author {
         author.clone()
     } else {
@@ -77,44 +215,137 @@ pub fn run_book(args: &BookArgs) -> Result<()> {
         author,
     };
 
+    let rendering = profile::span("rendering");
     let book_toml = tt
         .add_template("book_toml", BOOK_TOML_TEMPLATE)
         .and_then(|_| tt.render("book_toml", &book_toml_context))
         .context("Unable to render book.toml template")?;
+    drop(rendering);
 
-    std::fs::write(args.path.as_path().join("book.toml"), book_toml)?;
+    std::fs::write(path.join("book.toml"), book_toml)?;
 
+    if let Some(manifest) = &args.multi_repo {
+        return write_multi_repo_chapters(args, path, manifest, &mut tt);
+    }
+
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let index = ShortcodeIndex::load(&adr_dir)?;
+    let markers = Markers::load(args.markers)?;
     let mut adr_titles = Vec::new();
-    let adrs = list_adrs(Path::new(&adr_dir))?;
+    let adrs = args.filter.select_adrs(Path::new(&adr_dir))?;
     for adr in adrs {
-        std::fs::copy(
+        let relative = relative_path(&adr_dir, &adr);
+        let target = path.join("src").join(&relative);
+        {
+            let _span = profile::span("writing");
+            create_dir_all(target.parent().unwrap())?;
+            let markdown = std::fs::read_to_string(&adr)
+                .with_context(|| format!("Unable to read {}", adr.display()))?;
+            std::fs::write(target, index.expand(&markdown, &relative))?;
+        }
+        adr_titles.push(summary_item(
             &adr,
-            args.path
-                .as_path()
-                .join("src")
-                .join(adr.file_name().unwrap()),
-        )?;
-        let adr_title = get_title(adr.as_path())?;
-        let (_number, title) = adr_title.split_once(char::is_whitespace).unwrap();
-        let item = format!(
-            "[{}]({})",
-            title,
-            adr.file_name().unwrap().to_str().unwrap()
-        );
-        adr_titles.push(item);
+            &to_forward_slashes(&relative),
+            &markers,
+        )?);
     }
 
     let summary_context = SummaryContext { adrs: adr_titles };
 
+    let _span = profile::span("rendering");
     let summary_mardkown = tt
         .add_template("SUMMARY.md", BOOK_SUMMARY_TEMPLATE)
         .and_then(|_| tt.render("SUMMARY.md", &summary_context))
         .context("Unable to render SUMMARY.md template")?;
 
-    std::fs::write(
-        args.path.as_path().join("src").join("SUMMARY.md"),
-        summary_mardkown,
-    )?;
+    std::fs::write(path.join("src").join("SUMMARY.md"), summary_mardkown)?;
+
+    Ok(())
+}
+
+// the SUMMARY.md link to the ADR, titled without its number and marked with its status
+fn summary_item(adr: &Path, link: &str, markers: &Markers) -> Result<String> {
+    let adr_title = get_title(adr)?;
+    let (_number, title) = adr_title.split_once(char::is_whitespace).unwrap();
+    // only read the status when there are markers to show
+    let marker = if markers.enabled() {
+        markers.prefix(get_current_status(adr)?.as_deref())
+    } else {
+        String::new()
+    };
+    Ok(format!("[{}{}]({})", marker, title, link))
+}
+
+// write a chapter for each repository in the manifest, with the repository's ADRs in it
+fn write_multi_repo_chapters(
+    args: &BookArgs,
+    path: &Path,
+    manifest: &Path,
+    tt: &mut TinyTemplate,
+) -> Result<()> {
+    let mut chapters = Vec::new();
+    for repo in read_manifest(manifest)? {
+        let adr_dir = repo
+            .adr_dir()
+            .with_context(|| format!("No ADR directory found in {}", repo.path.display()))?;
+        let dir = slugify(&repo.name);
+        if chapters
+            .iter()
+            .any(|chapter: &RepoChapter| chapter.dir == dir)
+        {
+            anyhow::bail!(
+                "More than one repository would be written to the {} chapter",
+                dir
+            );
+        }
+        chapters.push(RepoChapter { repo, adr_dir, dir });
+    }
 
+    let markers = Markers::load(args.markers)?;
+    let mut repos = Vec::new();
+    for chapter in &chapters {
+        let chapter_path = path.join("src").join(&chapter.dir);
+        create_dir_all(&chapter_path)?;
+        std::fs::write(
+            chapter_path.join("README.md"),
+            format!("# {}\n", chapter.repo.name),
+        )?;
+
+        let index = ShortcodeIndex::load(&chapter.adr_dir)?;
+        let mut adr_titles = Vec::new();
+        for adr in args.filter.select_adrs(&chapter.adr_dir)? {
+            let relative = relative_path(&chapter.adr_dir, &adr);
+            let target = chapter_path.join(&relative);
+            {
+                let _span = profile::span("writing");
+                create_dir_all(target.parent().unwrap())?;
+                let markdown = std::fs::read_to_string(&adr)
+                    .with_context(|| format!("Unable to read {}", adr.display()))?;
+                let depth = relative.components().count();
+                let markdown = rewrite_cross_repo_links(
+                    &index.expand(&markdown, &relative),
+                    adr.parent().unwrap_or(Path::new(".")),
+                    depth,
+                    chapter,
+                    &chapters,
+                );
+                std::fs::write(target, markdown)?;
+            }
+            let link = format!("{}/{}", chapter.dir, to_forward_slashes(&relative));
+            adr_titles.push(summary_item(&adr, &link, &markers)?);
+        }
+        repos.push(RepoChapterContext {
+            name: chapter.repo.name.clone(),
+            readme: format!("{}/README.md", chapter.dir),
+            adrs: adr_titles,
+        });
+    }
+
+    let _span = profile::span("rendering");
+    let summary_markdown = tt
+        .add_template("MULTI_SUMMARY.md", BOOK_MULTI_SUMMARY_TEMPLATE)
+        .and_then(|_| tt.render("MULTI_SUMMARY.md", &MultiSummaryContext { repos }))
+        .context("Unable to render SUMMARY.md template")?;
+    std::fs::write(path.join("src").join("SUMMARY.md"), summary_markdown)?;
     Ok(())
 }
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use super::relative_path;
+use crate::adr::{find_adr_dir, list_adrs, to_forward_slashes};
+use crate::git::git;
+use crate::model::Adr;
+
+#[derive(Debug, Args)]
+pub(crate) struct ChangelogArgs {
+    /// The git revision to list changes since, such as the tag of the last release
+    #[clap(long, value_name = "REF")]
+    since: String,
+    /// The git revision to list changes up to
+    #[clap(long, value_name = "REF", default_value = "HEAD")]
+    until: String,
+    /// Prefix each decision file link with the given string
+    #[clap(long, short)]
+    prefix: Option<String>,
+    /// Write the changelog to this file instead of printing it
+    #[clap(long, short)]
+    output: Option<PathBuf>,
+}
+
+// what happened to an ADR between the two revisions
+#[derive(Debug, PartialEq)]
+enum Change {
+    Created(Option<String>),
+    StatusChanged(Option<String>, Option<String>),
+    Superseded(Vec<i32>),
+}
+
+// the ADR as it was at the revision, from its path relative to the current directory
+fn adr_at(revision: &str, path: &str) -> Option<Adr> {
+    let markdown = git(&["show", &format!("{}:./{}", revision, path)])?;
+    Adr::parse(Path::new(path), &markdown).ok()
+}
+
+// how the ADR changed, if it was created, changed status or was superseded
+fn classify(before: Option<&Adr>, after: &Adr) -> Option<Change> {
+    let Some(before) = before else {
+        return Some(Change::Created(after.current_status()));
+    };
+    let (old, new) = (before.current_status(), after.current_status());
+    if old == new {
+        return None;
+    }
+    if new.as_deref() == Some("Superseded") {
+        let by = after
+            .links
+            .iter()
+            .filter(|link| link.kind == "Superseded by")
+            .map(|link| link.target)
+            .collect();
+        return Some(Change::Superseded(by));
+    }
+    Some(Change::StatusChanged(old, new))
+}
+
+// the ADRs created, changed or superseded between the revisions, with their paths relative to
+// the current directory, in order of path
+fn changes(adr_dir: &Path, since: &str, until: &str) -> Result<Vec<(String, Adr, Change)>> {
+    let diff = git(&[
+        "diff",
+        "--name-status",
+        "-M",
+        "--relative",
+        since,
+        until,
+        "--",
+        &adr_dir.to_string_lossy(),
+    ])
+    .context("Unable to list the changes to the ADRs")?;
+
+    let mut changes = Vec::new();
+    for line in diff.lines() {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        // renames and copies name the old path before the new one
+        let (before, after) = match fields.as_slice() {
+            [status, path] if status.starts_with('A') => (None, *path),
+            [status, path] if status.starts_with('M') => (Some(*path), *path),
+            [status, old, new] if status.starts_with('R') => (Some(*old), *new),
+            _ => continue,
+        };
+        let is_adr = Path::new(after).file_name().is_some_and(|name| {
+            let name = name.to_string_lossy();
+            name.starts_with(char::is_numeric) && name.ends_with(".md")
+        });
+        if !is_adr {
+            continue;
+        }
+        let Some(adr) = adr_at(until, after) else {
+            continue;
+        };
+        let previous = before.and_then(|before| adr_at(since, before));
+        if let Some(change) = classify(previous.as_ref(), &adr) {
+            changes.push((after.to_string(), adr, change));
+        }
+    }
+    Ok(changes)
+}
+
+pub(crate) fn run_changelog(args: &ChangelogArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    for revision in [&args.since, &args.until] {
+        if git(&[
+            "rev-parse",
+            "--verify",
+            "-q",
+            &format!("{}^{{commit}}", revision),
+        ])
+        .is_none()
+        {
+            anyhow::bail!(
+                "Unknown git revision {}, or this isn't a git repository",
+                revision
+            );
+        }
+    }
+
+    let link = |number: i32, title: &str, path: &Path| {
+        let path = relative_path(&adr_dir, path);
+        let path = match &args.prefix {
+            Some(prefix) => PathBuf::from(prefix).join(path),
+            None => path,
+        };
+        format!("[{}. {}]({})", number, title, to_forward_slashes(&path))
+    };
+    // the superseding ADRs are linked by their titles in the working tree
+    let mut current = BTreeMap::new();
+    for path in list_adrs(&adr_dir)? {
+        if let Ok(adr) = Adr::from_path(&adr_dir, &path) {
+            current.insert(adr.number, link(adr.number, &adr.title, &path));
+        }
+    }
+
+    let (mut created, mut changed, mut superseded) = (Vec::new(), Vec::new(), Vec::new());
+    for (path, adr, change) in changes(&adr_dir, &args.since, &args.until)? {
+        let item = link(adr.number, &adr.title, Path::new(&path));
+        match change {
+            Change::Created(status) => created.push(match status {
+                Some(status) => format!("* {}, {}", item, status),
+                None => format!("* {}", item),
+            }),
+            Change::StatusChanged(old, new) => changed.push(format!(
+                "* {}: {} to {}",
+                item,
+                old.as_deref().unwrap_or("no status"),
+                new.as_deref().unwrap_or("no status")
+            )),
+            Change::Superseded(by) => {
+                let by = by
+                    .iter()
+                    .map(|number| {
+                        current
+                            .get(number)
+                            .cloned()
+                            .unwrap_or_else(|| format!("ADR {}", number))
+                    })
+                    .collect::<Vec<_>>();
+                superseded.push(if by.is_empty() {
+                    format!("* {}", item)
+                } else {
+                    format!("* {}, by {}", item, by.join(", "))
+                });
+            }
+        }
+    }
+
+    let mut out = String::new();
+    writeln!(out, "# Decision Changelog\n")?;
+    writeln!(
+        out,
+        "Changes to the ADRs between {} and {}.",
+        args.since, args.until
+    )?;
+    let sections = [
+        ("Created", created),
+        ("Status changed", changed),
+        ("Superseded", superseded),
+    ];
+    if sections.iter().all(|(_, items)| items.is_empty()) {
+        writeln!(out, "\nNo ADRs were created, changed status or superseded.")?;
+    }
+    for (heading, items) in sections {
+        if items.is_empty() {
+            continue;
+        }
+        writeln!(out, "\n## {} ({})\n", heading, items.len())?;
+        for item in items {
+            writeln!(out, "{}", item)?;
+        }
+    }
+
+    match &args.output {
+        Some(output) => std::fs::write(output, out)
+            .with_context(|| format!("Unable to write {}", output.display()))?,
+        None => print!("{}", out),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adr(status: &str) -> Adr {
+        let markdown = format!(
+            "# 2. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\n{}\n\n## Context\n",
+            status
+        );
+        Adr::parse(Path::new("0002-use-mysql.md"), &markdown).unwrap()
+    }
+
+    #[test]
+    fn test_classify() {
+        let (proposed, accepted) = (adr("Proposed"), adr("Accepted"));
+        let superseded = adr("Superseded by [3. Use Postgres](0003-use-postgres.md)");
+        assert_eq!(
+            classify(None, &proposed),
+            Some(Change::Created(Some("Proposed".to_string())))
+        );
+        assert_eq!(classify(Some(&proposed), &proposed), None);
+        assert_eq!(
+            classify(Some(&proposed), &accepted),
+            Some(Change::StatusChanged(
+                Some("Proposed".to_string()),
+                Some("Accepted".to_string())
+            ))
+        );
+        assert_eq!(
+            classify(Some(&accepted), &superseded),
+            Some(Change::Superseded(vec![3]))
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use time::format_description::well_known::{Rfc2822, Rfc3339};
+use time::Date;
+use tinytemplate::TinyTemplate;
+
+use super::{relative_path, FilterArgs};
+use crate::adr::{find_adr_dir, parse_date, to_forward_slashes};
+use crate::history::last_status_change;
+use crate::model::Adr;
+
+static ATOM_TEMPLATE: &str = include_str!("../../../templates/feed/atom.xml");
+static RSS_TEMPLATE: &str = include_str!("../../../templates/feed/rss.xml");
+
+/// The kind of feed to generate
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum FeedFormat {
+    /// An Atom feed
+    #[default]
+    Atom,
+    /// An RSS 2.0 feed
+    Rss,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct FeedArgs {
+    /// Where the ADRs are published. Each entry links to this URL followed by the ADR's path in
+    /// the ADR directory
+    #[clap(long)]
+    base_url: String,
+    /// The kind of feed to generate
+    #[clap(long, value_enum, default_value_t = FeedFormat::Atom)]
+    format: FeedFormat,
+    /// The title of the feed
+    #[clap(long, default_value = "Architecture Decision Records")]
+    title: String,
+    /// The number of most recently changed ADRs to include
+    #[clap(long, default_value_t = 20)]
+    limit: usize,
+    /// Write the feed to this file instead of printing it
+    #[clap(long, short)]
+    output: Option<PathBuf>,
+    #[clap(flatten)]
+    filter: FilterArgs,
+}
+
+#[derive(Debug, Serialize)]
+struct EntryContext {
+    title: String,
+    link: String,
+    updated: String,
+    summary: String,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct FeedContext {
+    title: String,
+    link: String,
+    // empty when there are no entries
+    updated: String,
+    entries: Vec<EntryContext>,
+}
+
+// the date in the form the feed uses, at midnight UTC as ADRs are dated by the day
+fn format_date(date: Date, format: FeedFormat) -> Result<String> {
+    let datetime = date.midnight().assume_utc();
+    Ok(match format {
+        FeedFormat::Atom => datetime.format(&Rfc3339)?,
+        FeedFormat::Rss => datetime.format(&Rfc2822)?,
+    })
+}
+
+// what happened to the ADR on the date it appears in the feed
+fn summary(adr: &Adr, changed: bool) -> String {
+    match (adr.current_status(), changed) {
+        (Some(status), true) => format!("Status changed to {}", status),
+        (Some(status), false) => format!("Created with status {}", status),
+        (None, _) => "Created".to_string(),
+    }
+}
+
+pub(crate) fn run_feed(args: &FeedArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let base_url = args.base_url.trim_end_matches('/');
+
+    // each ADR appears once, on the day it was created or last changed status
+    let mut entries = Vec::new();
+    for path in args.filter.select_adrs(&adr_dir)? {
+        let adr = Adr::from_path(&adr_dir, &path)?;
+        let created = adr.date.as_deref().and_then(|date| parse_date(date).ok());
+        let changed = last_status_change(&adr_dir, &adr).filter(|changed| Some(*changed) > created);
+        let Some(date) = changed.or(created) else {
+            continue;
+        };
+        let link = format!(
+            "{}/{}",
+            base_url,
+            to_forward_slashes(&relative_path(&adr_dir, &path))
+        );
+        entries.push((date, adr, link, changed.is_some()));
+    }
+    entries.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.number.cmp(&a.1.number)));
+    entries.truncate(args.limit);
+
+    let context = FeedContext {
+        title: args.title.clone(),
+        link: base_url.to_string(),
+        updated: match entries.first() {
+            Some((date, ..)) => format_date(*date, args.format)?,
+            None => String::new(),
+        },
+        entries: entries
+            .iter()
+            .map(|(date, adr, link, changed)| {
+                Ok(EntryContext {
+                    title: format!("{}. {}", adr.number, adr.title),
+                    link: link.clone(),
+                    updated: format_date(*date, args.format)?,
+                    summary: summary(adr, *changed),
+                    tags: adr.tags.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?,
+    };
+
+    let mut tt = TinyTemplate::new();
+    let template = match args.format {
+        FeedFormat::Atom => ATOM_TEMPLATE,
+        FeedFormat::Rss => RSS_TEMPLATE,
+    };
+    tt.add_template("feed", template)?;
+    let feed = tt
+        .render("feed", &context)
+        .context("Unable to render the feed template")?;
+
+    match &args.output {
+        Some(output) => std::fs::write(output, feed)
+            .with_context(|| format!("Unable to write {}", output.display()))?,
+        None => print!("{}", feed),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(
+            format_date(date!(2024 - 03 - 01), FeedFormat::Atom).unwrap(),
+            "2024-03-01T00:00:00Z"
+        );
+        assert_eq!(
+            format_date(date!(2024 - 03 - 01), FeedFormat::Rss).unwrap(),
+            "Fri, 01 Mar 2024 00:00:00 +0000"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::Serialize;
+use tinytemplate::TinyTemplate;
+
+use crate::adr::{find_adr, find_adr_dir, slugify};
+use crate::config::load_config;
+use crate::model::{normalize_status, Adr};
+
+// the conventional commit recording the ADR's status, e.g. `docs(adr): accept 0007 use postgresql`
+static COMMIT_MSG_TEMPLATE: &str = "docs(adr): {action} {number} {lower_title}";
+static BRANCH_NAME_TEMPLATE: &str = "adr/{number}-{slug}";
+
+#[derive(Debug, Args)]
+pub(crate) struct CommitMsgArgs {
+    /// The Architectural Decision Record number or file name match
+    adr: String,
+    /// The status the commit records, instead of the ADR's current status
+    #[clap(long)]
+    status: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct BranchNameArgs {
+    /// The Architectural Decision Record number or file name match
+    adr: String,
+    /// The status the branch works towards, instead of the ADR's current status
+    #[clap(long)]
+    status: Option<String>,
+}
+
+// what the commit and branch templates can use
+#[derive(Debug, Serialize)]
+struct GitContext {
+    number: String,
+    title: String,
+    lower_title: String,
+    slug: String,
+    status: String,
+    action: String,
+}
+
+// the verb for moving an ADR to the status, e.g. accept for Accepted
+fn status_action(status: &str) -> String {
+    match status {
+        "Proposed" => "propose".to_string(),
+        "Accepted" => "accept".to_string(),
+        "Rejected" => "reject".to_string(),
+        "Deprecated" => "deprecate".to_string(),
+        "Superseded" => "supersede".to_string(),
+        "Implemented" => "implement".to_string(),
+        "Withdrawn" => "withdraw".to_string(),
+        status => status.to_lowercase(),
+    }
+}
+
+// render the template for the ADR
+fn render(adr: &Adr, status: Option<&str>, template: &str) -> Result<String> {
+    let status = match status {
+        Some(status) => normalize_status(status),
+        None => adr
+            .current_status()
+            .unwrap_or_else(|| "Proposed".to_string()),
+    };
+    let context = GitContext {
+        number: format!("{:0>4}", adr.number),
+        title: adr.title.clone(),
+        lower_title: adr.title.to_lowercase(),
+        slug: slugify(&adr.title),
+        action: status_action(&status),
+        status: status.to_lowercase(),
+    };
+    let mut tt = TinyTemplate::new();
+    tt.set_default_formatter(&tinytemplate::format_unescaped);
+    tt.add_template("git", template)
+        .with_context(|| format!("Invalid template: {}", template))?;
+    Ok(tt.render("git", &context)?.trim().to_string())
+}
+
+// make the name safe to use as a git branch, replacing what git doesn't allow in refs
+fn branch_safe(name: &str) -> String {
+    let mut name = name
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || c.is_control() || "~^:?*[\\".contains(c) {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect::<String>();
+    for (run, single) in [("--", "-"), ("..", ".")] {
+        while name.contains(run) {
+            name = name.replace(run, single);
+        }
+    }
+    name.trim_matches(|c| c == '-' || c == '/' || c == '.')
+        .trim_end_matches(".lock")
+        .to_string()
+}
+
+// read the ADR the arguments name
+fn load_adr(name: &str) -> Result<Adr> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let path = find_adr(&adr_dir, name)?;
+    Adr::from_path(&adr_dir, &path)
+}
+
+pub(crate) fn run_commit_msg(args: &CommitMsgArgs) -> Result<()> {
+    let config = load_config().context("Unable to load configuration")?;
+    let template = config.generate.commit_msg.as_deref();
+    let adr = load_adr(&args.adr)?;
+    println!(
+        "{}",
+        render(
+            &adr,
+            args.status.as_deref(),
+            template.unwrap_or(COMMIT_MSG_TEMPLATE)
+        )?
+    );
+    Ok(())
+}
+
+pub(crate) fn run_branch_name(args: &BranchNameArgs) -> Result<()> {
+    let config = load_config().context("Unable to load configuration")?;
+    let template = config.generate.branch_name.as_deref();
+    let adr = load_adr(&args.adr)?;
+    let name = render(
+        &adr,
+        args.status.as_deref(),
+        template.unwrap_or(BRANCH_NAME_TEMPLATE),
+    )?;
+    println!("{}", branch_safe(&name));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let adr = Adr {
+            number: 7,
+            title: "Use PostgreSQL".to_string(),
+            status: Some("Proposed".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            render(&adr, Some("accepted"), COMMIT_MSG_TEMPLATE).unwrap(),
+            "docs(adr): accept 0007 use postgresql"
+        );
+        assert_eq!(
+            render(&adr, None, BRANCH_NAME_TEMPLATE).unwrap(),
+            "adr/0007-use-postgresql"
+        );
+        assert_eq!(
+            branch_safe(&render(&adr, None, "adr/{status}/{title}").unwrap()),
+            "adr/proposed/Use-PostgreSQL"
+        );
+    }
+
+    #[test]
+    fn test_branch_safe() {
+        assert_eq!(branch_safe("adr/0007 why? ..now:"), "adr/0007-why-.now");
+        assert_eq!(branch_safe("/adr/x.lock"), "adr/x");
+    }
+}
@@ -1,9 +1,20 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use clap::Args;
+use clap::{Args, ValueEnum};
+use pulldown_cmark::escape::escape_html;
+use serde::Serialize;
 
-use crate::adr::{find_adr_dir, get_links, get_title, list_adrs};
+use super::{check_artifact, relative_path, FilterArgs};
+use crate::adr::{
+    find_adr, find_adr_dir, get_current_status, get_links, get_number, get_title,
+    reverse_link_kind, to_forward_slashes,
+};
+use crate::config::{load_config, MarkerStyle, RankDir, CONFIG_FILE};
+use crate::markers::Markers;
+use crate::model::Adr;
 
 #[derive(Debug, Args)]
 pub(crate) struct GraphArgs {
@@ -13,61 +24,809 @@ pub(crate) struct GraphArgs {
     /// Link prefix
     #[clap(long, short)]
     prefix: Option<String>,
+    /// Fail if the given file differs from the graph instead of printing it
+    #[clap(long, value_name = "FILE")]
+    check: Option<PathBuf>,
+    /// Explain how two ADRs are related by printing the shortest chain of links between them
+    #[clap(long, num_args = 2, value_names = ["FROM", "TO"], conflicts_with = "check")]
+    why: Option<Vec<String>>,
+    /// The diagram language to write the graph in
+    #[clap(long, value_enum, default_value_t = GraphFormat::Dot)]
+    format: GraphFormat,
+    /// A title for the diagram
+    #[clap(long)]
+    title: Option<String>,
+    /// Add a legend of the statuses in the graph and their colours
+    #[clap(long, default_value_t = false)]
+    legend: bool,
+    /// Which way the graph runs, overriding generate.graph.rankdir in the configuration
+    #[clap(long, value_enum)]
+    rankdir: Option<RankDir>,
+    /// Mark each ADR with its status, overriding markers.style in the configuration
+    #[clap(long, value_enum)]
+    markers: Option<MarkerStyle>,
+    /// Graph the ADRs of every configured scope together, each labelled with its scope
+    #[clap(long, default_value_t = false, conflicts_with = "why")]
+    all_scopes: bool,
+    #[clap(flatten)]
+    filter: FilterArgs,
+}
+
+/// Diagram languages for the graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum GraphFormat {
+    /// Graphviz dot
+    Dot,
+    /// PlantUML, with ADRs styled by status
+    Plantuml,
+    /// GraphML, for analysis in Gephi, yEd or Cytoscape
+    Graphml,
+    /// Cytoscape.js JSON elements, for analysis in Cytoscape
+    CytoscapeJson,
+    /// A self-contained HTML page with a force-directed graph to explore: click an ADR to open
+    /// it, drag to rearrange and filter by status, tag or title
+    Html,
+}
+
+// the page for the html format, with {{title}} and {{graph}} filled in when rendering. The
+// script's braces rule out TinyTemplate
+static GRAPH_HTML_TEMPLATE: &str = include_str!("../../../templates/graph/graph.html");
+
+// the shortest chain of links from one ADR to another, as the kind of each link and the ADR it
+// leads to. Links are followed in either direction, a link written only on the other ADR being
+// read as its reverse
+fn shortest_chain(adrs: &[Adr], from: i32, to: i32) -> Option<Vec<(String, i32)>> {
+    let mut edges: BTreeMap<i32, Vec<(String, i32)>> = BTreeMap::new();
+    for adr in adrs {
+        for link in &adr.links {
+            edges
+                .entry(adr.number)
+                .or_default()
+                .push((link.kind.clone(), link.target));
+            edges
+                .entry(link.target)
+                .or_default()
+                .push((reverse_link_kind(&link.kind), adr.number));
+        }
+    }
+
+    // breadth first, remembering how each ADR was reached
+    let mut reached: BTreeMap<i32, Option<(String, i32)>> = BTreeMap::from([(from, None)]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(number) = queue.pop_front() {
+        if number == to {
+            let mut chain = Vec::new();
+            let mut current = to;
+            while let Some(Some((kind, previous))) = reached.get(&current) {
+                chain.push((kind.clone(), current));
+                current = *previous;
+            }
+            chain.reverse();
+            return Some(chain);
+        }
+        for (kind, target) in edges.get(&number).into_iter().flatten() {
+            if !reached.contains_key(target) {
+                reached.insert(*target, Some((kind.clone(), number)));
+                queue.push_back(*target);
+            }
+        }
+    }
+    None
+}
+
+fn run_why(adr_dir: &Path, paths: &[PathBuf], from: &str, to: &str) -> Result<()> {
+    let adrs = paths
+        .iter()
+        .map(|path| Adr::from_path(adr_dir, path))
+        .collect::<Result<Vec<_>>>()?;
+    let titles = adrs
+        .iter()
+        .map(|adr| (adr.number, format!("{}. {}", adr.number, adr.title)))
+        .collect::<BTreeMap<_, _>>();
+    let title = |number: i32| {
+        titles
+            .get(&number)
+            .cloned()
+            .unwrap_or_else(|| format!("ADR {}", number))
+    };
+
+    let number = |reference: &str| -> Result<i32> {
+        let path = find_adr(adr_dir, reference)?;
+        get_number(&path).with_context(|| format!("{} isn't a numbered ADR", path.display()))
+    };
+    let (from, to) = (number(from)?, number(to)?);
+    match shortest_chain(&adrs, from, to) {
+        Some(chain) => {
+            println!("{}", title(from));
+            for (kind, number) in chain {
+                println!("  {} {}", kind, title(number));
+            }
+        }
+        None => println!(
+            "No chain of links connects {} and {}",
+            title(from),
+            title(to)
+        ),
+    }
+    Ok(())
+}
+
+// what an ADR is known by in the graph: its number, and its scope in a combined graph, as scopes
+// number their ADRs independently
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct NodeKey {
+    scope: Option<String>,
+    number: i32,
+}
+
+impl NodeKey {
+    // the key as text, e.g. 12 or payments/12
+    fn name(&self) -> String {
+        match &self.scope {
+            Some(scope) => format!("{}/{}", scope, self.number),
+            None => self.number.to_string(),
+        }
+    }
+
+    // the key as an identifier for the diagram languages, e.g. _12 or _payments_12
+    fn id(&self) -> String {
+        format!(
+            "_{}",
+            self.name()
+                .replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+        )
+    }
+}
+
+// an ADR in the graph: its key, numbered title, status, tags, URL and outgoing links as
+// (kind, key)
+struct Node {
+    key: NodeKey,
+    title: String,
+    status: Option<String>,
+    tags: Vec<String>,
+    url: String,
+    links: Vec<(String, NodeKey)>,
+}
+
+// how the graph looks, from the options and the [generate.graph] configuration
+struct Theme {
+    title: Option<String>,
+    legend: bool,
+    rankdir: RankDir,
+    font: Option<String>,
+    // the fill colour for each status
+    colors: BTreeMap<String, String>,
+}
+
+impl Theme {
+    fn color(&self, node: &Node) -> Option<&str> {
+        node.status
+            .as_ref()
+            .and_then(|status| self.colors.get(status))
+            .map(String::as_str)
+    }
+
+    // the statuses of the ADRs in the graph, for the legend
+    fn statuses<'a>(&self, nodes: &'a [Node]) -> BTreeSet<&'a str> {
+        nodes
+            .iter()
+            .filter_map(|node| node.status.as_deref())
+            .collect()
+    }
+}
+
+// the Graphviz dot graph, with the ADRs in order and labelled links between them
+fn render_dot(nodes: &[Node], theme: &Theme) -> Result<String> {
+    let mut out = String::new();
+    writeln!(out, "digraph {{")?;
+    if let Some(title) = &theme.title {
+        writeln!(out, "  label=\"{}\";\n  labelloc=\"t\";", title)?;
+    }
+    if theme.rankdir != RankDir::Tb {
+        writeln!(
+            out,
+            "  rankdir={};",
+            format!("{:?}", theme.rankdir).to_uppercase()
+        )?;
+    }
+    if let Some(font) = &theme.font {
+        writeln!(
+            out,
+            "  fontname=\"{0}\";\n  node [fontname=\"{0}\"]\n  edge [fontname=\"{0}\"]",
+            font
+        )?;
+    }
+    writeln!(out, "  node [shape=plaintext]\n  subgraph {{")?;
+    let mut previous = None;
+    for node in nodes {
+        let style = match theme.color(node) {
+            Some(color) => format!("; shape=box; style=filled; fillcolor=\"{}\"", color),
+            None => String::new(),
+        };
+        writeln!(
+            out,
+            "\t{} [label=\"{}\"; URL=\"{}\"{}];",
+            node.key.id(),
+            node.title,
+            node.url,
+            style
+        )?;
+        if let Some(previous) = previous {
+            writeln!(
+                out,
+                "\t{} -> {} [style=\"dotted\", weight=1];",
+                previous,
+                node.key.id()
+            )?;
+        }
+        previous = Some(node.key.id());
+    }
+    writeln!(out, "  }}")?;
+    for node in nodes {
+        for (kind, target) in &node.links {
+            writeln!(
+                out,
+                "  {} -> {} [label=\"{}\", weight=0];",
+                node.key.id(),
+                target.id(),
+                kind
+            )?;
+        }
+    }
+    if theme.legend {
+        writeln!(out, "  subgraph cluster_legend {{\n    label=\"Legend\";")?;
+        for (i, status) in theme.statuses(nodes).into_iter().enumerate() {
+            let style = match theme.colors.get(status) {
+                Some(color) => format!("; style=filled; fillcolor=\"{}\"", color),
+                None => String::new(),
+            };
+            writeln!(
+                out,
+                "    legend_{} [label=\"{}\"; shape=box{}];",
+                i, status, style
+            )?;
+        }
+        writeln!(out, "  }}")?;
+    }
+    writeln!(out, "}}")?;
+    Ok(out)
+}
+
+// the background colour of ADRs in each status, in PlantUML's notation
+static PLANTUML_STATUS_COLORS: &[(&str, &str)] = &[
+    ("Proposed", "#FFF9C4"),
+    ("Accepted", "#C8E6C9"),
+    ("Implemented", "#A5D6A7"),
+    ("Superseded", "#E0E0E0"),
+    ("Deprecated", "#FFE0B2"),
+    ("Rejected", "#FFCDD2"),
+    ("Withdrawn", "#F5F5F5"),
+    ("Retired", "#CFD8DC"),
+];
+
+// the PlantUML diagram, with each ADR a rectangle styled by its status
+fn render_plantuml(nodes: &[Node], theme: &Theme) -> Result<String> {
+    let mut out = String::new();
+    writeln!(out, "@startuml")?;
+    if let Some(title) = &theme.title {
+        writeln!(out, "title {}", title)?;
+    }
+    match theme.rankdir {
+        RankDir::Tb => {}
+        RankDir::Lr => writeln!(out, "left to right direction")?,
+        // PlantUML only lays out top to bottom or left to right
+        RankDir::Bt | RankDir::Rl => {}
+    }
+    if let Some(font) = &theme.font {
+        writeln!(out, "skinparam defaultFontName {}", font)?;
+    }
+    // the built-in colours, with the configured ones taking their place
+    let mut colors = PLANTUML_STATUS_COLORS
+        .iter()
+        .map(|(status, color)| (status.to_string(), color.to_string()))
+        .collect::<Vec<_>>();
+    for (status, color) in &theme.colors {
+        match colors.iter_mut().find(|(s, _)| s == status) {
+            Some((_, c)) => *c = color.clone(),
+            None => colors.push((status.clone(), color.clone())),
+        }
+    }
+    writeln!(out, "skinparam rectangle {{")?;
+    for (status, color) in &colors {
+        writeln!(out, "  BackgroundColor<<{}>> {}", status, color)?;
+    }
+    writeln!(out, "}}")?;
+    let mut previous = None;
+    for node in nodes {
+        let stereotype = node
+            .status
+            .as_ref()
+            .map(|status| format!(" <<{}>>", status))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "rectangle \"{}\"{} as {} [[{}]]",
+            node.title.replace('"', "'"),
+            stereotype,
+            node.key.id(),
+            node.url
+        )?;
+        if let Some(previous) = previous {
+            writeln!(out, "{} .[#999999].> {}", previous, node.key.id())?;
+        }
+        previous = Some(node.key.id());
+    }
+    for node in nodes {
+        for (kind, target) in &node.links {
+            writeln!(out, "{} --> {} : {}", node.key.id(), target.id(), kind)?;
+        }
+    }
+    if theme.legend {
+        writeln!(out, "legend right\n|= Status |")?;
+        for status in theme.statuses(nodes) {
+            match colors.iter().find(|(s, _)| s == status) {
+                Some((_, color)) => writeln!(out, "|<{}> {} |", color, status)?,
+                None => writeln!(out, "| {} |", status)?,
+            }
+        }
+        writeln!(out, "endlegend")?;
+    }
+    writeln!(out, "@enduml")?;
+    Ok(out)
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::new();
+    let _ = escape_html(&mut out, text);
+    out
+}
+
+// the GraphML graph, with the number, scope, title, status and URL of each ADR as node data and
+// the kind of each link as edge data. Only the links are edges, as the order of the ADRs is only there to
+// lay out the other formats
+fn render_graphml(nodes: &[Node], theme: &Theme) -> Result<String> {
+    let mut out = String::new();
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        out,
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+    )?;
+    let scoped = nodes.iter().any(|node| node.key.scope.is_some());
+    for (id, target, name, kind) in [
+        ("number", "node", "number", "int"),
+        ("scope", "node", "scope", "string"),
+        ("title", "node", "title", "string"),
+        ("status", "node", "status", "string"),
+        ("url", "node", "url", "string"),
+        ("kind", "edge", "kind", "string"),
+    ] {
+        // only a combined graph has scopes
+        if id == "scope" && !scoped {
+            continue;
+        }
+        writeln!(
+            out,
+            "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>",
+            id, target, name, kind
+        )?;
+    }
+    let id = theme
+        .title
+        .as_deref()
+        .map(|title| format!(" id=\"{}\"", escape(title)))
+        .unwrap_or_default();
+    writeln!(out, "  <graph{} edgedefault=\"directed\">", id)?;
+    for node in nodes {
+        writeln!(out, "    <node id=\"{}\">", node.key.id())?;
+        writeln!(out, "      <data key=\"number\">{}</data>", node.key.number)?;
+        if let Some(scope) = &node.key.scope {
+            writeln!(out, "      <data key=\"scope\">{}</data>", escape(scope))?;
+        }
+        writeln!(
+            out,
+            "      <data key=\"title\">{}</data>",
+            escape(&node.title)
+        )?;
+        if let Some(status) = &node.status {
+            writeln!(out, "      <data key=\"status\">{}</data>", escape(status))?;
+        }
+        writeln!(out, "      <data key=\"url\">{}</data>", escape(&node.url))?;
+        writeln!(out, "    </node>")?;
+    }
+    for node in nodes {
+        for (kind, target) in &node.links {
+            writeln!(
+                out,
+                "    <edge source=\"{}\" target=\"{}\">\n      <data key=\"kind\">{}</data>\n    </edge>",
+                node.key.id(),
+                target.id(),
+                escape(kind)
+            )?;
+        }
+    }
+    writeln!(out, "  </graph>\n</graphml>")?;
+    Ok(out)
+}
+
+// the graph as Cytoscape.js elements, which Cytoscape imports as a network
+#[derive(Debug, Serialize)]
+struct CytoscapeGraph {
+    elements: CytoscapeElements,
+}
+
+#[derive(Debug, Serialize)]
+struct CytoscapeElements {
+    nodes: Vec<CytoscapeElement<CytoscapeNode>>,
+    edges: Vec<CytoscapeElement<CytoscapeEdge>>,
+}
+
+#[derive(Debug, Serialize)]
+struct CytoscapeElement<T> {
+    data: T,
+}
+
+#[derive(Debug, Serialize)]
+struct CytoscapeNode {
+    id: String,
+    number: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CytoscapeEdge {
+    id: String,
+    source: String,
+    target: String,
+    kind: String,
+}
+
+fn render_cytoscape(nodes: &[Node]) -> Result<String> {
+    let graph = CytoscapeGraph {
+        elements: CytoscapeElements {
+            nodes: nodes
+                .iter()
+                .map(|node| CytoscapeElement {
+                    data: CytoscapeNode {
+                        id: node.key.name(),
+                        number: node.key.number,
+                        scope: node.key.scope.clone(),
+                        name: node.title.clone(),
+                        status: node.status.clone(),
+                        url: node.url.clone(),
+                    },
+                })
+                .collect(),
+            edges: nodes
+                .iter()
+                .flat_map(|node| {
+                    node.links
+                        .iter()
+                        .enumerate()
+                        .map(|(i, (kind, target))| CytoscapeElement {
+                            data: CytoscapeEdge {
+                                id: format!("{}-{}-{}", node.key.name(), target.name(), i),
+                                source: node.key.name(),
+                                target: target.name(),
+                                kind: kind.clone(),
+                            },
+                        })
+                })
+                .collect(),
+        },
+    };
+    Ok(serde_json::to_string_pretty(&graph)? + "\n")
+}
+
+// the graph for the html page, with the colour of each ADR from the theme
+#[derive(Debug, Serialize)]
+struct HtmlGraph<'a> {
+    nodes: Vec<HtmlNode<'a>>,
+    edges: Vec<HtmlEdge<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct HtmlNode<'a> {
+    id: String,
+    number: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scope: Option<&'a str>,
+    title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<&'a str>,
+    tags: &'a [String],
+    url: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct HtmlEdge<'a> {
+    source: String,
+    target: String,
+    kind: &'a str,
+}
+
+fn render_html(nodes: &[Node], theme: &Theme) -> Result<String> {
+    let graph = HtmlGraph {
+        nodes: nodes
+            .iter()
+            .map(|node| HtmlNode {
+                id: node.key.name(),
+                number: node.key.number,
+                scope: node.key.scope.as_deref(),
+                title: &node.title,
+                status: node.status.as_deref(),
+                tags: &node.tags,
+                url: &node.url,
+                color: theme.color(node),
+            })
+            .collect(),
+        edges: nodes
+            .iter()
+            .flat_map(|node| {
+                node.links.iter().map(|(kind, target)| HtmlEdge {
+                    source: node.key.name(),
+                    target: target.name(),
+                    kind,
+                })
+            })
+            .collect(),
+    };
+    // the JSON sits in a script element, which mustn't see a closing tag inside it
+    let json = serde_json::to_string(&graph)?.replace("</", "<\\/");
+    let title = theme
+        .title
+        .as_deref()
+        .unwrap_or("Architectural Decision Records");
+    let mut escaped = String::new();
+    escape_html(&mut escaped, title)?;
+    Ok(GRAPH_HTML_TEMPLATE
+        .replace("{{title}}", &escaped)
+        .replace("{{graph}}", &json))
+}
+
+// the scope of the ADR a link leads to: the scope whose directory the linked file is in, or the
+// linking ADR's own scope for a file beside it. None if the file is outside the graph's directories
+fn link_scope<'a>(
+    scope: &'a Option<String>,
+    adr_dir: &Path,
+    file: &str,
+    dirs: &'a [(Option<String>, PathBuf)],
+) -> Option<&'a Option<String>> {
+    if !file.contains('/') {
+        return Some(scope);
+    }
+    let target_dir = adr_dir.join(file).parent()?.canonicalize().ok()?;
+    dirs.iter()
+        .find(|(_, dir)| dir.canonicalize().ok().as_ref() == Some(&target_dir))
+        .map(|(scope, _)| scope)
 }
 
 pub fn run_graph(args: &GraphArgs) -> Result<()> {
-    let adr_dir = find_adr_dir().context("No ADR directory found")?;
-    let adrs = list_adrs(Path::new(&adr_dir))?;
+    let config = load_config().context("Unable to load configuration")?;
+    // the ADR directories in the graph, with their scope in a combined graph
+    let dirs = if args.all_scopes {
+        if config.scopes.is_empty() {
+            anyhow::bail!(
+                "No scopes are configured, add them to [scopes] in {}",
+                CONFIG_FILE
+            );
+        }
+        config
+            .scopes
+            .iter()
+            // a scope without any ADRs yet may not have its directory
+            .filter(|(_, dir)| dir.is_dir())
+            .map(|(scope, dir)| (Some(scope.clone()), dir.clone()))
+            .collect::<Vec<_>>()
+    } else {
+        let adr_dir = find_adr_dir().context("No ADR directory found")?;
+        if let Some(why) = &args.why {
+            let adrs = args.filter.select_adrs(&adr_dir)?;
+            return run_why(&adr_dir, &adrs, &why[0], &why[1]);
+        }
+        vec![(None, adr_dir)]
+    };
 
     let extension = args
         .extension
         .trim_start_matches(|c| char::is_ascii_punctuation(&c));
-    let items = adrs
-        .into_iter()
-        .map(|path| {
-            let title = get_title(path.as_path()).unwrap();
-            let filename = path.file_name().unwrap().to_str().unwrap().to_owned();
-            let number = filename.split('-').next().unwrap().parse::<i32>().unwrap();
-            let links = get_links(path.as_path()).unwrap();
-            (number, title, filename, links)
-        })
-        .collect::<Vec<_>>();
+    // files without a number in their name, such as 3.md, have nothing to key them by
+    let mut adrs = Vec::new();
+    for (scope, adr_dir) in &dirs {
+        for path in args.filter.select_adrs(adr_dir)? {
+            if let Some(number) = get_number(&path) {
+                let key = NodeKey {
+                    scope: scope.clone(),
+                    number,
+                };
+                adrs.push((key, adr_dir, path));
+            }
+        }
+    }
+    let keys = adrs
+        .iter()
+        .map(|(key, _, _)| key.clone())
+        .collect::<BTreeSet<_>>();
+    let markers = Markers::load(args.markers)?;
+    let mut nodes = Vec::new();
+    for (key, adr_dir, path) in adrs {
+        let scope = &key.scope;
+        // a combined graph links to the ADRs by their paths from the project
+        let mut url = match scope {
+            Some(_) => path.clone(),
+            None => relative_path(adr_dir, &path),
+        };
+        url.set_extension(extension);
+        if let Some(prefix) = &args.prefix {
+            url = PathBuf::from(prefix).join(url);
+        }
+        let links = get_links(&path)?
+            .into_iter()
+            .filter_map(|(kind, title, file)| {
+                let target = NodeKey {
+                    scope: link_scope(scope, adr_dir, &file, &dirs)?.clone(),
+                    number: title.split_once(". ")?.0.parse().ok()?,
+                };
+                // leave out links to ADRs that were filtered out of the graph
+                keys.contains(&target).then_some((kind, target))
+            })
+            .collect();
+        let status = get_current_status(&path)?;
+        // only the html page filters by tag
+        let tags = match args.format {
+            GraphFormat::Html => Adr::from_path(adr_dir, &path)?.tags,
+            _ => Vec::new(),
+        };
+        let scope_prefix = scope
+            .as_ref()
+            .map(|scope| format!("{}/", scope))
+            .unwrap_or_default();
+        let title = format!(
+            "{}{}{}",
+            markers.prefix(status.as_deref()),
+            scope_prefix,
+            get_title(&path)?
+        );
+        nodes.push(Node {
+            key,
+            title,
+            status,
+            tags,
+            url: to_forward_slashes(&url),
+            links,
+        });
+    }
 
-    println!("digraph {{\n  node [shape=plaintext]\n  subgraph {{");
-    for (number, title, filename, _links) in &items {
-        let mut path = PathBuf::from(&filename);
-        path.set_extension(extension);
+    let config = config.generate.graph;
+    let theme = Theme {
+        title: args.title.clone(),
+        legend: args.legend,
+        rankdir: args.rankdir.or(config.rankdir).unwrap_or_default(),
+        font: config.font,
+        colors: config.colors,
+    };
+    let out = match args.format {
+        GraphFormat::Dot => render_dot(&nodes, &theme)?,
+        GraphFormat::Plantuml => render_plantuml(&nodes, &theme)?,
+        GraphFormat::Graphml => render_graphml(&nodes, &theme)?,
+        GraphFormat::CytoscapeJson => render_cytoscape(&nodes)?,
+        GraphFormat::Html => render_html(&nodes, &theme)?,
+    };
+    match &args.check {
+        Some(path) => check_artifact(path, &out),
+        None => {
+            print!("{}", out);
+            Ok(())
+        }
+    }
+}
 
-        path = match &args.prefix {
-            Some(prefix) => PathBuf::from(prefix).join(path),
-            None => path,
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        println!(
-            "\t_{} [label=\"{}\"; URL=\"{}\"];",
+    fn key(number: i32) -> NodeKey {
+        NodeKey {
+            scope: None,
             number,
-            title,
-            &path.display()
+        }
+    }
+
+    #[test]
+    fn test_shortest_chain() {
+        let adrs = [
+            "# 1. A\n\n## Status\n\nAccepted\n",
+            "# 2. B\n\n## Status\n\nAccepted\n\nAmends [1. A](0001-a.md)\n",
+            "# 3. C\n\n## Status\n\nAccepted\n\nDepends on [2. B](0002-b.md)\n",
+            "# 4. D\n\n## Status\n\nAccepted\n",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, markdown)| {
+            Adr::parse(Path::new(&format!("{:04}-x.md", i + 1)), markdown).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            shortest_chain(&adrs, 3, 1),
+            Some(vec![
+                ("Depends on".to_string(), 2),
+                ("Amends".to_string(), 1)
+            ])
+        );
+        assert_eq!(
+            shortest_chain(&adrs, 1, 3),
+            Some(vec![
+                ("Amended by".to_string(), 2),
+                ("Required by".to_string(), 3)
+            ])
         );
+        assert_eq!(shortest_chain(&adrs, 1, 1), Some(Vec::new()));
+        assert_eq!(shortest_chain(&adrs, 1, 4), None);
+    }
 
-        if *number > 1 {
-            println!(
-                "\t_{} -> _{} [style=\"dotted\", weight=1];",
-                number - 1,
-                number
-            );
-        }
+    #[test]
+    fn test_render_graphml_escapes() {
+        let nodes = [Node {
+            key: key(1),
+            title: "1. Use <R&D> \"budget\"".to_string(),
+            status: None,
+            tags: Vec::new(),
+            url: "0001-use-r-d-budget.html".to_string(),
+            links: Vec::new(),
+        }];
+        let theme = Theme {
+            title: None,
+            legend: false,
+            rankdir: RankDir::Tb,
+            font: None,
+            colors: BTreeMap::new(),
+        };
+        assert!(render_graphml(&nodes, &theme)
+            .unwrap()
+            .contains("<data key=\"title\">1. Use &lt;R&amp;D&gt; &quot;budget&quot;</data>"));
     }
-    println!("  }}");
-    for (number, _title, _filename, links) in &items {
-        for (link, title, _file) in links {
-            let linked_number = title.split_once(". ").unwrap().0;
-            println!(
-                "  _{} -> _{} [label=\"{}\", weight=0];",
-                number, linked_number, link
-            )
-        }
+
+    #[test]
+    fn test_render_html() {
+        let nodes = [
+            Node {
+                key: key(1),
+                title: "1. Keep </script> out".to_string(),
+                status: Some("Accepted".to_string()),
+                tags: vec!["web".to_string()],
+                url: "0001-keep-script-out.html".to_string(),
+                links: Vec::new(),
+            },
+            Node {
+                key: key(2),
+                title: "2. Use Postgres".to_string(),
+                status: None,
+                tags: Vec::new(),
+                url: "0002-use-postgres.html".to_string(),
+                links: vec![("Amends".to_string(), key(1))],
+            },
+        ];
+        let theme = Theme {
+            title: Some("R&D".to_string()),
+            legend: false,
+            rankdir: RankDir::Tb,
+            font: None,
+            colors: BTreeMap::from([("Accepted".to_string(), "#00ff00".to_string())]),
+        };
+        let html = render_html(&nodes, &theme).unwrap();
+        assert!(html.contains("<title>R&amp;D</title>"));
+        assert!(html.contains(
+            r##"{"id":"1","number":1,"title":"1. Keep <\/script> out","status":"Accepted","tags":["web"],"url":"0001-keep-script-out.html","color":"#00ff00"}"##
+        ));
+        assert!(html.contains(r#""edges":[{"source":"2","target":"1","kind":"Amends"}]"#));
+        assert_eq!(html.matches("</script>").count(), 2);
     }
-    println!("}}");
-    Ok(())
 }
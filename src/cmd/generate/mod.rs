@@ -1,7 +1,15 @@
-use anyhow::Result;
-use clap::Subcommand;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+
+use crate::adr::{get_current_status, list_adrs, list_archived_adrs};
+use crate::config::load_config;
 
 pub mod book;
+pub mod changelog;
+pub mod feed;
+pub mod git;
 pub mod graph;
 pub mod toc;
 
@@ -13,6 +21,87 @@ pub(crate) enum GenerateCommands {
     Graph(graph::GraphArgs),
     /// Generate a book of the ADRs
     Book(book::BookArgs),
+    /// Generate an Atom or RSS feed of the most recently created or changed ADRs
+    Feed(feed::FeedArgs),
+    /// Generate a markdown changelog of the ADRs created, changed or superseded between two git
+    /// revisions, for release notes
+    Changelog(changelog::ChangelogArgs),
+    /// Generate the commit message for a change to an ADR, from the generate.commit_msg template
+    CommitMsg(git::CommitMsgArgs),
+    /// Generate the name of a branch for work on an ADR, from the generate.branch_name template
+    BranchName(git::BranchNameArgs),
+}
+
+// which ADRs to include in generated documentation, defaulting to the [generate] configuration
+#[derive(Debug, Args)]
+pub(crate) struct FilterArgs {
+    /// Include ADRs from the archive subdirectory
+    #[clap(long, default_value_t = false)]
+    include_archived: bool,
+    /// Leave out ADRs with this status. May be repeated [default: Rejected, Withdrawn]
+    #[clap(long)]
+    exclude_status: Vec<String>,
+    /// Include every ADR, archived or not, whatever its status
+    #[clap(long, default_value_t = false, conflicts_with_all = ["include_archived", "exclude_status"])]
+    all: bool,
+}
+
+impl FilterArgs {
+    // the ADRs to include, in order
+    pub(crate) fn select_adrs(&self, adr_dir: &Path) -> Result<Vec<PathBuf>> {
+        let config = load_config().context("Unable to load configuration")?;
+        let include_archived =
+            self.all || self.include_archived || config.generate.include_archived;
+        let exclude_status = match (self.all, self.exclude_status.is_empty()) {
+            (true, _) => Vec::new(),
+            (false, true) => config.generate.exclude_status,
+            (false, false) => self.exclude_status.clone(),
+        };
+
+        let mut adrs = list_adrs(adr_dir)?;
+        if include_archived {
+            adrs.extend(list_archived_adrs(adr_dir)?);
+        }
+
+        let mut selected = Vec::new();
+        for adr in adrs {
+            let status = get_current_status(&adr)?.unwrap_or_default();
+            if !exclude_status
+                .iter()
+                .any(|excluded| excluded.eq_ignore_ascii_case(&status))
+            {
+                selected.push(adr);
+            }
+        }
+        Ok(selected)
+    }
+}
+
+// the path of the ADR relative to the ADR directory, as used in generated links
+pub(crate) fn relative_path(adr_dir: &Path, adr: &Path) -> PathBuf {
+    adr.strip_prefix(adr_dir).unwrap_or(adr).to_path_buf()
+}
+
+// compare a generated artifact with the committed copy, failing when they differ so that CI can
+// enforce that generated documentation is kept up to date
+pub(crate) fn check_artifact(path: &Path, generated: &str) -> Result<()> {
+    let committed = std::fs::read_to_string(path)
+        .with_context(|| format!("Unable to read {}", path.display()))?;
+    let (committed, generated) = (committed.trim_end(), generated.trim_end());
+    if committed != generated {
+        let line = committed
+            .lines()
+            .zip(generated.lines())
+            .position(|(a, b)| a != b)
+            .unwrap_or_else(|| committed.lines().count().min(generated.lines().count()));
+        anyhow::bail!(
+            "{} is out of date, first difference at line {}",
+            path.display(),
+            line + 1
+        );
+    }
+    println!("{} is up to date", path.display());
+    Ok(())
 }
 
 pub(crate) fn run(args: &GenerateCommands) -> Result<()> {
@@ -20,5 +109,9 @@ pub(crate) fn run(args: &GenerateCommands) -> Result<()> {
         GenerateCommands::Toc(args) => toc::run_toc(args),
         GenerateCommands::Graph(args) => graph::run_graph(args),
         GenerateCommands::Book(args) => book::run_book(args),
+        GenerateCommands::Feed(args) => feed::run_feed(args),
+        GenerateCommands::Changelog(args) => changelog::run_changelog(args),
+        GenerateCommands::CommitMsg(args) => git::run_commit_msg(args),
+        GenerateCommands::BranchName(args) => git::run_branch_name(args),
     }
 }
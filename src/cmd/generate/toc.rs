@@ -1,13 +1,19 @@
 use std::{
+    collections::BTreeMap,
+    fmt::Write,
     fs::read_to_string,
     path::{Path, PathBuf},
 };
 
 use anyhow::{Context, Result};
-use clap::Args;
+use clap::{Args, ValueEnum};
 use regex::Regex;
 
-use crate::adr::{find_adr_dir, get_title, list_adrs};
+use super::{check_artifact, relative_path, FilterArgs};
+use crate::adr::{find_adr_dir, get_current_status, get_title, to_forward_slashes};
+use crate::config::MarkerStyle;
+use crate::markers::Markers;
+use crate::model::{Adr, STANDARD_STATUSES};
 
 #[derive(Debug, Args)]
 pub(crate) struct TocArgs {
@@ -23,6 +29,60 @@ pub(crate) struct TocArgs {
     /// Generate an ordered list with numbered ADR titles
     #[clap(long, short = 'O', default_value_t = false)]
     ordered: bool,
+    /// Split the list into sections, each headed with the group and how many ADRs are in it. ADRs
+    /// with several tags are listed under each
+    #[clap(long, value_enum, conflicts_with = "ordered")]
+    group_by: Option<TocGroup>,
+    /// Fail if the given file differs from the table of contents instead of printing it
+    #[clap(long, value_name = "FILE")]
+    check: Option<PathBuf>,
+    /// Mark each ADR with its status, overriding markers.style in the configuration
+    #[clap(long, value_enum)]
+    markers: Option<MarkerStyle>,
+    #[clap(flatten)]
+    filter: FilterArgs,
+}
+
+/// How to group the table of contents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum TocGroup {
+    /// A section per status, in the order ADRs move through them
+    Status,
+    /// A section per tag, alphabetically, with untagged ADRs last
+    Tag,
+}
+
+// the sections of a grouped table of contents, in order, each with its list items. Entries are
+// the item and the groups the ADR belongs to, none putting it in the last section
+fn group_toc(entries: Vec<(String, Vec<String>)>, group: TocGroup) -> Vec<(String, Vec<String>)> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut ungrouped = Vec::new();
+    for (item, keys) in entries {
+        if keys.is_empty() {
+            ungrouped.push(item.clone());
+        }
+        for key in keys {
+            groups.entry(key).or_default().push(item.clone());
+        }
+    }
+    let mut sections = groups.into_iter().collect::<Vec<_>>();
+    if group == TocGroup::Status {
+        // the standard statuses first, in order, then any others alphabetically
+        sections.sort_by_key(|(status, _)| {
+            STANDARD_STATUSES
+                .iter()
+                .position(|standard| standard == status)
+                .unwrap_or(STANDARD_STATUSES.len())
+        });
+    }
+    if !ungrouped.is_empty() {
+        let heading = match group {
+            TocGroup::Status => "No status",
+            TocGroup::Tag => "Untagged",
+        };
+        sections.push((heading.to_string(), ungrouped));
+    }
+    sections
 }
 
 pub fn get_ordinal(title: &String) -> Result<(u32, String)> {
@@ -39,53 +99,103 @@ pub fn get_ordinal(title: &String) -> Result<(u32, String)> {
     }
 }
 
-pub fn print_ordered_toc(mut toc_lines: Vec<(u32, String, PathBuf)>) -> Result<()> {
-    toc_lines.sort_by(|a, b| a.0.cmp(&b.0));
-    let mut expected_next_ordinal = 1;
+// write the ADRs as an ordered list. The numbers are left to the markdown renderer when they run
+// from 1 without gaps, and written out when ADRs left out of the table of contents, such as
+// rejected ones, leave gaps
+pub fn write_ordered_toc(
+    out: &mut String,
+    mut toc_lines: Vec<(u32, String, PathBuf)>,
+) -> Result<()> {
+    toc_lines.sort_by_key(|line| line.0);
+    if let Some(pair) = toc_lines.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        anyhow::bail!("More than one ADR is numbered {}", pair[0].0);
+    }
+    let contiguous = (1..).zip(&toc_lines).all(|(n, line)| line.0 == n);
     for line in toc_lines {
-        if line.0 != expected_next_ordinal {
-            return Err(anyhow::anyhow!(
-                "ADR ordering must start at 1 and increase linearly with no gaps"
-            ));
-        }
-        expected_next_ordinal += 1;
-        println!("1. [{}]({})", line.1, line.2.display());
+        let ordinal = if contiguous { 1 } else { line.0 };
+        writeln!(
+            out,
+            "{}. [{}]({})",
+            ordinal,
+            line.1,
+            to_forward_slashes(&line.2)
+        )?;
     }
     Ok(())
 }
 
 pub fn run_toc(args: &TocArgs) -> Result<()> {
     let adr_dir = find_adr_dir().context("No ADR directory found")?;
-    let adrs = list_adrs(Path::new(&adr_dir))?;
+    let adrs = args.filter.select_adrs(Path::new(&adr_dir))?;
 
-    println! {"# Architecture Decision Records\n"};
+    let mut out = String::new();
+    writeln!(out, "# Architecture Decision Records\n")?;
     if let Some(intro) = &args.intro {
-        println!("{}", read_to_string(intro)?);
+        writeln!(out, "{}", read_to_string(intro)?)?;
     }
 
+    let markers = Markers::load(args.markers)?;
     let mut toc_lines = Vec::<(u32, String, PathBuf)>::new();
+    let mut entries = Vec::new();
     for path in adrs {
+        let keys = match args.group_by {
+            Some(TocGroup::Status) => get_current_status(&path)?.into_iter().collect(),
+            Some(TocGroup::Tag) => Adr::from_path(&adr_dir, &path)?.tags,
+            None => Vec::new(),
+        };
         let title = get_title(&path)?;
-        let mut path = PathBuf::from(&path.file_name().unwrap().to_str().unwrap().to_owned());
+        let marker = if markers.enabled() {
+            markers.prefix(get_current_status(&path)?.as_deref())
+        } else {
+            String::new()
+        };
+        let mut path = relative_path(&adr_dir, &path);
 
         path = match &args.prefix {
             Some(prefix) => PathBuf::from(prefix).join(path),
             None => path,
         };
 
-        if !args.ordered {
-            println!("* [{}]({})", title, &path.display());
+        if args.group_by.is_some() {
+            let item = format!("* [{}{}]({})", marker, title, to_forward_slashes(&path));
+            entries.push((item, keys));
+        } else if !args.ordered {
+            writeln!(
+                out,
+                "* [{}{}]({})",
+                marker,
+                title,
+                to_forward_slashes(&path)
+            )?;
         } else {
-            let (ordinal, text) = get_ordinal(&title).unwrap();
-            toc_lines.push((ordinal, text, path));
+            let (ordinal, text) = get_ordinal(&title)?;
+            toc_lines.push((ordinal, marker + &text, path));
         }
     }
     if args.ordered {
-        print_ordered_toc(toc_lines).unwrap();
+        write_ordered_toc(&mut out, toc_lines)?;
+    }
+    if let Some(group) = args.group_by {
+        for (i, (heading, items)) in group_toc(entries, group).into_iter().enumerate() {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            writeln!(out, "## {} ({})\n", heading, items.len())?;
+            for item in items {
+                writeln!(out, "{}", item)?;
+            }
+        }
     }
 
     if let Some(outro) = &args.outro {
-        println!("\n{}", read_to_string(outro)?);
+        writeln!(out, "\n{}", read_to_string(outro)?)?;
+    }
+
+    match &args.check {
+        Some(path) => check_artifact(path, &out),
+        None => {
+            print!("{}", out);
+            Ok(())
+        }
     }
-    Ok(())
 }
@@ -0,0 +1,57 @@
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{find_adr, find_adr_dir, now, set_frontmatter_field, split_frontmatter};
+use crate::cmd::fmt::with_formatted_frontmatter;
+use crate::config::load_config;
+use crate::model::Implemented;
+use crate::transaction::Transaction;
+
+#[derive(Debug, Args)]
+pub(crate) struct ImplementArgs {
+    /// The Architectural Decision Record number or file name match
+    adr: String,
+    /// How far the decision has been implemented
+    #[arg(value_enum, default_value_t = Implemented::True)]
+    implemented: Implemented,
+    /// Where the implementation is tracked, e.g. an issue or pull request
+    #[arg(short, long)]
+    link: Option<String>,
+}
+
+pub(crate) fn run(args: &ImplementArgs) -> Result<()> {
+    let config = load_config().context("Unable to load configuration")?;
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let path = find_adr(&adr_dir, &args.adr)?;
+    let date = now()?;
+
+    let mut tx = Transaction::new();
+    tx.update(&path, |markdown| {
+        if split_frontmatter(markdown).0.is_empty() {
+            anyhow::bail!(
+                "{} has no frontmatter, implementation tracking needs nextgen mode",
+                path.display()
+            );
+        }
+        let mut markdown =
+            set_frontmatter_field(markdown, "implemented", args.implemented.as_str());
+        if args.implemented == Implemented::True {
+            markdown = set_frontmatter_field(&markdown, "implemented_date", &date);
+        }
+        if let Some(link) = &args.link {
+            markdown = set_frontmatter_field(&markdown, "implementation_link", link);
+        }
+        Ok(with_formatted_frontmatter(
+            &markdown,
+            &config.fmt.frontmatter_order,
+        ))
+    })?;
+    tx.commit()?;
+
+    println!(
+        "{}: implemented={}",
+        path.display(),
+        args.implemented.as_str()
+    );
+    Ok(())
+}
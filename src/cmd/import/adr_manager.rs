@@ -0,0 +1,280 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::Deserialize;
+
+use crate::adr::{find_adr_dir, list_adrs, next_adr_number, now};
+use crate::cmd::import::json::write_adrs;
+use crate::config::{load_config, Mode, TemplateFormat};
+use crate::model::{bullet_list, index_adrs, normalize_status, Adr, DECISION_DRIVERS};
+
+#[derive(Debug, Args)]
+pub(crate) struct AdrManagerArgs {
+    /// The JSON exported by adr-manager, with one ADR or a list of them
+    file: PathBuf,
+}
+
+// an ADR as adr-manager keeps it, with the fields of a MADR document
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct AdrManagerAdr {
+    title: String,
+    status: String,
+    deciders: Deciders,
+    date: String,
+    technical_story: String,
+    context_and_problem_statement: String,
+    decision_drivers: Vec<String>,
+    considered_options: Vec<AdrManagerOption>,
+    decision_outcome: AdrManagerOutcome,
+    links: Vec<String>,
+}
+
+// adr-manager writes the deciders as one comma separated string, later versions as a list
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Deciders {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl Default for Deciders {
+    fn default() -> Self {
+        Deciders::List(Vec::new())
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AdrManagerOption {
+    title: String,
+    description: String,
+    pros: Vec<String>,
+    cons: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct AdrManagerOutcome {
+    chosen_option: String,
+    explanation: String,
+    positive_consequences: Vec<String>,
+    negative_consequences: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum AdrManagerExport {
+    Many(Vec<AdrManagerAdr>),
+    One(Box<AdrManagerAdr>),
+}
+
+// the non-empty, trimmed items
+fn items(items: &[String]) -> Vec<String> {
+    items
+        .iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+// MADR's good and bad points, as bullets
+fn pros_and_cons(pros: &[String], cons: &[String]) -> String {
+    let good = items(pros)
+        .into_iter()
+        .map(|pro| format!("Good, because {}", pro));
+    let bad = items(cons)
+        .into_iter()
+        .map(|con| format!("Bad, because {}", con));
+    bullet_list(&good.chain(bad).collect::<Vec<_>>())
+}
+
+// the paragraphs that aren't empty, separated by blank lines
+fn paragraphs<'a>(paragraphs: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let body = paragraphs
+        .into_iter()
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    (!body.is_empty()).then_some(body)
+}
+
+// map the adr-manager ADR onto the sections of the MADR template
+fn to_adr(adr: &AdrManagerAdr, number: i32, today: &str) -> Adr {
+    let deciders = match &adr.deciders {
+        Deciders::Text(text) => items(&text.split(',').map(str::to_string).collect::<Vec<_>>()),
+        Deciders::List(list) => items(list),
+    };
+    let technical_story = (!adr.technical_story.trim().is_empty())
+        .then(|| format!("Technical Story: {}", adr.technical_story.trim()));
+
+    let outcome = &adr.decision_outcome;
+    let chosen = (!outcome.chosen_option.trim().is_empty()).then(|| {
+        let mut chosen = format!("Chosen option: \"{}\"", outcome.chosen_option.trim());
+        let explanation = outcome.explanation.trim().trim_end_matches('.');
+        if !explanation.is_empty() {
+            chosen += &format!(", because {}", explanation);
+        }
+        chosen + "."
+    });
+    let consequences = pros_and_cons(
+        &outcome.positive_consequences,
+        &outcome.negative_consequences,
+    );
+    let consequences =
+        (!consequences.is_empty()).then(|| format!("### Consequences\n\n{}", consequences));
+
+    let options = adr
+        .considered_options
+        .iter()
+        .filter(|option| !option.title.trim().is_empty())
+        .collect::<Vec<_>>();
+    let mut custom_sections = Vec::new();
+    let drivers = items(&adr.decision_drivers);
+    if !drivers.is_empty() {
+        custom_sections.push((DECISION_DRIVERS.to_string(), bullet_list(&drivers)));
+    }
+    if !options.is_empty() {
+        let titles = options
+            .iter()
+            .map(|option| option.title.trim().to_string())
+            .collect::<Vec<_>>();
+        custom_sections.push(("Considered Options".to_string(), bullet_list(&titles)));
+        let details = options
+            .iter()
+            .map(|option| {
+                let body = paragraphs([
+                    option.description.as_str(),
+                    &pros_and_cons(&option.pros, &option.cons),
+                ]);
+                match body {
+                    Some(body) => format!("### {}\n\n{}", option.title.trim(), body),
+                    None => format!("### {}", option.title.trim()),
+                }
+            })
+            .collect::<Vec<_>>();
+        custom_sections.push((
+            "Pros and Cons of the Options".to_string(),
+            details.join("\n\n"),
+        ));
+    }
+    let links = items(&adr.links);
+    if !links.is_empty() {
+        custom_sections.push(("More Information".to_string(), bullet_list(&links)));
+    }
+
+    let date = adr.date.trim();
+    Adr {
+        number,
+        title: adr.title.trim().to_string(),
+        date: Some(if date.is_empty() { today } else { date }.to_string()),
+        deciders,
+        status: Some(normalize_status(if adr.status.trim().is_empty() {
+            "Proposed"
+        } else {
+            adr.status.trim()
+        })),
+        context: paragraphs([
+            technical_story.as_deref().unwrap_or_default(),
+            &adr.context_and_problem_statement,
+        ]),
+        decision: paragraphs([
+            chosen.as_deref().unwrap_or_default(),
+            consequences.as_deref().unwrap_or_default(),
+        ]),
+        custom_sections,
+        section_order: [
+            "Status",
+            "Context and Problem Statement",
+            DECISION_DRIVERS,
+            "Considered Options",
+            "Decision Outcome",
+            "Pros and Cons of the Options",
+            "More Information",
+        ]
+        .map(str::to_string)
+        .to_vec(),
+        format: TemplateFormat::Madr,
+        ..Default::default()
+    }
+}
+
+pub(crate) fn run_adr_manager(args: &AdrManagerArgs) -> Result<()> {
+    let config = load_config().context("Unable to load configuration")?;
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let json = std::fs::read_to_string(&args.file)
+        .with_context(|| format!("Unable to read {}", args.file.display()))?;
+    let export = serde_json::from_str::<AdrManagerExport>(&json)
+        .with_context(|| format!("{} isn't an adr-manager export", args.file.display()))?;
+    let export = match export {
+        AdrManagerExport::Many(adrs) => adrs,
+        AdrManagerExport::One(adr) => vec![*adr],
+    };
+
+    let today = now()?;
+    let first = next_adr_number(&adr_dir)?;
+    let mut adrs = Vec::new();
+    for (number, adr) in (first..).zip(&export) {
+        if adr.title.trim().is_empty() {
+            anyhow::bail!(
+                "The ADR at position {} of the export has no title",
+                adrs.len() + 1
+            );
+        }
+        adrs.push(to_adr(adr, number, &today));
+    }
+    if config.mode == Mode::Compatible && adrs.iter().any(|adr| !adr.deciders.is_empty()) {
+        eprintln!("Warning: the deciders are only kept in the frontmatter, in nextgen mode");
+    }
+
+    let mut existing = Vec::new();
+    for path in list_adrs(&adr_dir)? {
+        existing.push(Adr::from_path(&adr_dir, &path)?);
+    }
+    let index = index_adrs(existing.iter().chain(&adrs));
+    for path in write_adrs(&adrs, &adr_dir, config.mode, &index)? {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::AdrIndex;
+
+    #[test]
+    fn test_to_adr() {
+        let json = r#"{
+            "title": "Use Postgres",
+            "status": "accepted",
+            "deciders": "Ada, Grace",
+            "date": "2024-01-01",
+            "technicalStory": "",
+            "contextAndProblemStatement": "We need a database.",
+            "decisionDrivers": ["Cost", ""],
+            "consideredOptions": [
+                {"title": "Postgres", "description": "The usual.", "pros": ["mature"], "cons": ["ops"]},
+                {"title": "MySQL", "description": "", "pros": [], "cons": []}
+            ],
+            "decisionOutcome": {
+                "chosenOption": "Postgres",
+                "explanation": "it is mature.",
+                "positiveConsequences": ["one database"],
+                "negativeConsequences": []
+            },
+            "links": []
+        }"#;
+        let AdrManagerExport::One(adr) = serde_json::from_str(json).unwrap() else {
+            panic!("expected one ADR");
+        };
+        let adr = to_adr(&adr, 2, "2024-06-01");
+        assert_eq!(adr.deciders, ["Ada", "Grace"]);
+        assert_eq!(
+            adr.to_markdown(&AdrIndex::new()),
+            "# 2. Use Postgres\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\n## Context and Problem Statement\n\nWe need a database.\n\n## Decision Drivers\n\n* Cost\n\n## Considered Options\n\n* Postgres\n* MySQL\n\n## Decision Outcome\n\nChosen option: \"Postgres\", because it is mature.\n\n### Consequences\n\n* Good, because one database\n\n## Pros and Cons of the Options\n\n### Postgres\n\nThe usual.\n\n* Good, because mature\n* Bad, because ops\n\n### MySQL\n"
+        );
+    }
+}
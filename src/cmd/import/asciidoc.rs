@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+use regex::Regex;
+
+use crate::cmd::import::markdown::import_documents;
+
+#[derive(Debug, Args)]
+pub(crate) struct AsciidocArgs {
+    /// The directory of AsciiDoc decision records to import, including its subdirectories
+    #[arg(value_name = "DIR")]
+    source: PathBuf,
+    /// Report how each file would be imported without writing any ADRs
+    #[arg(long, default_value_t = false)]
+    pub(crate) dry_run: bool,
+}
+
+// the document attributes that carry ADR metadata, and the line markdown import reads them from
+static METADATA_ATTRIBUTES: &[(&str, &str)] = &[
+    ("status", "Status"),
+    ("date", "Date"),
+    ("revdate", "Date"),
+    ("deciders", "Deciders"),
+    ("authors", "Deciders"),
+    ("tags", "Tags"),
+    ("keywords", "Tags"),
+];
+
+// convert the inline markup of a line: strong text, links and cross references
+fn convert_inline(line: &str) -> String {
+    let link =
+        Regex::new(r"(?:link:)?(?<url>(?:https?://|mailto:)[^\s\[]+)\[(?<text>[^\]]*)\]").unwrap();
+    let xref = Regex::new(r"<<[^,>]+,\s*(?<text>[^>]+)>>|<<(?<id>[^>]+)>>").unwrap();
+    let strong = Regex::new(r"(^|[^\w*])\*(?<text>[^*\s](?:[^*]*[^*\s])?)\*($|[^\w*])").unwrap();
+
+    let line = link.replace_all(line, |caps: &regex::Captures| match caps["text"].trim() {
+        "" => caps["url"].to_string(),
+        text => format!("[{}]({})", text, &caps["url"]),
+    });
+    let line = xref.replace_all(&line, |caps: &regex::Captures| {
+        caps.name("text")
+            .or(caps.name("id"))
+            .map_or(String::new(), |text| text.as_str().trim().to_string())
+    });
+    strong
+        .replace_all(&line, "$1**$text**$3")
+        .trim_end_matches(" +")
+        .to_string()
+}
+
+// convert an AsciiDoc decision record to markdown: headings, the metadata attributes, lists,
+// admonitions, quotes and listing blocks. Anything else is kept as written
+pub(crate) fn to_markdown(asciidoc: &str) -> String {
+    let attribute = Regex::new(r"^:(?<name>[\w-]+):[ \t]*(?<value>.*)$").unwrap();
+    let heading = Regex::new(r"^(?<level>={1,6})[ \t]+(?<text>.+?)[ \t]*$").unwrap();
+    let admonition =
+        Regex::new(r"^(?<kind>NOTE|TIP|IMPORTANT|WARNING|CAUTION):[ \t]+(?<text>.*)$").unwrap();
+
+    let mut out = Vec::new();
+    // the delimiter that closes the open listing block
+    let mut listing: Option<&str> = None;
+    let mut language = None;
+    let mut in_comment = false;
+    let mut in_quote = false;
+    for line in asciidoc.lines() {
+        let trimmed = line.trim_end();
+        if let Some(delimiter) = listing {
+            if trimmed == delimiter {
+                listing = None;
+                out.push("```".to_string());
+            } else {
+                out.push(line.to_string());
+            }
+            continue;
+        }
+        if trimmed == "////" {
+            in_comment = !in_comment;
+            continue;
+        }
+        if in_comment || (trimmed.starts_with("//") && !trimmed.starts_with("///")) {
+            continue;
+        }
+        if trimmed == "----" || trimmed == "...." {
+            listing = Some(if trimmed == "----" { "----" } else { "...." });
+            out.push(format!("```{}", language.take().unwrap_or_default()));
+            continue;
+        }
+        if trimmed == "____" {
+            in_quote = !in_quote;
+            continue;
+        }
+        // block attributes such as [source,rust] only affect the block that follows
+        if trimmed.starts_with('[') && trimmed.ends_with(']') && !trimmed.contains("](") {
+            let attributes = trimmed
+                .trim_matches(['[', ']'])
+                .split(',')
+                .collect::<Vec<_>>();
+            if attributes.first() == Some(&"source") {
+                language = attributes
+                    .get(1)
+                    .map(|language| language.trim().to_string());
+            }
+            continue;
+        }
+
+        let converted = if let Some(caps) = heading.captures(trimmed) {
+            format!("{} {}", "#".repeat(caps["level"].len()), &caps["text"])
+        } else if let Some(caps) = attribute.captures(trimmed) {
+            let name = caps["name"].to_lowercase();
+            match METADATA_ATTRIBUTES
+                .iter()
+                .find(|(attribute, _)| *attribute == name)
+            {
+                Some((_, key)) => format!("{}: {}", key, caps["value"].trim()),
+                // other attributes configure the document rather than describe the decision
+                None => continue,
+            }
+        } else if let Some(caps) = admonition.captures(trimmed) {
+            let kind = &caps["kind"];
+            format!(
+                "> **{}{}:** {}",
+                &kind[..1],
+                kind[1..].to_lowercase(),
+                convert_inline(&caps["text"])
+            )
+        } else if let Some(item) = trimmed.strip_prefix(". ") {
+            format!("1. {}", convert_inline(item))
+        } else if let Some((stars, item)) = trimmed
+            .split_once(' ')
+            .filter(|(stars, _)| stars.len() > 1 && stars.chars().all(|c| c == '*'))
+        {
+            format!("{}* {}", "  ".repeat(stars.len() - 1), convert_inline(item))
+        } else if let Some(item) = trimmed.strip_prefix("* ") {
+            format!("* {}", convert_inline(item))
+        } else if trimmed == "+" {
+            String::new()
+        } else {
+            convert_inline(trimmed)
+        };
+        if in_quote {
+            out.push(format!("> {}", converted).trim_end().to_string());
+        } else {
+            out.push(converted);
+        }
+    }
+    out.join("\n") + "\n"
+}
+
+pub(crate) fn run_asciidoc(args: &AsciidocArgs) -> Result<()> {
+    import_documents(
+        &args.source,
+        &["adoc", "asciidoc", "asc"],
+        args.dry_run,
+        to_markdown,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_inline() {
+        assert_eq!(
+            convert_inline(
+                "Use *Postgres* (see https://postgresql.org[the docs] and <<adr-2,ADR 2>>) +"
+            ),
+            "Use **Postgres** (see [the docs](https://postgresql.org) and ADR 2)"
+        );
+        assert_eq!(convert_inline("a * b * c"), "a * b * c");
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let asciidoc = "= ADR 7: Use Postgres\n:status: Accepted\n:revdate: 2024-01-01\n:toc:\n\n// reviewed by the platform team\n== Context\n\nWe need *one* database.\n\nNOTE: Hosting is out of scope.\n\n=== Options\n\n. Postgres\n. MySQL\n** managed\n\n== Decision\n\n[source,sql]\n----\nCREATE DATABASE app;\n----\n\n____\nKeep it boring.\n____\n";
+        assert_eq!(
+            to_markdown(asciidoc),
+            "# ADR 7: Use Postgres\nStatus: Accepted\nDate: 2024-01-01\n\n## Context\n\nWe need **one** database.\n\n> **Note:** Hosting is out of scope.\n\n### Options\n\n1. Postgres\n1. MySQL\n  * managed\n\n## Decision\n\n```sql\nCREATE DATABASE app;\n```\n\n> Keep it boring.\n"
+        );
+    }
+}
@@ -0,0 +1,797 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use sha2::{Digest, Sha256};
+
+use crate::adr::{
+    current_scope, find_adr_dir, get_number, list_adrs, list_archived_adrs, next_adr_number,
+    set_frontmatter_field, set_tags_line, with_frontmatter,
+};
+use crate::config::{load_config, Mode};
+use crate::json_adr::{JsonAdr, JsonAdrBulkExport};
+use crate::model::{index_adrs, normalize_status, Adr, AdrIndex};
+use crate::prompt::prompt_choice;
+use crate::transaction::Transaction;
+
+#[derive(Debug, Args)]
+pub(crate) struct JsonArgs {
+    /// The JSON-ADR export to import, as a file or, with the http feature, an https:// URL
+    #[arg(required_unless_present = "github", conflicts_with = "github")]
+    source: Option<String>,
+    /// Import the export attached to a release of this GitHub repository, e.g. org/repo
+    #[arg(long, value_name = "ORG/REPO")]
+    github: Option<String>,
+    /// The release to import from, by tag
+    #[arg(long, default_value = "latest", requires = "github")]
+    release: String,
+    /// The name of the export attached to the release
+    #[arg(long, default_value = "adrs.json", requires = "github")]
+    asset: String,
+    /// The SHA-256 checksum the export must have. Downloads are otherwise checked against a
+    /// checksum published next to them as URL.sha256, if there is one
+    #[arg(long, value_name = "HEX")]
+    sha256: Option<String>,
+    /// Reconcile the export with the ADRs already in the directory, e.g. when syncing between
+    /// repositories: ADRs that match an existing one by id or title aren't imported again, and
+    /// links to them point at the existing ADR
+    #[arg(long, default_value_t = false)]
+    merge_links: bool,
+    /// Sync the directory with the export: ADRs are matched by number, those whose content
+    /// differs are rewritten, identical ones are left alone and new ones are added
+    #[arg(long, default_value_t = false, conflicts_with = "merge_links")]
+    update: bool,
+    /// Ask what to do with each ADR whose number is already taken: skip it, overwrite the
+    /// existing ADR, import it under the next free number, or see how the two differ first
+    #[arg(long, default_value_t = false, conflicts_with = "update")]
+    interactive: bool,
+    /// Translate the statuses of the imported ADRs with a TOML file mapping each source status
+    /// to one of this repository's, e.g. `"In Review" = "Proposed"`. Statuses match ignoring case
+    #[arg(long, value_name = "FILE")]
+    status_map: Option<PathBuf>,
+    /// Check the export against the schema and show what the import would do without writing
+    /// anything: the files it would create, with the numbers they'd get, and a diff of each file
+    /// it would change
+    #[arg(long, default_value_t = false, conflicts_with = "interactive")]
+    pub(crate) dry_run: bool,
+}
+
+// what to do with an imported ADR whose number is already taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Resolution {
+    /// Keep the existing ADR and leave the imported one out
+    Skip,
+    /// Replace the existing ADR with the imported one
+    Overwrite,
+    /// Import the ADR under the next free number
+    Renumber,
+    /// Show how the imported ADR differs from the existing one, then ask again
+    Diff,
+}
+
+// the files an import writes, with their content
+type Files = Vec<(PathBuf, String)>;
+
+// read a status map, from each source status to the status to import it as
+fn load_status_map(path: &Path) -> Result<BTreeMap<String, String>> {
+    let toml = std::fs::read_to_string(path)
+        .with_context(|| format!("Unable to read {}", path.display()))?;
+    toml::from_str(&toml).with_context(|| {
+        format!(
+            "Invalid status map {}, expected source statuses mapped to statuses",
+            path.display()
+        )
+    })
+}
+
+// translate the statuses of the exported ADRs that the map has an entry for
+fn map_statuses(export: &mut JsonAdrBulkExport, map: &BTreeMap<String, String>) {
+    for json in &mut export.adrs {
+        let Some(status) = &json.status else {
+            continue;
+        };
+        let mapped = map
+            .iter()
+            .find(|(from, _)| from.trim().eq_ignore_ascii_case(status.trim()));
+        if let Some((_, to)) = mapped {
+            json.status = Some(normalize_status(to));
+        }
+    }
+}
+
+// keep only the ADRs of the scope being imported into from an export that combines several scopes,
+// as their numbers are only unique within each scope
+fn select_scope(export: &mut JsonAdrBulkExport, scope: Option<&str>) -> Result<()> {
+    let scopes = export
+        .adrs
+        .iter()
+        .filter_map(|json| json.scope.as_deref())
+        .collect::<BTreeSet<_>>();
+    let names = scopes.iter().copied().collect::<Vec<_>>().join(", ");
+    match scope {
+        _ if scopes.is_empty() => {}
+        Some(scope) if scopes.contains(scope) => export
+            .adrs
+            .retain(|json| json.scope.is_none() || json.scope.as_deref() == Some(scope)),
+        Some(scope) => anyhow::bail!(
+            "The export has no ADRs of scope '{}', only of: {}",
+            scope,
+            names
+        ),
+        None if scopes.len() > 1 => anyhow::bail!(
+            "The export combines the scopes {}, import them one at a time with --scope NAME",
+            names
+        ),
+        None => {}
+    }
+    Ok(())
+}
+
+// the existing ADR that an imported one is the same decision as, by id, or by title when either
+// has no id
+fn find_existing<'a>(adr: &Adr, existing: &'a [Adr]) -> Option<&'a Adr> {
+    existing
+        .iter()
+        .find(|other| adr.id.is_some() && other.id == adr.id)
+        .or_else(|| {
+            existing.iter().find(|other| {
+                (adr.id.is_none() || other.id.is_none())
+                    && other.title.trim().eq_ignore_ascii_case(adr.title.trim())
+            })
+        })
+}
+
+// the exported ADRs to import. ADRs that are among the existing ones are left out, and links to
+// them are pointed at the existing ADRs
+fn prepare_adrs(export: &JsonAdrBulkExport, existing: &[Adr]) -> Vec<Adr> {
+    let mut adrs = export.adrs.iter().map(Adr::from).collect::<Vec<_>>();
+    let mut merged = BTreeMap::new();
+    adrs.retain(|adr| match find_existing(adr, existing) {
+        Some(other) => {
+            merged.insert(adr.number, other.number);
+            false
+        }
+        None => true,
+    });
+    let imported = adrs.iter().map(|adr| adr.number).collect::<BTreeSet<_>>();
+    for adr in &mut adrs {
+        for link in &mut adr.links {
+            if let Some(number) = merged.get(&link.target) {
+                link.target = *number;
+            } else if !imported.contains(&link.target) {
+                eprintln!(
+                    "Warning: ADR {} links to ADR {}, which isn't in the export",
+                    adr.number, link.target
+                );
+            }
+        }
+    }
+    adrs
+}
+
+// write the exported ADRs into the directory, refusing to replace any existing file. ADRs that
+// are among the existing ones are left out, and links to them are pointed at the existing ADRs
+pub(crate) fn import_adrs(
+    export: &JsonAdrBulkExport,
+    adr_dir: &Path,
+    mode: Mode,
+    existing: &[Adr],
+) -> Result<Vec<PathBuf>> {
+    let adrs = prepare_adrs(export, existing);
+    // an ADR whose file already exists is reported as such when it is written
+    let targets = adrs
+        .iter()
+        .map(|adr| adr_dir.join(adr.relative_path()))
+        .collect::<Vec<_>>();
+    let mut taken = taken_numbers(&adrs, adr_dir)?;
+    taken.retain(|(_, path)| !targets.contains(path));
+    if !taken.is_empty() {
+        anyhow::bail!(
+            "{} already taken, use --update or --interactive to replace or renumber them",
+            taken
+                .iter()
+                .map(|(number, path)| format!("ADR {} is {}", number, path.display()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    let index = index_adrs(existing.iter().chain(&adrs));
+    write_adrs(&adrs, adr_dir, mode, &index)
+}
+
+// the ADRs in the directory, archived or not, that have the number of one of the ADRs, as their
+// numbers and paths
+fn taken_numbers(adrs: &[Adr], adr_dir: &Path) -> Result<Vec<(i32, PathBuf)>> {
+    if !adr_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let numbers = adrs.iter().map(|adr| adr.number).collect::<BTreeSet<_>>();
+    let mut paths = list_adrs(adr_dir)?;
+    paths.extend(list_archived_adrs(adr_dir)?);
+    Ok(paths
+        .into_iter()
+        .filter_map(|path| Some((get_number(&path).filter(|n| numbers.contains(n))?, path)))
+        .collect())
+}
+
+// show what importing the export would do without writing anything: the ADRs merged into
+// existing ones, the files created with the numbers they get, and a diff of each file that is
+// in the way. Fails if there are any, as the import would
+fn preview_import(
+    export: &JsonAdrBulkExport,
+    adr_dir: &Path,
+    mode: Mode,
+    existing: &[Adr],
+) -> Result<()> {
+    for adr in export.adrs.iter().map(Adr::from) {
+        if let Some(other) = find_existing(&adr, existing) {
+            println!(
+                "merge: ADR {} ({}) is ADR {}",
+                adr.number, adr.title, other.number
+            );
+        }
+    }
+    let adrs = prepare_adrs(export, existing);
+    let index = index_adrs(existing.iter().chain(&adrs));
+    let files = render_files(&adrs, adr_dir, mode, &index)?;
+    let taken = taken_numbers(&adrs, adr_dir)?;
+    let mut conflicts = 0;
+    for (adr, (path, content)) in adrs.iter().zip(&files) {
+        if path.exists() {
+            conflicts += 1;
+            println!("exists: {} (ADR {})", path.display(), adr.number);
+            let current = std::fs::read_to_string(path)
+                .with_context(|| format!("Unable to read {}", path.display()))?;
+            print!("{}", unified_diff(path, &current, content));
+        } else if let Some((_, other)) = taken.iter().find(|(n, _)| *n == adr.number) {
+            conflicts += 1;
+            println!(
+                "taken: {} (ADR {} is {})",
+                path.display(),
+                adr.number,
+                other.display()
+            );
+        } else {
+            println!("create: {} (ADR {})", path.display(), adr.number);
+        }
+    }
+    if conflicts > 0 {
+        anyhow::bail!(
+            "{} file(s) already exist or have a number that is taken, use --update or --interactive to replace them",
+            conflicts
+        );
+    }
+    println!("{} to create", files.len());
+    Ok(())
+}
+
+// the lines of both texts in order, each marked as kept (' '), removed ('-') or added ('+')
+fn diff_ops<'a>(old: &'a str, new: &'a str) -> Vec<(char, &'a str)> {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+    // the length of the longest common subsequence of the lines from i and j on
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            ops.push(('-', old[i]));
+            i += 1;
+        } else {
+            ops.push(('+', new[j]));
+            j += 1;
+        }
+    }
+    ops
+}
+
+// the lines that differ between the texts, removed ones as `- line` and added ones as `+ line`
+fn diff_lines(old: &str, new: &str) -> Vec<String> {
+    diff_ops(old, new)
+        .into_iter()
+        .filter(|(op, _)| *op != ' ')
+        .map(|(op, line)| format!("{} {}", op, line))
+        .collect()
+}
+
+// the changes to the file as a unified diff, with three lines of context around each hunk
+fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    const CONTEXT: usize = 3;
+    let ops = diff_ops(old, new);
+    let changes = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _))| *op != ' ')
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    if changes.is_empty() {
+        return String::new();
+    }
+    // changes closer than twice the context share a hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &i in &changes {
+        match hunks.last_mut() {
+            Some((_, last)) if i - *last <= 2 * CONTEXT => *last = i,
+            _ => hunks.push((i, i)),
+        }
+    }
+    let mut out = format!("--- {}\n+++ {}\n", path.display(), path.display());
+    for (first, last) in hunks {
+        let (start, end) = (
+            first.saturating_sub(CONTEXT),
+            (last + CONTEXT + 1).min(ops.len()),
+        );
+        let count = |side: char, ops: &[(char, &str)]| {
+            ops.iter()
+                .filter(|(op, _)| *op == ' ' || *op == side)
+                .count()
+        };
+        let (old_before, new_before) = (count('-', &ops[..start]), count('+', &ops[..start]));
+        let (old_len, new_len) = (count('-', &ops[start..end]), count('+', &ops[start..end]));
+        // an empty side is numbered by the line before it, as diff does
+        let line = |before: usize, len: usize| if len == 0 { before } else { before + 1 };
+        out += &format!(
+            "@@ -{},{} +{},{} @@\n",
+            line(old_before, old_len),
+            old_len,
+            line(new_before, new_len),
+            new_len
+        );
+        for (op, text) in &ops[start..end] {
+            out += &format!("{}{}\n", op, text);
+        }
+    }
+    out
+}
+
+// import the exported ADRs, asking what to do with each one whose number is taken by an existing
+// ADR. Overwritten ADRs keep the existing file name, and links between imported ADRs follow the
+// renumbered ones
+fn import_interactively(
+    export: &JsonAdrBulkExport,
+    adr_dir: &Path,
+    mode: Mode,
+    existing: &[Adr],
+    merge_links: bool,
+) -> Result<Vec<PathBuf>> {
+    let adrs = prepare_adrs(export, if merge_links { existing } else { &[] });
+    let index = index_adrs(existing.iter().chain(&adrs));
+    let mut next = adrs
+        .iter()
+        .map(|adr| adr.number + 1)
+        .chain([next_adr_number(adr_dir)?])
+        .max()
+        .unwrap_or(1);
+
+    let mut kept = Vec::new();
+    let mut overwritten = BTreeSet::new();
+    let mut renumbered = BTreeMap::new();
+    for mut adr in adrs {
+        let Some(other) = existing.iter().find(|other| other.number == adr.number) else {
+            kept.push(adr);
+            continue;
+        };
+        let path = adr_dir.join(other.relative_path());
+        let question = format!(
+            "ADR {} ({}) is already taken by {}",
+            adr.number,
+            adr.title,
+            path.display()
+        );
+        loop {
+            match prompt_choice(&question, Resolution::Skip)? {
+                Resolution::Skip => eprintln!("Skipped ADR {}", adr.number),
+                Resolution::Overwrite => {
+                    adr.path = other.path.clone();
+                    overwritten.insert(path.clone());
+                    kept.push(adr);
+                }
+                Resolution::Renumber => {
+                    renumbered.insert(adr.number, next);
+                    adr.number = next;
+                    adr.path = None;
+                    next += 1;
+                    kept.push(adr);
+                }
+                Resolution::Diff => {
+                    let current = std::fs::read_to_string(&path)
+                        .with_context(|| format!("Unable to read {}", path.display()))?;
+                    for line in diff_lines(&current, &render_adr(&adr, mode, &index)) {
+                        eprintln!("  {}", line);
+                    }
+                    continue;
+                }
+            }
+            break;
+        }
+    }
+    for adr in &mut kept {
+        for link in &mut adr.links {
+            if let Some(number) = renumbered.get(&link.target) {
+                link.target = *number;
+            }
+        }
+    }
+
+    let numbers = kept.iter().map(|adr| adr.number).collect::<BTreeSet<_>>();
+    let index = index_adrs(
+        existing
+            .iter()
+            .filter(|adr| !numbers.contains(&adr.number))
+            .chain(&kept),
+    );
+    let mut tx = Transaction::new();
+    let mut paths = Vec::new();
+    for adr in &kept {
+        let path = adr_file(adr_dir, adr)?;
+        if path.exists() && !overwritten.contains(&path) {
+            anyhow::bail!("{} already exists", path.display());
+        }
+        tx.write(&path, render_adr(adr, mode, &index));
+        paths.push(path);
+    }
+    tx.commit()?;
+    Ok(paths)
+}
+
+// write the ADRs into the directory, refusing to replace any existing file
+pub(crate) fn write_adrs(
+    adrs: &[Adr],
+    adr_dir: &Path,
+    mode: Mode,
+    index: &AdrIndex,
+) -> Result<Vec<PathBuf>> {
+    let files = render_files(adrs, adr_dir, mode, index)?;
+    if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+        anyhow::bail!("{} already exists", path.display());
+    }
+    if let Some((number, path)) = taken_numbers(adrs, adr_dir)?.first() {
+        anyhow::bail!("ADR {} is already taken by {}", number, path.display());
+    }
+    write_files(&files)?;
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+// the file each ADR is written to, with its content
+fn render_files(adrs: &[Adr], adr_dir: &Path, mode: Mode, index: &AdrIndex) -> Result<Files> {
+    adrs.iter()
+        .map(|adr| Ok((adr_file(adr_dir, adr)?, render_adr(adr, mode, index))))
+        .collect()
+}
+
+// write the files together, so that a failure leaves none of them written
+fn write_files(files: &[(PathBuf, String)]) -> Result<()> {
+    let mut tx = Transaction::new();
+    for (path, content) in files {
+        tx.write(path, content.clone());
+    }
+    tx.commit()
+}
+
+// where the ADR is written, refusing paths that would leave the ADR directory
+fn adr_file(adr_dir: &Path, adr: &Adr) -> Result<PathBuf> {
+    let relative = adr.relative_path();
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        anyhow::bail!(
+            "Invalid path for ADR {}: {}",
+            adr.number,
+            relative.display()
+        );
+    }
+    Ok(adr_dir.join(relative))
+}
+
+// the markdown of an imported ADR, with its metadata in the frontmatter in nextgen mode
+fn render_adr(adr: &Adr, mode: Mode, index: &AdrIndex) -> String {
+    let mut markdown = with_frontmatter(mode, adr.to_markdown(index));
+    for (key, value) in adr.frontmatter_fields() {
+        markdown = set_frontmatter_field(&markdown, key, &value);
+    }
+    set_tags_line(&markdown, &adr.tags)
+}
+
+// whether the existing ADR already says what the exported one does, leaving aside where the file
+// is and what is derived from it
+fn same_content(existing: &JsonAdr, exported: &JsonAdr) -> bool {
+    let strip = |adr: &JsonAdr| JsonAdr {
+        path: None,
+        scope: None,
+        format: None,
+        source_uri: None,
+        time_in_status: BTreeMap::new(),
+        ..adr.clone()
+    };
+    strip(existing) == strip(exported)
+}
+
+// plan syncing the directory with the export: ADRs are matched by number, existing ones are
+// rewritten in place if their content differs and the others are added. Returns the changed and
+// added files, with their new content, and the number of unchanged ADRs
+fn plan_update(
+    export: &JsonAdrBulkExport,
+    adr_dir: &Path,
+    mode: Mode,
+    existing: &[Adr],
+) -> Result<(Files, Files, usize)> {
+    let mut changed = Vec::new();
+    let mut added = Vec::new();
+    let mut unchanged = 0;
+    for json in &export.adrs {
+        let mut adr = Adr::from(json);
+        match existing.iter().find(|other| other.number == adr.number) {
+            Some(other) if same_content(&JsonAdr::from(other), json) => unchanged += 1,
+            Some(other) => {
+                // the file keeps its name, even if the title changed
+                adr.path = other.path.clone();
+                changed.push(adr);
+            }
+            None => added.push(adr),
+        }
+    }
+    let updated = changed
+        .iter()
+        .chain(&added)
+        .map(|adr| adr.number)
+        .collect::<BTreeSet<_>>();
+    let index = index_adrs(
+        existing
+            .iter()
+            .filter(|adr| !updated.contains(&adr.number))
+            .chain(&changed)
+            .chain(&added),
+    );
+    Ok((
+        render_files(&changed, adr_dir, mode, &index)?,
+        render_files(&added, adr_dir, mode, &index)?,
+        unchanged,
+    ))
+}
+
+// the URL GitHub serves a file attached to a release from
+fn github_release_url(repo: &str, release: &str, asset: &str) -> Result<String> {
+    if repo.split('/').count() != 2 || repo.split('/').any(str::is_empty) {
+        anyhow::bail!("Invalid GitHub repository '{}', expected org/repo", repo);
+    }
+    Ok(match release {
+        "latest" => format!(
+            "https://github.com/{}/releases/latest/download/{}",
+            repo, asset
+        ),
+        tag => format!(
+            "https://github.com/{}/releases/download/{}/{}",
+            repo, tag, asset
+        ),
+    })
+}
+
+// check the content against a SHA-256 checksum, given as hex as written by sha256sum
+fn verify_checksum(source: &str, content: &[u8], expected: &str) -> Result<()> {
+    let expected = expected.split_whitespace().next().unwrap_or_default();
+    let actual = format!("{:x}", Sha256::digest(content));
+    if !actual.eq_ignore_ascii_case(expected) {
+        anyhow::bail!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            source,
+            expected,
+            actual
+        );
+    }
+    Ok(())
+}
+
+// download the URL, or None if there is nothing there
+#[cfg(feature = "http")]
+fn download(url: &str) -> Result<Option<Vec<u8>>> {
+    match ureq::get(url).call() {
+        Ok(mut response) => Ok(Some(
+            response
+                .body_mut()
+                .with_config()
+                .limit(100 * 1024 * 1024)
+                .read_to_vec()
+                .with_context(|| format!("Unable to download {}", url))?,
+        )),
+        Err(ureq::Error::StatusCode(404)) => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("Unable to download {}", url)),
+    }
+}
+
+#[cfg(not(feature = "http"))]
+fn download(url: &str) -> Result<Option<Vec<u8>>> {
+    anyhow::bail!(
+        "Unable to download {}: adrs was built without the http feature",
+        url
+    )
+}
+
+// read the export from the file or URL, checking it against the checksum
+fn read_export(source: &str, sha256: Option<&str>) -> Result<Vec<u8>> {
+    if !source.starts_with("https://") && !source.starts_with("http://") {
+        let content =
+            std::fs::read(source).with_context(|| format!("Unable to read {}", source))?;
+        if let Some(sha256) = sha256 {
+            verify_checksum(source, &content, sha256)?;
+        }
+        return Ok(content);
+    }
+    let content = download(source)?.with_context(|| format!("Nothing found at {}", source))?;
+    match sha256 {
+        Some(sha256) => verify_checksum(source, &content, sha256)?,
+        None => match download(&format!("{}.sha256", source))? {
+            Some(sha256) => verify_checksum(source, &content, &String::from_utf8_lossy(&sha256))?,
+            None => eprintln!(
+                "Warning: {} has no published checksum, use --sha256 to check it",
+                source
+            ),
+        },
+    }
+    Ok(content)
+}
+
+pub(crate) fn run_json(args: &JsonArgs) -> Result<()> {
+    let config = load_config().context("Unable to load configuration")?;
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let source = match &args.github {
+        Some(repo) => github_release_url(repo, &args.release, &args.asset)?,
+        None => args.source.clone().unwrap_or_default(),
+    };
+    let json = String::from_utf8(read_export(&source, args.sha256.as_deref())?)
+        .with_context(|| format!("{} isn't UTF-8", source))?;
+    let mut export = JsonAdrBulkExport::from_json(&json)?;
+    select_scope(&mut export, current_scope())?;
+    if let Some(path) = &args.status_map {
+        map_statuses(&mut export, &load_status_map(path)?);
+    }
+    if args.dry_run {
+        println!(
+            "{} is a valid JSON-ADR export of {} ADR(s)",
+            source,
+            export.adrs.len()
+        );
+    }
+
+    let mut existing = Vec::new();
+    if args.merge_links || args.update || args.interactive {
+        let mut paths = list_adrs(&adr_dir)?;
+        paths.extend(list_archived_adrs(&adr_dir)?);
+        for path in paths {
+            existing.push(Adr::from_path(&adr_dir, &path)?);
+        }
+    }
+    if args.update {
+        let (changed, added, unchanged) = plan_update(&export, &adr_dir, config.mode, &existing)?;
+        if args.dry_run {
+            for (path, content) in &changed {
+                println!("change: {}", path.display());
+                let current = std::fs::read_to_string(path)
+                    .with_context(|| format!("Unable to read {}", path.display()))?;
+                print!("{}", unified_diff(path, &current, content));
+            }
+            for (path, _) in &added {
+                println!("add: {}", path.display());
+            }
+            println!(
+                "{} to change, {} to add, {} unchanged",
+                changed.len(),
+                added.len(),
+                unchanged
+            );
+            return Ok(());
+        }
+        write_files(&[changed.as_slice(), added.as_slice()].concat())?;
+        for (path, _) in &changed {
+            println!("changed: {}", path.display());
+        }
+        for (path, _) in &added {
+            println!("added: {}", path.display());
+        }
+        println!(
+            "{} changed, {} added, {} unchanged",
+            changed.len(),
+            added.len(),
+            unchanged
+        );
+        return Ok(());
+    }
+    if args.interactive {
+        for path in
+            import_interactively(&export, &adr_dir, config.mode, &existing, args.merge_links)?
+        {
+            println!("{}", path.display());
+        }
+        return Ok(());
+    }
+    if args.dry_run {
+        return preview_import(&export, &adr_dir, config.mode, &existing);
+    }
+    for path in import_adrs(&export, &adr_dir, config.mode, &existing)? {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_statuses() {
+        let json = |status: &str| JsonAdr {
+            status: Some(status.to_string()),
+            ..JsonAdr::from(&Adr::default())
+        };
+        let mut export =
+            JsonAdrBulkExport::new(vec![json("In Review"), json("approved"), json("Open")]);
+        let map = BTreeMap::from([
+            ("in review".to_string(), "proposed".to_string()),
+            ("Approved".to_string(), "Accepted".to_string()),
+        ]);
+        map_statuses(&mut export, &map);
+        let statuses = export
+            .adrs
+            .iter()
+            .map(|json| json.status.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, ["Proposed", "Accepted", "Open"]);
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        assert_eq!(
+            diff_lines("# 1. A\n\nOld\n\nSame\n", "# 1. A\n\nNew\n\nSame\nMore\n"),
+            ["- Old", "+ New", "+ More"]
+        );
+        assert!(diff_lines("same\n", "same").is_empty());
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n";
+        let new = old.replacen("2\n", "two\n", 1).replace("\n14\n", "\n");
+        assert_eq!(
+            unified_diff(Path::new("doc/adr/0001-a.md"), old, &new),
+            "--- doc/adr/0001-a.md\n+++ doc/adr/0001-a.md\n@@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n@@ -11,5 +11,4 @@\n 11\n 12\n 13\n-14\n 15\n"
+        );
+        assert_eq!(unified_diff(Path::new("a.md"), "same\n", "same\n"), "");
+    }
+
+    #[test]
+    fn test_github_release_url() {
+        assert_eq!(
+            github_release_url("org/repo", "latest", "adrs.json").unwrap(),
+            "https://github.com/org/repo/releases/latest/download/adrs.json"
+        );
+        assert_eq!(
+            github_release_url("org/repo", "v1.2.0", "adrs.json").unwrap(),
+            "https://github.com/org/repo/releases/download/v1.2.0/adrs.json"
+        );
+        assert!(github_release_url("repo", "latest", "adrs.json").is_err());
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let sha256 = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert!(verify_checksum("hello.txt", b"hello", sha256).is_ok());
+        assert!(
+            verify_checksum("hello.txt", b"hello", &format!("{}  hello.txt\n", sha256)).is_ok()
+        );
+        assert!(verify_checksum("hello.txt", b"hello!", sha256).is_err());
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use regex::Regex;
+
+use crate::adr::{
+    find_adr_dir, get_frontmatter_field, get_frontmatter_list, list_adrs, next_adr_number, now,
+    parse_date, split_frontmatter, split_tags,
+};
+use crate::cmd::import::json::write_adrs;
+use crate::config::load_config;
+use crate::model::{index_adrs, normalize_status, Adr};
+
+#[derive(Debug, Args)]
+pub(crate) struct MarkdownArgs {
+    /// The directory of markdown files to import, e.g. a wiki export, including its
+    /// subdirectories
+    #[arg(value_name = "DIR")]
+    source: PathBuf,
+    /// Report how each file would be imported without writing any ADRs
+    #[arg(long, default_value_t = false)]
+    pub(crate) dry_run: bool,
+}
+
+// an ADR recovered from a markdown file, with notes on what couldn't be mapped
+struct Recovered {
+    adr: Adr,
+    notes: Vec<String>,
+}
+
+// the standard section for the headings wikis and other tools use, if the heading is one
+fn section_name(heading: &str) -> Option<&'static str> {
+    let heading = heading.trim().trim_end_matches(':').to_lowercase();
+    Some(match heading.as_str() {
+        "status" | "state" => "Status",
+        "context"
+        | "context and problem statement"
+        | "problem"
+        | "problem statement"
+        | "background"
+        | "motivation" => "Context",
+        "decision" | "decision outcome" | "outcome" | "resolution" | "proposal" => "Decision",
+        "consequences" | "implications" | "impact" => "Consequences",
+        _ => return None,
+    })
+}
+
+// the title without the numbering wikis often give it, e.g. `ADR-012: Use Postgres`
+fn strip_numbering(title: &str) -> String {
+    let numbering = Regex::new(r"(?i)^(?:adr[-_\s]*)?\d+(?:\s*[.:)\-–]\s*|\s+)").unwrap();
+    numbering.replace(title.trim(), "").trim().to_string()
+}
+
+// a title made from the file name, e.g. `use-postgres` becomes `Use postgres`
+fn title_from_file_name(stem: &str) -> String {
+    let title = strip_numbering(&stem.replace(['-', '_'], " "));
+    let mut chars = title.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => stem.to_string(),
+    }
+}
+
+// the text of a status line, without list markers or emphasis
+fn clean_value(value: &str) -> String {
+    value
+        .trim()
+        .trim_start_matches(['-', '*', '+'])
+        .trim()
+        .trim_matches(['*', '_'])
+        .trim()
+        .to_string()
+}
+
+// detect the title, status, date and sections of a markdown file that isn't an ADR
+fn recover(stem: &str, markdown: &str, number: i32, today: &str) -> Recovered {
+    let heading_re =
+        Regex::new(r"^(?<hashes>#{1,6})[ \t]+(?<text>.*?)(?:[ \t]+#+)?[ \t]*$").unwrap();
+    let meta_re = Regex::new(
+        r"(?i)^(?:[-*+][ \t]+)?(?:\*\*)?(?<key>status|date|deciders|tags)(?:\*\*)?[ \t]*:(?:\*\*)?[ \t]*(?<value>.*?)[ \t]*$",
+    )
+    .unwrap();
+    let mut notes = Vec::new();
+    let (_, body) = split_frontmatter(markdown);
+    let lines = body.lines().collect::<Vec<_>>();
+
+    // the headings outside fenced code, as (line, level, text)
+    let mut headings = Vec::new();
+    let mut in_fence = false;
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if let Some(caps) = heading_re.captures(line).filter(|_| !in_fence) {
+            headings.push((i, caps["hashes"].len(), caps["text"].trim().to_string()));
+        }
+    }
+
+    // the title is the first top level heading, or the first heading if it isn't a section
+    let title_at = headings
+        .iter()
+        .position(|(_, level, _)| *level == 1)
+        .or_else(|| {
+            headings
+                .first()
+                .filter(|(_, _, text)| section_name(text).is_none())
+                .map(|_| 0)
+        });
+    let title_line = title_at.map(|i| headings.remove(i));
+    let title = match title_line
+        .as_ref()
+        .map(|(_, _, text)| strip_numbering(text))
+    {
+        Some(title) if !title.is_empty() => title,
+        _ => {
+            notes.push("no title heading, titled from the file name".to_string());
+            title_from_file_name(stem)
+        }
+    };
+
+    // sections are the least nested of the other headings, and deeper headings are subsections
+    let section_level = headings.iter().map(|(_, level, _)| *level).min();
+    let starts = headings
+        .iter()
+        .filter(|(_, level, _)| Some(*level) == section_level)
+        .collect::<Vec<_>>();
+    let relevel = headings
+        .iter()
+        .map(|(i, level, text)| {
+            let level = level + 2 - section_level.unwrap_or(2);
+            (*i, format!("{} {}", "#".repeat(level.min(6)), text))
+        })
+        .collect::<BTreeMap<_, _>>();
+    let text = |from: usize, to: usize| {
+        (from..to)
+            .filter(|i| title_line.as_ref().map(|(t, _, _)| t) != Some(i))
+            .map(|i| relevel.get(&i).map_or(lines[i], String::as_str))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string()
+    };
+
+    let mut adr = Adr {
+        number,
+        title,
+        tags: get_frontmatter_list(markdown, "tags"),
+        deciders: get_frontmatter_list(markdown, "deciders"),
+        ..Default::default()
+    };
+    let mut status = get_frontmatter_field(markdown, "status");
+    let mut date = get_frontmatter_field(markdown, "date");
+
+    // status, date, deciders and tag lines before the first section, as wikis and MADR 2 write
+    let mut preamble = Vec::new();
+    let first = starts.first().map_or(lines.len(), |(i, _, _)| *i);
+    let before = text(0, first);
+    for line in before.lines() {
+        match meta_re.captures(line) {
+            Some(caps) => {
+                let value = clean_value(&caps["value"]);
+                match caps["key"].to_lowercase().as_str() {
+                    "status" => status = status.or(Some(value)),
+                    "date" => date = date.or(Some(value)),
+                    "deciders" if adr.deciders.is_empty() => adr.deciders = split_tags(&value),
+                    "tags" if adr.tags.is_empty() => adr.tags = split_tags(&value),
+                    _ => {}
+                }
+            }
+            None => preamble.push(line),
+        }
+    }
+    let preamble = preamble.join("\n").trim().to_string();
+
+    adr.section_order.push("Status".to_string());
+    for (n, (start, _, heading)) in starts.iter().enumerate() {
+        let end = starts.get(n + 1).map_or(lines.len(), |(i, _, _)| *i);
+        let body = text(start + 1, end);
+        let body = (!body.is_empty()).then_some(body);
+        match section_name(heading) {
+            Some("Status") => {
+                let line = body
+                    .as_deref()
+                    .and_then(|b| b.lines().find(|l| !l.trim().is_empty()));
+                status = status.or(line.map(clean_value));
+                continue;
+            }
+            Some("Context") => adr.context = body,
+            Some("Decision") => adr.decision = body,
+            Some("Consequences") => adr.consequences = body,
+            _ => {
+                notes.push(format!("kept the section '{}' as it is", heading));
+                adr.custom_sections
+                    .push((heading.clone(), body.unwrap_or_default()));
+                adr.section_order.push(heading.clone());
+                continue;
+            }
+        }
+        adr.section_order
+            .push(section_name(heading).unwrap_or_default().to_string());
+    }
+    if !preamble.is_empty() {
+        notes.push("used the text before the first section as the context".to_string());
+        adr.context = Some(match adr.context.take() {
+            Some(context) => format!("{}\n\n{}", preamble, context),
+            None => preamble,
+        });
+    }
+    for (section, found) in [
+        ("Context", adr.context.is_some()),
+        ("Decision", adr.decision.is_some()),
+    ] {
+        if !found {
+            notes.push(format!("no {} section found", section));
+        }
+    }
+
+    adr.status = Some(match status.filter(|status| !status.is_empty()) {
+        Some(status) => normalize_status(&status),
+        None => {
+            notes.push("no status found, imported as Proposed".to_string());
+            "Proposed".to_string()
+        }
+    });
+    adr.date = Some(match date {
+        Some(date) if parse_date(&date).is_ok() => date,
+        Some(date) => {
+            notes.push(format!("invalid date '{}', dated today", date));
+            today.to_string()
+        }
+        None => {
+            notes.push("no date found, dated today".to_string());
+            today.to_string()
+        }
+    });
+    Recovered { adr, notes }
+}
+
+// the files with one of the extensions under the directory in name order, leaving out its index
+// pages
+fn source_files(dir: &Path, adr_dir: &Path, extensions: &[&str]) -> Result<Vec<PathBuf>> {
+    let adr_dir = adr_dir.canonicalize().unwrap_or(adr_dir.to_path_buf());
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry?;
+        let path = entry.path();
+        let is_source = path.extension().is_some_and(|ext| {
+            extensions
+                .iter()
+                .any(|extension| ext.eq_ignore_ascii_case(extension))
+        });
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let is_index = ["readme", "index", "_sidebar", "_footer"]
+            .iter()
+            .any(|index| stem.eq_ignore_ascii_case(index));
+        let in_adr_dir = path
+            .canonicalize()
+            .is_ok_and(|path| path.starts_with(&adr_dir));
+        if entry.file_type().is_file() && is_source && !is_index && !in_adr_dir {
+            files.push(path.to_path_buf());
+        }
+    }
+    Ok(files)
+}
+
+// import the documents with one of the extensions under the directory, converting each to
+// markdown before recovering an ADR from it, and print where each one went
+pub(crate) fn import_documents(
+    source: &Path,
+    extensions: &[&str],
+    dry_run: bool,
+    to_markdown: fn(&str) -> String,
+) -> Result<()> {
+    let config = load_config().context("Unable to load configuration")?;
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    if !source.is_dir() {
+        anyhow::bail!("{} isn't a directory", source.display());
+    }
+
+    let today = now()?;
+    let first = next_adr_number(&adr_dir)?;
+    let files = source_files(source, &adr_dir, extensions)?;
+    let mut adrs = Vec::new();
+    for (number, file) in (first..).zip(&files) {
+        let markdown = to_markdown(
+            &std::fs::read_to_string(file)
+                .with_context(|| format!("Unable to read {}", file.display()))?,
+        );
+        let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+        let recovered = recover(&stem, &markdown, number, &today);
+        for note in &recovered.notes {
+            eprintln!("Warning: {}: {}", file.display(), note);
+        }
+        adrs.push(recovered.adr);
+    }
+
+    let paths = if dry_run {
+        adrs.iter()
+            .map(|adr| adr_dir.join(adr.relative_path()))
+            .collect()
+    } else {
+        let mut existing = Vec::new();
+        for path in list_adrs(&adr_dir)? {
+            existing.push(Adr::from_path(&adr_dir, &path)?);
+        }
+        let index = index_adrs(existing.iter().chain(&adrs));
+        write_adrs(&adrs, &adr_dir, config.mode, &index)?
+    };
+    for (file, path) in files.iter().zip(paths) {
+        println!("{} -> {}", file.display(), path.display());
+    }
+    Ok(())
+}
+
+pub(crate) fn run_markdown(args: &MarkdownArgs) -> Result<()> {
+    import_documents(
+        &args.source,
+        &["md", "markdown"],
+        args.dry_run,
+        |markdown| markdown.to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::AdrIndex;
+
+    #[test]
+    fn test_strip_numbering() {
+        assert_eq!(strip_numbering("ADR-012: Use Postgres"), "Use Postgres");
+        assert_eq!(strip_numbering("12. Use Postgres"), "Use Postgres");
+        assert_eq!(strip_numbering("2FA for admins"), "2FA for admins");
+        assert_eq!(title_from_file_name("adr_07-use-postgres"), "Use postgres");
+    }
+
+    #[test]
+    fn test_recover() {
+        let markdown = "# ADR 7: Use Postgres\n\n* **Status:** accepted\n* Date: 2024-01-01\n\nWe argued for a while.\n\n# Background\n\nWe need a database.\n\n## Options\n\nMany.\n\n# Resolution\n\nPostgres.\n\n# Risks\n\nOps.\n";
+        let recovered = recover("use-postgres", markdown, 3, "2024-06-01");
+        assert_eq!(
+            recovered.adr.to_markdown(&AdrIndex::new()),
+            "# 3. Use Postgres\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\n## Context\n\nWe argued for a while.\n\nWe need a database.\n\n### Options\n\nMany.\n\n## Decision\n\nPostgres.\n\n## Risks\n\nOps.\n"
+        );
+        assert_eq!(
+            recovered.notes,
+            [
+                "kept the section 'Risks' as it is",
+                "used the text before the first section as the context"
+            ]
+        );
+
+        let recovered = recover("caching", "Just some notes.\n", 4, "2024-06-01");
+        assert_eq!(recovered.adr.title, "Caching");
+        assert_eq!(recovered.adr.date.as_deref(), Some("2024-06-01"));
+        assert_eq!(
+            recovered.notes,
+            [
+                "no title heading, titled from the file name",
+                "used the text before the first section as the context",
+                "no Decision section found",
+                "no status found, imported as Proposed",
+                "no date found, dated today"
+            ]
+        );
+    }
+}
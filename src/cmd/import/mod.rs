@@ -0,0 +1,45 @@
+use anyhow::Result;
+use clap::Subcommand;
+
+pub mod adr_manager;
+#[cfg(feature = "asciidoc")]
+pub mod asciidoc;
+pub mod json;
+pub mod markdown;
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum ImportCommands {
+    /// Import ADRs from a JSON-ADR export
+    Json(json::JsonArgs),
+    /// Import ADRs from the JSON of the adr-manager web editor, as MADR documents
+    AdrManager(adr_manager::AdrManagerArgs),
+    /// Import a directory of markdown files that aren't ADRs, e.g. a wiki export, detecting
+    /// their titles, statuses and sections and reporting what couldn't be mapped
+    Markdown(markdown::MarkdownArgs),
+    /// Import a directory of AsciiDoc decision records, converting their sections to markdown
+    #[cfg(feature = "asciidoc")]
+    Asciidoc(asciidoc::AsciidocArgs),
+}
+
+impl ImportCommands {
+    // whether the import only reports what it would do, writing nothing
+    pub(crate) fn is_dry_run(&self) -> bool {
+        match self {
+            ImportCommands::Json(args) => args.dry_run,
+            ImportCommands::AdrManager(_) => false,
+            ImportCommands::Markdown(args) => args.dry_run,
+            #[cfg(feature = "asciidoc")]
+            ImportCommands::Asciidoc(args) => args.dry_run,
+        }
+    }
+}
+
+pub(crate) fn run(args: &ImportCommands) -> Result<()> {
+    match args {
+        ImportCommands::Json(args) => json::run_json(args),
+        ImportCommands::AdrManager(args) => adr_manager::run_adr_manager(args),
+        ImportCommands::Markdown(args) => markdown::run_markdown(args),
+        #[cfg(feature = "asciidoc")]
+        ImportCommands::Asciidoc(args) => asciidoc::run_asciidoc(args),
+    }
+}
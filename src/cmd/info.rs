@@ -0,0 +1,95 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{configured_adr_dir, find_repo_root, list_adrs};
+use crate::config::{load_config, CONFIG_FILE};
+use crate::paths;
+
+#[derive(Debug, Args)]
+pub(crate) struct InfoArgs {}
+
+// the optional cargo features this binary was built with
+pub(crate) fn compiled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "lsp") {
+        features.push("lsp");
+    }
+    if cfg!(feature = "sqlite") {
+        features.push("sqlite");
+    }
+    if cfg!(feature = "http") {
+        features.push("http");
+    }
+    if cfg!(feature = "asciidoc") {
+        features.push("asciidoc");
+    }
+    if cfg!(feature = "ansi") {
+        features.push("ansi");
+    }
+    features
+}
+
+// describe whether a file or directory exists
+fn presence(path: &Path) -> &'static str {
+    if path.exists() {
+        "found"
+    } else {
+        "not found"
+    }
+}
+
+fn show_path(name: &str, path: Option<PathBuf>) {
+    match path {
+        Some(path) => println!("{}={} ({})", name, path.display(), presence(&path)),
+        None => println!("{}=", name),
+    }
+}
+
+pub(crate) fn run(_args: &InfoArgs) -> Result<()> {
+    println!("adrs_version={}", env!("CARGO_PKG_VERSION"));
+    println!(
+        "platform={}-{}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    println!("features={}", compiled_features().join(","));
+
+    match find_repo_root() {
+        Some(root) => println!("repo_root={}", root.display()),
+        None => println!("repo_root="),
+    }
+    match configured_adr_dir() {
+        Ok(adr_dir) => {
+            let count = list_adrs(&adr_dir).map(|adrs| adrs.len()).unwrap_or(0);
+            println!("adrs_dir={}", adr_dir.display());
+            println!("adrs_count={}", count);
+        }
+        Err(_) => println!("adrs_dir="),
+    }
+
+    show_path("config_file", Some(PathBuf::from(CONFIG_FILE)));
+    show_path("global_config", paths::global_config_file());
+    show_path("cache_dir", paths::cache_dir());
+    show_path("state_dir", paths::state_dir());
+
+    let config = load_config().context("Unable to load configuration")?;
+    println!(
+        "mode={}",
+        toml::Value::try_from(config.mode)?.as_str().unwrap()
+    );
+    println!(
+        "template_format={}",
+        toml::Value::try_from(config.templates.format)?
+            .as_str()
+            .unwrap()
+    );
+
+    println!("\n# effective configuration");
+    print!(
+        "{}",
+        toml::to_string_pretty(&config).context("Unable to serialize configuration")?
+    );
+    Ok(())
+}
@@ -1,23 +1,115 @@
 use std::{
+    collections::BTreeMap,
     fs::create_dir_all,
+    io::IsTerminal,
     path::{Path, PathBuf},
 };
 
 use anyhow::{Context, Result};
-use clap::Args;
+use clap::{Args, ValueEnum};
+use regex::Regex;
 use serde::Serialize;
 use tinytemplate::TinyTemplate;
 
-use crate::adr::{format_adr_path, next_adr_number, now};
+use crate::adr::{
+    format_adr_path, get_title, next_adr_number, now, to_forward_slashes, with_frontmatter,
+};
+use crate::cmd::doctor::DEFAULT_RULES;
+use crate::config::{load_config, update_config_file, Config, Mode, TemplateFormat, CONFIG_FILE};
+use crate::profile;
+use crate::prompt::{prompt, prompt_bool, prompt_choice};
 
 static INIT_TEMPLATE: &str = include_str!("../../templates/nygard/init.md");
+static MADR_INIT_TEMPLATE: &str = include_str!("../../templates/madr/init.md");
+
+static DEFAULT_ADR_DIR: &str = "doc/adr";
 
 #[derive(Debug, Args)]
 #[command(version, about, long_about = None)]
 pub(crate) struct InitArgs {
-    /// Directory to initialize
-    #[arg(default_value = "doc/adr")]
-    directory: PathBuf,
+    /// Directory to initialize [default: doc/adr]
+    directory: Option<PathBuf>,
+    /// Walk through the setup interactively and write the choices to .adrs.toml. This is the
+    /// default when init is run without any arguments and stdin is a terminal
+    #[arg(short, long, default_value_t = false)]
+    interactive: bool,
+    /// Set up the repository using a named bundle of settings, written to .adrs.toml
+    #[arg(short, long)]
+    preset: Option<Preset>,
+    /// Don't create the initial "Record architecture decisions" ADR
+    #[arg(long, default_value_t = false)]
+    no_first_adr: bool,
+    /// Set up around a directory that already holds ADRs, without creating the initial ADR.
+    /// Files that don't follow the NNNN-title.md naming are listed, and can be renamed
+    #[arg(long, default_value_t = false)]
+    adopt_existing: bool,
+    /// Rename adopted ADRs with non-standard file names to NNNN-title.md without asking
+    #[arg(long, default_value_t = false, requires = "adopt_existing")]
+    normalize: bool,
+}
+
+/// Named bundles of mode, template format, initial ADR and lint rules
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum Preset {
+    /// MADR templates with nextgen metadata
+    MadrNg,
+    /// Nygard templates, compatible with adr-tools
+    NygardCompat,
+    /// Compatible mode without the initial ADR
+    Minimal,
+}
+
+impl Preset {
+    // the lint rules the preset checks on top of the default ones
+    fn extra_rules(&self) -> &'static [&'static str] {
+        match self {
+            Preset::NygardCompat => &["adr-tools"],
+            Preset::MadrNg | Preset::Minimal => &[],
+        }
+    }
+
+    // apply the preset to the configuration, returning whether the initial ADR should be created.
+    // The lint rules are only set when the preset adds to the defaults
+    fn apply(&self, config: &mut Config) -> bool {
+        let (mode, format, create_first_adr) = match self {
+            Preset::MadrNg => (Mode::Nextgen, TemplateFormat::Madr, true),
+            Preset::NygardCompat => (Mode::Compatible, TemplateFormat::Nygard, true),
+            Preset::Minimal => (Mode::Compatible, TemplateFormat::Nygard, false),
+        };
+        config.mode = mode;
+        config.templates.format = format;
+        if !self.extra_rules().is_empty() {
+            config.lint.rules = DEFAULT_RULES
+                .iter()
+                .chain(self.extra_rules())
+                .map(|rule| rule.to_string())
+                .collect();
+        }
+        create_first_adr
+    }
+}
+
+// the settings init chose, as they are written to .adrs.toml: the mode and template format, and
+// the starting tags and lint rules when they were chosen too
+fn chosen_settings(config: &Config, tags: bool, rules: bool) -> Result<toml::Table> {
+    let mut settings = toml::Table::new();
+    settings.insert("mode".to_string(), toml::Value::try_from(config.mode)?);
+    let templates = toml::Table::from_iter([(
+        "format".to_string(),
+        toml::Value::try_from(config.templates.format)?,
+    )]);
+    settings.insert("templates".to_string(), templates.into());
+    if tags {
+        settings.insert("tags".to_string(), toml::Value::try_from(&config.tags)?);
+    }
+    if rules {
+        let lint = toml::Table::from_iter([(
+            "rules".to_string(),
+            toml::Value::try_from(&config.lint.rules)?,
+        )]);
+        settings.insert("lint".to_string(), lint.into());
+    }
+    Ok(settings)
 }
 
 #[derive(Debug, Serialize)]
@@ -26,32 +118,244 @@ struct InitAdrContext {
     date: String,
 }
 
+// whether to walk through the setup: when asked to, or for a bare `adrs init` at a terminal
+fn runs_wizard(args: &InitArgs, terminal: bool) -> bool {
+    let no_options = args.directory.is_none()
+        && args.preset.is_none()
+        && !args.no_first_adr
+        && !args.adopt_existing;
+    args.interactive || (no_options && terminal)
+}
+
 pub(crate) fn run(args: &InitArgs) -> Result<()> {
-    create_dir_all(&args.directory)
-        .with_context(|| format!("Unable to create {}", args.directory.display()))?;
+    if runs_wizard(args, std::io::stdin().is_terminal()) {
+        return run_interactive(args);
+    }
+
+    let mut config = load_config().context("Unable to load configuration")?;
+    let directory = args
+        .directory
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_ADR_DIR));
+    let create_first_adr = match args.preset {
+        Some(preset) => {
+            let create_first_adr = preset.apply(&mut config);
+            update_config_file(
+                Path::new(CONFIG_FILE),
+                chosen_settings(&config, false, !preset.extra_rules().is_empty())?,
+            )?;
+            create_first_adr
+        }
+        None => true,
+    };
+    if args.adopt_existing {
+        return adopt_adr_dir(&directory, args.normalize);
+    }
+    init_adr_dir(&directory, &config, create_first_adr && !args.no_first_adr)
+}
+
+// prompt for each of the setup choices, then write the configuration and initialize the directory
+fn run_interactive(args: &InitArgs) -> Result<()> {
+    let mut config = load_config().context("Unable to load configuration")?;
+    let create_first_adr = match args.preset {
+        Some(preset) => preset.apply(&mut config),
+        None => true,
+    };
+    let default_dir = args
+        .directory
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_ADR_DIR));
+
+    let directory = PathBuf::from(prompt("ADR directory", default_dir.to_str().unwrap())?);
+    let mode = prompt_choice("Mode", config.mode)?;
+    let format = prompt_choice("Default template format", config.templates.format)?;
+    let tags = prompt(
+        "Tags to start with, comma separated",
+        &config.tags.join(", "),
+    )?
+    .split(',')
+    .map(|tag| tag.trim().to_string())
+    .filter(|tag| !tag.is_empty())
+    .collect::<Vec<_>>();
+    let create_first_adr = !args.adopt_existing
+        && prompt_bool(
+            "Create the initial ADR",
+            create_first_adr && !args.no_first_adr,
+        )?;
+
+    config.mode = mode;
+    config.templates.format = format;
+    config.tags = tags;
+    update_config_file(
+        Path::new(CONFIG_FILE),
+        chosen_settings(
+            &config,
+            true,
+            args.preset
+                .is_some_and(|preset| !preset.extra_rules().is_empty()),
+        )?,
+    )?;
+
+    if args.adopt_existing {
+        return adopt_adr_dir(&directory, args.normalize);
+    }
+    init_adr_dir(&directory, &config, create_first_adr)
+}
+
+// record the ADR directory in .adr-dir
+fn record_adr_dir(directory: &Path) -> Result<()> {
+    std::fs::write(
+        std::env::current_dir()?.join(".adr-dir"),
+        to_forward_slashes(directory),
+    )?;
+    Ok(())
+}
+
+// the number and title of an existing ADR file name such as `adr-001-use-postgres.md` or
+// `12_Use_Postgres.md`, if it has a number
+fn parse_existing_name(filename: &str) -> Option<(i32, &str)> {
+    let re = Regex::new(r"^(?i:adr)?[-_ ]?(?<number>\d+)[-_. ]*(?<title>.*)\.md$").unwrap();
+    let caps = re.captures(filename)?;
+    let title = caps.name("title").unwrap().as_str();
+    Some((caps["number"].parse().ok()?, title))
+}
+
+// the numbered markdown files in the directory, and the renames that would give the ones with
+// non-standard names the conventional NNNN-title.md name
+fn adoption_plan(directory: &Path) -> Result<(usize, Vec<(PathBuf, PathBuf)>)> {
+    let mut files = std::fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    files.sort();
+
+    let mut adopted = 0;
+    let mut renames = Vec::new();
+    let mut numbers: BTreeMap<i32, PathBuf> = BTreeMap::new();
+    for path in files.into_iter().filter(|path| path.is_file()) {
+        let filename = path.file_name().unwrap().to_string_lossy();
+        let Some((number, title)) = parse_existing_name(&filename) else {
+            continue;
+        };
+        if let Some(other) = numbers.insert(number, path.clone()) {
+            anyhow::bail!(
+                "{} and {} are both numbered {}, renumber one of them first",
+                other.display(),
+                path.display(),
+                number
+            );
+        }
+        adopted += 1;
+        // a file named only by its number takes its title from the heading, without its number
+        let title = match title {
+            "" => {
+                let heading = get_title(&path).unwrap_or_default();
+                match heading.split_once(". ") {
+                    Some((number, title)) if number.parse::<i32>().is_ok() => title.to_string(),
+                    _ => heading,
+                }
+            }
+            title => title.to_string(),
+        };
+        let normalized = format_adr_path(directory, number, &title);
+        if normalized != path && !title.is_empty() {
+            renames.push((path, normalized));
+        }
+    }
+    Ok((adopted, renames))
+}
+
+// record an already populated directory as the ADR directory, offering to rename the files
+// that don't follow the conventional naming
+fn adopt_adr_dir(directory: &Path, normalize: bool) -> Result<()> {
+    if !directory.is_dir() {
+        anyhow::bail!(
+            "Unable to adopt {}, it isn't an existing directory",
+            directory.display()
+        );
+    }
+    let (adopted, renames) = adoption_plan(directory)?;
+    record_adr_dir(directory)?;
+    println!(
+        "Adopted {} ADR(s) in {}",
+        adopted,
+        to_forward_slashes(directory)
+    );
+    if renames.is_empty() {
+        return Ok(());
+    }
+
+    println!(
+        "{} file(s) don't follow the NNNN-title.md naming:",
+        renames.len()
+    );
+    for (from, to) in &renames {
+        println!(
+            "  {} -> {}",
+            to_forward_slashes(from),
+            to_forward_slashes(to)
+        );
+    }
+    let normalize =
+        normalize || (std::io::stdin().is_terminal() && prompt_bool("Rename them", false)?);
+    if !normalize {
+        println!("Run again with --normalize to rename them");
+        return Ok(());
+    }
+    // check every target before renaming anything, and put the files back if a rename fails
+    if let Some((_, to)) = renames.iter().find(|(_, to)| to.exists()) {
+        anyhow::bail!("{} already exists", to.display());
+    }
+    for (i, (from, to)) in renames.iter().enumerate() {
+        if let Err(e) = std::fs::rename(from, to) {
+            for (from, to) in &renames[..i] {
+                let _ = std::fs::rename(to, from);
+            }
+            return Err(e).with_context(|| format!("Unable to rename {}", from.display()));
+        }
+    }
+    println!(
+        "Renamed {} file(s), run `adrs fix-links` to update links between them",
+        renames.len()
+    );
+    Ok(())
+}
 
-    let number = next_adr_number(Path::new(&args.directory))
-        .context("Unable to determine next ADR number")?;
+// create the ADR directory, record it in .adr-dir and optionally write the first ADR
+fn init_adr_dir(directory: &Path, config: &Config, create_first_adr: bool) -> Result<()> {
+    create_dir_all(directory)
+        .with_context(|| format!("Unable to create {}", directory.display()))?;
+
+    record_adr_dir(directory)?;
+
+    if !create_first_adr {
+        return Ok(());
+    }
+
+    let number = next_adr_number(directory).context("Unable to determine next ADR number")?;
 
     let title = "Record architecture decisions";
 
-    let filename = format_adr_path(&args.directory, number, title);
+    let filename = format_adr_path(directory, number, title);
 
     let init_context = InitAdrContext {
         number,
         date: now()?,
     };
 
-    std::fs::write(
-        std::env::current_dir()?.join(".adr-dir"),
-        args.directory.to_str().unwrap(),
-    )?;
+    let template = match config.templates.format {
+        TemplateFormat::Nygard => INIT_TEMPLATE,
+        TemplateFormat::Madr => MADR_INIT_TEMPLATE,
+    };
 
-    let mut tt = TinyTemplate::new();
-    tt.add_template("init_adr", INIT_TEMPLATE)?;
-    let rendered = tt
-        .render("init_adr", &init_context)
-        .context("Unable to render template")?;
+    let rendered = {
+        let _span = profile::span("rendering");
+        let mut tt = TinyTemplate::new();
+        tt.add_template("init_adr", template)?;
+        tt.render("init_adr", &init_context)
+            .context("Unable to render template")?
+    };
+    let rendered = with_frontmatter(config.mode, rendered);
+    let _span = profile::span("writing");
     std::fs::write(&filename, rendered)
         .with_context(|| format!("Unable to write ADR file: {}", filename.display()))?;
 
@@ -59,3 +363,64 @@ pub(crate) fn run(args: &InitArgs) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_existing_name() {
+        assert_eq!(
+            parse_existing_name("adr-001-use-postgres.md"),
+            Some((1, "use-postgres"))
+        );
+        assert_eq!(
+            parse_existing_name("ADR_12_Use_Postgres.md"),
+            Some((12, "Use_Postgres"))
+        );
+        assert_eq!(
+            parse_existing_name("0003-use-mysql.md"),
+            Some((3, "use-mysql"))
+        );
+        assert_eq!(parse_existing_name("7.md"), Some((7, "")));
+        assert_eq!(parse_existing_name("README.md"), None);
+        assert_eq!(parse_existing_name("0001-notes.txt"), None);
+    }
+
+    #[test]
+    fn test_runs_wizard() {
+        let bare = || InitArgs {
+            directory: None,
+            interactive: false,
+            preset: None,
+            no_first_adr: false,
+            adopt_existing: false,
+            normalize: false,
+        };
+        assert!(runs_wizard(&bare(), true));
+        assert!(!runs_wizard(&bare(), false));
+        for args in [
+            InitArgs {
+                preset: Some(Preset::Minimal),
+                ..bare()
+            },
+            InitArgs {
+                no_first_adr: true,
+                ..bare()
+            },
+            InitArgs {
+                adopt_existing: true,
+                ..bare()
+            },
+        ] {
+            assert!(!runs_wizard(&args, true));
+            assert!(runs_wizard(
+                &InitArgs {
+                    interactive: true,
+                    ..args
+                },
+                false
+            ));
+        }
+    }
+}
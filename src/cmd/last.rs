@@ -0,0 +1,34 @@
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{find_adr_dir, get_number, list_adrs};
+
+#[derive(Debug, Args)]
+pub(crate) struct LastArgs {
+    /// Show the most recently modified ADR instead of the most recently created one
+    #[arg(short, long, default_value_t = false)]
+    modified: bool,
+}
+
+pub(crate) fn run(args: &LastArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let adrs = list_adrs(&adr_dir)?;
+
+    let last = if args.modified {
+        adrs.into_iter()
+            .filter_map(|path| {
+                let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+                Some((modified, path))
+            })
+            .max()
+            .map(|(_, path)| path)
+    } else {
+        // the highest number is the most recently created
+        adrs.into_iter().max_by_key(|path| get_number(path))
+    };
+    let Some(last) = last else {
+        anyhow::bail!("No ADRs found in {}", adr_dir.display());
+    };
+    println!("{}", last.display());
+    Ok(())
+}
@@ -3,7 +3,8 @@ use std::path::Path;
 use anyhow::{Context, Result};
 use clap::Args;
 
-use crate::adr::{append_status, find_adr, find_adr_dir, get_title};
+use crate::adr::{append_status_markdown, find_adr, find_adr_dir, get_title};
+use crate::transaction::Transaction;
 
 #[derive(Debug, Args)]
 pub(crate) struct LinkArgs {
@@ -36,8 +37,16 @@ pub(crate) fn run(args: &LinkArgs) -> Result<()> {
         args.reverse_link, source_title, source_filename
     );
 
-    append_status(&source, &source_link).context("Unable to append status for source ADR")?;
-    append_status(&target, &target_link).context("Unable to append status for target ADR")?;
+    let mut tx = Transaction::new();
+    tx.update(&source, |markdown| {
+        append_status_markdown(markdown, &source_link)
+            .context("Unable to append status for source ADR")
+    })?;
+    tx.update(&target, |markdown| {
+        append_status_markdown(markdown, &target_link)
+            .context("Unable to append status for target ADR")
+    })?;
+    tx.commit()?;
 
     Ok(())
 }
@@ -1,17 +1,228 @@
+use std::path::Path;
+
 use anyhow::{Context, Result};
 use clap::Args;
 
-use crate::adr::{find_adr_dir, list_adrs};
+use crate::adr::{find_adr_dir, get_number, list_adrs};
+use crate::config::{load_config, MarkerStyle, CONFIG_FILE};
+use crate::markers::Markers;
+use crate::model::{Adr, Implemented};
+
+#[derive(Debug, Args)]
+pub(crate) struct ListArgs {
+    /// Show the status, dates and format of each ADR
+    #[arg(short, long, default_value_t = false)]
+    long: bool,
+    /// Only list ADRs implemented this far. ADRs that don't record it count as not implemented
+    #[arg(long, value_enum)]
+    implemented: Option<Implemented>,
+    /// Only list ADRs with this tag, from the frontmatter or a `Tags:` line
+    #[arg(short, long)]
+    tag: Option<String>,
+    /// Mark each ADR with its status, overriding markers.style in the configuration
+    #[arg(long, value_enum)]
+    markers: Option<MarkerStyle>,
+    /// List the ADRs of every configured scope together, each labelled with its scope and
+    /// number, e.g. payments/0001
+    #[arg(long, default_value_t = false)]
+    all_scopes: bool,
+    #[command(flatten)]
+    relations: RelationArgs,
+}
 
+// which ADRs to list by how they are linked to the others, whichever side wrote the link
 #[derive(Debug, Args)]
-pub(crate) struct ListArgs {}
+pub(crate) struct RelationArgs {
+    /// Only list ADRs that aren't linked to or from any other ADR
+    #[arg(long, default_value_t = false)]
+    orphans: bool,
+    /// Only list ADRs that supersede another
+    #[arg(long, default_value_t = false)]
+    supersedes: bool,
+    /// Only list ADRs that have been superseded
+    #[arg(long, default_value_t = false)]
+    superseded: bool,
+    /// Only list ADRs that amend ADR N
+    #[arg(long, value_name = "N")]
+    amends: Option<i32>,
+    /// Only list ADRs linked to ADR N, in either direction
+    #[arg(long, value_name = "N")]
+    by: Option<i32>,
+}
+
+// whether the ADR links to the target with a link of the kind, or the target links back to it
+// with the reverse kind. Any kind matches when none is given
+fn related(adr: &Adr, target: i32, kinds: Option<(&str, &str)>, adrs: &[Adr]) -> bool {
+    let matches = |kind: &str, wanted: Option<&str>| {
+        wanted.is_none_or(|wanted| kind.eq_ignore_ascii_case(wanted))
+    };
+    let (forward, reverse) = kinds.unzip();
+    adr.links
+        .iter()
+        .any(|link| link.target == target && matches(&link.kind, forward))
+        || adrs
+            .iter()
+            .filter(|other| other.number == target)
+            .any(|other| {
+                other
+                    .links
+                    .iter()
+                    .any(|link| link.target == adr.number && matches(&link.kind, reverse))
+            })
+}
+
+impl RelationArgs {
+    fn any(&self) -> bool {
+        self.orphans
+            || self.supersedes
+            || self.superseded
+            || self.amends.is_some()
+            || self.by.is_some()
+    }
+
+    // whether the ADR passes every relationship filter given
+    fn matches(&self, adr: &Adr, adrs: &[Adr]) -> bool {
+        let others = || adrs.iter().filter(|other| other.number != adr.number);
+        let has_link = |kind: &str| {
+            adr.links
+                .iter()
+                .any(|link| link.kind.eq_ignore_ascii_case(kind))
+        };
+        let linked_from = |kind: &str| {
+            others().any(|other| {
+                other
+                    .links
+                    .iter()
+                    .any(|link| link.target == adr.number && link.kind.eq_ignore_ascii_case(kind))
+            })
+        };
+        if self.orphans
+            && (adr.links.iter().any(|link| link.target != adr.number)
+                || others().any(|other| related(other, adr.number, None, adrs)))
+        {
+            return false;
+        }
+        if self.supersedes && !has_link("Supersedes") && !linked_from("Superseded by") {
+            return false;
+        }
+        if self.superseded
+            && adr.current_status().as_deref() != Some("Superseded")
+            && !linked_from("Supersedes")
+        {
+            return false;
+        }
+        if let Some(target) = self.amends {
+            if !related(adr, target, Some(("Amends", "Amended by")), adrs) {
+                return false;
+            }
+        }
+        if let Some(target) = self.by {
+            if adr.number == target || !related(adr, target, None, adrs) {
+                return false;
+            }
+        }
+        true
+    }
+}
 
-pub(crate) fn run(_args: &ListArgs) -> Result<()> {
+// the status and dates of the ADR, for the long listing
+fn describe(adr: &Adr) -> String {
+    let mut fields = vec![adr.current_status().unwrap_or_else(|| "-".to_string())];
+    let dates = [
+        ("date", &adr.date),
+        ("proposed", &adr.proposed_date),
+        ("decided", &adr.decided_date),
+        ("implemented", &adr.implemented_date),
+    ];
+    for (name, date) in dates {
+        if let Some(date) = date {
+            fields.push(format!("{}={}", name, date));
+        }
+    }
+    if let Some(implemented) = adr.implemented {
+        fields.push(format!("implemented={}", implemented.as_str()));
+    }
+    if let Some(link) = &adr.implementation_link {
+        fields.push(format!("tracking={}", link));
+    }
+    fields.push(format!("format={}", adr.format.as_str()));
+    fields.join(" ")
+}
+
+// the ADR as listed, labelled with its scope and number in a combined listing
+fn label(path: &Path, scope: Option<&str>) -> String {
+    match scope {
+        Some(scope) => format!(
+            "{}/{:04} {}",
+            scope,
+            get_number(path).unwrap_or_default(),
+            path.display()
+        ),
+        None => path.display().to_string(),
+    }
+}
+
+pub(crate) fn run(args: &ListArgs) -> Result<()> {
+    let markers = Markers::load(args.markers)?;
+    if args.all_scopes {
+        let config = load_config().context("Unable to load configuration")?;
+        if config.scopes.is_empty() {
+            anyhow::bail!(
+                "No scopes are configured, add them to [scopes] in {}",
+                CONFIG_FILE
+            );
+        }
+        for (scope, adr_dir) in &config.scopes {
+            // a scope without any ADRs yet may not have its directory
+            if adr_dir.is_dir() {
+                list_dir(args, &markers, adr_dir, Some(scope))?;
+            }
+        }
+        return Ok(());
+    }
     let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    list_dir(args, &markers, &adr_dir, None)
+}
+
+// list the ADRs in the directory that pass the filters
+fn list_dir(args: &ListArgs, markers: &Markers, adr_dir: &Path, scope: Option<&str>) -> Result<()> {
+    let paths = list_adrs(adr_dir)?;
+    if !args.long
+        && args.implemented.is_none()
+        && args.tag.is_none()
+        && !markers.enabled()
+        && !args.relations.any()
+    {
+        for adr in paths {
+            println!("{}", label(&adr, scope));
+        }
+        return Ok(());
+    }
 
-    let adrs = list_adrs(&adr_dir)?;
-    for adr in adrs {
-        println!("{}", adr.display());
+    let adrs = paths
+        .iter()
+        .map(|path| Adr::from_path(adr_dir, path))
+        .collect::<Result<Vec<_>>>()?;
+    for (adr, parsed) in paths.iter().zip(&adrs) {
+        if !args.relations.matches(parsed, &adrs) {
+            continue;
+        }
+        if let Some(tag) = &args.tag {
+            if !parsed.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                continue;
+            }
+        }
+        if let Some(implemented) = args.implemented {
+            if parsed.implemented.unwrap_or(Implemented::No) != implemented {
+                continue;
+            }
+        }
+        let marker = markers.prefix(parsed.current_status().as_deref());
+        if args.long {
+            println!("{}{}\t{}", marker, label(adr, scope), describe(parsed));
+        } else {
+            println!("{}{}", marker, label(adr, scope));
+        }
     }
     Ok(())
 }
@@ -0,0 +1,485 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, DiagnosticSeverity,
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, Location, MarkupContent, MarkupKind, OneOf, Position,
+    PublishDiagnosticsParams, Range, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, Uri,
+};
+use regex::Regex;
+
+use crate::adr::{list_adrs, split_frontmatter, to_forward_slashes};
+use crate::cmd::doctor::{check_adr, lint_rules, Severity};
+use crate::config::load_config;
+use crate::model::{Adr, Implemented, STANDARD_STATUSES};
+
+#[derive(Debug, Args)]
+pub(crate) struct LspArgs {}
+
+struct Server {
+    rules: Vec<String>,
+    // the text of the open documents, which may not have been saved yet
+    documents: HashMap<Uri, String>,
+}
+
+// the local path of a file:// uri
+fn uri_to_path(uri: &Uri) -> Option<PathBuf> {
+    if !uri.scheme()?.as_str().eq_ignore_ascii_case("file") {
+        return None;
+    }
+    let path = uri.path().as_estr().decode().into_string_lossy();
+    // windows paths are written as /C:/...
+    let path = match path.as_bytes() {
+        [b'/', _, b':', ..] => &path[1..],
+        _ => &path[..],
+    };
+    Some(PathBuf::from(path))
+}
+
+fn path_to_uri(path: &Path) -> Option<Uri> {
+    let path = std::fs::canonicalize(path).ok()?;
+    let mut path = to_forward_slashes(&path);
+    if let Some(stripped) = path.strip_prefix("//?/") {
+        path = stripped.to_string();
+    }
+    if !path.starts_with('/') {
+        path.insert(0, '/');
+    }
+    format!("file://{}", path.replace('%', "%25").replace(' ', "%20"))
+        .parse()
+        .ok()
+}
+
+// the byte offset of an lsp character position in a line
+fn byte_offset(line: &str, character: u32) -> usize {
+    let mut utf16 = 0;
+    for (offset, c) in line.char_indices() {
+        if utf16 >= character as usize {
+            return offset;
+        }
+        utf16 += c.len_utf16();
+    }
+    line.len()
+}
+
+// the target of the markdown link under the cursor
+fn link_at(line: &str, offset: usize) -> Option<&str> {
+    let re = Regex::new(r"\[[^\]]*\]\(([^)\s]+)\)").unwrap();
+    let target = re
+        .captures_iter(line)
+        .find(|captures| {
+            let all = captures.get(0).unwrap();
+            all.start() <= offset && offset <= all.end()
+        })
+        .map(|captures| captures.get(1).unwrap().as_str());
+    target
+}
+
+// the line the cursor is on, and the text before the cursor
+fn cursor_line(text: &str, position: Position) -> Option<(&str, usize)> {
+    let line = text.lines().nth(position.line as usize)?;
+    Some((line, byte_offset(line, position.character)))
+}
+
+// whether the line is inside the frontmatter
+fn in_frontmatter(text: &str, line: u32) -> bool {
+    let (frontmatter, _) = split_frontmatter(text);
+    !frontmatter.is_empty() && (line as usize) < frontmatter.lines().count()
+}
+
+// the heading of the section the line is in
+fn current_section(text: &str, line: u32) -> Option<&str> {
+    text.lines()
+        .take(line as usize + 1)
+        .filter_map(|line| line.strip_prefix("## "))
+        .last()
+        .map(str::trim)
+}
+
+fn read_adrs(dir: &Path) -> Vec<Adr> {
+    list_adrs(dir)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|path| Adr::from_path(dir, path).ok())
+        .collect()
+}
+
+fn link_completions(dir: &Path) -> Vec<CompletionItem> {
+    read_adrs(dir)
+        .into_iter()
+        .map(|adr| {
+            let title = format!("{}. {}", adr.number, adr.title);
+            let filename = adr
+                .path
+                .as_deref()
+                .map(to_forward_slashes)
+                .unwrap_or_default();
+            CompletionItem {
+                insert_text: Some(format!("[{}]({})", title, filename)),
+                detail: adr.current_status(),
+                kind: Some(CompletionItemKind::REFERENCE),
+                label: title,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+fn value_completions<'a>(values: impl IntoIterator<Item = &'a str>) -> Vec<CompletionItem> {
+    values
+        .into_iter()
+        .map(|value| CompletionItem {
+            label: value.to_string(),
+            kind: Some(CompletionItemKind::VALUE),
+            ..Default::default()
+        })
+        .collect()
+}
+
+// the tags used by any ADR
+fn tag_completions(dir: &Path) -> Vec<CompletionItem> {
+    let tags = read_adrs(dir)
+        .into_iter()
+        .flat_map(|adr| adr.tags)
+        .collect::<BTreeSet<_>>();
+    value_completions(tags.iter().map(String::as_str))
+}
+
+// tags and implementation state in the frontmatter or on a `Tags:` line, statuses and links in
+// the Status section, and links to the other ADRs anywhere else
+fn completions(dir: &Path, text: &str, position: Position) -> Vec<CompletionItem> {
+    let Some((line, offset)) = cursor_line(text, position) else {
+        return Vec::new();
+    };
+    if in_frontmatter(text, position.line) {
+        let prefix = &line[..offset];
+        if prefix.starts_with("tags:") || prefix.trim_start().starts_with('-') {
+            return tag_completions(dir);
+        }
+        if prefix.starts_with("implemented:") {
+            return value_completions(
+                [Implemented::True, Implemented::Partial, Implemented::No].map(|i| i.as_str()),
+            );
+        }
+        return Vec::new();
+    }
+
+    if line.starts_with("Tags:") && current_section(text, position.line).is_none() {
+        return tag_completions(dir);
+    }
+
+    let mut items = Vec::new();
+    if current_section(text, position.line) == Some("Status") {
+        // the statuses offered when completing in the Status section
+        items.extend(value_completions(STANDARD_STATUSES.iter().copied()));
+    }
+    items.extend(link_completions(dir));
+    items
+}
+
+// the title and status of the linked ADR
+fn hover(path: &Path, text: &str, position: Position) -> Option<Hover> {
+    let (line, offset) = cursor_line(text, position)?;
+    let target = path.parent()?.join(link_at(line, offset)?);
+    let adr = Adr::from_path(target.parent()?, &target).ok()?;
+    let mut value = format!("**{}. {}**", adr.number, adr.title);
+    if let Some(status) = adr.current_status() {
+        value += &format!("\n\nStatus: {}", status);
+    }
+    if let Some(date) = &adr.date {
+        value += &format!("\n\nDate: {}", date);
+    }
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value,
+        }),
+        range: None,
+    })
+}
+
+fn definition(path: &Path, text: &str, position: Position) -> Option<Location> {
+    let (line, offset) = cursor_line(text, position)?;
+    let target = path.parent()?.join(link_at(line, offset)?);
+    if !target.is_file() {
+        return None;
+    }
+    Some(Location::new(path_to_uri(&target)?, Range::default()))
+}
+
+// the line a diagnostic should be shown on, the first line when it isn't about a particular line
+fn diagnostic_line(text: &str, rule: &str, message: &str) -> u32 {
+    let found = text.lines().position(|line| match rule {
+        "date" => line.trim().starts_with("Date:"),
+        "status" => line.starts_with("## Status"),
+        "links" => message
+            .rsplit_once(' ')
+            .is_some_and(|(_, target)| line.contains(&format!("]({})", target))),
+        _ => false,
+    });
+    found.unwrap_or(0) as u32
+}
+
+impl Server {
+    fn diagnostics(&self, uri: &Uri) -> Option<Notification> {
+        let path = uri_to_path(uri)?;
+        let text = std::fs::read_to_string(&path).ok()?;
+        let rules = self.rules.iter().map(String::as_str).collect::<Vec<_>>();
+        let mut found = Vec::new();
+        check_adr(&path, &rules, &mut found);
+
+        let diagnostics = found
+            .into_iter()
+            .map(|d| {
+                let line = diagnostic_line(&text, d.rule, &d.message);
+                lsp_types::Diagnostic {
+                    range: Range::new(Position::new(line, 0), Position::new(line, u32::MAX)),
+                    severity: Some(match d.severity {
+                        Severity::Error => DiagnosticSeverity::ERROR,
+                        Severity::Warning => DiagnosticSeverity::WARNING,
+                        Severity::Info => DiagnosticSeverity::INFORMATION,
+                    }),
+                    code: Some(lsp_types::NumberOrString::String(d.rule.to_string())),
+                    source: Some("adrs".to_string()),
+                    message: d.message,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        let params = PublishDiagnosticsParams::new(uri.clone(), diagnostics, None);
+        Some(Notification::new(
+            PublishDiagnostics::METHOD.to_string(),
+            params,
+        ))
+    }
+
+    // the text of an open document, or the file on disk
+    fn document(&self, uri: &Uri) -> Option<(PathBuf, String)> {
+        let path = uri_to_path(uri)?;
+        let text = match self.documents.get(uri) {
+            Some(text) => text.clone(),
+            None => std::fs::read_to_string(&path).ok()?,
+        };
+        Some((path, text))
+    }
+
+    fn handle_request(&self, request: Request) -> Result<Response> {
+        let id = request.id.clone();
+        let response = match request.method.as_str() {
+            Completion::METHOD => {
+                let params: CompletionParams = serde_json::from_value(request.params)?;
+                let position = params.text_document_position;
+                let items = self
+                    .document(&position.text_document.uri)
+                    .map(|(path, text)| {
+                        let dir = path.parent().unwrap_or(Path::new("."));
+                        completions(dir, &text, position.position)
+                    })
+                    .unwrap_or_default();
+                Response::new_ok(id, items)
+            }
+            HoverRequest::METHOD => {
+                let params: HoverParams = serde_json::from_value(request.params)?;
+                let position = params.text_document_position_params;
+                let result = self
+                    .document(&position.text_document.uri)
+                    .and_then(|(path, text)| hover(&path, &text, position.position));
+                Response::new_ok(id, result)
+            }
+            GotoDefinition::METHOD => {
+                let params: GotoDefinitionParams = serde_json::from_value(request.params)?;
+                let position = params.text_document_position_params;
+                let result = self
+                    .document(&position.text_document.uri)
+                    .and_then(|(path, text)| definition(&path, &text, position.position))
+                    .map(GotoDefinitionResponse::Scalar);
+                Response::new_ok(id, result)
+            }
+            method => Response::new_err(
+                id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request {}", method),
+            ),
+        };
+        Ok(response)
+    }
+
+    // diagnostics are refreshed when a document is opened or saved, as the checks read the file
+    fn handle_notification(&mut self, notification: Notification) -> Result<Option<Notification>> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents
+                    .insert(uri.clone(), params.text_document.text);
+                Ok(self.diagnostics(&uri))
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.documents.insert(params.text_document.uri, change.text);
+                }
+                Ok(None)
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: DidSaveTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                Ok(self.diagnostics(&params.text_document.uri))
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                self.documents.remove(&params.text_document.uri);
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+pub(crate) fn run(_args: &LspArgs) -> Result<()> {
+    let config = load_config().context("Unable to load configuration")?;
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["[".to_string()]),
+            ..Default::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    connection
+        .initialize(serde_json::to_value(capabilities)?)
+        .context("Unable to initialize the language server")?;
+
+    let mut server = Server {
+        rules: lint_rules(&config).into_iter().map(String::from).collect(),
+        documents: HashMap::new(),
+    };
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                let id = request.id.clone();
+                let response = server.handle_request(request).unwrap_or_else(|e| {
+                    Response::new_err(id, ErrorCode::InvalidParams as i32, format!("{:#}", e))
+                });
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                if let Some(reply) = server.handle_notification(notification)? {
+                    connection.sender.send(Message::Notification(reply))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_link_at() {
+        let line = "Supersedes [1. Use MySQL](0001-use-mysql.md) and [2. B](0002-b.md)";
+        assert_eq!(link_at(line, 0), None);
+        assert_eq!(link_at(line, 15), Some("0001-use-mysql.md"));
+        assert_eq!(link_at(line, 52), Some("0002-b.md"));
+    }
+
+    #[test]
+    fn test_byte_offset() {
+        assert_eq!(byte_offset("abc", 2), 2);
+        assert_eq!(byte_offset("é b", 2), 3);
+        assert_eq!(byte_offset("abc", 10), 3);
+    }
+
+    #[test]
+    fn test_uri_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("0001 record.md");
+        std::fs::write(&path, "# 1. Record\n").unwrap();
+        let uri = path_to_uri(&path).unwrap();
+        assert!(uri.as_str().ends_with("/0001%20record.md"));
+        assert_eq!(
+            std::fs::canonicalize(uri_to_path(&uri).unwrap()).unwrap(),
+            std::fs::canonicalize(&path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_completions_and_hover() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("0001-use-mysql.md"),
+            "---\ntags: [database]\n---\n\n# 1. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n",
+        )
+        .unwrap();
+        let path = temp.path().join("0002-use-postgres.md");
+        let text = "---\ntags: []\n---\n\n# 2. Use Postgres\n\n## Status\n\nSupersedes [1. Use MySQL](0001-use-mysql.md)\n";
+
+        let tags = completions(temp.path(), text, Position::new(1, 7));
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].label, "database");
+        let compatible = "# 2. Use Postgres\n\nTags: \n\n## Status\n\nAccepted\n";
+        let tags = completions(temp.path(), compatible, Position::new(2, 6));
+        assert_eq!(tags.len(), 1);
+
+        let status = completions(temp.path(), text, Position::new(8, 0));
+        assert!(status.iter().any(|item| item.label == "Accepted"));
+        let link = status
+            .iter()
+            .find(|item| item.label == "1. Use MySQL")
+            .unwrap();
+        assert_eq!(
+            link.insert_text.as_deref(),
+            Some("[1. Use MySQL](0001-use-mysql.md)")
+        );
+
+        let hover = hover(&path, text, Position::new(8, 14)).unwrap();
+        let HoverContents::Markup(content) = hover.contents else {
+            panic!("expected markdown");
+        };
+        assert_eq!(
+            content.value,
+            "**1. Use MySQL**\n\nStatus: Accepted\n\nDate: 2024-01-01"
+        );
+        assert!(definition(&path, text, Position::new(8, 14)).is_some());
+        assert!(definition(&path, text, Position::new(4, 0)).is_none());
+    }
+
+    #[test]
+    fn test_diagnostic_line() {
+        let text = "# 1. A\n\nDate: someday\n\n## Status\n\nAmends [2. B](0002-b.md)\n";
+        assert_eq!(diagnostic_line(text, "date", ""), 2);
+        assert_eq!(diagnostic_line(text, "status", ""), 4);
+        assert_eq!(
+            diagnostic_line(text, "links", "'Amends' links to missing file 0002-b.md"),
+            6
+        );
+        assert_eq!(diagnostic_line(text, "title", ""), 0);
+    }
+}
@@ -1,101 +1,350 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
 use anyhow::{Context, Result};
 use clap::Args;
-use edit::edit;
 use serde::Serialize;
 use tinytemplate::TinyTemplate;
 
 use crate::adr::{
-    append_status, find_adr, find_adr_dir, format_adr_path, get_title, next_adr_number, now,
-    remove_status,
+    append_status_markdown, find_adr, find_adr_by_number, find_adr_dir, find_repo_root,
+    format_adr_path, get_title, next_adr_number, now, record_status_date, remove_status_markdown,
+    reverse_link_kind, set_frontmatter_field, to_forward_slashes, with_frontmatter,
 };
+use crate::clipboard::{read_clipboard, split_draft_title};
+use crate::config::{load_config, Mode, TemplateFormat};
+use crate::editor::edit_text;
+use crate::git::{current_branch, user_name};
+use crate::profile;
+use crate::reservations::Reservations;
+use crate::state::State;
+use crate::transaction::Transaction;
 
 static NEW_TEMPLATE: &str = include_str!("../../templates/nygard/new.md");
+static MADR_NEW_TEMPLATE: &str = include_str!("../../templates/madr/new.md");
 
 #[derive(Debug, Args)]
 #[command(version, about, long_about = None)]
 pub(crate) struct NewArgs {
     /// A reference to a previous decision to supersede with this new one
-    #[arg(short, long, alias("superceded"))]
+    #[arg(short, long, aliases(["superceded", "supersedes"]))]
     superseded: Vec<String>,
-    /// Link the new Architectural Decision to a previous Architectural Decision Record
+    /// Link the new Architectural Decision to a previous Architectural Decision Record, as
+    /// TARGET:LINK[:REVERSE-LINK]. May be repeated; the reverse link is derived when omitted
     #[arg(short, long)]
     link: Vec<String>,
+    /// The template format to use, overriding the configured default
+    #[arg(short, long)]
+    format: Option<TemplateFormat>,
+    /// Use a number previously reserved with `adrs reserve`, releasing the reservation
+    #[arg(long, value_name = "NUMBER")]
+    reserved: Option<i32>,
+    /// Use the clipboard contents as the draft body. The title is taken from its first heading
+    /// when TITLE is omitted
+    #[arg(long, default_value_t = false)]
+    from_clipboard: bool,
+    /// Tag the new Architectural Decision Record. May be repeated. Compatible mode writes the tags
+    /// on a `Tags:` line under the date, nextgen mode in the frontmatter
+    #[arg(short, long)]
+    tag: Vec<String>,
+    /// Set a variable for the template, as KEY=VALUE. May be repeated; templates use it as
+    /// `{vars.KEY}`
+    #[arg(long = "var", value_name = "KEY=VALUE")]
+    vars: Vec<String>,
     /// Title of the new Architectural Decision Record
-    #[arg(trailing_var_arg = true, required = true)]
+    #[arg(trailing_var_arg = true, required_unless_present = "from_clipboard")]
     title: Vec<String>,
 }
 
+// everything a template for a new ADR can use. Templates written for an older release keep
+// working as fields are added
 #[derive(Debug, Serialize)]
 struct NewAdrContext {
     number: i32,
     title: String,
+    // today, as YYYY-MM-DD
     date: String,
+    today: TodayContext,
+    repo: RepoContext,
+    git: GitContext,
+    // the --var values
+    vars: BTreeMap<String, String>,
+    // the comma separated tags for the `Tags:` line, empty in nextgen mode
+    tags: String,
     superseded: Vec<String>,
     linked: Vec<String>,
+    custom_sections: Vec<String>,
+}
+
+// today's date in parts, for templates that lay it out differently
+#[derive(Debug, Serialize)]
+struct TodayContext {
+    year: String,
+    month: String,
+    day: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RepoContext {
+    // the name of the directory at the root of the repository
+    name: String,
+    adr_dir: String,
+}
+
+// empty when git isn't installed or this isn't a repository
+#[derive(Debug, Serialize)]
+struct GitContext {
+    branch: String,
+    author: String,
+}
+
+impl TodayContext {
+    fn from_date(date: &str) -> Self {
+        let mut parts = date.splitn(3, '-').map(str::to_string);
+        Self {
+            year: parts.next().unwrap_or_default(),
+            month: parts.next().unwrap_or_default(),
+            day: parts.next().unwrap_or_default(),
+        }
+    }
+}
+
+impl RepoContext {
+    fn new(adr_dir: &Path) -> Result<Self> {
+        let root = match find_repo_root() {
+            Some(root) => root,
+            None => std::env::current_dir()?,
+        };
+        Ok(Self {
+            name: root
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            adr_dir: to_forward_slashes(adr_dir),
+        })
+    }
+}
+
+impl GitContext {
+    fn new() -> Self {
+        Self {
+            branch: current_branch().unwrap_or_default(),
+            author: user_name().unwrap_or_default(),
+        }
+    }
+}
+
+// split KEY=VALUE template variables into a map
+fn parse_vars(vars: &[String]) -> Result<BTreeMap<String, String>> {
+    vars.iter()
+        .map(|var| match var.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.to_string()))
+            }
+            _ => Err(anyhow::anyhow!(
+                "Invalid variable '{}', expected KEY=VALUE",
+                var
+            )),
+        })
+        .collect()
+}
+
+// split a TARGET:LINK[:REVERSE-LINK] link specification into its parts
+fn parse_link_spec(spec: &str) -> Result<(&str, &str, String)> {
+    let mut parts = spec.splitn(3, ':').map(str::trim);
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(target), Some(kind), reverse) if !target.is_empty() && !kind.is_empty() => {
+            let reverse = match reverse {
+                Some(reverse) if !reverse.is_empty() => reverse.to_string(),
+                _ => reverse_link_kind(kind),
+            };
+            Ok((target, kind, reverse))
+        }
+        _ => Err(anyhow::anyhow!(
+            "Invalid link '{}', expected TARGET:LINK[:REVERSE-LINK]",
+            spec
+        )),
+    }
+}
+
+// keep the title, date and status of the rendered template, and use the draft for the sections
+// that follow
+fn with_draft_body(rendered: &str, draft: &str) -> String {
+    if draft.trim().is_empty() {
+        return rendered.to_string();
+    }
+    let mut offset = 0;
+    let mut in_status = false;
+    for line in rendered.split_inclusive('\n') {
+        if line.starts_with("## ") {
+            if in_status {
+                break;
+            }
+            in_status = line.starts_with("## Status");
+        }
+        offset += line.len();
+    }
+    format!("{}\n\n{}\n", rendered[..offset].trim_end(), draft.trim())
 }
 
 pub(crate) fn run(args: &NewArgs) -> Result<()> {
+    let config = load_config().context("Unable to load configuration")?;
     let adr_dir = find_adr_dir().context("No ADR directory found")?;
-    let number = next_adr_number(&adr_dir)?;
-
-    let title = args.title.join(" ");
-
-    let superseded = args
-        .superseded
-        .iter()
-        .map(|adr| {
-            let adr_path = find_adr(&adr_dir, adr).expect("No ADR found");
-            let adr_title = get_title(&adr_path).expect("No title found");
-
-            remove_status(&adr_path, "Accepted").expect("Unable to update status");
-            format!(
-                "Supersedes [{}]({})",
-                adr_title,
-                adr_path.file_name().unwrap().to_str().unwrap(),
-            )
-        })
-        .collect::<Vec<_>>();
+    let mut reservations = Reservations::load()?;
+    let number = match args.reserved {
+        Some(number) => {
+            if reservations.release(number).is_none() {
+                anyhow::bail!("ADR number {} is not reserved", number);
+            }
+            if find_adr_by_number(&adr_dir, number).is_ok() {
+                anyhow::bail!("ADR number {} is reserved but already in use", number);
+            }
+            number
+        }
+        None => next_adr_number(&adr_dir)?,
+    };
+
+    let draft = if args.from_clipboard {
+        let clipboard = read_clipboard()?;
+        if clipboard.trim().is_empty() {
+            anyhow::bail!("The clipboard is empty");
+        }
+        Some(clipboard)
+    } else {
+        None
+    };
+    let (draft_title, draft_body) = match &draft {
+        Some(draft) => split_draft_title(draft),
+        None => (None, ""),
+    };
+    let title = match draft_title {
+        Some(draft_title) if args.title.is_empty() => draft_title,
+        _ if args.title.is_empty() => {
+            anyhow::bail!("No title given, and the clipboard doesn't start with a heading")
+        }
+        _ => args.title.join(" "),
+    };
+
+    // resolve every superseded ADR and link target before anything is written so that a bad
+    // reference leaves no changes behind
+    let mut superseded = Vec::new();
+    let mut superseded_paths = Vec::new();
+    for adr in &args.superseded {
+        let adr_path = find_adr(&adr_dir, adr)
+            .with_context(|| format!("Unable to find superseded ADR {}", adr))?;
+        let adr_title = get_title(&adr_path)
+            .with_context(|| format!("Unable to get title for superseded ADR {}", adr))?;
+
+        superseded.push(format!(
+            "Supersedes [{}]({})",
+            adr_title,
+            adr_path.file_name().unwrap().to_str().unwrap(),
+        ));
+        superseded_paths.push(adr_path);
+    }
 
     let path = format_adr_path(adr_dir.as_ref(), number, &title);
-    let linked = args
-        .link
-        .iter()
-        .map(|link| {
-            let parts = link.split(':').collect::<Vec<_>>();
-            let source_filename = &path.file_name().unwrap().to_str().unwrap();
-            let source_title = format!("{}. {}", number, &title);
-
-            let target_link = format!("{} [{}]({})", parts[2], source_title, source_filename);
-            let target_filename = find_adr(&adr_dir, parts[0]).expect("No ADR found");
-            let target_title = get_title(&target_filename).expect("No ADR found");
-
-            append_status(&target_filename, &target_link).expect("Unable to append status");
-
-            let source_link = format!(
-                "{} [{}]({})",
-                parts[1],
-                target_title,
-                target_filename.file_name().unwrap().to_str().unwrap(),
-            );
-
-            source_link
-        })
-        .collect::<Vec<_>>();
+    let source_filename = path.file_name().unwrap().to_str().unwrap();
+    let source_title = format!("{}. {}", number, &title);
+
+    let mut linked = Vec::new();
+    let mut reverse_links = Vec::new();
+    for link in &args.link {
+        let (target, kind, reverse_kind) = parse_link_spec(link)?;
+        let target_filename = find_adr(&adr_dir, target)
+            .with_context(|| format!("Unable to find link target ADR {}", target))?;
+        let target_title = get_title(&target_filename)
+            .with_context(|| format!("Unable to get title for link target ADR {}", target))?;
 
+        linked.push(format!(
+            "{} [{}]({})",
+            kind,
+            target_title,
+            target_filename.file_name().unwrap().to_str().unwrap(),
+        ));
+        reverse_links.push((
+            target_filename,
+            format!("{} [{}]({})", reverse_kind, source_title, source_filename),
+        ));
+    }
+
+    let date = now()?;
     let new_context = NewAdrContext {
         number,
-        date: now()?,
+        today: TodayContext::from_date(&date),
+        date,
+        repo: RepoContext::new(&adr_dir)?,
+        git: GitContext::new(),
+        vars: parse_vars(&args.vars)?,
         title: title.clone(),
+        tags: match config.mode {
+            Mode::Compatible => args.tag.join(", "),
+            Mode::Nextgen => String::new(),
+        },
         superseded,
         linked,
+        custom_sections: config.templates.custom_sections.clone(),
+    };
+
+    // an explicit --format picks a built-in template over the configured file
+    let custom =
+        match (&config.templates.new, args.format) {
+            (Some(path), None) => Some(std::fs::read_to_string(path).with_context(|| {
+                format!("Unable to read the new ADR template {}", path.display())
+            })?),
+            _ => None,
+        };
+    let template = match (&custom, args.format.unwrap_or(config.templates.format)) {
+        (Some(custom), _) => custom.as_str(),
+        (None, TemplateFormat::Nygard) => NEW_TEMPLATE,
+        (None, TemplateFormat::Madr) => MADR_NEW_TEMPLATE,
     };
 
-    let mut tt = TinyTemplate::new();
-    tt.add_template("new_adr", NEW_TEMPLATE)?;
-    let rendered = tt.render("new_adr", &new_context)?;
-    let edited = edit(rendered)?;
+    let rendered = {
+        let _span = profile::span("rendering");
+        let mut tt = TinyTemplate::new();
+        tt.add_template("new_adr", template)?;
+        let rendered = tt.render("new_adr", &new_context)?;
+        with_draft_body(&rendered, draft_body)
+    };
+    let edited = {
+        let _span = profile::span("editing");
+        let mut markdown = with_frontmatter(config.mode, rendered);
+        if !args.tag.is_empty() {
+            let tags = format!("[{}]", args.tag.join(", "));
+            markdown = set_frontmatter_field(&markdown, "tags", &tags);
+        }
+        edit_text(&record_status_date(
+            &markdown,
+            "Accepted",
+            &new_context.date,
+        ))?
+    };
 
-    std::fs::write(&path, edited)?;
+    let mut tx = Transaction::new();
+    tx.write(&path, edited);
+    if let (Some(_), Some(reservations_path)) = (args.reserved, Reservations::path()) {
+        tx.write(reservations_path, reservations.to_toml()?);
+    }
+    let mut state = State::load()?;
+    state.allocate(number);
+    if let Some(state_path) = State::path() {
+        tx.write(state_path, state.to_toml()?);
+    }
+    for adr_path in &superseded_paths {
+        tx.update(adr_path, |markdown| {
+            remove_status_markdown(markdown, "Accepted")
+                .with_context(|| format!("Unable to update status of {}", adr_path.display()))
+        })?;
+    }
+    for (target_filename, target_link) in &reverse_links {
+        tx.update(target_filename, |markdown| {
+            append_status_markdown(markdown, target_link).with_context(|| {
+                format!("Unable to append status to {}", target_filename.display())
+            })
+        })?;
+    }
+    tx.commit()?;
 
     println!("{}", path.display());
     Ok(())
@@ -0,0 +1,22 @@
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{find_adr_dir, next_adr_number};
+
+#[derive(Debug, Args)]
+pub(crate) struct NextNumberArgs {
+    /// Pad the number with zeros to four digits, as in ADR file names
+    #[arg(short, long, default_value_t = false)]
+    padded: bool,
+}
+
+pub(crate) fn run(args: &NextNumberArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let number = next_adr_number(&adr_dir)?;
+    if args.padded {
+        println!("{:04}", number);
+    } else {
+        println!("{}", number);
+    }
+    Ok(())
+}
@@ -0,0 +1,241 @@
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+
+use crate::adr::{find_adr, find_adr_dir, set_section_markdown};
+use crate::model::{bullet_items, bullet_list, section_matches, Adr, DECISION_DRIVERS};
+use crate::transaction::Transaction;
+
+// the MADR sections the options are kept in
+static CONSIDERED_OPTIONS: &str = "Considered Options";
+static PROS_AND_CONS: &str = "Pros and Cons of the Options";
+static DECISION_OUTCOME: &str = "Decision Outcome";
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum OptionCommands {
+    /// Add a considered option, with its pros and cons, to an Architectural Decision Record
+    Add(AddOptionArgs),
+    /// Record which of the considered options was chosen
+    Choose(ChooseOptionArgs),
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct AddOptionArgs {
+    /// The Architectural Decision Record number or file name match
+    adr: String,
+    /// The option, e.g. "SQLite"
+    option: String,
+    /// A good thing about the option, may be repeated
+    #[arg(long)]
+    pro: Vec<String>,
+    /// A bad thing about the option, may be repeated
+    #[arg(long)]
+    con: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct ChooseOptionArgs {
+    /// The Architectural Decision Record number or file name match
+    adr: String,
+    /// The chosen option, which must be one of the considered options
+    option: String,
+    /// Why the option was chosen
+    #[arg(long)]
+    because: Option<String>,
+}
+
+// the body of the custom section, or nothing if the ADR doesn't have it
+fn custom_section<'a>(adr: &'a Adr, heading: &str) -> &'a str {
+    adr.custom_sections
+        .iter()
+        .find(|(h, _)| section_matches(h, heading))
+        .map_or("", |(_, body)| body.as_str())
+}
+
+// add the pros and cons to the option's subsection, adding the subsection if it's new
+fn add_pros_and_cons(body: &str, option: &str, pros: &[String], cons: &[String]) -> String {
+    let items = pros
+        .iter()
+        .map(|pro| format!("* Good, because {}", pro))
+        .chain(cons.iter().map(|con| format!("* Bad, because {}", con)))
+        .collect::<Vec<_>>();
+    let mut lines = body.lines().collect::<Vec<_>>();
+    let start = lines.iter().position(|line| {
+        line.strip_prefix("### ")
+            .is_some_and(|heading| heading.trim().eq_ignore_ascii_case(option))
+    });
+    match start {
+        Some(start) => {
+            let end = lines[start + 1..]
+                .iter()
+                .position(|line| line.starts_with("### "))
+                .map_or(lines.len(), |i| start + 1 + i);
+            // after the subsection's last line, or its heading if it has none
+            let last = lines[start..end]
+                .iter()
+                .rposition(|line| !line.trim().is_empty())
+                .map_or(start, |i| start + i);
+            let blank = (last == start).then_some("");
+            let items = blank
+                .into_iter()
+                .chain(items.iter().map(String::as_str))
+                .collect::<Vec<_>>();
+            lines.splice(last + 1..last + 1, items).for_each(drop);
+            lines.join("\n")
+        }
+        None => {
+            let mut out = body.trim_end().to_string();
+            if !out.is_empty() {
+                out.push_str("\n\n");
+            }
+            out += &format!("### {}", option);
+            if !items.is_empty() {
+                out += &format!("\n\n{}", items.join("\n"));
+            }
+            out
+        }
+    }
+}
+
+// the decision outcome with the chosen option, replacing the option chosen before
+fn choose_outcome(decision: &str, option: &str, because: Option<&str>) -> String {
+    let mut chosen = format!("Chosen option: \"{}\"", option);
+    if let Some(because) = because {
+        chosen += &format!(", because {}", because.trim_end_matches('.'));
+    }
+    chosen.push('.');
+    let decision = decision.trim();
+    let rest = match decision.strip_prefix("Chosen option:") {
+        // the first paragraph is the choice
+        Some(_) => decision
+            .split_once("\n\n")
+            .map_or("", |(_, rest)| rest.trim()),
+        None => decision,
+    };
+    if rest.is_empty() {
+        chosen
+    } else {
+        format!("{}\n\n{}", chosen, rest)
+    }
+}
+
+pub(crate) fn run(args: &OptionCommands) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    match args {
+        OptionCommands::Add(args) => {
+            let option = args.option.trim();
+            if option.is_empty() {
+                anyhow::bail!("The option can't be empty");
+            }
+            let path = find_adr(&adr_dir, &args.adr)?;
+            let mut tx = Transaction::new();
+            tx.update(&path, |markdown| {
+                let adr = Adr::parse(path.strip_prefix(&adr_dir).unwrap_or(&path), markdown)?;
+                let mut options = bullet_items(custom_section(&adr, CONSIDERED_OPTIONS));
+                let mut markdown = markdown.to_string();
+                if !options.iter().any(|o| o.eq_ignore_ascii_case(option)) {
+                    options.push(option.to_string());
+                    // MADR lists the options after the drivers, or the context without them
+                    let after = if adr.decision_drivers().is_empty() {
+                        "Context"
+                    } else {
+                        DECISION_DRIVERS
+                    };
+                    markdown = set_section_markdown(
+                        &markdown,
+                        CONSIDERED_OPTIONS,
+                        &bullet_list(&options),
+                        after,
+                    );
+                }
+                if !args.pro.is_empty() || !args.con.is_empty() {
+                    let body = add_pros_and_cons(
+                        custom_section(&adr, PROS_AND_CONS),
+                        option,
+                        &args.pro,
+                        &args.con,
+                    );
+                    markdown =
+                        set_section_markdown(&markdown, PROS_AND_CONS, &body, DECISION_OUTCOME);
+                }
+                Ok(markdown)
+            })?;
+            tx.commit()?;
+            println!("{}: added option {}", path.display(), option);
+        }
+        OptionCommands::Choose(args) => {
+            let path = find_adr(&adr_dir, &args.adr)?;
+            let mut tx = Transaction::new();
+            let mut chosen = String::new();
+            tx.update(&path, |markdown| {
+                let adr = Adr::parse(path.strip_prefix(&adr_dir).unwrap_or(&path), markdown)?;
+                let options = bullet_items(custom_section(&adr, CONSIDERED_OPTIONS));
+                let Some(option) = options
+                    .iter()
+                    .find(|o| o.eq_ignore_ascii_case(args.option.trim()))
+                else {
+                    anyhow::bail!(
+                        "{} is not one of the considered options of {}, add it with `adrs option add`",
+                        args.option.trim(),
+                        path.display()
+                    );
+                };
+                chosen = option.clone();
+                let decision = choose_outcome(
+                    adr.decision.as_deref().unwrap_or_default(),
+                    option,
+                    args.because.as_deref(),
+                );
+                Ok(set_section_markdown(
+                    markdown,
+                    DECISION_OUTCOME,
+                    &decision,
+                    CONSIDERED_OPTIONS,
+                ))
+            })?;
+            tx.commit()?;
+            println!("{}: chose option {}", path.display(), chosen);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_pros_and_cons() {
+        let body = add_pros_and_cons("", "SQLite", &["simple".to_string()], &[]);
+        assert_eq!(body, "### SQLite\n\n* Good, because simple");
+        let body = add_pros_and_cons(&body, "PostgreSQL", &[], &[]);
+        assert_eq!(
+            body,
+            "### SQLite\n\n* Good, because simple\n\n### PostgreSQL"
+        );
+        let body = add_pros_and_cons(&body, "sqlite", &[], &["no concurrency".to_string()]);
+        assert_eq!(
+            body,
+            "### SQLite\n\n* Good, because simple\n* Bad, because no concurrency\n\n### PostgreSQL"
+        );
+        assert_eq!(
+            add_pros_and_cons(&body, "PostgreSQL", &["mature".to_string()], &[]),
+            "### SQLite\n\n* Good, because simple\n* Bad, because no concurrency\n\n### PostgreSQL\n\n* Good, because mature"
+        );
+    }
+
+    #[test]
+    fn test_choose_outcome() {
+        assert_eq!(
+            choose_outcome(
+                "Chosen option: \"The first option\", because it is the only option.\n\n### Consequences\n\n* Good",
+                "PostgreSQL",
+                Some("it scales.")
+            ),
+            "Chosen option: \"PostgreSQL\", because it scales.\n\n### Consequences\n\n* Good"
+        );
+        assert_eq!(
+            choose_outcome("We will use a database.", "SQLite", None),
+            "Chosen option: \"SQLite\".\n\nWe will use a database."
+        );
+    }
+}
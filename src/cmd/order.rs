@@ -0,0 +1,181 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{find_adr_dir, list_adrs};
+use crate::model::{normalize_status, Adr};
+
+#[derive(Debug, Args)]
+pub(crate) struct OrderArgs {
+    /// Order the ADRs with this status. Can be given more than once, defaults to Proposed
+    #[arg(short, long, conflicts_with = "all")]
+    status: Vec<String>,
+    /// Order every ADR, whatever its status
+    #[arg(short, long, default_value_t = false)]
+    all: bool,
+    /// Only order ADRs with this tag
+    #[arg(short, long)]
+    tag: Option<String>,
+}
+
+// the statuses of decisions that can't be built on
+static UNSATISFIABLE_STATUSES: &[&str] = &[
+    "Rejected",
+    "Withdrawn",
+    "Deprecated",
+    "Superseded",
+    "Retired",
+];
+
+// the numbers of the ADRs the ADR depends on, whichever side wrote the link
+fn dependencies(adr: &Adr, adrs: &[Adr]) -> BTreeSet<i32> {
+    let mut dependencies = adr
+        .links
+        .iter()
+        .filter(|link| link.kind.eq_ignore_ascii_case("Depends on"))
+        .map(|link| link.target)
+        .collect::<BTreeSet<_>>();
+    for other in adrs {
+        if other
+            .links
+            .iter()
+            .any(|link| link.target == adr.number && link.kind.eq_ignore_ascii_case("Required by"))
+        {
+            dependencies.insert(other.number);
+        }
+    }
+    dependencies.remove(&adr.number);
+    dependencies
+}
+
+// order the selected ADRs so that each comes after the selected ADRs it depends on, lowest
+// number first where the dependencies allow. Fails with the ADRs left over if they depend on
+// each other in a cycle
+fn order(selected: &BTreeMap<i32, BTreeSet<i32>>) -> Result<Vec<i32>> {
+    let mut waiting = selected
+        .iter()
+        .map(|(number, dependencies)| {
+            let pending = dependencies
+                .iter()
+                .filter(|dependency| selected.contains_key(dependency))
+                .copied()
+                .collect::<BTreeSet<_>>();
+            (*number, pending)
+        })
+        .collect::<BTreeMap<_, _>>();
+    let mut ordered = Vec::new();
+    while let Some(next) = waiting
+        .iter()
+        .find(|(_, pending)| pending.is_empty())
+        .map(|(number, _)| *number)
+    {
+        waiting.remove(&next);
+        for pending in waiting.values_mut() {
+            pending.remove(&next);
+        }
+        ordered.push(next);
+    }
+    if !waiting.is_empty() {
+        anyhow::bail!(
+            "The dependencies between ADRs {} form a cycle",
+            waiting
+                .keys()
+                .map(|number| number.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    Ok(ordered)
+}
+
+pub(crate) fn run(args: &OrderArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let mut adrs = Vec::new();
+    for path in list_adrs(&adr_dir)? {
+        adrs.push(Adr::from_path(&adr_dir, &path)?);
+    }
+
+    let statuses = if args.status.is_empty() {
+        vec!["Proposed".to_string()]
+    } else {
+        args.status.iter().map(|s| normalize_status(s)).collect()
+    };
+    let selected = adrs
+        .iter()
+        .filter(|adr| {
+            args.all
+                || adr
+                    .current_status()
+                    .is_some_and(|status| statuses.contains(&status))
+        })
+        .filter(|adr| {
+            args.tag
+                .as_ref()
+                .is_none_or(|tag| adr.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        })
+        .map(|adr| (adr.number, dependencies(adr, &adrs)))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut unsatisfied = 0;
+    for (position, number) in order(&selected)?.into_iter().enumerate() {
+        let adr = adrs.iter().find(|adr| adr.number == number).unwrap();
+        println!("{}. {}. {}", position + 1, adr.number, adr.title);
+        let mut after = Vec::new();
+        for dependency in &selected[&number] {
+            if selected.contains_key(dependency) {
+                after.push(dependency.to_string());
+                continue;
+            }
+            match adrs.iter().find(|adr| adr.number == *dependency) {
+                None => {
+                    unsatisfied += 1;
+                    println!("   ! depends on ADR {}, which doesn't exist", dependency);
+                }
+                Some(other) => {
+                    let status = other.current_status().unwrap_or_default();
+                    if UNSATISFIABLE_STATUSES.contains(&status.as_str()) {
+                        unsatisfied += 1;
+                        println!(
+                            "   ! depends on {}. {}, which is {}",
+                            other.number, other.title, status
+                        );
+                    }
+                }
+            }
+        }
+        if !after.is_empty() {
+            println!("   after {}", after.join(", "));
+        }
+    }
+    if unsatisfied > 0 {
+        anyhow::bail!("Found {} unsatisfied dependencies", unsatisfied);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order() {
+        let selected = BTreeMap::from([
+            (2, BTreeSet::from([4])),
+            (3, BTreeSet::from([1])),
+            (4, BTreeSet::new()),
+            (5, BTreeSet::from([2, 3])),
+        ]);
+        assert_eq!(order(&selected).unwrap(), [3, 4, 2, 5]);
+
+        let cycle = BTreeMap::from([
+            (1, BTreeSet::new()),
+            (2, BTreeSet::from([3])),
+            (3, BTreeSet::from([2])),
+        ]);
+        assert_eq!(
+            order(&cycle).unwrap_err().to_string(),
+            "The dependencies between ADRs 2, 3 form a cycle"
+        );
+    }
+}
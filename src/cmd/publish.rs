@@ -0,0 +1,210 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{find_adr_dir, list_adrs, list_archived_adrs};
+use crate::cmd::export::html::write_site;
+use crate::config::{load_config, PublishConfig, PublishTarget};
+use crate::git::git;
+use crate::journal;
+use crate::json_adr::export_adrs;
+
+// the JSON-ADR bundle in the published catalog, next to the site's index.html
+static EXPORT_FILE: &str = "adrs.json";
+
+// files that are already published but not part of the catalog, such as the custom domain of a
+// GitHub Pages site, and that publishing keeps rather than removes
+static KEPT_FILES: &[&str] = &["CNAME", ".nojekyll"];
+
+#[derive(Debug, Args)]
+pub(crate) struct PublishArgs {
+    /// Where to publish, overriding publish.target in the configuration
+    #[arg(long, value_enum)]
+    target: Option<PublishTarget>,
+    /// The directory to publish to, for the directory target
+    #[arg(long)]
+    path: Option<PathBuf>,
+    /// The git remote to push to, for the github-pages target
+    #[arg(long)]
+    remote: Option<String>,
+    /// The branch to push to, for the github-pages target
+    #[arg(long)]
+    branch: Option<String>,
+    /// The bucket to sync to, for the s3 target, e.g. s3://decisions/platform
+    #[arg(long)]
+    bucket: Option<String>,
+    /// The title of the site
+    #[arg(long)]
+    title: Option<String>,
+}
+
+// copy the files in the directory into another, replacing files of the same name
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    for entry in walkdir::WalkDir::new(from) {
+        let entry = entry?;
+        let dest = to.join(entry.path().strip_prefix(from)?);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&dest)?;
+        } else {
+            std::fs::copy(entry.path(), &dest)
+                .with_context(|| format!("Unable to write {}", dest.display()))?;
+        }
+    }
+    Ok(())
+}
+
+// run git in the directory, failing with its output if it fails
+fn git_in(dir: &Path, args: &[&str]) -> Result<()> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .context("Unable to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+// commit the catalog on top of the branch of the remote and push it, keeping the branch's history
+// and the kept files on it. Returns false if the branch already had the same content
+fn push_branch(
+    catalog: &Path,
+    remote: &str,
+    branch: &str,
+    message: &str,
+    keep: &[String],
+) -> Result<bool> {
+    let url = git(&["remote", "get-url", remote])
+        .map(|url| url.trim().to_string())
+        .with_context(|| format!("Unable to find the git remote {}", remote))?;
+    // the commit is made as the user of this repository, if it has one
+    let name = git(&["config", "user.name"]).unwrap_or_else(|| "adrs".to_string());
+    let email = git(&["config", "user.email"]).unwrap_or_else(|| "adrs@localhost".to_string());
+    let name = format!("user.name={}", name.trim());
+    let email = format!("user.email={}", email.trim());
+
+    git_in(catalog, &["init", "-q"])?;
+    // the branch doesn't exist the first time the catalog is published
+    if git_in(catalog, &["fetch", "-q", &url, branch]).is_ok() {
+        git_in(catalog, &["reset", "-q", "FETCH_HEAD"])?;
+        for file in keep {
+            let on_branch = format!("FETCH_HEAD:{}", file);
+            if !catalog.join(file).exists()
+                && git_in(catalog, &["cat-file", "-e", &on_branch]).is_ok()
+            {
+                git_in(catalog, &["checkout", "-q", "FETCH_HEAD", "--", file])?;
+            }
+        }
+    }
+    git_in(catalog, &["add", "-A"])?;
+    if git_in(catalog, &["diff", "--cached", "--quiet", "FETCH_HEAD"]).is_ok() {
+        return Ok(false);
+    }
+    git_in(
+        catalog,
+        &["-c", &name, "-c", &email, "commit", "-q", "-m", message],
+    )?;
+    git_in(
+        catalog,
+        &["push", "-q", &url, &format!("HEAD:refs/heads/{}", branch)],
+    )?;
+    Ok(true)
+}
+
+// sync the catalog to the bucket with the aws CLI, removing files that are no longer published
+// apart from the kept files
+fn sync_bucket(catalog: &Path, bucket: &str, keep: &[String]) -> Result<()> {
+    let status = Command::new("aws")
+        .args(["s3", "sync", "--delete"])
+        .args(keep.iter().flat_map(|file| ["--exclude", file]))
+        .arg(catalog)
+        .arg(bucket)
+        .status()
+        .context("Unable to run the aws CLI to sync to S3")?;
+    if !status.success() {
+        anyhow::bail!("aws was unable to sync to {}", bucket);
+    }
+    Ok(())
+}
+
+// render the JSON-ADR bundle and the static site, and push them to the target, recording the
+// publish in the journal
+pub(crate) fn run(args: &PublishArgs) -> Result<()> {
+    let config = load_config()
+        .context("Unable to load configuration")?
+        .publish;
+    let PublishConfig {
+        target,
+        path,
+        remote,
+        branch,
+        bucket,
+        title,
+        keep,
+    } = config;
+    let keep = KEPT_FILES
+        .iter()
+        .map(|file| file.to_string())
+        .chain(keep)
+        .collect::<Vec<_>>();
+    let target = args
+        .target
+        .or(target)
+        .context("No publish target, set publish.target in .adrs.toml or use --target")?;
+
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let mut paths = list_adrs(&adr_dir)?;
+    paths.extend(list_archived_adrs(&adr_dir)?);
+    let catalog = tempfile::tempdir()?;
+    let title = args
+        .title
+        .clone()
+        .or(title)
+        .unwrap_or_else(|| "Architecture Decision Records".to_string());
+    let count = write_site(&adr_dir, catalog.path(), &title)?;
+    std::fs::write(
+        catalog.path().join(EXPORT_FILE),
+        export_adrs(&adr_dir, &paths)?.to_json()?,
+    )?;
+
+    let destination = match target {
+        PublishTarget::Directory => {
+            let path = args
+                .path
+                .clone()
+                .or(path)
+                .context("No directory to publish to, set publish.path or use --path")?;
+            copy_dir(catalog.path(), &path)?;
+            path.display().to_string()
+        }
+        PublishTarget::GithubPages => {
+            let remote = args.remote.clone().or(remote).unwrap_or("origin".into());
+            let branch = args.branch.clone().or(branch).unwrap_or("gh-pages".into());
+            let message = format!("Publish {} ADR(s)", count);
+            if !push_branch(catalog.path(), &remote, &branch, &message, &keep)? {
+                println!("{} {} is already up to date", remote, branch);
+                return Ok(());
+            }
+            format!("{} {}", remote, branch)
+        }
+        PublishTarget::S3 => {
+            let bucket = args
+                .bucket
+                .clone()
+                .or(bucket)
+                .context("No bucket to publish to, set publish.bucket or use --bucket")?;
+            sync_bucket(catalog.path(), &bucket, &keep)?;
+            bucket
+        }
+    };
+    journal::record("publish", &format!("{} ADR(s) to {}", count, destination))?;
+    println!("Published {} ADR(s) to {}", count, destination);
+    Ok(())
+}
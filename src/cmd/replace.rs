@@ -0,0 +1,241 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use regex::{NoExpand, Regex};
+
+use crate::adr::{find_adr_dir, get_current_status, list_adrs, split_frontmatter};
+use crate::model::section_matches;
+use crate::transaction::Transaction;
+
+#[derive(Debug, Args)]
+pub(crate) struct ReplaceArgs {
+    /// The text to find
+    #[arg(long)]
+    pattern: String,
+    /// The text to replace it with
+    #[arg(long = "with", value_name = "REPLACEMENT")]
+    replacement: String,
+    /// Treat the pattern as a regular expression, whose groups the replacement can use as $1
+    #[arg(long, default_value_t = false)]
+    regex: bool,
+    /// Only replace in this section, e.g. decision. May be repeated
+    #[arg(long)]
+    section: Vec<String>,
+    /// Only replace in ADRs with this status. May be repeated
+    #[arg(long)]
+    status: Vec<String>,
+    /// Also replace in links to other ADRs
+    #[arg(long, default_value_t = false)]
+    include_links: bool,
+    /// Also replace in the values of the nextgen frontmatter. Keys are never changed
+    #[arg(long, default_value_t = false)]
+    include_frontmatter: bool,
+    /// Write the changes instead of only showing them
+    #[arg(long, default_value_t = false)]
+    apply: bool,
+}
+
+struct Replacer<'a> {
+    pattern: Regex,
+    replacement: &'a str,
+    expand: bool,
+    include_links: bool,
+}
+
+impl Replacer<'_> {
+    fn replace_all(&self, text: &str) -> (String, usize) {
+        let count = self.pattern.find_iter(text).count();
+        let replaced = if self.expand {
+            self.pattern.replace_all(text, self.replacement)
+        } else {
+            self.pattern.replace_all(text, NoExpand(self.replacement))
+        };
+        (replaced.into_owned(), count)
+    }
+
+    // replace in a line of markdown, leaving links alone unless asked
+    fn replace_line(&self, line: &str) -> (String, usize) {
+        if self.include_links {
+            return self.replace_all(line);
+        }
+        let link = Regex::new(r"\[[^\]]*\]\([^)]*\)").unwrap();
+        let mut out = String::new();
+        let mut count = 0;
+        let mut last = 0;
+        for found in link.find_iter(line) {
+            let (replaced, n) = self.replace_all(&line[last..found.start()]);
+            out += &replaced;
+            out += found.as_str();
+            count += n;
+            last = found.end();
+        }
+        let (replaced, n) = self.replace_all(&line[last..]);
+        (out + &replaced, count + n)
+    }
+
+    // replace in the value of a frontmatter line, never the key
+    fn replace_frontmatter_line(&self, line: &str) -> (String, usize) {
+        let split = match line.trim_start().strip_prefix("- ") {
+            Some(item) => line.len() - item.len(),
+            None => match line.find(':') {
+                Some(colon) => colon + 1,
+                None => return (line.to_string(), 0),
+            },
+        };
+        let (replaced, count) = self.replace_all(&line[split..]);
+        (format!("{}{}", &line[..split], replaced), count)
+    }
+}
+
+// replace in the markdown of an ADR, returning the new markdown and the changed lines as
+// (line number, before, after)
+fn replace_markdown(
+    markdown: &str,
+    replacer: &Replacer,
+    sections: &[String],
+    include_frontmatter: bool,
+) -> (String, Vec<(usize, String, String)>) {
+    let (frontmatter, _) = split_frontmatter(markdown);
+    let frontmatter_lines = frontmatter.lines().count();
+    let mut section: Option<&str> = None;
+    let mut out = String::new();
+    let mut changes = Vec::new();
+    for (i, line) in markdown.split_inclusive('\n').enumerate() {
+        let (text, ending) = match line.strip_suffix('\n') {
+            Some(text) => (text, "\n"),
+            None => (line, ""),
+        };
+        let in_frontmatter = i < frontmatter_lines;
+        if !in_frontmatter {
+            if let Some(heading) = text.strip_prefix("## ") {
+                section = Some(heading.trim());
+            }
+        }
+
+        let (replaced, count) = if in_frontmatter {
+            let delimiter = i == 0 || i + 1 == frontmatter_lines;
+            if include_frontmatter && !delimiter {
+                replacer.replace_frontmatter_line(text)
+            } else {
+                (text.to_string(), 0)
+            }
+        } else if sections.is_empty()
+            || section
+                .is_some_and(|heading| sections.iter().any(|name| section_matches(heading, name)))
+        {
+            replacer.replace_line(text)
+        } else {
+            (text.to_string(), 0)
+        };
+        if count > 0 && replaced != text {
+            changes.push((i + 1, text.to_string(), replaced.clone()));
+        }
+        out += &replaced;
+        out += ending;
+    }
+    (out, changes)
+}
+
+fn status_matches(path: &Path, statuses: &[String]) -> Result<bool> {
+    if statuses.is_empty() {
+        return Ok(true);
+    }
+    let status = get_current_status(path)?.unwrap_or_default();
+    Ok(statuses.iter().any(|s| s.eq_ignore_ascii_case(&status)))
+}
+
+pub(crate) fn run(args: &ReplaceArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let pattern = if args.regex {
+        Regex::new(&args.pattern).context("Invalid pattern")?
+    } else {
+        Regex::new(&regex::escape(&args.pattern))?
+    };
+    let replacer = Replacer {
+        pattern,
+        replacement: &args.replacement,
+        expand: args.regex,
+        include_links: args.include_links,
+    };
+
+    let mut tx = Transaction::new();
+    let mut adrs = 0;
+    let mut replacements = 0;
+    for path in list_adrs(&adr_dir)? {
+        if !status_matches(&path, &args.status)? {
+            continue;
+        }
+        let markdown = tx.read(&path)?;
+        let (replaced, changes) = replace_markdown(
+            &markdown,
+            &replacer,
+            &args.section,
+            args.include_frontmatter,
+        );
+        if changes.is_empty() {
+            continue;
+        }
+
+        println!("{}:", path.display());
+        for (line, before, after) in &changes {
+            println!("  {}: - {}", line, before);
+            println!("  {}: + {}", line, after);
+        }
+        adrs += 1;
+        replacements += changes.len();
+        tx.write(&path, replaced);
+    }
+
+    if adrs == 0 {
+        println!("No matches found");
+        return Ok(());
+    }
+    if args.apply {
+        tx.commit()?;
+        println!("Changed {} line(s) in {} ADR(s)", replacements, adrs);
+    } else {
+        println!(
+            "{} line(s) in {} ADR(s) would change, run again with --apply to write them",
+            replacements, adrs
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacer(
+        pattern: &str,
+        replacement: &'static str,
+        include_links: bool,
+    ) -> Replacer<'static> {
+        Replacer {
+            pattern: Regex::new(&regex::escape(pattern)).unwrap(),
+            replacement,
+            expand: false,
+            include_links,
+        }
+    }
+
+    #[test]
+    fn test_replace_markdown() {
+        let markdown = "---\nkafka: Kafka\ntags:\n  - Kafka\n---\n\n# 1. Use Kafka\n\n## Status\n\nAmends [2. Kafka topics](0002-kafka-topics.md)\n\n## Decision\n\nWe use Kafka, see [Kafka](https://kafka.apache.org).\n";
+        let (replaced, changes) = replace_markdown(
+            markdown,
+            &replacer("Kafka", "Redpanda", false),
+            &["decision".to_string()],
+            false,
+        );
+        assert_eq!(changes.len(), 1);
+        assert!(replaced.contains("We use Redpanda, see [Kafka](https://kafka.apache.org).\n"));
+        assert!(replaced.contains("# 1. Use Kafka\n"));
+
+        let (replaced, _) =
+            replace_markdown(markdown, &replacer("Kafka", "Redpanda", true), &[], true);
+        assert!(replaced.starts_with("---\nkafka: Redpanda\ntags:\n  - Redpanda\n---\n"));
+        assert!(replaced.contains("Amends [2. Redpanda topics](0002-kafka-topics.md)"));
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use regex::Regex;
+
+use crate::adr::{
+    find_project_root, find_repo_root, list_adrs, list_archived_adrs, read_adr_dir_file,
+};
+use crate::cmd::info::compiled_features;
+use crate::config::{load_config, Config, CONFIG_FILE};
+use crate::git::git;
+use crate::model::Adr;
+use crate::paths;
+
+#[derive(Debug, Args)]
+pub(crate) struct ReportIssueArgs {}
+
+// the last error recorded in the journal for the project, as its time and message. The journal is
+// shared by every project, and the errors of the others aren't this project's to report
+fn last_error<'a>(journal: &'a str, root: &str) -> Option<(&'a str, &'a str)> {
+    journal.lines().rev().find_map(|line| {
+        let mut fields = line.splitn(4, '\t');
+        let (time, operation) = (fields.next()?, fields.next()?);
+        let (project, detail) = (fields.next()?, fields.next()?);
+        (operation == "error" && project == root).then_some((time, detail))
+    })
+}
+
+// the configuration sections that differ from the defaults, without their values
+fn customized_sections(config: &Config) -> Result<Vec<String>> {
+    let value = |config: &Config| -> Result<toml::Table> {
+        toml::Table::try_from(config).context("Unable to serialize configuration")
+    };
+    let (config, default) = (value(config)?, value(&Config::default())?);
+    let mut keys = config.keys().chain(default.keys()).collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    Ok(keys
+        .into_iter()
+        .filter(|key| config.get(*key) != default.get(*key))
+        .cloned()
+        .collect())
+}
+
+// remove what identifies the user or their project: paths are made relative to the repository
+// or the home directory, and email addresses are dropped
+fn redact(text: &str, repo_root: Option<&PathBuf>, home: Option<&PathBuf>) -> String {
+    let mut text = text.to_string();
+    for (path, replacement) in [(repo_root, "<repo>"), (home, "~")] {
+        if let Some(path) = path.and_then(|path| path.to_str()) {
+            if path.len() > 1 {
+                text = text.replace(path, replacement);
+            }
+        }
+    }
+    let email = Regex::new(r"[\w.+-]+@[\w-]+(\.[\w-]+)+").unwrap();
+    email.replace_all(&text, "<email>").into_owned()
+}
+
+// count the values, as `value (count)` in descending order
+fn tally(values: impl IntoIterator<Item = String>) -> String {
+    let mut counts = BTreeMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts
+        .iter()
+        .map(|(value, count)| format!("{} ({})", value, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// the program the ADRs are edited with, without its arguments or location
+fn editor() -> String {
+    std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .and_then(|editor| {
+            let program = editor.split_whitespace().next()?.to_string();
+            let name = program.rsplit(['/', '\\']).next()?.to_string();
+            Some(name)
+        })
+        .unwrap_or_else(|| "default".to_string())
+}
+
+// the report, as markdown
+fn report() -> Result<String> {
+    let mut out = String::new();
+    out += "### Environment\n\n";
+    out += &format!("- adrs: {}\n", env!("CARGO_PKG_VERSION"));
+    out += &format!(
+        "- platform: {}-{}\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let features = compiled_features();
+    out += &format!(
+        "- features: {}\n",
+        if features.is_empty() {
+            "none".to_string()
+        } else {
+            features.join(", ")
+        }
+    );
+    let git_version = git(&["--version"]);
+    out += &format!(
+        "- git: {}\n",
+        git_version
+            .as_deref()
+            .map_or("not found", |version| version.trim())
+    );
+    out += &format!("- editor: {}\n", editor());
+
+    out += "\n### Configuration\n\n";
+    let config = load_config().context("Unable to load configuration")?;
+    out += &format!(
+        "- mode: {}\n",
+        toml::Value::try_from(config.mode)?.as_str().unwrap()
+    );
+    out += &format!("- template format: {}\n", config.templates.format.as_str());
+    let exists = |path: Option<PathBuf>| path.is_some_and(|path| path.exists());
+    out += &format!(
+        "- {}: {}\n",
+        CONFIG_FILE,
+        if exists(Some(PathBuf::from(CONFIG_FILE))) {
+            "found"
+        } else {
+            "not found"
+        }
+    );
+    out += &format!(
+        "- global configuration: {}\n",
+        if exists(paths::global_config_file()) {
+            "found"
+        } else {
+            "not found"
+        }
+    );
+    let customized = customized_sections(&config)?;
+    out += &format!(
+        "- customized: {}\n",
+        if customized.is_empty() {
+            "nothing".to_string()
+        } else {
+            customized.join(", ")
+        }
+    );
+
+    out += "\n### Repository\n\n";
+    match read_adr_dir_file() {
+        Ok(adr_dir) => {
+            let mut adrs = Vec::new();
+            for path in list_adrs(&adr_dir).unwrap_or_default() {
+                adrs.extend(Adr::from_path(&adr_dir, &path).ok());
+            }
+            let archived = list_archived_adrs(&adr_dir).unwrap_or_default().len();
+            out += &format!("- ADRs: {} ({} archived)\n", adrs.len(), archived);
+            let statuses = adrs
+                .iter()
+                .map(|adr| adr.current_status().unwrap_or_else(|| "(none)".to_string()));
+            out += &format!("- statuses: {}\n", tally(statuses));
+            out += &format!(
+                "- formats: {}\n",
+                tally(adrs.iter().map(|adr| adr.format.as_str().to_string()))
+            );
+            out += &format!(
+                "- links: {}\n",
+                adrs.iter().map(|adr| adr.links.len()).sum::<usize>()
+            );
+        }
+        Err(_) => out += "- no ADR directory found\n",
+    }
+    out += &format!(
+        "- git repository: {}\n",
+        if find_repo_root().is_some() {
+            "yes"
+        } else {
+            "no"
+        }
+    );
+
+    // outside a repository, the working directory is as identifying
+    let root = find_project_root();
+    out += "\n### Last error\n\n";
+    let journal = paths::journal_file()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    let root_name = root
+        .as_deref()
+        .unwrap_or(Path::new(""))
+        .display()
+        .to_string();
+    match last_error(&journal, &root_name) {
+        Some((time, detail)) => out += &format!("{}\n\n```\n{}\n```\n", time, detail),
+        None => out += "None recorded\n",
+    }
+
+    Ok(redact(&out, root.as_ref(), dirs::home_dir().as_ref()))
+}
+
+pub(crate) fn run(_args: &ReportIssueArgs) -> Result<()> {
+    print!("{}", report()?);
+    eprintln!("Nothing has been sent anywhere. Check the report before pasting it into an issue");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_error() {
+        let journal = "2024-01-01T00:00:00Z\terror\t/work/project\tnew: first\n2024-01-02T00:00:00Z\terror\t/work/project\tshow: second\n2024-01-03T00:00:00Z\terror\t/work/other\tlink: third\n2024-01-04T00:00:00Z\tpublish\t3 ADR(s) to site\n";
+        assert_eq!(
+            last_error(journal, "/work/project"),
+            Some(("2024-01-02T00:00:00Z", "show: second"))
+        );
+        assert_eq!(
+            last_error(journal, "/work/other"),
+            Some(("2024-01-03T00:00:00Z", "link: third"))
+        );
+        assert_eq!(last_error(journal, "/work/elsewhere"), None);
+    }
+
+    #[test]
+    fn test_redact() {
+        let repo = PathBuf::from("/home/ada/work/project");
+        let home = PathBuf::from("/home/ada");
+        assert_eq!(
+            redact(
+                "Unable to read /home/ada/work/project/doc/adr/x.md for ada@example.com in /home/ada/other",
+                Some(&repo),
+                Some(&home)
+            ),
+            "Unable to read <repo>/doc/adr/x.md for <email> in ~/other"
+        );
+    }
+
+    #[test]
+    fn test_customized_sections() {
+        let mut config = Config::default();
+        assert!(customized_sections(&config).unwrap().is_empty());
+        config.tags = vec!["db".to_string()];
+        assert_eq!(customized_sections(&config).unwrap(), ["tags"]);
+    }
+}
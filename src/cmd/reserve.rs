@@ -0,0 +1,48 @@
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{find_adr_dir, highest_adr_number, now};
+use crate::reservations::Reservations;
+use crate::transaction::Transaction;
+
+#[derive(Debug, Args)]
+pub(crate) struct ReserveArgs {
+    /// How many ADR numbers to reserve
+    #[arg(value_parser = clap::value_parser!(i32).range(1..))]
+    count: i32,
+    /// A note recording who or what the numbers are for
+    #[arg(short, long)]
+    note: Option<String>,
+}
+
+pub(crate) fn run(args: &ReserveArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let highest = highest_adr_number(&adr_dir)?;
+
+    let mut reservations = Reservations::load()?;
+    let numbers = reservations.reserve(
+        highest,
+        args.count,
+        &whoami::username(),
+        &now()?,
+        args.note.as_deref(),
+    )?;
+
+    let path = Reservations::path().with_context(|| {
+        format!(
+            "{} isn't in a project to keep reservations in",
+            adr_dir.display()
+        )
+    })?;
+    let mut tx = Transaction::new();
+    tx.write(path, reservations.to_toml()?);
+    tx.commit()?;
+
+    let (first, last) = (numbers[0], numbers[numbers.len() - 1]);
+    if first == last {
+        println!("Reserved ADR number {}", first);
+    } else {
+        println!("Reserved ADR numbers {}-{}", first, last);
+    }
+    Ok(())
+}
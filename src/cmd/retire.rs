@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{
+    find_adr, find_adr_dir, get_frontmatter_field, now, remove_frontmatter_field,
+    set_frontmatter_field, split_frontmatter,
+};
+use crate::cmd::fmt::format_markdown;
+use crate::config::load_config;
+use crate::model::{Adr, AdrIndex};
+use crate::transaction::Transaction;
+
+// the status of a retired ADR, which exports show like any other status
+pub(crate) static RETIRED: &str = "Retired";
+
+// the frontmatter keys that track a decision's lifecycle, none of which apply once it's retired
+static LIFECYCLE_KEYS: &[&str] = &[
+    "proposed_date",
+    "decided_date",
+    "implemented_date",
+    "implemented",
+    "implementation_link",
+    "confirmation_result",
+    "confirmed_date",
+    "confirmation_note",
+    "confirmation_command",
+];
+
+#[derive(Debug, Args)]
+pub(crate) struct RetireArgs {
+    /// The Architectural Decision Record number or file name match
+    adr: String,
+    /// Why the ADR is being retired
+    #[arg(long)]
+    reason: String,
+}
+
+// the frontmatter of a tombstone: the id and the original date stay, the lifecycle keys go and
+// the retirement date gets a key of its own
+fn tombstone_frontmatter(frontmatter: &str, date: &str) -> String {
+    let mut frontmatter = LIFECYCLE_KEYS
+        .iter()
+        .fold(frontmatter.to_string(), |frontmatter, key| {
+            remove_frontmatter_field(&frontmatter, key)
+        });
+    if get_frontmatter_field(&frontmatter, "status").is_some() {
+        frontmatter = set_frontmatter_field(&frontmatter, "status", &RETIRED.to_lowercase());
+    }
+    set_frontmatter_field(&frontmatter, "retired_date", date)
+}
+
+// the tombstone that takes the place of a retired ADR. It keeps the file, and so the number and
+// the inbound links, along with the frontmatter so that the id survives
+fn tombstone(frontmatter: &str, adr: &Adr, reason: &str, date: &str) -> String {
+    let dated = adr
+        .date
+        .as_ref()
+        .map(|original| format!("Date: {}\n\n", original))
+        .unwrap_or_default();
+    format!(
+        "{}# {}. {}\n\n{}## Status\n\n{}\n\n## Retirement\n\nThis decision was retired on {}: {}\n\nIts content has been removed and its number won't be reused.\n",
+        tombstone_frontmatter(frontmatter, date),
+        adr.number,
+        adr.title,
+        dated,
+        RETIRED,
+        date,
+        reason.trim()
+    )
+}
+
+pub(crate) fn run(args: &RetireArgs) -> Result<()> {
+    let reason = args.reason.trim();
+    if reason.is_empty() {
+        anyhow::bail!("The reason can't be empty");
+    }
+    let config = load_config().context("Unable to load configuration")?;
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let path = find_adr(&adr_dir, &args.adr)?;
+    let date = now()?;
+    let mut tx = Transaction::new();
+    tx.update(&path, |markdown| {
+        let adr = Adr::parse(path.strip_prefix(&adr_dir).unwrap_or(&path), markdown)?;
+        if adr.current_status().as_deref() == Some(RETIRED) {
+            anyhow::bail!("{} has already been retired", path.display());
+        }
+        let (frontmatter, _) = split_frontmatter(markdown);
+        // the tombstone has no links for an index to retitle
+        Ok(format_markdown(
+            &tombstone(frontmatter, &adr, reason, &date),
+            &config.fmt.frontmatter_order,
+            &AdrIndex::new(),
+        ))
+    })?;
+    tx.commit()?;
+    println!("{}: retired", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_tombstone() {
+        let adr = Adr::parse(
+            Path::new("0003-use-mongodb.md"),
+            "# 3. Use MongoDB\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\n## Context\n\nSecret.\n",
+        )
+        .unwrap();
+        assert_eq!(
+            tombstone("", &adr, "posted by mistake", "2024-02-01"),
+            "# 3. Use MongoDB\n\nDate: 2024-01-01\n\n## Status\n\nRetired\n\n## Retirement\n\nThis decision was retired on 2024-02-01: posted by mistake\n\nIts content has been removed and its number won't be reused.\n"
+        );
+    }
+
+    #[test]
+    fn test_tombstone_frontmatter() {
+        let frontmatter = "---\nid: 1234\nstatus: accepted\ndate: 2024-01-01\nproposed_date: 2023-12-01\ndecided_date: 2024-01-01\nimplemented: true\nconfirmation_note: \"checked\"\n---\n";
+        assert_eq!(
+            tombstone_frontmatter(frontmatter, "2024-02-01"),
+            "---\nid: 1234\nstatus: retired\ndate: 2024-01-01\nretired_date: 2024-02-01\n---\n"
+        );
+        assert_eq!(tombstone_frontmatter("", "2024-02-01"), "");
+    }
+}
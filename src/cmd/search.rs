@@ -0,0 +1,136 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use regex::{Regex, RegexBuilder};
+
+use crate::adr::{find_adr_dir, list_adrs, list_archived_adrs};
+use crate::git::git;
+
+#[derive(Debug, Args)]
+pub(crate) struct SearchArgs {
+    /// The text to search for, ignoring case
+    query: String,
+    /// Treat the query as a regular expression
+    #[arg(long, default_value_t = false)]
+    regex: bool,
+    /// Also search earlier versions of the ADRs and deleted ADRs in the git history, marking
+    /// what was found there as historical
+    #[arg(long, default_value_t = false)]
+    include_history: bool,
+    /// Only search the history since this date, in any form git understands, e.g. 2024-01-01 or
+    /// "6 months ago"
+    #[arg(long, requires = "include_history")]
+    since: Option<String>,
+}
+
+// the lines of the markdown that match, numbered from 1
+fn matching_lines<'a>(pattern: &Regex, markdown: &'a str) -> Vec<(usize, &'a str)> {
+    markdown
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| pattern.is_match(line))
+        .map(|(i, line)| (i + 1, line.trim()))
+        .collect()
+}
+
+// search every version of the ADRs committed to git, newest first, leaving out lines that are
+// still in the ADR and reporting each line only once, from the last version that had it
+fn search_history(adr_dir: &Path, pattern: &Regex, since: Option<&str>) -> Result<()> {
+    let root = git(&["rev-parse", "--show-toplevel"])
+        .map(|root| root.trim().to_string())
+        .context("The history can only be searched in a git repository")?;
+    let since = since.map(|since| format!("--since={}", since));
+    let mut args = vec!["log", "--format=commit %H %as", "--name-only"];
+    args.extend(since.as_deref());
+    let adr_dir = adr_dir.to_string_lossy();
+    args.extend(["--", &adr_dir]);
+    let log = git(&args).context("Unable to read the git history")?;
+
+    let mut seen = BTreeSet::new();
+    let mut commit = None;
+    for line in log.lines().filter(|line| !line.is_empty()) {
+        if let Some(rest) = line.strip_prefix("commit ") {
+            commit = rest.split_once(' ');
+            continue;
+        }
+        // the file name in this commit, relative to the root of the repository
+        let Some((sha, date)) = commit else {
+            continue;
+        };
+        if !line.ends_with(".md") {
+            continue;
+        }
+        // files deleted by the commit have no version in it
+        let Some(markdown) = git(&["show", &format!("{}:{}", sha, line)]) else {
+            continue;
+        };
+        let current = std::fs::read_to_string(Path::new(&root).join(line));
+        let deleted = current.is_err();
+        let current = current.unwrap_or_default();
+        let current = current.lines().map(str::trim).collect::<BTreeSet<_>>();
+        for (number, text) in matching_lines(pattern, &markdown) {
+            if current.contains(text) || !seen.insert((line.to_string(), text.to_string())) {
+                continue;
+            }
+            println!(
+                "[historical {} {}{}] {}:{}: {}",
+                &sha[..7],
+                date,
+                if deleted { ", deleted" } else { "" },
+                line,
+                number,
+                text
+            );
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn run(args: &SearchArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let query = if args.regex {
+        args.query.clone()
+    } else {
+        regex::escape(&args.query)
+    };
+    let pattern = RegexBuilder::new(&query)
+        .case_insensitive(true)
+        .build()
+        .with_context(|| format!("Invalid regular expression: {}", args.query))?;
+
+    let mut paths = list_adrs(&adr_dir)?;
+    paths.extend(list_archived_adrs(&adr_dir)?);
+    for path in paths {
+        let markdown = std::fs::read_to_string(&path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        for (number, text) in matching_lines(&pattern, &markdown) {
+            println!("{}:{}: {}", path.display(), number, text);
+        }
+    }
+    if args.include_history {
+        search_history(&adr_dir, &pattern, args.since.as_deref())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_lines() {
+        let pattern = RegexBuilder::new("mysql")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        assert_eq!(
+            matching_lines(
+                &pattern,
+                "# 2. Use MySQL\n\n## Decision\n\n  We pick mysql 8.\n"
+            ),
+            [(1, "# 2. Use MySQL"), (5, "We pick mysql 8.")]
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{find_adr, find_adr_dir};
+use crate::config::load_config;
+use crate::render::renderer;
+
+#[derive(Debug, Args)]
+pub(crate) struct ShowArgs {
+    /// The Architectural Decision Record number, id or file name match
+    adr: String,
+    /// Print only the path of the ADR
+    #[arg(long, default_value_t = false)]
+    path: bool,
+    /// Render the ADR for the terminal, with render.terminal from the configuration or, when
+    /// adrs is built with the ansi feature, the built-in renderer
+    #[arg(long, default_value_t = false, conflicts_with = "path")]
+    render: bool,
+}
+
+pub(crate) fn run(args: &ShowArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let path = find_adr(&adr_dir, &args.adr)?;
+    if args.path {
+        println!("{}", path.display());
+        return Ok(());
+    }
+    let markdown = std::fs::read_to_string(&path)
+        .with_context(|| format!("Unable to read {}", path.display()))?;
+    if args.render {
+        let config = load_config().context("Unable to load configuration")?;
+        print!("{}", renderer(&config.render).terminal(&markdown)?);
+    } else {
+        print!("{}", markdown);
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{find_adr_dir, list_adrs, list_archived_adrs};
+use crate::history::time_in_status;
+use crate::model::Adr;
+use crate::stats::Stats;
+
+#[derive(Debug, Args)]
+pub(crate) struct StatsArgs {
+    /// Print the statistics as JSON
+    #[arg(long, default_value_t = false)]
+    json: bool,
+    /// Show how many ADRs were written each month, by status
+    #[arg(long, default_value_t = false)]
+    activity: bool,
+    /// Follow the activity table with a sparkline of the ADRs written each month
+    #[arg(
+        long,
+        default_value_t = false,
+        requires = "activity",
+        conflicts_with = "json"
+    )]
+    sparkline: bool,
+}
+
+// one bar per value, scaled to the largest
+fn sparkline(values: &[usize]) -> String {
+    static BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or_default().max(1);
+    values
+        .iter()
+        .map(|value| BARS[value * (BARS.len() - 1) / max])
+        .collect()
+}
+
+// the month by status matrix as a markdown table
+fn print_activity(stats: &Stats, with_sparkline: bool) {
+    let statuses = stats
+        .activity
+        .values()
+        .flat_map(|statuses| statuses.keys())
+        .collect::<BTreeSet<_>>();
+    let mut header = vec!["Month"];
+    header.extend(statuses.iter().map(|status| status.as_str()));
+    header.push("Total");
+    println!("| {} |", header.join(" | "));
+    println!("|{}", "---|".repeat(header.len()));
+
+    let mut totals = Vec::new();
+    for (month, counts) in &stats.activity {
+        let total = counts.values().sum::<usize>();
+        let mut row = vec![month.clone()];
+        row.extend(
+            statuses
+                .iter()
+                .map(|status| counts.get(*status).copied().unwrap_or_default().to_string()),
+        );
+        row.push(total.to_string());
+        println!("| {} |", row.join(" | "));
+        totals.push(total);
+    }
+    if with_sparkline && !totals.is_empty() {
+        println!("\n{}", sparkline(&totals));
+    }
+}
+
+fn print_counts<K: Display>(heading: &str, counts: &BTreeMap<K, usize>) {
+    if counts.is_empty() {
+        return;
+    }
+    println!("\n{}:", heading);
+    for (key, count) in counts {
+        println!("  {}: {}", key, count);
+    }
+}
+
+pub(crate) fn run(args: &StatsArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let mut paths = list_adrs(&adr_dir)?;
+    paths.extend(list_archived_adrs(&adr_dir)?);
+    let adrs = paths
+        .iter()
+        .map(|path| Adr::from_path(&adr_dir, path))
+        .collect::<Result<Vec<_>>>()?;
+    let stats = Stats::from_adrs(&adrs, |adr| time_in_status(&adr_dir, adr));
+
+    if args.json {
+        let json = if args.activity {
+            serde_json::to_string_pretty(&stats.activity)
+        } else {
+            serde_json::to_string_pretty(&stats)
+        };
+        println!("{}", json.context("Unable to serialize statistics")?);
+        return Ok(());
+    }
+    if args.activity {
+        print_activity(&stats, args.sparkline);
+        return Ok(());
+    }
+
+    println!("ADRs: {}", stats.total);
+    print_counts("By status", &stats.by_status);
+    print_counts("By tag", &stats.by_tag);
+    print_counts("By month", &stats.by_month);
+    print_counts("ADRs by number of linked ADRs", &stats.link_degrees);
+    print_counts("By confirmation", &stats.by_confirmation);
+    if !stats.time_in_status.is_empty() {
+        println!("\nDays in status:");
+        for (status, days) in &stats.time_in_status {
+            println!(
+                "  {}: count={} min={} median={} p90={} max={}",
+                status, days.count, days.min, days.median, days.p90, days.max
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0, 1, 2, 4, 8]), "▁▁▂▄█");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+    }
+}
@@ -0,0 +1,105 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{
+    find_adr, find_adr_dir, get_current_status, get_links, get_title, now, record_status_date,
+    set_link_markdown, set_status_markdown,
+};
+use crate::model::normalize_status;
+use crate::transaction::Transaction;
+
+#[derive(Debug, Args)]
+pub(crate) struct StatusArgs {
+    /// The Architectural Decision Record number or file name match
+    adr: String,
+    /// The new status, e.g. Proposed, Accepted, Rejected or Implemented. Prints the current status
+    /// when omitted
+    status: Option<String>,
+    /// The Architectural Decision Record that supersedes this one. Links the two both ways with
+    /// their current titles, and implies the Superseded status
+    #[arg(long, value_name = "ADR")]
+    by: Option<String>,
+}
+
+// capitalize the first letter of the status, so that `accepted` is written as `Accepted`
+fn capitalize(status: &str) -> String {
+    let mut chars = status.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// mark the ADR superseded by another, linking each to the other
+fn supersede(adr_dir: &Path, path: &Path, status: Option<&str>, by: &str) -> Result<()> {
+    if let Some(status) = status.map(capitalize) {
+        if status != "Superseded" {
+            anyhow::bail!("--by marks an ADR Superseded, not {}", status);
+        }
+    }
+    let by =
+        find_adr(adr_dir, by).with_context(|| format!("Unable to find superseding ADR {}", by))?;
+    if by == path {
+        anyhow::bail!("{} can't supersede itself", path.display());
+    }
+    let title = get_title(path)?;
+    let by_title = get_title(&by)?;
+    let filename = path.file_name().unwrap().to_str().unwrap();
+    let by_filename = by.file_name().unwrap().to_str().unwrap();
+
+    if get_current_status(&by)?.as_deref() == Some("Superseded") {
+        eprintln!("Warning: {} is itself superseded", by.display());
+    }
+    for (kind, _, dest) in get_links(path)? {
+        if kind == "Superseded by" && dest.rsplit('/').next() != Some(by_filename) {
+            eprintln!("Warning: {} is also superseded by {}", path.display(), dest);
+        }
+    }
+
+    let date = now()?;
+    let mut tx = Transaction::new();
+    tx.update(path, |markdown| {
+        let markdown = set_link_markdown(markdown, "Superseded by", &by_title, by_filename, true)
+            .with_context(|| format!("Unable to update status of {}", path.display()))?;
+        Ok(record_status_date(&markdown, "Superseded", &date))
+    })?;
+    tx.update(&by, |markdown| {
+        set_link_markdown(markdown, "Supersedes", &title, filename, false)
+            .with_context(|| format!("Unable to update status of {}", by.display()))
+    })?;
+    tx.commit()?;
+
+    println!("{}: Superseded by {}", path.display(), by_title);
+    Ok(())
+}
+
+pub(crate) fn run(args: &StatusArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let path = find_adr(&adr_dir, &args.adr)?;
+
+    if let Some(by) = &args.by {
+        return supersede(&adr_dir, &path, args.status.as_deref(), by);
+    }
+    let Some(status) = &args.status else {
+        println!("{}", get_current_status(&path)?.unwrap_or_default());
+        return Ok(());
+    };
+    let status = normalize_status(&capitalize(status));
+    if status.is_empty() {
+        anyhow::bail!("The status can't be empty");
+    }
+
+    let date = now()?;
+    let mut tx = Transaction::new();
+    tx.update(&path, |markdown| {
+        let markdown = set_status_markdown(markdown, &status)
+            .with_context(|| format!("Unable to update status of {}", path.display()))?;
+        Ok(record_status_date(&markdown, &status, &date))
+    })?;
+    tx.commit()?;
+
+    println!("{}: {}", path.display(), status);
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{find_adr_dir, list_adrs};
+use crate::model::Adr;
+
+#[derive(Debug, Args)]
+pub(crate) struct TodosArgs {
+    /// Include the items that have been checked off
+    #[arg(short, long, default_value_t = false)]
+    all: bool,
+    /// Only show items from ADRs with this tag
+    #[arg(short, long)]
+    tag: Option<String>,
+    /// Group the items by tag instead of by ADR
+    #[arg(long, default_value_t = false)]
+    by_tag: bool,
+}
+
+fn checkbox(done: bool) -> &'static str {
+    if done {
+        "- [x]"
+    } else {
+        "- [ ]"
+    }
+}
+
+pub(crate) fn run(args: &TodosArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut by_adr = Vec::new();
+    for path in list_adrs(&adr_dir)? {
+        let adr = Adr::from_path(&adr_dir, &path)?;
+        if let Some(tag) = &args.tag {
+            if !adr.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                continue;
+            }
+        }
+        let items = adr
+            .action_items()
+            .into_iter()
+            .filter(|(done, _)| args.all || !done)
+            .collect::<Vec<_>>();
+        if items.is_empty() {
+            continue;
+        }
+
+        let title = format!("{}. {}", adr.number, adr.title);
+        if args.by_tag {
+            let tags = if adr.tags.is_empty() {
+                vec!["untagged".to_string()]
+            } else {
+                adr.tags.clone()
+            };
+            for tag in tags {
+                let group = groups.entry(tag).or_default();
+                for (done, text) in &items {
+                    group.push(format!("{} {} ({})", checkbox(*done), text, title));
+                }
+            }
+        } else {
+            let lines = items
+                .iter()
+                .map(|(done, text)| format!("{} {}", checkbox(*done), text))
+                .collect();
+            by_adr.push((format!("{} ({})", title, path.display()), lines));
+        }
+    }
+
+    let output = if args.by_tag {
+        groups.into_iter().collect::<Vec<_>>()
+    } else {
+        by_adr
+    };
+    for (i, (heading, lines)) in output.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}", heading);
+        for line in lines {
+            println!("  {}", line);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{
+    find_adr_dir, list_adrs, list_archived_adrs, new_adr_id, set_frontmatter_field,
+    split_frontmatter, to_forward_slashes,
+};
+use crate::config::{migrate_config, read_config_table, Config, CONFIG_FILE};
+use crate::json_adr::migrate_json;
+use crate::model::MISSPELLED_LINKS;
+use crate::transaction::Transaction;
+
+#[derive(Debug, Args)]
+pub(crate) struct UpgradeArgs {
+    /// Also rewrite the ADR files to the current conventions
+    #[arg(long, default_value_t = false)]
+    adrs: bool,
+    /// Also rewrite these JSON-ADR exports to the current version of the format. Can be given
+    /// more than once
+    #[arg(long, value_name = "FILE")]
+    json: Vec<PathBuf>,
+    /// Report the migrations without changing any files
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+}
+
+// bring an ADR up to the current conventions, returning the updated markdown along with a
+// description of each change
+fn upgrade_adr(markdown: &str) -> (String, Vec<String>) {
+    let mut changes = Vec::new();
+    let mut lines = Vec::new();
+    for line in markdown.split_inclusive('\n') {
+        match MISSPELLED_LINKS
+            .iter()
+            .find(|(old, _)| line.starts_with(old))
+        {
+            Some((old, new)) => {
+                changes.push(format!("spelled `{}` as `{}`", old, new));
+                lines.push(line.replacen(old, new, 1));
+            }
+            None => lines.push(line.to_string()),
+        }
+    }
+    let mut upgraded = lines.concat();
+
+    // nextgen ADRs written before ids were assigned get one
+    let (frontmatter, _) = split_frontmatter(&upgraded);
+    if !frontmatter.is_empty() && !frontmatter.lines().any(|line| line.starts_with("id:")) {
+        upgraded = set_frontmatter_field(&upgraded, "id", &new_adr_id());
+        changes.push("assigned an id".to_string());
+    }
+    (upgraded, changes)
+}
+
+// migrate the project configuration file, adding the rewritten file to the transaction
+fn upgrade_config(path: &Path, tx: &mut Transaction) -> Result<Vec<String>> {
+    let mut table = read_config_table(path)?;
+    let report = migrate_config(&mut table)?;
+    if !report.is_empty() {
+        let config: Config = table
+            .try_into()
+            .context("Invalid configuration after the migration")?;
+        let contents =
+            toml::to_string_pretty(&config).context("Unable to serialize configuration")?;
+        tx.write(path, contents);
+    }
+    Ok(report)
+}
+
+// migrate a JSON-ADR export to the current version of the format, adding the rewritten file to
+// the transaction
+fn upgrade_json(path: &Path, tx: &mut Transaction) -> Result<Vec<String>> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Unable to read {}", path.display()))?;
+    let mut value: serde_json::Value =
+        serde_json::from_str(&json).with_context(|| format!("Invalid JSON: {}", path.display()))?;
+    let report = migrate_json(&mut value)?;
+    if !report.is_empty() {
+        let contents =
+            serde_json::to_string_pretty(&value).context("Unable to serialize JSON-ADR export")?;
+        tx.write(path, contents + "\n");
+    }
+    Ok(report)
+}
+
+pub(crate) fn run(args: &UpgradeArgs) -> Result<()> {
+    let mut tx = Transaction::new();
+    let mut changes = 0;
+
+    let config_file = Path::new(CONFIG_FILE);
+    if config_file.exists() {
+        for line in upgrade_config(config_file, &mut tx)? {
+            println!("{}: {}", CONFIG_FILE, line);
+            changes += 1;
+        }
+    }
+
+    for path in &args.json {
+        for line in upgrade_json(path, &mut tx)? {
+            println!("{}: {}", to_forward_slashes(path), line);
+            changes += 1;
+        }
+    }
+
+    if args.adrs {
+        let adr_dir = find_adr_dir().context("No ADR directory found")?;
+        let mut adrs = list_adrs(&adr_dir)?;
+        adrs.extend(list_archived_adrs(&adr_dir)?);
+        for adr in &adrs {
+            let markdown = std::fs::read_to_string(adr)?;
+            let (upgraded, fixes) = upgrade_adr(&markdown);
+            for fix in &fixes {
+                println!("{}: {}", to_forward_slashes(adr), fix);
+            }
+            if upgraded != markdown {
+                changes += fixes.len();
+                tx.write(PathBuf::from(adr), upgraded);
+            }
+        }
+    }
+
+    if changes == 0 {
+        println!("Already up to date");
+        return Ok(());
+    }
+    if args.dry_run {
+        println!("Would make {} change(s)", changes);
+    } else {
+        tx.commit()?;
+        println!("Made {} change(s)", changes);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade_adr() {
+        let (upgraded, changes) = upgrade_adr(
+            "# 1. Use MySQL\n\n## Status\n\nSuperceded by [2. Use Postgres](0002-use-postgres.md)\n",
+        );
+        assert_eq!(
+            upgraded,
+            "# 1. Use MySQL\n\n## Status\n\nSuperseded by [2. Use Postgres](0002-use-postgres.md)\n"
+        );
+        assert_eq!(changes, vec!["spelled `Superceded by` as `Superseded by`"]);
+
+        let (upgraded, changes) = upgrade_adr("---\ntags: []\n---\n\n# 1. Use MySQL\n");
+        assert!(upgraded.starts_with("---\ntags: []\nid: "));
+        assert_eq!(changes, vec!["assigned an id"]);
+
+        let current = "# 1. Use MySQL\n\n## Status\n\nAccepted\n";
+        assert_eq!(upgrade_adr(current), (current.to_string(), Vec::new()));
+    }
+}
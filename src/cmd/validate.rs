@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use serde::Serialize;
+
+use crate::cmd::doctor::{
+    apply_severities, check_adr, count, lint_rules, Diagnostic, ReportFormat, Severity,
+};
+use crate::config::load_config;
+use crate::json_adr::schema_errors;
+use crate::sarif::SarifLog;
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub(crate) struct ValidateArgs {
+    #[command(subcommand)]
+    command: Option<ValidateCommands>,
+    /// The markdown file to check, which doesn't need to be in an ADR directory
+    #[arg(required = true)]
+    file: Option<PathBuf>,
+    /// How to report the problems found
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    format: ReportFormat,
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum ValidateCommands {
+    /// Check a JSON-ADR document against the JSON-ADR schema
+    Json(ValidateJsonArgs),
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct ValidateJsonArgs {
+    /// The JSON-ADR document to check
+    file: PathBuf,
+    /// How to report the problems found
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    format: ReportFormat,
+}
+
+#[derive(Debug, Serialize)]
+struct ValidateReport<'a> {
+    file: &'a Path,
+    errors: usize,
+    warnings: usize,
+    diagnostics: &'a [Diagnostic],
+}
+
+// the problems with a JSON-ADR document, each at the JSON pointer of the value that breaks the
+// schema. A file that isn't JSON at all is a single problem
+fn check_json(file: &Path) -> Result<Vec<Diagnostic>> {
+    let json = std::fs::read_to_string(file)
+        .with_context(|| format!("Unable to read {}", file.display()))?;
+    let errors = match serde_json::from_str(&json) {
+        Ok(value) => schema_errors(&value)?,
+        Err(e) => vec![("/".to_string(), format!("invalid JSON: {}", e))],
+    };
+    Ok(errors
+        .into_iter()
+        .map(|(pointer, message)| {
+            Diagnostic::new(
+                Severity::Error,
+                "json-schema",
+                Some(file),
+                format!("{}: {}", pointer, message),
+            )
+        })
+        .collect())
+}
+
+pub(crate) fn run(args: &ValidateArgs) -> Result<()> {
+    if let Some(ValidateCommands::Json(args)) = &args.command {
+        if !args.file.is_file() {
+            anyhow::bail!("No such file: {}", args.file.display());
+        }
+        return report(&args.file, &check_json(&args.file)?, args.format);
+    }
+
+    let config = load_config().context("Unable to load configuration")?;
+    let file = args.file.as_ref().context("No file to check")?;
+    if !file.is_file() {
+        anyhow::bail!("No such file: {}", file.display());
+    }
+
+    let mut diagnostics = Vec::new();
+    check_adr(file, &lint_rules(&config), &mut diagnostics);
+    apply_severities(&config, &mut diagnostics);
+    report(file, &diagnostics, args.format)
+}
+
+// print the problems found in the file, failing if any of them are errors
+fn report(file: &Path, diagnostics: &[Diagnostic], format: ReportFormat) -> Result<()> {
+    let (errors, warnings) = count(diagnostics);
+    match format {
+        ReportFormat::Text => {
+            for diagnostic in diagnostics {
+                println!("{}", diagnostic);
+            }
+            if diagnostics.is_empty() {
+                println!("{} is valid", file.display());
+            }
+        }
+        ReportFormat::Json => {
+            let report = ValidateReport {
+                file,
+                errors,
+                warnings,
+                diagnostics,
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        ReportFormat::Sarif => {
+            let log = SarifLog::new(diagnostics.iter().map(Diagnostic::finding));
+            println!("{}", serde_json::to_string_pretty(&log)?);
+        }
+    }
+    if errors > 0 {
+        anyhow::bail!("Found {} error(s) in {}", errors, file.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use crate::adr::{
+    find_adr_dir, list_adrs, list_archived_adrs, split_frontmatter, to_forward_slashes,
+};
+use crate::cmd::import::json::import_adrs;
+use crate::config::Mode;
+use crate::json_adr::{export_adrs, JsonAdrBulkExport};
+use crate::model::split_sections;
+
+#[derive(Debug, Args)]
+pub(crate) struct VerifyExportArgs {}
+
+// the text with blank lines and surrounding whitespace removed, so that only content is compared
+fn normalize(text: &str) -> Vec<&str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+// describe everything in the original ADR that didn't survive the round trip
+pub(crate) fn compare_markdown(original: &str, round_tripped: &str) -> Vec<String> {
+    let (_, original) = split_frontmatter(original);
+    let (_, round_tripped) = split_frontmatter(round_tripped);
+    let (original_preamble, original_sections) = split_sections(original);
+    let (preamble, sections) = split_sections(round_tripped);
+
+    let mut problems = Vec::new();
+    let preamble = normalize(preamble);
+    for line in normalize(original_preamble) {
+        if !preamble.contains(&line) {
+            problems.push(format!("'{}' was lost", line));
+        }
+    }
+    for (heading, body) in &original_sections {
+        match sections.iter().find(|(h, _)| h == heading) {
+            None => problems.push(format!("section '{}' was lost", heading)),
+            Some((_, round_tripped)) if normalize(body) != normalize(round_tripped) => {
+                problems.push(format!("section '{}' changed", heading))
+            }
+            Some(_) => {}
+        }
+    }
+    for (heading, _) in &sections {
+        if !original_sections.iter().any(|(h, _)| h == heading) {
+            problems.push(format!("section '{}' was added", heading));
+        }
+    }
+    problems
+}
+
+pub(crate) fn run(_args: &VerifyExportArgs) -> Result<()> {
+    let adr_dir = find_adr_dir().context("No ADR directory found")?;
+    let mut adrs = list_adrs(&adr_dir)?;
+    adrs.extend(list_archived_adrs(&adr_dir)?);
+
+    // go through the serialized form, so that anything serde drops is caught too
+    let json = export_adrs(&adr_dir, &adrs)?.to_json()?;
+    let export = JsonAdrBulkExport::from_json(&json)?;
+
+    let temp = tempfile::Builder::new()
+        .prefix("adrs-verify-")
+        .tempdir()
+        .context("Unable to create a temporary directory")?;
+    import_adrs(&export, temp.path(), Mode::Compatible, &[])?;
+
+    let mut lossy = 0;
+    for adr in &adrs {
+        let relative = adr.strip_prefix(&adr_dir).unwrap_or(adr);
+        let original = std::fs::read_to_string(adr)?;
+        let problems = match std::fs::read_to_string(temp.path().join(relative)) {
+            Ok(round_tripped) => compare_markdown(&original, &round_tripped),
+            Err(_) => vec!["missing after the round trip".to_string()],
+        };
+        for problem in &problems {
+            println!("{}: {}", to_forward_slashes(Path::new(adr)), problem);
+        }
+        if !problems.is_empty() {
+            lossy += 1;
+        }
+    }
+
+    if lossy > 0 {
+        anyhow::bail!(
+            "The JSON-ADR export lost information from {} of {} ADR(s)",
+            lossy,
+            adrs.len()
+        );
+    }
+    println!("{} ADR(s) round-tripped without loss", adrs.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_markdown() {
+        let original = "# 1. Title\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\n## Security Review\n\nDone\n";
+        let round_tripped = "# 1. Title\n\n## Status\n\nAccepted\n\n\n## Context\n\nNew\n";
+        assert_eq!(
+            compare_markdown(original, round_tripped),
+            vec![
+                "'Date: 2024-01-01' was lost",
+                "section 'Security Review' was lost",
+                "section 'Context' was added",
+            ]
+        );
+        assert!(compare_markdown(original, original).is_empty());
+    }
+}
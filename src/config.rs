@@ -0,0 +1,659 @@
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::cmd::doctor::Severity;
+use crate::paths;
+
+// the name of the project configuration file
+pub(crate) static CONFIG_FILE: &str = ".adrs.toml";
+
+// the version of the configuration layout this release reads and writes
+pub(crate) static CONFIG_VERSION: u32 = 1;
+
+/// How ADR files are written
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Mode {
+    /// Plain markdown, compatible with adr-tools
+    #[default]
+    Compatible,
+    /// Markdown with a YAML frontmatter block for additional metadata
+    Nextgen,
+}
+
+/// The template used for new ADRs
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum TemplateFormat {
+    /// Michael Nygard's original format
+    #[default]
+    Nygard,
+    /// Markdown Architectural Decision Records
+    Madr,
+}
+
+impl TemplateFormat {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            TemplateFormat::Nygard => "nygard",
+            TemplateFormat::Madr => "madr",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct TemplatesConfig {
+    /// The default template format for new ADRs
+    pub(crate) format: TemplateFormat,
+    /// Additional sections added to the end of every new ADR, e.g. "Security Review"
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) custom_sections: Vec<String>,
+    /// A template file for new ADRs, used instead of the built-in one for the format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) new: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct LintConfig {
+    /// The lint rules to check
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) rules: Vec<String>,
+    /// The severity to report each rule with, overriding its default, e.g. `islands = "warning"`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) severity: BTreeMap<String, Severity>,
+    /// Which side `doctor --fix` trusts when an ADR's status and links disagree
+    #[serde(skip_serializing_if = "Reconcile::is_default")]
+    pub(crate) reconcile: Reconcile,
+}
+
+/// What an ADR's status is reconciled from when it contradicts the ADR's links
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Reconcile {
+    /// The links win, so an ADR with a "Superseded by" link loses its other status
+    #[default]
+    Links,
+    /// The status wins, so a "Superseded by" link on an ADR with another status is removed
+    Status,
+}
+
+impl Reconcile {
+    fn is_default(&self) -> bool {
+        *self == Reconcile::default()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct GenerateConfig {
+    /// Include ADRs from the archive subdirectory in generated documentation
+    pub(crate) include_archived: bool,
+    /// ADRs with these statuses are left out of generated documentation
+    pub(crate) exclude_status: Vec<String>,
+    /// How `generate graph` draws the decision graph
+    pub(crate) graph: GraphConfig,
+    /// The template of `generate commit-msg`, using {number}, {title}, {lower_title}, {slug},
+    /// {status} and {action}, by default `docs(adr): {action} {number} {lower_title}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) commit_msg: Option<String>,
+    /// The template of `generate branch-name`, with the same fields, by default
+    /// `adr/{number}-{slug}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) branch_name: Option<String>,
+}
+
+impl Default for GenerateConfig {
+    fn default() -> Self {
+        Self {
+            include_archived: false,
+            exclude_status: vec!["Rejected".to_string(), "Withdrawn".to_string()],
+            graph: GraphConfig::default(),
+            commit_msg: None,
+            branch_name: None,
+        }
+    }
+}
+
+/// Which way the decision graph runs
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum RankDir {
+    /// Top to bottom
+    #[default]
+    Tb,
+    /// Left to right
+    Lr,
+    /// Bottom to top
+    Bt,
+    /// Right to left
+    Rl,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct GraphConfig {
+    /// The fill colour of ADRs in each status, e.g. `Accepted = "#C8E6C9"`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) colors: BTreeMap<String, String>,
+    /// The font for labels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) font: Option<String>,
+    /// Which way the graph runs, unless --rankdir is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) rankdir: Option<RankDir>,
+}
+
+/// How statuses are marked in listings and generated documentation
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum MarkerStyle {
+    /// No markers
+    #[default]
+    None,
+    /// Emoji, e.g. ✅ for Accepted
+    Emoji,
+    /// Plain ASCII, e.g. [+] for Accepted, for terminals and tools that can't show emoji
+    Ascii,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct MarkersConfig {
+    /// Which markers list, toc, book and graph show, unless --markers is given
+    pub(crate) style: MarkerStyle,
+    /// Emoji markers by status, in place of the built-in ones, e.g. `Accepted = "✅"`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) emoji: BTreeMap<String, String>,
+    /// ASCII markers by status, in place of the built-in ones, e.g. `Accepted = "[+]"`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) ascii: BTreeMap<String, String>,
+}
+
+impl MarkersConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct FmtConfig {
+    /// The order `adrs fmt` puts the frontmatter keys in. Other keys follow in their own order
+    pub(crate) frontmatter_order: Vec<String>,
+}
+
+impl Default for FmtConfig {
+    fn default() -> Self {
+        Self {
+            frontmatter_order: [
+                "id",
+                "status",
+                "date",
+                "tags",
+                "deciders",
+                "consulted",
+                "informed",
+                "proposed_date",
+                "decided_date",
+                "implemented_date",
+                "implemented",
+                "implementation_link",
+                "confirmation_result",
+                "confirmed_date",
+                "confirmation_note",
+                "confirmation_command",
+            ]
+            .map(str::to_string)
+            .to_vec(),
+        }
+    }
+}
+
+impl FmtConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Commands that render ADRs in place of the built-in renderer. Each reads the markdown on stdin
+/// and writes the rendering to stdout
+#[derive(Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct RenderConfig {
+    /// Renders an ADR for the terminal, for `adrs show --render`, e.g. `glow -s dark -`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) terminal: Option<String>,
+    /// Renders an ADR to HTML for the generated sites, e.g. `pandoc -f gfm -t html`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) html: Option<String>,
+}
+
+impl RenderConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Where `adrs publish` pushes the published catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum PublishTarget {
+    /// A plain directory, e.g. one served by a web server
+    Directory,
+    /// A branch of a git remote served by GitHub Pages
+    GithubPages,
+    /// An S3 bucket, synced with the aws CLI
+    S3,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct PublishConfig {
+    /// Where to publish, unless --target is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) target: Option<PublishTarget>,
+    /// The directory to publish to, for the directory target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) path: Option<PathBuf>,
+    /// The git remote to push to, for the github-pages target, by default origin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) remote: Option<String>,
+    /// The branch to push to, for the github-pages target, by default gh-pages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) branch: Option<String>,
+    /// The bucket to sync to, for the s3 target, e.g. s3://decisions/platform
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) bucket: Option<String>,
+    /// The title of the site
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) title: Option<String>,
+    /// Files already on the github-pages branch or in the bucket that publishing keeps, besides
+    /// CNAME and .nojekyll
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) keep: Vec<String>,
+}
+
+impl PublishConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+// project configuration, read from .adrs.toml
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct Config {
+    /// The version of the configuration layout. Files without one predate versioning, and are
+    /// migrated by `adrs upgrade`
+    pub(crate) version: u32,
+    pub(crate) mode: Mode,
+    pub(crate) templates: TemplatesConfig,
+    pub(crate) lint: LintConfig,
+    pub(crate) generate: GenerateConfig,
+    /// The markers shown beside each status
+    #[serde(skip_serializing_if = "MarkersConfig::is_default")]
+    pub(crate) markers: MarkersConfig,
+    /// Where `adrs publish` pushes the published catalog
+    #[serde(skip_serializing_if = "PublishConfig::is_default")]
+    pub(crate) publish: PublishConfig,
+    /// How `adrs fmt` formats ADRs
+    #[serde(skip_serializing_if = "FmtConfig::is_default")]
+    pub(crate) fmt: FmtConfig,
+    /// How ADRs are rendered for the terminal and the generated sites
+    #[serde(skip_serializing_if = "RenderConfig::is_default")]
+    pub(crate) render: RenderConfig,
+    /// The tags teams are expected to use
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tags: Vec<String>,
+    /// User-defined command aliases, e.g. `accept = "status {} accepted"`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) alias: BTreeMap<String, String>,
+    /// Teams or components that keep their own ADRs and number them independently, as the name
+    /// and ADR directory of each, e.g. `payments = "services/payments/doc/adr"`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) scopes: BTreeMap<String, PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            mode: Mode::default(),
+            templates: TemplatesConfig::default(),
+            lint: LintConfig::default(),
+            generate: GenerateConfig::default(),
+            markers: MarkersConfig::default(),
+            publish: PublishConfig::default(),
+            fmt: FmtConfig::default(),
+            render: RenderConfig::default(),
+            tags: Vec::new(),
+            alias: BTreeMap::new(),
+            scopes: BTreeMap::new(),
+        }
+    }
+}
+
+// parse a configuration file into a table, to be merged with others before deserializing
+pub(crate) fn read_config_table(path: &Path) -> Result<toml::Table> {
+    let contents = read_to_string(path)?;
+    toml::from_str(&contents).with_context(|| format!("Invalid configuration: {}", path.display()))
+}
+
+// recursively merge the overlay table into the base table, the overlay winning on conflicts
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge_tables(base, overlay)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+// merge the settings into the project configuration file, creating it if there isn't one. What
+// else the file has is kept, and nothing from the global configuration or the defaults is added
+pub(crate) fn update_config_file(path: &Path, settings: toml::Table) -> Result<()> {
+    let mut table = if path.exists() {
+        read_config_table(path)?
+    } else {
+        toml::Table::from_iter([("version".to_string(), CONFIG_VERSION.into())])
+    };
+    merge_tables(&mut table, settings);
+    let _: Config = table.clone().try_into().context("Invalid configuration")?;
+    let contents = toml::to_string_pretty(&table).context("Unable to serialize configuration")?;
+    std::fs::write(path, contents)
+        .with_context(|| format!("Unable to write configuration: {}", path.display()))
+}
+
+// load the configuration for the current directory, layered over the global configuration
+pub(crate) fn load_config() -> Result<Config> {
+    let mut table = match paths::global_config_file() {
+        Some(path) if path.exists() => read_config_table(&path)?,
+        _ => toml::Table::new(),
+    };
+
+    let path = Path::new(CONFIG_FILE);
+    if path.exists() {
+        merge_tables(&mut table, read_config_table(path)?);
+    }
+    let config: Config = table.try_into().context("Invalid configuration")?;
+    if config.version > CONFIG_VERSION {
+        anyhow::bail!(
+            "The configuration is version {}, but this adrs only understands up to version {}",
+            config.version,
+            CONFIG_VERSION
+        );
+    }
+    Ok(config)
+}
+
+// the settings that were written at the top level before the [templates], [lint] and
+// [generate] tables, and were otherwise silently ignored, with the table each belongs in
+static TOP_LEVEL_KEYS: &[(&str, &str)] = &[
+    ("format", "templates"),
+    ("custom_sections", "templates"),
+    ("rules", "lint"),
+    ("severity", "lint"),
+    ("include_archived", "generate"),
+    ("exclude_status", "generate"),
+];
+
+// keys that have been renamed, as (old, new)
+static RENAMED_KEYS: &[(&str, &str)] = &[("aliases", "alias")];
+
+// version 0 to 1: move top level settings into their tables and rename deprecated keys
+fn migrate_v0(table: &mut toml::Table) -> Vec<String> {
+    let mut report = Vec::new();
+    for (key, section) in TOP_LEVEL_KEYS {
+        let Some(value) = table.remove(*key) else {
+            continue;
+        };
+        let section_table = table
+            .entry(*section)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        match section_table {
+            toml::Value::Table(section_table) if !section_table.contains_key(*key) => {
+                section_table.insert(key.to_string(), value);
+                report.push(format!("moved `{}` to `{}.{}`", key, section, key));
+            }
+            _ => report.push(format!(
+                "removed `{}`, `{}.{}` is already set",
+                key, section, key
+            )),
+        }
+    }
+    for (old, new) in RENAMED_KEYS {
+        let Some(value) = table.remove(*old) else {
+            continue;
+        };
+        if table.contains_key(*new) {
+            report.push(format!("removed `{}`, `{}` is already set", old, new));
+        } else {
+            table.insert(new.to_string(), value);
+            report.push(format!("renamed `{}` to `{}`", old, new));
+        }
+    }
+    report
+}
+
+// the migration from each version to the next, indexed by the version it migrates from
+static MIGRATIONS: &[fn(&mut toml::Table) -> Vec<String>] = &[migrate_v0];
+
+// migrate a configuration table to the current version, returning what was changed
+pub(crate) fn migrate_config(table: &mut toml::Table) -> Result<Vec<String>> {
+    let version = match table.get("version") {
+        Some(toml::Value::Integer(version)) => u32::try_from(*version)
+            .with_context(|| format!("Invalid configuration version {}", version))?,
+        Some(_) => anyhow::bail!("The configuration version must be a number"),
+        None => 0,
+    };
+    if version > CONFIG_VERSION {
+        anyhow::bail!(
+            "The configuration is version {}, but this adrs only understands up to version {}",
+            version,
+            CONFIG_VERSION
+        );
+    }
+    let mut report = Vec::new();
+    for migration in &MIGRATIONS[version as usize..] {
+        report.extend(migration(table));
+    }
+    if version < CONFIG_VERSION {
+        table.insert("version".to_string(), CONFIG_VERSION.into());
+        report.push(format!(
+            "stamped version {} (was {})",
+            CONFIG_VERSION, version
+        ));
+    }
+    Ok(report)
+}
+
+impl Config {
+    // the ADR directory of the scope
+    pub(crate) fn scope_dir(&self, scope: &str) -> Result<PathBuf> {
+        if self.scopes.is_empty() {
+            anyhow::bail!(
+                "Unknown scope '{}': no scopes are configured, add them to [scopes] in {}",
+                scope,
+                CONFIG_FILE
+            );
+        }
+        match self.scopes.get(scope) {
+            Some(dir) => Ok(dir.clone()),
+            None => anyhow::bail!(
+                "Unknown scope '{}', the configured scopes are: {}",
+                scope,
+                self.scopes.keys().cloned().collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+}
+
+// expand a user-defined alias in the command line arguments. `{}` placeholders in the alias are
+// filled with the arguments that follow it, and any remaining arguments are appended. Aliases
+// never shadow built in commands and are not expanded recursively. Global options may come
+// before the alias, and value_options are the long names of those that take a value
+pub(crate) fn expand_alias(
+    args: Vec<String>,
+    config: &Config,
+    builtins: &[&str],
+    value_options: &[&str],
+) -> Vec<String> {
+    // the position of the command, after any global options
+    let mut position = 1;
+    while let Some(arg) = args.get(position).filter(|arg| arg.starts_with('-')) {
+        let takes_value = arg
+            .strip_prefix("--")
+            .is_some_and(|name| value_options.contains(&name));
+        position += if takes_value { 2 } else { 1 };
+    }
+    let Some(name) = args.get(position) else {
+        return args;
+    };
+    if builtins.contains(&name.as_str()) {
+        return args;
+    }
+    let Some(expansion) = config.alias.get(name) else {
+        return args;
+    };
+
+    let mut rest = args[position + 1..].iter().cloned();
+    let mut expanded = args[..position].to_vec();
+    for token in expansion.split_whitespace() {
+        if token == "{}" {
+            expanded.extend(rest.next());
+        } else {
+            expanded.push(token.to_string());
+        }
+    }
+    expanded.extend(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+
+    #[test]
+    #[serial_test::serial]
+    fn test_load_config() {
+        let temp = TempDir::new().unwrap();
+        std::env::set_current_dir(temp.path()).unwrap();
+
+        assert!(load_config().unwrap().alias.is_empty());
+        assert_eq!(load_config().unwrap().mode, Mode::Compatible);
+
+        temp.child(".adrs.toml")
+            .write_str("mode = \"nextgen\"\n\n[alias]\naccept = \"status {} accepted\"\n")
+            .unwrap();
+
+        let config = load_config().unwrap();
+        assert_eq!(config.alias.get("accept").unwrap(), "status {} accepted");
+        assert_eq!(config.mode, Mode::Nextgen);
+        assert_eq!(config.templates.format, TemplateFormat::Nygard);
+
+        temp.child(".adrs.toml").write_str("alias = 1").unwrap();
+        assert!(load_config().is_err());
+
+        temp.child(".adrs.toml").write_str("version = 99").unwrap();
+        assert!(load_config().is_err());
+    }
+
+    #[test]
+    fn test_migrate_config() {
+        let mut table: toml::Table = toml::from_str(
+            "format = \"madr\"\nrules = [\"title\"]\n[lint]\nrules = [\"date\"]\n[aliases]\na = \"list\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            migrate_config(&mut table).unwrap(),
+            vec![
+                "moved `format` to `templates.format`",
+                "removed `rules`, `lint.rules` is already set",
+                "renamed `aliases` to `alias`",
+                "stamped version 1 (was 0)",
+            ]
+        );
+        let config: Config = table.clone().try_into().unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.templates.format, TemplateFormat::Madr);
+        assert_eq!(config.lint.rules, vec!["date"]);
+        assert_eq!(config.alias.get("a").unwrap(), "list");
+
+        assert!(migrate_config(&mut table).unwrap().is_empty());
+        assert!(migrate_config(&mut toml::from_str("version = 99").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_merge_tables() {
+        let mut base: toml::Table =
+            toml::from_str("mode = \"nextgen\"\n[alias]\na = \"list\"\nb = \"list\"\n").unwrap();
+        let overlay: toml::Table = toml::from_str("[alias]\nb = \"new\"\n").unwrap();
+        merge_tables(&mut base, overlay);
+
+        let config: Config = base.try_into().unwrap();
+        assert_eq!(config.mode, Mode::Nextgen);
+        assert_eq!(config.alias.get("a").unwrap(), "list");
+        assert_eq!(config.alias.get("b").unwrap(), "new");
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        let mut config = Config::default();
+        config
+            .alias
+            .insert("accept".into(), "status {} accepted".into());
+        config
+            .alias
+            .insert("adr".into(), "new --format madr".into());
+        config.alias.insert("list".into(), "generate toc".into());
+
+        let args = |s: &str| s.split(' ').map(String::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            expand_alias(args("adrs accept 5"), &config, &["list"], &["dir"]),
+            args("adrs status 5 accepted")
+        );
+        assert_eq!(
+            expand_alias(args("adrs adr Use Postgres"), &config, &["list"], &["dir"]),
+            args("adrs new --format madr Use Postgres")
+        );
+        assert_eq!(
+            expand_alias(args("adrs list"), &config, &["list"], &["dir"]),
+            args("adrs list")
+        );
+        assert_eq!(
+            expand_alias(args("adrs other 1"), &config, &["list"], &["dir"]),
+            args("adrs other 1")
+        );
+        assert_eq!(
+            expand_alias(args("adrs"), &config, &["list"], &["dir"]),
+            args("adrs")
+        );
+        assert_eq!(
+            expand_alias(
+                args("adrs --profile --dir accept accept 5"),
+                &config,
+                &["list"],
+                &["dir"]
+            ),
+            args("adrs --profile --dir accept status 5 accepted")
+        );
+        assert_eq!(
+            expand_alias(args("adrs --read-only"), &config, &["list"], &["dir"]),
+            args("adrs --read-only")
+        );
+    }
+}
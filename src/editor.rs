@@ -0,0 +1,177 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+// editors to try on Windows when neither VISUAL nor EDITOR is set
+#[cfg(windows)]
+static DEFAULT_EDITORS: &[&str] = &["code -w", "notepad"];
+
+// find a program, either as given or on the PATH. On Windows the PATHEXT extensions are tried too,
+// so that `code` finds `code.cmd`
+fn resolve_program(program: &str) -> Option<PathBuf> {
+    let candidates = |dir: &Path| {
+        let mut candidates = vec![dir.join(program)];
+        if cfg!(windows) && Path::new(program).extension().is_none() {
+            let pathext = env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".into());
+            candidates.extend(
+                pathext
+                    .split(';')
+                    .filter(|ext| !ext.is_empty())
+                    .map(|ext| dir.join(format!("{}{}", program, ext.to_lowercase()))),
+            );
+        }
+        candidates
+    };
+
+    if Path::new(program).components().count() > 1 {
+        return candidates(Path::new(""))
+            .into_iter()
+            .find(|candidate| candidate.is_file());
+    }
+    env::split_paths(&env::var_os("PATH")?)
+        .flat_map(|dir| candidates(&dir))
+        .find(|candidate| candidate.is_file())
+}
+
+// split an editor command line into the program and its arguments the way a shell would, so that
+// quoted paths with spaces work as they do for git, and add the flag that makes VS Code wait for
+// the file to be closed. A command that names an existing program as a whole is taken as is, and
+// on Windows backslashes are path separators rather than escapes
+fn editor_command(command: &str) -> Option<(PathBuf, Vec<String>)> {
+    let command = command.trim();
+    let (program, mut args) = match resolve_program(command) {
+        Some(program) => (program, Vec::new()),
+        None => {
+            let command = if cfg!(windows) {
+                command.replace('\\', "\\\\")
+            } else {
+                command.to_string()
+            };
+            let mut parts = shell_words::split(&command).ok()?.into_iter();
+            (resolve_program(&parts.next()?)?, parts.collect())
+        }
+    };
+
+    let stem = program.file_stem()?.to_str()?.to_lowercase();
+    if (stem == "code" || stem == "code-insiders")
+        && !args.iter().any(|arg| arg == "-w" || arg == "--wait")
+    {
+        args.push("--wait".to_string());
+    }
+    Some((program, args))
+}
+
+// the editor configured through VISUAL or EDITOR, or on Windows the first available default
+fn find_editor() -> Option<(PathBuf, Vec<String>)> {
+    let configured = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|command| !command.trim().is_empty());
+    if let Some(command) = configured {
+        return editor_command(&command);
+    }
+
+    #[cfg(windows)]
+    return DEFAULT_EDITORS
+        .iter()
+        .find_map(|command| editor_command(command));
+    #[cfg(not(windows))]
+    None
+}
+
+// open the text in the user's editor and return the edited text
+pub(crate) fn edit_text(text: &str) -> Result<String> {
+    let Some((program, args)) = find_editor() else {
+        // let the edit crate try the usual suspects for this platform
+        return edit::edit(text).context("Unable to find an editor, set VISUAL or EDITOR");
+    };
+
+    let file = edit::Builder::new()
+        .prefix("adrs-")
+        .suffix(".md")
+        .tempfile()
+        .context("Unable to create a temporary file to edit")?;
+    std::fs::write(file.path(), text)?;
+
+    let status = Command::new(&program)
+        .args(&args)
+        .arg(file.path())
+        .status()
+        .with_context(|| format!("Unable to run editor {}", program.display()))?;
+    if !status.success() {
+        anyhow::bail!("Editor {} exited with {}", program.display(), status);
+    }
+
+    std::fs::read_to_string(file.path()).context("Unable to read the edited file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editor_command() {
+        let (program, args) = editor_command("cargo --quiet").unwrap();
+        assert_eq!(program.file_stem().unwrap(), "cargo");
+        assert_eq!(args, vec!["--quiet"]);
+
+        assert!(editor_command("no-such-editor-anywhere").is_none());
+        assert!(editor_command("").is_none());
+        assert!(editor_command("\"cargo").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_editor_command_quoted() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("Sublime Text.app");
+        std::fs::create_dir(&dir).unwrap();
+        let subl = dir.join("subl");
+        std::fs::write(&subl, "").unwrap();
+
+        let (program, args) = editor_command(&format!("\"{}\" -w", subl.display())).unwrap();
+        assert_eq!(program, subl);
+        assert_eq!(args, vec!["-w"]);
+
+        let (program, args) = editor_command(&format!("'{}' --wait", subl.display())).unwrap();
+        assert_eq!(program, subl);
+        assert_eq!(args, vec!["--wait"]);
+
+        let escaped = subl.display().to_string().replace(' ', "\\ ");
+        let (program, args) = editor_command(&format!("{} -n 'a b'", escaped)).unwrap();
+        assert_eq!(program, subl);
+        assert_eq!(args, vec!["-n", "a b"]);
+
+        // an unquoted path with spaces still works when it names the program as a whole
+        let (program, args) = editor_command(&subl.display().to_string()).unwrap();
+        assert_eq!(program, subl);
+        assert!(args.is_empty());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_editor_command_quoted() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("Microsoft VS Code");
+        std::fs::create_dir(&dir).unwrap();
+        let code = dir.join("code.exe");
+        std::fs::write(&code, "").unwrap();
+
+        let (program, args) = editor_command(&format!("\"{}\" --wait", code.display())).unwrap();
+        assert_eq!(program, code);
+        assert_eq!(args, vec!["--wait"]);
+
+        let (program, args) = editor_command(&format!("\"{}\"", code.display())).unwrap();
+        assert_eq!(program, code);
+        assert_eq!(args, vec!["--wait"]);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_resolve_program_pathext() {
+        let notepad = resolve_program("notepad").unwrap();
+        assert_eq!(notepad.extension().unwrap().to_ascii_lowercase(), "exe");
+    }
+}
@@ -0,0 +1,30 @@
+use std::process::Command;
+
+// run git and return its output, or nothing if it isn't installed or this isn't a repository
+pub(crate) fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// the name of the checked out branch, which is empty for a detached HEAD
+pub(crate) fn current_branch() -> Option<String> {
+    git(&["branch", "--show-current"]).map(|branch| branch.trim().to_string())
+}
+
+// the name git records as the author of new commits
+pub(crate) fn user_name() -> Option<String> {
+    git(&["config", "user.name"]).map(|name| name.trim().to_string())
+}
+
+// whether the working tree has changes that aren't committed
+pub(crate) fn is_dirty() -> Option<bool> {
+    git(&["status", "--porcelain"]).map(|status| !status.trim().is_empty())
+}
+
+// the full SHA of the commit that is checked out
+pub(crate) fn head_commit() -> Option<String> {
+    git(&["rev-parse", "HEAD"]).map(|sha| sha.trim().to_string())
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::adr::parse_date;
+use crate::git::git;
+use crate::model::Adr;
+
+// the statuses an ADR has had, oldest first, as (date, status) pairs from the commits that
+// changed it. Renames are followed
+pub(crate) fn status_history(path: &Path) -> Vec<(time::Date, String)> {
+    let Some(log) = git(&[
+        "log",
+        "--follow",
+        "--format=commit %H %as",
+        "--name-only",
+        "--",
+        &path.to_string_lossy(),
+    ]) else {
+        return Vec::new();
+    };
+
+    let mut history = Vec::new();
+    let mut commit = None;
+    for line in log.lines().filter(|line| !line.is_empty()) {
+        if let Some(rest) = line.strip_prefix("commit ") {
+            commit = rest.split_once(' ');
+            continue;
+        }
+        // the file name in this commit, relative to the root of the repository
+        let Some((sha, date)) = commit.take() else {
+            continue;
+        };
+        let (Ok(date), Some(markdown)) = (
+            parse_date(date),
+            git(&["show", &format!("{}:{}", sha, line)]),
+        ) else {
+            continue;
+        };
+        if let Some(status) = Adr::parse(Path::new(line), &markdown)
+            .ok()
+            .and_then(|adr| adr.current_status())
+        {
+            history.push((date, status));
+        }
+    }
+    history.reverse();
+    history
+}
+
+// the days spent in each status that has since changed
+pub(crate) fn days_in_status(history: &[(time::Date, String)]) -> BTreeMap<String, i64> {
+    let mut days = BTreeMap::new();
+    let mut current: Option<&(time::Date, String)> = None;
+    for entry in history {
+        match current {
+            Some((_, status)) if *status == entry.1 => {}
+            Some((since, status)) => {
+                *days.entry(status.clone()).or_default() += (entry.0 - *since).whole_days();
+                current = Some(entry);
+            }
+            None => current = Some(entry),
+        }
+    }
+    days
+}
+
+// the days spent proposed before a decision and accepted before being implemented, from the
+// nextgen frontmatter dates
+fn frontmatter_days_in_status(adr: &Adr) -> BTreeMap<String, i64> {
+    let between = |from: Option<&str>, to: Option<&str>| {
+        Some((parse_date(to?).ok()? - parse_date(from?).ok()?).whole_days())
+    };
+    let (proposed, decided, implemented) = (
+        adr.proposed_date.as_deref(),
+        adr.decided_date.as_deref(),
+        adr.implemented_date.as_deref(),
+    );
+    [
+        ("Proposed", between(proposed, decided)),
+        ("Accepted", between(decided, implemented)),
+    ]
+    .into_iter()
+    .filter_map(|(status, days)| Some((status.to_string(), days?)))
+    .collect()
+}
+
+// when the ADR entered its current status, from the frontmatter dates when they are recorded and
+// otherwise from the git history of the file. None if its status has never changed
+pub(crate) fn last_status_change(adr_dir: &Path, adr: &Adr) -> Option<time::Date> {
+    let recorded = [&adr.proposed_date, &adr.decided_date, &adr.implemented_date]
+        .into_iter()
+        .filter_map(|date| parse_date(date.as_deref()?).ok())
+        .max();
+    if recorded.is_some() {
+        return recorded;
+    }
+    let history = status_history(&adr_dir.join(adr.path.as_ref()?));
+    let (_, current) = history.last()?;
+    let entered = history.iter().rposition(|(_, status)| status != current)? + 1;
+    Some(history[entered].0)
+}
+
+// how long the ADR spent in each status it has left, from the frontmatter dates when they are
+// recorded and otherwise from the git history of the file
+pub(crate) fn time_in_status(adr_dir: &Path, adr: &Adr) -> BTreeMap<String, i64> {
+    let days = frontmatter_days_in_status(adr);
+    if !days.is_empty() {
+        return days;
+    }
+    match &adr.path {
+        Some(path) => days_in_status(&status_history(&adr_dir.join(path))),
+        None => BTreeMap::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn test_days_in_status() {
+        let history = [
+            (date!(2024 - 01 - 01), "Proposed".to_string()),
+            (date!(2024 - 01 - 05), "Proposed".to_string()),
+            (date!(2024 - 01 - 11), "Accepted".to_string()),
+            (date!(2024 - 02 - 10), "Superseded".to_string()),
+        ];
+        assert_eq!(
+            days_in_status(&history),
+            BTreeMap::from([("Proposed".to_string(), 10), ("Accepted".to_string(), 30)])
+        );
+        assert!(days_in_status(&history[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_frontmatter_days_in_status() {
+        let adr = Adr::parse(
+            Path::new("0001-use-mysql.md"),
+            "---\nproposed_date: 2024-01-01\ndecided_date: 2024-01-11\n---\n\n# 1. Use MySQL\n",
+        )
+        .unwrap();
+        assert_eq!(
+            time_in_status(Path::new("."), &adr),
+            BTreeMap::from([("Proposed".to_string(), 10)])
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use time::format_description::well_known::Rfc3339;
+
+use crate::paths;
+
+// append an entry to the journal of operations, as the time, the operation and what it did,
+// separated by tabs
+pub(crate) fn record(operation: &str, detail: &str) -> Result<()> {
+    let Some(path) = paths::journal_file() else {
+        return Ok(());
+    };
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    let time = time::OffsetDateTime::now_utc().format(&Rfc3339)?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Unable to open the journal {}", path.display()))?;
+    writeln!(file, "{}\t{}\t{}", time, operation, detail)
+        .with_context(|| format!("Unable to write the journal {}", path.display()))
+}
+
+// append an error to the journal, along with the root of the project it happened in so that it's
+// only reported from that project
+pub(crate) fn record_error(root: &Path, detail: &str) -> Result<()> {
+    record("error", &format!("{}\t{}", root.display(), detail))
+}
@@ -0,0 +1,593 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::adr::to_forward_slashes;
+use crate::config::TemplateFormat;
+use crate::model::{Adr, ConfirmationResult, Implemented, Link, MISSPELLED_LINKS};
+
+// the version of the JSON-ADR format written by this version of adrs
+pub(crate) static JSON_ADR_VERSION: &str = "1.1.0";
+
+// the ADR fields each version of the JSON-ADR format added, oldest first. Versions with the same
+// major version only add optional fields, so older documents are read by migrating them and
+// newer ones by dropping the fields this version doesn't know
+static JSON_ADR_CHANGES: &[(&str, &[&str])] = &[
+    (
+        "1.0.0",
+        &[
+            "number",
+            "title",
+            "status",
+            "date",
+            "context",
+            "decision",
+            "consequences",
+            "links",
+            "path",
+        ],
+    ),
+    (
+        "1.1.0",
+        &[
+            "id",
+            "proposed_date",
+            "decided_date",
+            "implemented_date",
+            "implemented",
+            "implementation_link",
+            "confirmation_result",
+            "confirmed_date",
+            "confirmation_note",
+            "confirmation_command",
+            "tags",
+            "deciders",
+            "decision_drivers",
+            "custom_sections",
+            "section_order",
+            "scope",
+            "format",
+            "source_uri",
+            "time_in_status",
+        ],
+    ),
+];
+
+// the migration from each minor version to the next, indexed by the version it migrates from
+static MIGRATIONS: &[fn(&mut serde_json::Value) -> Vec<String>] = &[migrate_v1_0];
+
+// the JSON Schema for the JSON-ADR format, shipped with the binary so that exports can carry it
+pub(crate) static JSON_ADR_SCHEMA: &str = include_str!("../schema/json-adr.schema.json");
+
+/// A link from one ADR to another
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct JsonAdrLink {
+    /// The kind of link, e.g. "Supersedes" or "Amended by"
+    #[serde(rename = "type")]
+    pub(crate) kind: String,
+    /// The number of the linked ADR
+    pub(crate) target: i32,
+}
+
+/// A section of an ADR that isn't one of the standard sections
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct JsonAdrSection {
+    pub(crate) heading: String,
+    #[serde(default)]
+    pub(crate) content: String,
+}
+
+/// A single ADR in JSON-ADR format
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct JsonAdr {
+    /// The immutable id of the ADR, for references that must survive renumbering
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) id: Option<String>,
+    pub(crate) number: i32,
+    pub(crate) title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) proposed_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) decided_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) implemented_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) implemented: Option<Implemented>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) implementation_link: Option<String>,
+    /// The outcome of the last check that the decision is followed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) confirmation_result: Option<ConfirmationResult>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) confirmed_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) confirmation_note: Option<String>,
+    /// The shell command that checks the decision, when declared in the frontmatter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) confirmation_command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tags: Vec<String>,
+    /// Who made the decision
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) deciders: Vec<String>,
+    /// The forces behind the decision, from MADR's Decision Drivers section
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) decision_drivers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) context: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) decision: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) consequences: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) links: Vec<JsonAdrLink>,
+    /// Any other sections, in document order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) custom_sections: Vec<JsonAdrSection>,
+    /// The section headings in the order they were written
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) section_order: Vec<String>,
+    /// The path of the ADR file relative to the ADR directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) path: Option<String>,
+    /// The scope the ADR belongs to, in exports combining several scopes. Numbers are only
+    /// unique within a scope
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) scope: Option<String>,
+    /// The format the ADR was written in, detected from its sections and frontmatter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) format: Option<TemplateFormat>,
+    /// Where the ADR file can be viewed, when the export was given a base URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) source_uri: Option<String>,
+    /// The days spent in each status the ADR has since left. Derived from the history, so it is
+    /// ignored on import
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) time_in_status: BTreeMap<String, i64>,
+}
+
+/// The tool that produced an export
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct JsonAdrTool {
+    pub(crate) name: String,
+    pub(crate) version: String,
+}
+
+impl Default for JsonAdrTool {
+    fn default() -> Self {
+        Self {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Where an export came from, so that catalogs consuming it can trace it back
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct JsonAdrProvenance {
+    /// The commit that was checked out, when exported from a git repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) commit: Option<String>,
+    /// Whether the working tree had uncommitted changes, which the commit doesn't include
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) dirty: Option<bool>,
+    /// Who ran the export
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) exported_by: Option<String>,
+    /// When the export was made, as YYYY-MM-DD, left out of deterministic exports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) exported_at: Option<String>,
+}
+
+/// A set of ADRs exported together
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct JsonAdrBulkExport {
+    pub(crate) version: String,
+    #[serde(default)]
+    pub(crate) tool: JsonAdrTool,
+    /// The JSON Schema of the format, when embedded so the export can be validated offline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) schema: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) provenance: Option<JsonAdrProvenance>,
+    pub(crate) adrs: Vec<JsonAdr>,
+}
+
+impl JsonAdrBulkExport {
+    pub(crate) fn new(adrs: Vec<JsonAdr>) -> Self {
+        Self {
+            version: JSON_ADR_VERSION.to_string(),
+            tool: JsonAdrTool::default(),
+            schema: None,
+            provenance: None,
+            adrs,
+        }
+    }
+
+    // parse an export, checking that it is a version this version of adrs understands and
+    // that it follows the schema
+    pub(crate) fn from_json(json: &str) -> Result<Self> {
+        let mut value: serde_json::Value = serde_json::from_str(json).context("Invalid JSON")?;
+        let version = value["version"].clone();
+        for line in migrate_json(&mut value)? {
+            eprintln!("Warning: {}", line);
+        }
+        if value["version"] != version {
+            eprintln!(
+                "Warning: rewrite the export with `adrs upgrade --json FILE` to keep it current"
+            );
+        }
+        let errors = schema_errors(&value)?;
+        if !errors.is_empty() {
+            let errors = errors
+                .iter()
+                .map(|(path, message)| format!("  {}: {}", path, message))
+                .collect::<Vec<_>>();
+            anyhow::bail!("Invalid JSON-ADR export:\n{}", errors.join("\n"));
+        }
+        serde_json::from_value(value).context("Invalid JSON-ADR export")
+    }
+
+    pub(crate) fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Unable to serialize JSON-ADR export")
+    }
+
+    // the same structure as YAML, for pipelines that prefer it
+    pub(crate) fn to_yaml(&self) -> Result<String> {
+        serde_yaml::to_string(self).context("Unable to serialize JSON-ADR export as YAML")
+    }
+}
+
+impl From<&Adr> for JsonAdr {
+    fn from(adr: &Adr) -> Self {
+        Self {
+            id: adr.id.clone(),
+            number: adr.number,
+            title: adr.title.clone(),
+            status: adr.current_status(),
+            date: adr.date.clone(),
+            proposed_date: adr.proposed_date.clone(),
+            decided_date: adr.decided_date.clone(),
+            implemented_date: adr.implemented_date.clone(),
+            implemented: adr.implemented,
+            implementation_link: adr.implementation_link.clone(),
+            confirmation_result: adr.confirmation_result,
+            confirmed_date: adr.confirmed_date.clone(),
+            confirmation_note: adr.confirmation_note.clone(),
+            confirmation_command: adr.confirmation_command.clone(),
+            tags: adr.tags.clone(),
+            deciders: adr.deciders.clone(),
+            decision_drivers: adr.decision_drivers(),
+            context: adr.context.clone(),
+            decision: adr.decision.clone(),
+            consequences: adr.consequences.clone(),
+            links: adr
+                .links
+                .iter()
+                .map(|link| JsonAdrLink {
+                    kind: link.kind.clone(),
+                    target: link.target,
+                })
+                .collect(),
+            custom_sections: adr
+                .custom_sections
+                .iter()
+                .map(|(heading, content)| JsonAdrSection {
+                    heading: heading.clone(),
+                    content: content.clone(),
+                })
+                .collect(),
+            section_order: adr.section_order.clone(),
+            path: adr.path.as_deref().map(to_forward_slashes),
+            scope: None,
+            format: Some(adr.format),
+            source_uri: None,
+            time_in_status: BTreeMap::new(),
+        }
+    }
+}
+
+impl From<&JsonAdr> for Adr {
+    fn from(json: &JsonAdr) -> Self {
+        let links = json
+            .links
+            .iter()
+            .map(|link| Link {
+                kind: link.kind.clone(),
+                target: link.target,
+            })
+            .collect::<Vec<_>>();
+        // a superseded ADR is written with only its "Superseded by" link
+        let superseded = json.status.as_deref() == Some("Superseded")
+            && links.iter().any(|link| link.kind == "Superseded by");
+        let mut adr = Self {
+            id: json.id.clone(),
+            number: json.number,
+            title: json.title.clone(),
+            date: json.date.clone(),
+            proposed_date: json.proposed_date.clone(),
+            decided_date: json.decided_date.clone(),
+            implemented_date: json.implemented_date.clone(),
+            implemented: json.implemented,
+            implementation_link: json.implementation_link.clone(),
+            confirmation_result: json.confirmation_result,
+            confirmed_date: json.confirmed_date.clone(),
+            confirmation_note: json.confirmation_note.clone(),
+            confirmation_command: json.confirmation_command.clone(),
+            tags: json.tags.clone(),
+            deciders: json.deciders.clone(),
+            status: json.status.clone().filter(|_| !superseded),
+            links,
+            context: json.context.clone(),
+            decision: json.decision.clone(),
+            consequences: json.consequences.clone(),
+            custom_sections: json
+                .custom_sections
+                .iter()
+                .map(|section| (section.heading.clone(), section.content.clone()))
+                .collect(),
+            section_order: json.section_order.clone(),
+            path: json.path.as_deref().map(PathBuf::from),
+            format: json.format.unwrap_or_default(),
+        };
+        // the drivers win over the section they were exported from, so that editing either one
+        // of them is imported
+        if json.decision_drivers != adr.decision_drivers() {
+            adr.set_decision_drivers(&json.decision_drivers);
+        }
+        adr
+    }
+}
+
+// the major and minor numbers of a version of the format
+fn parse_version(version: &str) -> (String, u32) {
+    let mut parts = version.split('.');
+    let major = parts.next().unwrap_or_default().to_string();
+    (
+        major,
+        parts
+            .next()
+            .and_then(|minor| minor.parse().ok())
+            .unwrap_or(0),
+    )
+}
+
+// version 1.0 to 1.1: respell the link types adr-tools once misspelled
+fn migrate_v1_0(value: &mut serde_json::Value) -> Vec<String> {
+    let mut report = Vec::new();
+    // indexing a missing field mutably would add it, so fields are looked up with get_mut
+    let adrs = value.get_mut("adrs").and_then(|adrs| adrs.as_array_mut());
+    for adr in adrs.into_iter().flatten() {
+        let number = adr["number"].clone();
+        let links = adr.get_mut("links").and_then(|links| links.as_array_mut());
+        for link in links.into_iter().flatten() {
+            let Some(kind) = link["type"].as_str() else {
+                continue;
+            };
+            if let Some((old, new)) = MISSPELLED_LINKS.iter().find(|(old, _)| *old == kind) {
+                link["type"] = (*new).into();
+                report.push(format!("ADR {}: spelled `{}` as `{}`", number, old, new));
+            }
+        }
+    }
+    report
+}
+
+// migrate a JSON-ADR document to the current version of the format, returning what was changed.
+// Documents from a newer minor version are left as they are, reporting the fields this version
+// will drop
+pub(crate) fn migrate_json(value: &mut serde_json::Value) -> Result<Vec<String>> {
+    // documents without a version are left for the schema to report
+    let Some(version) = value["version"].as_str().map(str::to_string) else {
+        return Ok(Vec::new());
+    };
+    let (major, minor) = parse_version(&version);
+    let (current_major, current_minor) = parse_version(JSON_ADR_VERSION);
+    if major != current_major {
+        anyhow::bail!(
+            "Unsupported JSON-ADR version {}, expected {}",
+            version,
+            JSON_ADR_VERSION
+        );
+    }
+    let mut report = Vec::new();
+    if minor > current_minor {
+        let known = JSON_ADR_CHANGES
+            .iter()
+            .flat_map(|(_, fields)| fields.iter())
+            .collect::<BTreeSet<_>>();
+        let unknown = value["adrs"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|adr| adr.as_object())
+            .flat_map(|adr| adr.keys())
+            .filter(|key| !known.contains(&key.as_str()))
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        if !unknown.is_empty() {
+            report.push(format!(
+                "JSON-ADR version {} is newer than {}, these fields are dropped: {}",
+                version,
+                JSON_ADR_VERSION,
+                unknown.into_iter().collect::<Vec<_>>().join(", ")
+            ));
+        }
+        return Ok(report);
+    }
+    for migration in &MIGRATIONS[(minor as usize).min(MIGRATIONS.len())..] {
+        report.extend(migration(value));
+    }
+    if minor < current_minor {
+        value["version"] = JSON_ADR_VERSION.into();
+        report.push(format!(
+            "migrated from JSON-ADR version {} to {}",
+            version, JSON_ADR_VERSION
+        ));
+    }
+    Ok(report)
+}
+
+// the places a JSON-ADR document breaks the bundled schema, as (JSON pointer, message) pairs
+pub(crate) fn schema_errors(json: &serde_json::Value) -> Result<Vec<(String, String)>> {
+    let schema: serde_json::Value =
+        serde_json::from_str(JSON_ADR_SCHEMA).context("Invalid JSON-ADR schema")?;
+    let validator = jsonschema::validator_for(&schema)
+        .map_err(|e| anyhow::anyhow!("Invalid JSON-ADR schema: {}", e))?;
+    Ok(validator
+        .iter_errors(json)
+        .map(|error| {
+            let path = error.instance_path.to_string();
+            let path = if path.is_empty() {
+                "/".to_string()
+            } else {
+                path
+            };
+            (path, error.to_string())
+        })
+        .collect())
+}
+
+// export the ADRs, with their paths relative to the ADR directory
+pub(crate) fn export_adrs(adr_dir: &Path, adrs: &[PathBuf]) -> Result<JsonAdrBulkExport> {
+    let adrs = adrs
+        .iter()
+        .map(|path| Adr::from_path(adr_dir, path).map(|adr| JsonAdr::from(&adr)))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(JsonAdrBulkExport::new(adrs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let adr = Adr::parse(
+            Path::new("0002-use-mysql.md"),
+            "# 2. Use MySQL\n\n## Status\n\nSuperseded by [3. Use Postgres](0003-use-postgres.md)\n\n## Security Review\n\nApproved.\n",
+        )
+        .unwrap();
+        let json = JsonAdr::from(&adr);
+        assert_eq!(json.status.as_deref(), Some("Superseded"));
+        assert_eq!(json.custom_sections[0].heading, "Security Review");
+        assert_eq!(Adr::from(&json), adr);
+
+        let export = JsonAdrBulkExport::new(vec![json]);
+        let parsed = JsonAdrBulkExport::from_json(&export.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, export);
+    }
+
+    #[test]
+    fn test_decision_drivers() {
+        let adr = Adr::parse(
+            Path::new("0001-x.md"),
+            "# 1. X\n\n## Decision Drivers\n\nWe weighed:\n\n* Cost\n",
+        )
+        .unwrap();
+        let mut json = JsonAdr::from(&adr);
+        assert_eq!(json.decision_drivers, vec!["Cost"]);
+        assert_eq!(Adr::from(&json), adr);
+
+        json.decision_drivers.push("Team expertise".to_string());
+        assert_eq!(
+            Adr::from(&json).custom_sections,
+            vec![(
+                "Decision Drivers".to_string(),
+                "* Cost\n* Team expertise".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_schema() {
+        let schema: serde_json::Value = serde_json::from_str(JSON_ADR_SCHEMA).unwrap();
+        assert_eq!(schema["title"], "JSON-ADR bulk export");
+        assert!(schema["$defs"]["adr"]["properties"]
+            .as_object()
+            .unwrap()
+            .contains_key("time_in_status"));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let json = r#"{"version": "2.0.0", "adrs": []}"#;
+        assert!(JsonAdrBulkExport::from_json(json).is_err());
+        let json = r#"{"version": "1.9.0", "adrs": []}"#;
+        assert!(JsonAdrBulkExport::from_json(json).is_ok());
+    }
+
+    #[test]
+    fn test_migrate_json() {
+        let mut json = serde_json::json!({
+            "version": "1.0.0",
+            "adrs": [{"number": 2, "title": "B", "links": [{"type": "Supercedes", "target": 1}]}]
+        });
+        assert_eq!(
+            migrate_json(&mut json).unwrap(),
+            [
+                "ADR 2: spelled `Supercedes` as `Supersedes`",
+                "migrated from JSON-ADR version 1.0.0 to 1.1.0"
+            ]
+        );
+        assert_eq!(json["version"], "1.1.0");
+        assert_eq!(json["adrs"][0]["links"][0]["type"], "Supersedes");
+        assert!(migrate_json(&mut json).unwrap().is_empty());
+
+        let mut json = serde_json::json!({
+            "version": "1.4.0",
+            "adrs": [{"number": 1, "title": "A", "owner": "x", "tags": []}]
+        });
+        assert_eq!(
+            migrate_json(&mut json).unwrap(),
+            ["JSON-ADR version 1.4.0 is newer than 1.1.0, these fields are dropped: owner"]
+        );
+    }
+
+    #[test]
+    fn test_changes_match_schema() {
+        let schema: serde_json::Value = serde_json::from_str(JSON_ADR_SCHEMA).unwrap();
+        let properties = schema["$defs"]["adr"]["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect::<BTreeSet<_>>();
+        let changes = JSON_ADR_CHANGES
+            .iter()
+            .flat_map(|(_, fields)| fields.iter().copied())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(properties, changes);
+        assert_eq!(JSON_ADR_CHANGES.last().unwrap().0, JSON_ADR_VERSION);
+    }
+
+    #[test]
+    fn test_schema_errors() {
+        let json = serde_json::json!({
+            "version": "1.0.0",
+            "adrs": [{"number": "one", "title": "Use MySQL"}, {"number": 2}]
+        });
+        assert_eq!(
+            schema_errors(&json).unwrap(),
+            [
+                (
+                    "/adrs/0/number".to_string(),
+                    "\"one\" is not of type \"integer\"".to_string()
+                ),
+                (
+                    "/adrs/1".to_string(),
+                    "\"title\" is a required property".to_string()
+                ),
+            ]
+        );
+        let export = JsonAdrBulkExport::new(Vec::new()).to_json().unwrap();
+        let json = serde_json::from_str(&export).unwrap();
+        assert!(schema_errors(&json).unwrap().is_empty());
+    }
+}
@@ -1,13 +1,55 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 
 pub mod adr;
+mod clipboard;
 mod cmd;
+mod config;
+mod editor;
+mod git;
+mod history;
+mod journal;
+mod json_adr;
+mod manifest;
+mod markers;
+mod model;
+mod paths;
+mod profile;
+mod prompt;
+mod render;
+mod reservations;
+mod sarif;
+mod shortcodes;
+mod state;
+mod stats;
+mod transaction;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None )]
 #[command(propagate_version = true)]
 struct Cli {
+    /// Print where the time went once the command has finished
+    #[arg(long, global = true, default_value_t = false)]
+    profile: bool,
+    /// Work on this directory of ADRs instead of the one recorded in .adr-dir, e.g. for a one-off
+    /// look at another repository's ADRs
+    #[arg(long, global = true, value_name = "PATH")]
+    dir: Option<PathBuf>,
+    /// Work on the ADRs of this scope, one of those configured under [scopes], each of which
+    /// numbers its ADRs independently
+    #[arg(long, global = true, value_name = "NAME", conflicts_with = "dir")]
+    scope: Option<String>,
+    /// Leave the time out of generated artifacts, such as export provenance and backups, so that
+    /// regenerating them from the same ADRs gives the same output. SOURCE_DATE_EPOCH pins the
+    /// time instead
+    #[arg(long, global = true, default_value_t = false)]
+    deterministic: bool,
+    /// Refuse to change the ADRs, for shared environments. Commands that only read them, such as
+    /// list, search, export and doctor, still work
+    #[arg(long, global = true, default_value_t = false)]
+    read_only: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -18,44 +60,313 @@ enum Commands {
     Init(cmd::init::InitArgs),
     /// Create a new, numbered Architectural Decision Record
     New(cmd::new::NewArgs),
+    /// Print the number the next new Architectural Decision Record will use
+    NextNumber(cmd::next_number::NextNumberArgs),
+    /// Reserve a block of ADR numbers, so that parallel work doesn't collide
+    Reserve(cmd::reserve::ReserveArgs),
+    /// Print an Architectural Decision Record, found by number, id or file name
+    Show(cmd::show::ShowArgs),
     /// Edit an existing Architectural Decision Record
     Edit(cmd::edit::EditArgs),
+    /// Show or change the status of an Architectural Decision Record
+    Status(cmd::status::StatusArgs),
+    /// Record how far an Architectural Decision Record has been implemented
+    Implement(cmd::implement::ImplementArgs),
+    /// Record the outcome of checking that an Architectural Decision Record is being followed
+    Confirm(cmd::confirm::ConfirmArgs),
+    /// Manage the decision drivers of an Architectural Decision Record
+    #[command(subcommand)]
+    Drivers(cmd::drivers::DriversCommands),
+    /// Manage the considered options of an Architectural Decision Record
+    #[command(subcommand)]
+    Option(cmd::option::OptionCommands),
+    /// Replace an Architectural Decision Record with a tombstone explaining why it was retired,
+    /// keeping its number and inbound links
+    Retire(cmd::retire::RetireArgs),
+    /// Find and replace text across Architectural Decision Records, showing the changes first
+    Replace(cmd::replace::ReplaceArgs),
     /// Link Architectural Decision Records
     Link(cmd::link::LinkArgs),
+    /// Repair links between Architectural Decision Records after files were renamed
+    FixLinks(cmd::fix_links::FixLinksArgs),
+    /// Format Architectural Decision Records canonically: headings, blank lines, frontmatter key
+    /// order, link lines and trailing whitespace
+    Fmt(cmd::fmt::FmtArgs),
     /// List Architectural Decision Records
     List(cmd::list::ListArgs),
+    /// Search the Architectural Decision Records, including archived ones, and optionally their
+    /// git history
+    Search(cmd::search::SearchArgs),
+    /// Show the most recently created Architectural Decision Record
+    Last(cmd::last::LastArgs),
+    /// List the open action items from the consequences of each decision
+    Todos(cmd::todos::TodosArgs),
+    /// Order the proposed Architectural Decision Records so that each comes after the decisions it
+    /// depends on, flagging dependencies on rejected or superseded decisions
+    Order(cmd::order::OrderArgs),
+    /// Summarize the Architectural Decision Records by status, tag, month and links
+    Stats(cmd::stats::StatsArgs),
+    /// Find directories that look like collections of Architectural Decision Records, e.g. in a
+    /// monorepo adopting adrs, and offer to register them as scopes
+    Discover(cmd::discover::DiscoverArgs),
+    /// Check the Architectural Decision Records for problems
+    Doctor(cmd::doctor::DoctorArgs),
+    /// Check a single markdown file, which doesn't need to be in an ADR directory
+    Validate(cmd::validate::ValidateArgs),
+    /// Export the Architectural Decision Records
+    #[command(subcommand)]
+    Export(cmd::export::ExportCommands),
+    /// Import Architectural Decision Records
+    #[command(subcommand)]
+    Import(cmd::import::ImportCommands),
+    /// Check that a JSON-ADR export and import round trip doesn't lose anything
+    VerifyExport(cmd::verify_export::VerifyExportArgs),
+    /// Publish the ADRs as a JSON-ADR bundle and a static site to the configured target: a
+    /// directory, a GitHub Pages branch or an S3 bucket
+    Publish(cmd::publish::PublishArgs),
+    /// Bundle the ADRs, configuration and project state into a timestamped tar.gz
+    Backup(cmd::backup::BackupArgs),
+    /// Restore the ADRs, configuration and project state from a backup
+    RestoreBackup(cmd::backup::RestoreBackupArgs),
     /// Show the current configuration
     Config(cmd::config::ConfigArgs),
+    /// Migrate the configuration, and optionally the ADRs, to the current conventions
+    Upgrade(cmd::upgrade::UpgradeArgs),
+    /// Show diagnostic information for bug reports
+    #[command(alias = "env")]
+    Info(cmd::info::InfoArgs),
+    /// Gather versions, a configuration summary, anonymized repository statistics and the last
+    /// error into a redacted markdown report to paste into a bug report. Nothing is sent
+    ReportIssue(cmd::report_issue::ReportIssueArgs),
     /// Generates summary documentation about the Architectural Decision Records
     #[command(subcommand)]
     Generate(cmd::generate::GenerateCommands),
+    /// Run a language server for editing Architectural Decision Records
+    #[cfg(feature = "lsp")]
+    Lsp(cmd::lsp::LspArgs),
+    /// Generate shell completions
+    Completions(cmd::completions::CompletionsArgs),
+}
+
+impl Commands {
+    // whether the command always changes the ADRs, so that it can be refused before it starts.
+    // Commands that only change them for some arguments are refused when they write
+    fn changes_adrs(&self) -> bool {
+        if let Commands::Import(import) = self {
+            return !import.is_dry_run();
+        }
+        matches!(
+            self,
+            Commands::Init(_)
+                | Commands::New(_)
+                | Commands::Reserve(_)
+                | Commands::Edit(_)
+                | Commands::Implement(_)
+                | Commands::Confirm(_)
+                | Commands::Option(_)
+                | Commands::Retire(_)
+                | Commands::Link(_)
+                | Commands::RestoreBackup(_)
+        )
+    }
 }
 
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let command = Cli::command();
+    let builtins = command
+        .get_subcommands()
+        .flat_map(|cmd| std::iter::once(cmd.get_name()).chain(cmd.get_all_aliases()))
+        .chain(["help"])
+        .collect::<Vec<_>>();
+    let value_options = command
+        .get_arguments()
+        .filter(|arg| arg.is_global_set() && arg.get_action().takes_values())
+        .filter_map(|arg| arg.get_long())
+        .collect::<Vec<_>>();
+    // a broken configuration only fails the commands that use it, so that --help, completions
+    // and config still work to fix it
+    let config = config::load_config();
+    let aliases = match &config {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Warning: aliases are unavailable: {:#}", err);
+            &config::Config::default()
+        }
+    };
+    let args = config::expand_alias(
+        std::env::args().collect(),
+        aliases,
+        &builtins,
+        &value_options,
+    );
+    let matches = Cli::command().get_matches_from(args);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let start = std::time::Instant::now();
+    if cli.profile {
+        profile::enable();
+    }
+    if let Some(dir) = &cli.dir {
+        adr::set_adr_dir(dir.clone());
+        state::set_dir_project(dir);
+    }
+    if cli.deterministic {
+        adr::set_deterministic();
+    }
+    if cli.read_only {
+        adr::set_read_only();
+    }
+    if let Some(scope) = &cli.scope {
+        let dir = config
+            .as_ref()
+            .map_err(|err| anyhow::anyhow!("{:#}", err))?
+            .scope_dir(scope)?;
+        // like the default ADR directory, a scope's directory is created when first used
+        if !dir.exists() && !adr::is_read_only() {
+            std::fs::create_dir_all(&dir)
+                .with_context(|| format!("Unable to create {}", dir.display()))?;
+        }
+        adr::set_adr_dir(dir);
+        adr::set_scope(scope.clone());
+    }
+    if cli.command.changes_adrs() {
+        adr::ensure_writable(matches.subcommand_name().unwrap_or_default())?;
+    }
 
-    match &cli.command {
+    if let Err(err) = run(&cli.command) {
+        // kept for `adrs report-issue`, whether or not the journal can be written
+        let detail = format!(
+            "{}: {:#}",
+            matches.subcommand_name().unwrap_or_default(),
+            err
+        );
+        let root = adr::find_project_root().unwrap_or_default();
+        let _ = journal::record_error(&root, &detail.replace(['\n', '\t'], " "));
+        return Err(err);
+    }
+    if cli.profile {
+        eprint!("{}", profile::report(start.elapsed()));
+    }
+    Ok(())
+}
+
+// run the command
+fn run(command: &Commands) -> Result<()> {
+    match command {
         Commands::Init(args) => {
             cmd::init::run(args)?;
         }
         Commands::New(args) => {
             cmd::new::run(args)?;
         }
+        Commands::NextNumber(args) => {
+            cmd::next_number::run(args)?;
+        }
+        Commands::Reserve(args) => {
+            cmd::reserve::run(args)?;
+        }
+        Commands::Show(args) => {
+            cmd::show::run(args)?;
+        }
         Commands::Edit(args) => {
             cmd::edit::run(args)?;
         }
+        Commands::Status(args) => {
+            cmd::status::run(args)?;
+        }
+        Commands::Implement(args) => {
+            cmd::implement::run(args)?;
+        }
+        Commands::Confirm(args) => {
+            cmd::confirm::run(args)?;
+        }
+        Commands::Retire(args) => {
+            cmd::retire::run(args)?;
+        }
+        Commands::Replace(args) => {
+            cmd::replace::run(args)?;
+        }
         Commands::Link(args) => {
             cmd::link::run(args)?;
         }
+        Commands::FixLinks(args) => {
+            cmd::fix_links::run(args)?;
+        }
+        Commands::Fmt(args) => {
+            cmd::fmt::run(args)?;
+        }
         Commands::List(args) => {
             cmd::list::run(args)?;
         }
+        Commands::Search(args) => {
+            cmd::search::run(args)?;
+        }
+        Commands::Last(args) => {
+            cmd::last::run(args)?;
+        }
+        Commands::Todos(args) => {
+            cmd::todos::run(args)?;
+        }
+        Commands::Discover(args) => {
+            cmd::discover::run(args)?;
+        }
+        Commands::Order(args) => {
+            cmd::order::run(args)?;
+        }
+        Commands::Stats(args) => {
+            cmd::stats::run(args)?;
+        }
+        Commands::Doctor(args) => {
+            cmd::doctor::run(args)?;
+        }
+        Commands::Validate(args) => {
+            cmd::validate::run(args)?;
+        }
+        Commands::Export(args) => {
+            cmd::export::run(args)?;
+        }
+        Commands::Drivers(args) => {
+            cmd::drivers::run(args)?;
+        }
+        Commands::Option(args) => {
+            cmd::option::run(args)?;
+        }
+        Commands::Import(args) => {
+            cmd::import::run(args)?;
+        }
+        Commands::VerifyExport(args) => {
+            cmd::verify_export::run(args)?;
+        }
+        Commands::Publish(args) => {
+            cmd::publish::run(args)?;
+        }
+        Commands::Backup(args) => {
+            cmd::backup::run(args)?;
+        }
+        Commands::RestoreBackup(args) => {
+            cmd::backup::run_restore(args)?;
+        }
         Commands::Config(args) => {
             cmd::config::run(args)?;
         }
+        Commands::Upgrade(args) => {
+            cmd::upgrade::run(args)?;
+        }
+        Commands::Info(args) => {
+            cmd::info::run(args)?;
+        }
+        Commands::ReportIssue(args) => {
+            cmd::report_issue::run(args)?;
+        }
         Commands::Generate(args) => {
             cmd::generate::run(args)?;
         }
+        #[cfg(feature = "lsp")]
+        Commands::Lsp(args) => {
+            cmd::lsp::run(args)?;
+        }
+        Commands::Completions(args) => {
+            cmd::completions::run(args, Cli::command())?;
+        }
     }
     Ok(())
 }
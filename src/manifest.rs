@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+// a manifest of repositories with ADRs, as `[[repos]]` tables with a `path` relative to the
+// manifest and an optional `name`
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    repos: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestEntry {
+    path: PathBuf,
+    name: Option<String>,
+}
+
+// a repository in the manifest, with its path resolved and a name to show for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ManifestRepo {
+    pub(crate) name: String,
+    pub(crate) path: PathBuf,
+}
+
+impl ManifestRepo {
+    // the repository's ADR directory, from its .adr-dir or the doc/adr default, if it has one
+    pub(crate) fn adr_dir(&self) -> Option<PathBuf> {
+        if let Ok(dir) = std::fs::read_to_string(self.path.join(".adr-dir")) {
+            return Some(self.path.join(dir.trim().replace('\\', "/")));
+        }
+        let default = self.path.join("doc/adr");
+        default.is_dir().then_some(default)
+    }
+}
+
+// the repositories in the manifest, with paths relative to the base directory. Repositories
+// without a name are named after their directory
+fn parse_manifest(contents: &str, base: &Path) -> Result<Vec<ManifestRepo>> {
+    let manifest: Manifest = toml::from_str(contents)?;
+    Ok(manifest
+        .repos
+        .into_iter()
+        .map(|entry| {
+            let path = base.join(&entry.path);
+            let name = entry.name.unwrap_or_else(|| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.display().to_string())
+            });
+            ManifestRepo { name, path }
+        })
+        .collect())
+}
+
+// read the repositories listed in the manifest file
+pub(crate) fn read_manifest(path: &Path) -> Result<Vec<ManifestRepo>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Unable to read {}", path.display()))?;
+    parse_manifest(&contents, path.parent().unwrap_or(Path::new(".")))
+        .with_context(|| format!("Invalid manifest: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let repos = parse_manifest(
+            "[[repos]]\npath = \"services/billing\"\n\n[[repos]]\npath = \"web\"\nname = \"Website\"\n",
+            Path::new("org"),
+        )
+        .unwrap();
+        assert_eq!(
+            repos,
+            vec![
+                ManifestRepo {
+                    name: "billing".to_string(),
+                    path: PathBuf::from("org/services/billing"),
+                },
+                ManifestRepo {
+                    name: "Website".to_string(),
+                    path: PathBuf::from("org/web"),
+                },
+            ]
+        );
+        assert!(parse_manifest("repos = 1", Path::new(".")).is_err());
+    }
+}
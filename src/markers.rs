@@ -0,0 +1,100 @@
+use anyhow::{Context, Result};
+
+use crate::config::{load_config, MarkerStyle, MarkersConfig};
+
+// the built-in markers for the standard statuses
+static EMOJI_MARKERS: &[(&str, &str)] = &[
+    ("Proposed", "🚧"),
+    ("Accepted", "✅"),
+    ("Implemented", "🚀"),
+    ("Rejected", "❌"),
+    ("Withdrawn", "🚫"),
+    ("Deprecated", "📦"),
+    ("Superseded", "📦"),
+    ("Retired", "🪦"),
+];
+static ASCII_MARKERS: &[(&str, &str)] = &[
+    ("Proposed", "[?]"),
+    ("Accepted", "[+]"),
+    ("Implemented", "[*]"),
+    ("Rejected", "[x]"),
+    ("Withdrawn", "[/]"),
+    ("Deprecated", "[-]"),
+    ("Superseded", "[-]"),
+    ("Retired", "[~]"),
+];
+
+// the markers to show beside statuses, in the configured style or the one given on the command
+// line
+#[derive(Debug, Default)]
+pub(crate) struct Markers {
+    style: MarkerStyle,
+    config: MarkersConfig,
+}
+
+impl Markers {
+    pub(crate) fn load(style: Option<MarkerStyle>) -> Result<Self> {
+        let config = load_config()
+            .context("Unable to load configuration")?
+            .markers;
+        Ok(Self {
+            style: style.unwrap_or(config.style),
+            config,
+        })
+    }
+
+    pub(crate) fn enabled(&self) -> bool {
+        self.style != MarkerStyle::None
+    }
+
+    // the marker for the status, matched case-insensitively, if the style has one for it
+    pub(crate) fn marker(&self, status: &str) -> Option<&str> {
+        let (configured, built_in) = match self.style {
+            MarkerStyle::None => return None,
+            MarkerStyle::Emoji => (&self.config.emoji, EMOJI_MARKERS),
+            MarkerStyle::Ascii => (&self.config.ascii, ASCII_MARKERS),
+        };
+        configured
+            .iter()
+            .map(|(status, marker)| (status.as_str(), marker.as_str()))
+            .chain(built_in.iter().copied())
+            .find(|(s, _)| s.eq_ignore_ascii_case(status))
+            .map(|(_, marker)| marker)
+    }
+
+    // the marker followed by a space, to go in front of a title, or nothing
+    pub(crate) fn prefix(&self, status: Option<&str>) -> String {
+        match status.and_then(|status| self.marker(status)) {
+            Some(marker) => format!("{} ", marker),
+            None => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_marker() {
+        let markers = Markers {
+            style: MarkerStyle::Emoji,
+            config: MarkersConfig {
+                emoji: BTreeMap::from([("Accepted".to_string(), "👍".to_string())]),
+                ..Default::default()
+            },
+        };
+        assert_eq!(markers.marker("accepted"), Some("👍"));
+        assert_eq!(markers.prefix(Some("Rejected")), "❌ ");
+        assert_eq!(markers.prefix(Some("Draft")), "");
+
+        let ascii = Markers {
+            style: MarkerStyle::Ascii,
+            ..Default::default()
+        };
+        assert_eq!(ascii.prefix(Some("Superseded")), "[-] ");
+        assert_eq!(Markers::default().prefix(Some("Accepted")), "");
+    }
+}
@@ -0,0 +1,777 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::adr::{
+    format_adr_path, get_frontmatter_field, get_frontmatter_list, get_number,
+    quote_frontmatter_value, split_frontmatter, split_tags, to_forward_slashes,
+};
+use crate::config::TemplateFormat;
+use crate::profile;
+
+// the statuses adrs knows about, in the order an ADR usually moves through them. Any other
+// status is kept as written
+pub(crate) static STANDARD_STATUSES: &[&str] = &[
+    "Proposed",
+    "Accepted",
+    "Implemented",
+    "Rejected",
+    "Withdrawn",
+    "Deprecated",
+    "Superseded",
+    "Retired",
+];
+
+// status link kinds as adr-tools once spelled them, with the current spelling
+pub(crate) static MISSPELLED_LINKS: &[(&str, &str)] = &[
+    ("Superceded by", "Superseded by"),
+    ("Supercedes", "Supersedes"),
+];
+
+// the status in its standard spelling, so that "rejected" and "Rejected" are counted, filtered
+// and coloured the same
+pub(crate) fn normalize_status(status: &str) -> String {
+    let status = status.trim();
+    STANDARD_STATUSES
+        .iter()
+        .find(|standard| standard.eq_ignore_ascii_case(status))
+        .map_or_else(|| status.to_string(), |standard| standard.to_string())
+}
+
+// the current filename and title of each ADR, by number
+pub(crate) type AdrIndex = BTreeMap<i32, (String, String)>;
+
+// a link from one ADR to another, as written in the status section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Link {
+    pub(crate) kind: String,
+    pub(crate) target: i32,
+}
+
+/// Whether a decision has been implemented
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Implemented {
+    /// The decision has been implemented
+    #[value(name = "true")]
+    #[serde(rename = "true")]
+    True,
+    /// The decision has been partly implemented
+    Partial,
+    /// The decision hasn't been implemented yet
+    No,
+}
+
+impl Implemented {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Implemented::True => "true",
+            Implemented::Partial => "partial",
+            Implemented::No => "no",
+        }
+    }
+}
+
+/// The outcome of checking that a decision is being followed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ConfirmationResult {
+    /// The check found the decision is being followed
+    Pass,
+    /// The check found the decision isn't being followed
+    Fail,
+}
+
+impl ConfirmationResult {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ConfirmationResult::Pass => "pass",
+            ConfirmationResult::Fail => "fail",
+        }
+    }
+}
+
+// the sections every ADR may have, whichever heading they are written under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Status,
+    Context,
+    Decision,
+    Consequences,
+}
+
+// the standard section a heading introduces, if any
+fn standard_section(heading: &str) -> Option<Section> {
+    match heading.to_lowercase().as_str() {
+        "status" => Some(Section::Status),
+        "context" | "context and problem statement" => Some(Section::Context),
+        "decision" | "decision outcome" => Some(Section::Decision),
+        "consequences" => Some(Section::Consequences),
+        _ => None,
+    }
+}
+
+// whether a heading introduces the named section, so that `decision` also matches MADR's
+// "Decision Outcome"
+pub(crate) fn section_matches(heading: &str, name: &str) -> bool {
+    heading.trim().eq_ignore_ascii_case(name.trim())
+        || standard_section(heading).is_some_and(|section| standard_section(name) == Some(section))
+}
+
+// the headings only MADR uses, which tell a MADR document from a Nygard one
+static MADR_HEADINGS: &[&str] = &[
+    "Context and Problem Statement",
+    "Decision Drivers",
+    "Considered Options",
+    "Decision Outcome",
+    "Pros and Cons of the Options",
+];
+
+// the format the ADR was written in, from its section headings and the shape of its frontmatter.
+// MADR keeps the status in the frontmatter rather than in a Status section
+pub(crate) fn detect_format(markdown: &str) -> TemplateFormat {
+    let (frontmatter, body) = split_frontmatter(markdown);
+    let (_, sections) = split_sections(body);
+    let madr_headings = sections.iter().any(|(heading, _)| {
+        MADR_HEADINGS
+            .iter()
+            .any(|madr| heading.eq_ignore_ascii_case(madr))
+    });
+    let madr_frontmatter = !frontmatter.is_empty()
+        && get_frontmatter_field(markdown, "status").is_some()
+        && !sections
+            .iter()
+            .any(|(heading, _)| standard_section(heading) == Some(Section::Status));
+    if madr_headings || madr_frontmatter {
+        TemplateFormat::Madr
+    } else {
+        TemplateFormat::Nygard
+    }
+}
+
+// the heading of MADR's list of the forces behind a decision
+pub(crate) static DECISION_DRIVERS: &str = "Decision Drivers";
+// the heading of MADR's description of how compliance with a decision is checked
+pub(crate) static CONFIRMATION: &str = "Confirmation";
+
+// the text of each top level bullet in a section body
+pub(crate) fn bullet_items(body: &str) -> Vec<String> {
+    let item = Regex::new(r"^[-*+] (?<text>.+)$").unwrap();
+    body.lines()
+        .filter_map(|line| Some(item.captures(line)?["text"].trim().to_string()))
+        .collect()
+}
+
+// a section body listing the items as bullets
+pub(crate) fn bullet_list(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|item| format!("* {}", item))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// the structured content of an ADR
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Adr {
+    // the immutable id from the nextgen frontmatter, which survives renumbering
+    pub(crate) id: Option<String>,
+    pub(crate) number: i32,
+    pub(crate) title: String,
+    pub(crate) date: Option<String>,
+    // when the ADR was proposed, decided and implemented, kept in the nextgen frontmatter
+    pub(crate) proposed_date: Option<String>,
+    pub(crate) decided_date: Option<String>,
+    pub(crate) implemented_date: Option<String>,
+    // whether the decision has been implemented, and where that work is tracked
+    pub(crate) implemented: Option<Implemented>,
+    pub(crate) implementation_link: Option<String>,
+    // the outcome of the last check that the decision is followed, kept in the nextgen
+    // frontmatter
+    pub(crate) confirmation_result: Option<ConfirmationResult>,
+    pub(crate) confirmed_date: Option<String>,
+    pub(crate) confirmation_note: Option<String>,
+    // the shell command that checks the decision, when it is declared in the frontmatter
+    pub(crate) confirmation_command: Option<String>,
+    pub(crate) tags: Vec<String>,
+    // who made the decision, kept in the nextgen frontmatter as in MADR
+    pub(crate) deciders: Vec<String>,
+    // the status as written, which is empty once an ADR has been superseded
+    pub(crate) status: Option<String>,
+    pub(crate) links: Vec<Link>,
+    pub(crate) context: Option<String>,
+    pub(crate) decision: Option<String>,
+    pub(crate) consequences: Option<String>,
+    // any other sections, as (heading, body) pairs in document order
+    pub(crate) custom_sections: Vec<(String, String)>,
+    // the section headings as written, so that re-rendering keeps the author's order
+    pub(crate) section_order: Vec<String>,
+    // the path relative to the ADR directory
+    pub(crate) path: Option<PathBuf>,
+    // the format the ADR was written in, detected from the document
+    pub(crate) format: TemplateFormat,
+}
+
+// split the ADR markdown into everything before the first second level heading and the sections
+// that follow, as (heading, body) pairs in document order
+pub(crate) fn split_sections(markdown: &str) -> (&str, Vec<(String, &str)>) {
+    let mut headings = Vec::new();
+    for (event, offset) in Parser::new(markdown).into_offset_iter() {
+        if let Event::Start(Tag::Heading(HeadingLevel::H2, _, _)) = event {
+            let line = markdown[offset.clone()].lines().next().unwrap_or_default();
+            let heading = line.trim().trim_matches('#').trim().to_string();
+            headings.push((offset.start, offset.end, heading));
+        }
+    }
+
+    let preamble = &markdown[..headings.first().map_or(markdown.len(), |h| h.0)];
+    let sections = headings
+        .iter()
+        .enumerate()
+        .map(|(i, (_, end, heading))| {
+            let next = headings.get(i + 1).map_or(markdown.len(), |h| h.0);
+            (heading.clone(), &markdown[*end..next])
+        })
+        .collect();
+    (preamble, sections)
+}
+
+// the trimmed body, or nothing if it is empty
+fn non_empty(body: &str) -> Option<String> {
+    let body = body.trim();
+    (!body.is_empty()).then(|| body.to_string())
+}
+
+impl Adr {
+    // parse the markdown of an ADR
+    pub(crate) fn parse(path: &Path, markdown: &str) -> Result<Self> {
+        let _span = profile::span("parsing");
+        let mut adr = Adr {
+            id: get_frontmatter_field(markdown, "id"),
+            number: get_number(path).unwrap_or_default(),
+            proposed_date: get_frontmatter_field(markdown, "proposed_date"),
+            decided_date: get_frontmatter_field(markdown, "decided_date"),
+            implemented_date: get_frontmatter_field(markdown, "implemented_date"),
+            implemented: get_frontmatter_field(markdown, "implemented")
+                .and_then(|value| Implemented::from_str(&value, true).ok()),
+            implementation_link: get_frontmatter_field(markdown, "implementation_link"),
+            confirmation_result: get_frontmatter_field(markdown, "confirmation_result")
+                .and_then(|value| ConfirmationResult::from_str(&value, true).ok()),
+            confirmed_date: get_frontmatter_field(markdown, "confirmed_date"),
+            confirmation_note: get_frontmatter_field(markdown, "confirmation_note"),
+            confirmation_command: get_frontmatter_field(markdown, "confirmation_command"),
+            tags: get_frontmatter_list(markdown, "tags"),
+            deciders: get_frontmatter_list(markdown, "deciders"),
+            path: Some(path.to_path_buf()),
+            format: detect_format(markdown),
+            ..Default::default()
+        };
+        // MADR keeps the status and date in the frontmatter, which the sections take precedence
+        // over
+        let frontmatter_status = get_frontmatter_field(markdown, "status");
+        let frontmatter_date = get_frontmatter_field(markdown, "date");
+        let (_, markdown) = split_frontmatter(markdown);
+        let (preamble, sections) = split_sections(markdown);
+
+        for line in preamble.lines().map(str::trim) {
+            if let Some(title) = line.strip_prefix("# ") {
+                adr.title = match title.split_once(". ") {
+                    Some((number, title)) if number.parse::<i32>().is_ok() => title.to_string(),
+                    _ => title.to_string(),
+                };
+            } else if let Some(date) = line.strip_prefix("Date:") {
+                adr.date = non_empty(date);
+            } else if let Some(tags) = line.strip_prefix("Tags:") {
+                // compatible mode keeps the tags on a line under the title, frontmatter wins
+                if adr.tags.is_empty() {
+                    adr.tags = split_tags(tags);
+                }
+            }
+        }
+        if adr.title.is_empty() {
+            anyhow::bail!("No title found for ADR {}", path.display());
+        }
+        if adr.date.is_none() {
+            adr.date = frontmatter_date;
+        }
+
+        let link = Regex::new(r"^(?<kind>.+?)\s*\[[^\]]*\]\((?<dest>[^)\s]+)\)$").unwrap();
+        for (heading, body) in sections {
+            adr.section_order.push(heading.clone());
+            match standard_section(&heading) {
+                Some(Section::Status) => {
+                    for line in body.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                        let target = link.captures(line).and_then(|caps| {
+                            let dest = caps.name("dest").unwrap().as_str();
+                            let filename = dest.rsplit('/').next().unwrap_or(dest);
+                            let target = get_number(Path::new(filename))?;
+                            Some((caps["kind"].to_string(), target))
+                        });
+                        match target {
+                            Some((kind, target)) => adr.links.push(Link { kind, target }),
+                            None => adr.status = Some(line.to_string()),
+                        }
+                    }
+                }
+                Some(Section::Context) => adr.context = non_empty(body),
+                Some(Section::Decision) => adr.decision = non_empty(body),
+                Some(Section::Consequences) => adr.consequences = non_empty(body),
+                None => adr
+                    .custom_sections
+                    .push((heading.clone(), body.trim().to_string())),
+            }
+        }
+        if adr.status.is_none() && adr.links.is_empty() {
+            adr.status = frontmatter_status;
+        }
+        Ok(adr)
+    }
+
+    // read and parse an ADR, recording its path relative to the ADR directory
+    pub(crate) fn from_path(adr_dir: &Path, path: &Path) -> Result<Self> {
+        let markdown = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        Adr::parse(path.strip_prefix(adr_dir).unwrap_or(path), &markdown)
+    }
+
+    // the frontmatter fields that are set, for writing the ADR in nextgen mode
+    pub(crate) fn frontmatter_fields(&self) -> Vec<(&'static str, String)> {
+        let implemented = self.implemented.map(|i| i.as_str().to_string());
+        let confirmation_result = self.confirmation_result.map(|r| r.as_str().to_string());
+        let list =
+            |items: &[String]| (!items.is_empty()).then(|| format!("[{}]", items.join(", ")));
+        [
+            ("id", self.id.clone()),
+            ("tags", list(&self.tags)),
+            ("deciders", list(&self.deciders)),
+            ("proposed_date", self.proposed_date.clone()),
+            ("decided_date", self.decided_date.clone()),
+            ("implemented_date", self.implemented_date.clone()),
+            ("implemented", implemented),
+            ("implementation_link", self.implementation_link.clone()),
+            ("confirmation_result", confirmation_result),
+            ("confirmed_date", self.confirmed_date.clone()),
+            (
+                "confirmation_note",
+                self.confirmation_note
+                    .as_deref()
+                    .map(quote_frontmatter_value),
+            ),
+            (
+                "confirmation_command",
+                self.confirmation_command
+                    .as_deref()
+                    .map(quote_frontmatter_value),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value?)))
+        .collect()
+    }
+
+    // the checklist items in the consequences, as (done, text) pairs. MADR keeps its consequences
+    // in a subsection of the decision outcome
+    pub(crate) fn action_items(&self) -> Vec<(bool, String)> {
+        let madr_consequences = self.decision.as_deref().and_then(|decision| {
+            let start = decision.find("### Consequences")?;
+            let rest = &decision[start + "### Consequences".len()..];
+            Some(&rest[..rest.find("\n#").unwrap_or(rest.len())])
+        });
+        let item = Regex::new(r"^\s*[-*+] \[(?<done>[ xX])\] (?<text>.+)$").unwrap();
+        self.consequences
+            .iter()
+            .map(String::as_str)
+            .chain(madr_consequences)
+            .flat_map(str::lines)
+            .filter_map(|line| {
+                let caps = item.captures(line)?;
+                Some((&caps["done"] != " ", caps["text"].trim().to_string()))
+            })
+            .collect()
+    }
+
+    // how compliance with the decision is checked, from MADR's Confirmation section, which MADR
+    // keeps in a subsection of the decision outcome
+    pub(crate) fn confirmation(&self) -> Option<String> {
+        let section = self
+            .custom_sections
+            .iter()
+            .find(|(heading, _)| section_matches(heading, CONFIRMATION))
+            .map(|(_, body)| body.as_str());
+        let subsection = self.decision.as_deref().and_then(|decision| {
+            let start = decision.find("### Confirmation")?;
+            let rest = &decision[start + "### Confirmation".len()..];
+            Some(&rest[..rest.find("\n#").unwrap_or(rest.len())])
+        });
+        section
+            .or(subsection)
+            .map(str::trim)
+            .filter(|body| !body.is_empty())
+            .map(str::to_string)
+    }
+
+    // the shell command that checks the decision, from the frontmatter or else the first shell
+    // code block of the Confirmation section
+    pub(crate) fn check_command(&self) -> Option<String> {
+        if let Some(command) = &self.confirmation_command {
+            return Some(command.clone());
+        }
+        let block = Regex::new(r"(?ms)^```(?:sh|bash|shell)[ \t]*\n(?<command>.*?)^```").unwrap();
+        let confirmation = self.confirmation()?;
+        let command = block.captures(&confirmation)?["command"].trim().to_string();
+        (!command.is_empty()).then_some(command)
+    }
+
+    // whether the decision has been made and not replaced, so it should be followed
+    pub(crate) fn is_in_force(&self) -> bool {
+        matches!(
+            self.current_status().as_deref(),
+            Some("Accepted" | "Implemented")
+        )
+    }
+
+    // whether the decision is in force and says how it's checked, but hasn't been checked yet
+    pub(crate) fn is_unconfirmed(&self) -> bool {
+        self.is_in_force() && self.confirmation().is_some() && self.confirmation_result.is_none()
+    }
+
+    // the forces behind the decision, from the bullets of MADR's Decision Drivers section
+    pub(crate) fn decision_drivers(&self) -> Vec<String> {
+        self.custom_sections
+            .iter()
+            .find(|(heading, _)| section_matches(heading, DECISION_DRIVERS))
+            .map(|(_, body)| bullet_items(body))
+            .unwrap_or_default()
+    }
+
+    // replace the decision drivers, adding the section if the ADR doesn't have one
+    pub(crate) fn set_decision_drivers(&mut self, drivers: &[String]) {
+        let body = bullet_list(drivers);
+        match self
+            .custom_sections
+            .iter_mut()
+            .find(|(heading, _)| section_matches(heading, DECISION_DRIVERS))
+        {
+            Some((_, existing)) => *existing = body,
+            None => self
+                .custom_sections
+                .push((DECISION_DRIVERS.to_string(), body)),
+        }
+    }
+
+    // the status to report, which is Superseded once another ADR has superseded this one
+    pub(crate) fn current_status(&self) -> Option<String> {
+        if self.links.iter().any(|l| l.kind == "Superseded by") {
+            return Some("Superseded".to_string());
+        }
+        self.status.as_deref().map(normalize_status)
+    }
+
+    // the path relative to the ADR directory, derived from the title if the ADR hasn't been
+    // written yet
+    pub(crate) fn relative_path(&self) -> PathBuf {
+        match &self.path {
+            Some(path) => path.clone(),
+            None => format_adr_path(Path::new(""), self.number, &self.title),
+        }
+    }
+
+    // the status and links, one per paragraph
+    fn status_markdown(&self, index: &AdrIndex) -> String {
+        let mut lines = self.status.iter().cloned().collect::<Vec<_>>();
+        for link in &self.links {
+            lines.push(match index.get(&link.target) {
+                Some((filename, title)) => format!("{} [{}]({})", link.kind, title, filename),
+                None => format!("{} ADR {}", link.kind, link.target),
+            });
+        }
+        lines.join("\n\n")
+    }
+
+    // render the ADR as markdown, using the index to write the titles and filenames of linked ADRs
+    pub(crate) fn to_markdown(&self, index: &AdrIndex) -> String {
+        let _span = profile::span("rendering");
+        let mut out = format!("# {}. {}\n\n", self.number, self.title);
+        if let Some(date) = &self.date {
+            out += &format!("Date: {}\n\n", date);
+        }
+
+        // sections keep the order they were written in, and sections the ADR didn't have before
+        // go at the end
+        let mut headings = self.section_order.clone();
+        let standard = [
+            ("Status", Section::Status, true),
+            ("Context", Section::Context, self.context.is_some()),
+            ("Decision", Section::Decision, self.decision.is_some()),
+            (
+                "Consequences",
+                Section::Consequences,
+                self.consequences.is_some(),
+            ),
+        ];
+        for (heading, section, present) in standard {
+            if present
+                && !headings
+                    .iter()
+                    .any(|h| standard_section(h) == Some(section))
+            {
+                headings.push(heading.to_string());
+            }
+        }
+        let mut custom = self.custom_sections.iter().collect::<Vec<_>>();
+        for (heading, _) in &self.custom_sections {
+            if !headings.contains(heading) {
+                headings.push(heading.clone());
+            }
+        }
+
+        for heading in &headings {
+            let body = match standard_section(heading) {
+                Some(Section::Status) => Some(self.status_markdown(index)),
+                Some(Section::Context) => self.context.clone(),
+                Some(Section::Decision) => self.decision.clone(),
+                Some(Section::Consequences) => self.consequences.clone(),
+                None => custom
+                    .iter()
+                    .position(|(h, _)| h == heading)
+                    .map(|i| custom.remove(i).1.clone()),
+            };
+            if let Some(body) = body {
+                out += &format!("## {}\n\n", heading);
+                if !body.is_empty() {
+                    out += &format!("{}\n\n", body);
+                }
+            }
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+        out
+    }
+}
+
+// the filename and numbered title of each ADR, for rendering links between them
+pub(crate) fn index_adrs<'a>(adrs: impl IntoIterator<Item = &'a Adr>) -> AdrIndex {
+    adrs.into_iter()
+        .map(|adr| {
+            let path = adr.relative_path();
+            let filename = to_forward_slashes(Path::new(path.file_name().unwrap()));
+            (
+                adr.number,
+                (filename, format!("{}. {}", adr.number, adr.title)),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static SUPERSEDED: &str = "# 2. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nSuperseded by [3. Use Postgres](0003-use-postgres.md)\n\n## Context\n\nWe need a database.\n\n## Decision\n\nMySQL.\n\n## Security Review\n\nApproved.\n";
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(detect_format(SUPERSEDED), TemplateFormat::Nygard);
+        assert_eq!(
+            detect_format("---\nstatus: accepted\n---\n# Use MySQL\n\n## Context and Problem Statement\n\nX\n"),
+            TemplateFormat::Madr
+        );
+        // the status in the frontmatter alone is enough
+        assert_eq!(
+            detect_format("---\nstatus: proposed\n---\n# Use MySQL\n\n## Context\n\nX\n"),
+            TemplateFormat::Madr
+        );
+        assert_eq!(
+            detect_format("---\ntags: [db]\n---\n# 2. Use MySQL\n\n## Status\n\nAccepted\n"),
+            TemplateFormat::Nygard
+        );
+    }
+
+    #[test]
+    fn test_split_sections() {
+        let (preamble, sections) = split_sections(SUPERSEDED);
+        assert_eq!(preamble, "# 2. Use MySQL\n\nDate: 2024-01-01\n\n");
+        let headings = sections.iter().map(|(h, _)| h.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            headings,
+            vec!["Status", "Context", "Decision", "Security Review"]
+        );
+        assert_eq!(sections[3].1.trim(), "Approved.");
+    }
+
+    #[test]
+    fn test_parse() {
+        let adr = Adr::parse(Path::new("0002-use-mysql.md"), SUPERSEDED).unwrap();
+        assert_eq!(adr.number, 2);
+        assert_eq!(adr.title, "Use MySQL");
+        assert_eq!(adr.date.as_deref(), Some("2024-01-01"));
+        assert_eq!(adr.status, None);
+        assert_eq!(adr.current_status().as_deref(), Some("Superseded"));
+        assert_eq!(
+            adr.links,
+            vec![Link {
+                kind: "Superseded by".to_string(),
+                target: 3
+            }]
+        );
+        assert_eq!(adr.context.as_deref(), Some("We need a database."));
+        assert_eq!(adr.consequences, None);
+        assert_eq!(
+            adr.custom_sections,
+            vec![("Security Review".to_string(), "Approved.".to_string())]
+        );
+
+        assert!(Adr::parse(Path::new("0001-x.md"), "no title").is_err());
+    }
+
+    #[test]
+    fn test_decision_drivers() {
+        let mut adr = Adr::parse(
+            Path::new("0001-x.md"),
+            "# 1. X\n\n## Status\n\nAccepted\n\n## Decision Drivers\n\n* Cost\n* Team expertise\n  * nested\n\nSome prose.\n",
+        )
+        .unwrap();
+        assert_eq!(adr.decision_drivers(), vec!["Cost", "Team expertise"]);
+
+        adr.set_decision_drivers(&["Cost".to_string()]);
+        assert_eq!(
+            adr.custom_sections,
+            vec![("Decision Drivers".to_string(), "* Cost".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_confirmation() {
+        let adr = Adr::parse(
+            Path::new("0002-use-mysql.md"),
+            "# 2. Use MySQL\n\n## Status\n\nAccepted\n\n## Decision Outcome\n\nChosen option: \"MySQL\".\n\n### Confirmation\n\nThe schema check passes in CI.\n\n### Consequences\n\n* Good\n",
+        )
+        .unwrap();
+        assert_eq!(
+            adr.confirmation().as_deref(),
+            Some("The schema check passes in CI.")
+        );
+        assert!(adr.is_unconfirmed());
+        let adr = Adr::parse(
+            Path::new("0002-use-mysql.md"),
+            "---\nconfirmation_result: fail\nconfirmed_date: 2024-03-01\n---\n\n# 2. Use MySQL\n\n## Status\n\nAccepted\n\n## Confirmation\n\nReviewed each quarter.\n",
+        )
+        .unwrap();
+        assert_eq!(
+            adr.confirmation().as_deref(),
+            Some("Reviewed each quarter.")
+        );
+        assert_eq!(adr.confirmation_result, Some(ConfirmationResult::Fail));
+        assert!(!adr.is_unconfirmed());
+        assert_eq!(adr.check_command(), None);
+    }
+
+    #[test]
+    fn test_check_command() {
+        let adr = Adr::parse(
+            Path::new("0002-use-mysql.md"),
+            "# 2. Use MySQL\n\n## Status\n\nAccepted\n\n## Confirmation\n\nNo service talks to the database directly:\n\n```sh\n./scripts/check-db-access.sh\n```\n",
+        )
+        .unwrap();
+        assert_eq!(
+            adr.check_command().as_deref(),
+            Some("./scripts/check-db-access.sh")
+        );
+        let adr = Adr::parse(
+            Path::new("0002-use-mysql.md"),
+            "---\nconfirmation_command: \"cargo test --test architecture\"\n---\n\n# 2. Use MySQL\n\n## Status\n\nAccepted\n",
+        )
+        .unwrap();
+        assert_eq!(
+            adr.check_command().as_deref(),
+            Some("cargo test --test architecture")
+        );
+    }
+
+    #[test]
+    fn test_normalize_status() {
+        assert_eq!(normalize_status("rejected"), "Rejected");
+        assert_eq!(normalize_status(" WITHDRAWN "), "Withdrawn");
+        assert_eq!(normalize_status("On hold"), "On hold");
+        let adr = Adr::parse(
+            Path::new("0002-use-mysql.md"),
+            "# 2. Use MySQL\n\n## Status\n\nwithdrawn\n",
+        )
+        .unwrap();
+        assert_eq!(adr.current_status().as_deref(), Some("Withdrawn"));
+    }
+
+    #[test]
+    fn test_parse_tags_line() {
+        let adr = Adr::parse(
+            Path::new("0001-x.md"),
+            "# 1. X\n\nDate: 2024-01-01\n\nTags: api, security\n\n## Status\n\nAccepted\n",
+        )
+        .unwrap();
+        assert_eq!(adr.tags, vec!["api", "security"]);
+
+        let adr = Adr::parse(
+            Path::new("0001-x.md"),
+            "---\ntags: [database]\n---\n\n# 1. X\n\nTags: api\n",
+        )
+        .unwrap();
+        assert_eq!(adr.tags, vec!["database"]);
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let adr = Adr::parse(Path::new("0002-use-mysql.md"), SUPERSEDED).unwrap();
+        let mut index = AdrIndex::new();
+        index.insert(
+            3,
+            (
+                "0003-use-postgres.md".to_string(),
+                "3. Use Postgres".to_string(),
+            ),
+        );
+        let markdown = adr.to_markdown(&index);
+        assert!(markdown.starts_with(
+            "# 2. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nSuperseded by [3. Use Postgres](0003-use-postgres.md)\n\n## Context\n"
+        ));
+        assert_eq!(
+            Adr::parse(Path::new("0002-use-mysql.md"), &markdown).unwrap(),
+            adr
+        );
+    }
+
+    #[test]
+    fn test_action_items() {
+        let markdown = "# 1. Items\n\n## Status\n\nAccepted\n\n## Consequences\n\n- [ ] set up replication\n- [x] pick a host\n* not an item\n";
+        let adr = Adr::parse(Path::new("0001-items.md"), markdown).unwrap();
+        assert_eq!(
+            adr.action_items(),
+            vec![
+                (false, "set up replication".to_string()),
+                (true, "pick a host".to_string())
+            ]
+        );
+
+        let madr = "# 2. Madr\n\n## Decision Outcome\n\nChosen.\n\n### Consequences\n\n* [ ] migrate\n\n### Confirmation\n\n- [ ] not a consequence\n";
+        let adr = Adr::parse(Path::new("0002-madr.md"), madr).unwrap();
+        assert_eq!(adr.action_items(), vec![(false, "migrate".to_string())]);
+    }
+
+    #[test]
+    fn test_to_markdown_section_order() {
+        let markdown = "# 1. Ordered\n\n## Context and Problem Statement\n\nWhy.\n\n## Notes\n\nA note.\n\n## Status\n\nAccepted\n\n## Decision Outcome\n\nWhat.\n";
+        let mut adr = Adr::parse(Path::new("0001-ordered.md"), markdown).unwrap();
+        assert_eq!(adr.to_markdown(&AdrIndex::new()), markdown);
+
+        adr.consequences = Some("Some.".to_string());
+        adr.custom_sections
+            .push(("Rollout".to_string(), String::new()));
+        assert!(adr
+            .to_markdown(&AdrIndex::new())
+            .ends_with("## Decision Outcome\n\nWhat.\n\n## Consequences\n\nSome.\n\n## Rollout\n"));
+    }
+}
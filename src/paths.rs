@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+// Well known locations outside of the repository. These follow the XDG base directory
+// specification on Linux, and the platform conventions on macOS and Windows (Known Folders).
+
+// the directory holding the global configuration file
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("adrs"))
+}
+
+// the user's global configuration file
+pub(crate) fn global_config_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+// the directory for data that can be regenerated, such as parsed ADR metadata
+pub(crate) fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("adrs"))
+}
+
+// the directory for data that should persist between runs but isn't configuration
+pub(crate) fn state_dir() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("adrs"))
+}
+
+// the journal of operations, used to undo and to report errors
+pub(crate) fn journal_file() -> Option<PathBuf> {
+    state_dir().map(|dir| dir.join("journal.log"))
+}
+
+// the audit log of changes made to ADRs
+pub(crate) fn audit_log_file() -> Option<PathBuf> {
+    state_dir().map(|dir| dir.join("audit.log"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    #[serial_test::serial]
+    fn test_xdg_paths() {
+        std::env::set_var("XDG_CONFIG_HOME", "/xdg/config");
+        std::env::set_var("XDG_CACHE_HOME", "/xdg/cache");
+        std::env::set_var("XDG_STATE_HOME", "/xdg/state");
+
+        assert_eq!(
+            global_config_file().unwrap(),
+            PathBuf::from("/xdg/config/adrs/config.toml")
+        );
+        assert_eq!(cache_dir().unwrap(), PathBuf::from("/xdg/cache/adrs"));
+        assert_eq!(
+            journal_file().unwrap(),
+            PathBuf::from("/xdg/state/adrs/journal.log")
+        );
+        assert_eq!(
+            audit_log_file().unwrap(),
+            PathBuf::from("/xdg/state/adrs/audit.log")
+        );
+
+        std::env::remove_var("XDG_CONFIG_HOME");
+        std::env::remove_var("XDG_CACHE_HOME");
+        std::env::remove_var("XDG_STATE_HOME");
+    }
+}
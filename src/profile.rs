@@ -0,0 +1,62 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// the time spent in each phase and the number of times it was entered, in order of first use
+static PHASES: Mutex<Vec<(&'static str, Duration, usize)>> = Mutex::new(Vec::new());
+
+// turn on timing for the rest of the run
+pub(crate) fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+// times a phase of work until it is dropped
+pub(crate) struct Span {
+    name: &'static str,
+    start: Instant,
+}
+
+// start timing a phase, doing nothing unless profiling is enabled
+pub(crate) fn span(name: &'static str) -> Option<Span> {
+    ENABLED.load(Ordering::Relaxed).then(|| Span {
+        name,
+        start: Instant::now(),
+    })
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        let mut phases = PHASES.lock().unwrap();
+        match phases.iter_mut().find(|(name, _, _)| *name == self.name) {
+            Some((_, total, count)) => {
+                *total += elapsed;
+                *count += 1;
+            }
+            None => phases.push((self.name, elapsed, 1)),
+        }
+    }
+}
+
+// format the time spent in each phase along with the total time of the run
+pub(crate) fn report(total: Duration) -> String {
+    let phases = PHASES.lock().unwrap();
+    let mut report = String::from("profile:\n");
+    for (name, elapsed, count) in phases.iter() {
+        report += &format!(
+            "  {:<12}{:>10.3}ms  ({} call{})\n",
+            name,
+            elapsed.as_secs_f64() * 1000.0,
+            count,
+            if *count == 1 { "" } else { "s" }
+        );
+    }
+    report += &format!(
+        "  {:<12}{:>10.3}ms\n",
+        "total",
+        total.as_secs_f64() * 1000.0
+    );
+    report
+}
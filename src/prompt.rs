@@ -0,0 +1,64 @@
+use std::io::{BufRead, Write};
+
+use anyhow::Result;
+use clap::ValueEnum;
+
+// print the prompt on stderr and read a line from stdin, returning None for an empty answer or
+// when stdin is closed
+fn read_answer(prompt: &str) -> Result<Option<String>> {
+    eprint!("{}: ", prompt);
+    std::io::stderr().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok((!answer.is_empty()).then(|| answer.to_string()))
+}
+
+// ask a question, using the default for an empty answer
+pub(crate) fn prompt(question: &str, default: &str) -> Result<String> {
+    let answer = if default.is_empty() {
+        read_answer(question)?
+    } else {
+        read_answer(&format!("{} [{}]", question, default))?
+    };
+    Ok(answer.unwrap_or_else(|| default.to_string()))
+}
+
+// ask a yes or no question
+pub(crate) fn prompt_bool(question: &str, default: bool) -> Result<bool> {
+    let hint = if default { "Y/n" } else { "y/N" };
+    loop {
+        let Some(answer) = read_answer(&format!("{} [{}]", question, hint))? else {
+            return Ok(default);
+        };
+        match answer.to_lowercase().as_str() {
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => eprintln!("Please answer yes or no"),
+        }
+    }
+}
+
+// ask the user to pick one of the variants of a clap value enum
+pub(crate) fn prompt_choice<T: ValueEnum>(question: &str, default: T) -> Result<T> {
+    let names = T::value_variants()
+        .iter()
+        .filter_map(|v| v.to_possible_value())
+        .map(|v| v.get_name().to_string())
+        .collect::<Vec<_>>();
+    let default_name = default
+        .to_possible_value()
+        .map(|v| v.get_name().to_string())
+        .unwrap_or_default();
+    let question = format!("{} ({}) [{}]", question, names.join(", "), default_name);
+    loop {
+        let Some(answer) = read_answer(&question)? else {
+            return Ok(default);
+        };
+        match T::from_str(&answer, true) {
+            Ok(choice) => return Ok(choice),
+            Err(_) => eprintln!("Please choose one of: {}", names.join(", ")),
+        }
+    }
+}
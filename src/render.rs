@@ -0,0 +1,297 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+use pulldown_cmark::{html, Event, Options};
+
+use crate::config::RenderConfig;
+
+// Rendering of ADRs for `show` and the generated sites. The built-in renderer can be replaced
+// by commands in the [render] configuration, e.g. to use another theme or syntax highlighting.
+
+// turns the markdown of an ADR into what is shown to readers
+pub(crate) trait Renderer {
+    // the markdown styled for a terminal
+    fn terminal(&self, markdown: &str) -> Result<String>;
+    // the markdown as HTML, given as events so that callers can rewrite links first
+    fn html(&self, events: Vec<Event<'_>>) -> Result<String>;
+}
+
+// the markdown extensions the renderers understand
+pub(crate) fn options() -> Options {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options
+}
+
+// the renderer the configuration asks for
+pub(crate) fn renderer(config: &RenderConfig) -> Box<dyn Renderer> {
+    if config.terminal.is_none() && config.html.is_none() {
+        return Box::new(BuiltinRenderer);
+    }
+    Box::new(CommandRenderer {
+        terminal: config.terminal.clone(),
+        html: config.html.clone(),
+    })
+}
+
+// renders with pulldown-cmark, and for the terminal with ANSI styles when built with the ansi
+// feature. Without it, the markdown is shown as written
+pub(crate) struct BuiltinRenderer;
+
+impl Renderer for BuiltinRenderer {
+    #[cfg(feature = "ansi")]
+    fn terminal(&self, markdown: &str) -> Result<String> {
+        Ok(ansi::render(pulldown_cmark::Parser::new_ext(
+            markdown,
+            options(),
+        )))
+    }
+
+    #[cfg(not(feature = "ansi"))]
+    fn terminal(&self, markdown: &str) -> Result<String> {
+        Ok(markdown.to_string())
+    }
+
+    fn html(&self, events: Vec<Event<'_>>) -> Result<String> {
+        let mut out = String::new();
+        html::push_html(&mut out, events.into_iter());
+        Ok(out)
+    }
+}
+
+// renders by piping the markdown through the configured commands, falling back to the built-in
+// renderer for whatever isn't configured
+struct CommandRenderer {
+    terminal: Option<String>,
+    html: Option<String>,
+}
+
+// run the command in a shell with the input on stdin, returning its output
+fn pipe(command: &str, input: &str) -> Result<String> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    };
+    let mut child = shell
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Unable to run {}", command))?;
+    // stdin is closed once written, so that the command sees the end of the input
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .with_context(|| format!("Unable to write to {}", command))?;
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Unable to run {}", command))?;
+    if !output.status.success() {
+        anyhow::bail!("`{}` failed: {}", command, output.status);
+    }
+    String::from_utf8(output.stdout).with_context(|| format!("`{}` didn't write UTF-8", command))
+}
+
+impl Renderer for CommandRenderer {
+    fn terminal(&self, markdown: &str) -> Result<String> {
+        match &self.terminal {
+            Some(command) => pipe(command, markdown),
+            None => BuiltinRenderer.terminal(markdown),
+        }
+    }
+
+    fn html(&self, events: Vec<Event<'_>>) -> Result<String> {
+        let Some(command) = &self.html else {
+            return BuiltinRenderer.html(events);
+        };
+        let mut markdown = String::new();
+        pulldown_cmark_to_cmark::cmark(events.into_iter(), &mut markdown)?;
+        pipe(command, &markdown)
+    }
+}
+
+#[cfg(feature = "ansi")]
+mod ansi {
+    use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Tag};
+
+    static RESET: &str = "\x1b[0m";
+
+    // the escape sequence for the styles, innermost last
+    fn style(styles: &[&str]) -> String {
+        styles
+            .iter()
+            .map(|style| format!("\x1b[{}m", style))
+            .collect()
+    }
+
+    // the markdown with headings, emphasis, code, links, lists and quotes styled with ANSI
+    // escape sequences
+    pub(super) fn render<'a>(events: impl Iterator<Item = Event<'a>>) -> String {
+        let mut out = String::new();
+        let mut styles: Vec<&str> = Vec::new();
+        // the number of the next item of each open list, None for bullets
+        let mut lists: Vec<Option<u64>> = Vec::new();
+        let mut quote = 0;
+        let mut in_code_block = false;
+        let mut link = None;
+        let start_line = |out: &mut String, quote: usize| {
+            if out.ends_with('\n') || out.is_empty() {
+                out.push_str(&"│ ".repeat(quote));
+            }
+        };
+        for event in events {
+            match event {
+                Event::Start(Tag::Heading(level, _, _)) => {
+                    styles.push(if level == HeadingLevel::H1 {
+                        "1;4"
+                    } else {
+                        "1"
+                    });
+                    start_line(&mut out, quote);
+                    out += &style(&styles);
+                }
+                Event::Start(Tag::Emphasis) => {
+                    styles.push("3");
+                    out += &style(&styles);
+                }
+                Event::Start(Tag::Strong) => {
+                    styles.push("1");
+                    out += &style(&styles);
+                }
+                Event::Start(Tag::Strikethrough) => {
+                    styles.push("9");
+                    out += &style(&styles);
+                }
+                Event::Start(Tag::Link(_, dest, _)) => {
+                    styles.push("4");
+                    out += &style(&styles);
+                    link = Some(dest.to_string());
+                }
+                Event::End(Tag::Heading(..)) => {
+                    styles.pop();
+                    out += RESET;
+                    out += "\n\n";
+                }
+                Event::End(Tag::Emphasis | Tag::Strong | Tag::Strikethrough) => {
+                    styles.pop();
+                    out += RESET;
+                    out += &style(&styles);
+                }
+                Event::End(Tag::Link(..)) => {
+                    styles.pop();
+                    out += RESET;
+                    if let Some(dest) = link.take() {
+                        out += &format!(" \x1b[2m({}){}", dest, RESET);
+                    }
+                    out += &style(&styles);
+                }
+                Event::Start(Tag::Paragraph) => start_line(&mut out, quote),
+                Event::End(Tag::Paragraph) => {
+                    // paragraphs are separated by a blank line, except in tight lists
+                    out += if lists.is_empty() { "\n\n" } else { "\n" };
+                }
+                Event::Start(Tag::BlockQuote) => quote += 1,
+                Event::End(Tag::BlockQuote) => quote -= 1,
+                Event::Start(Tag::List(first)) => {
+                    if !out.ends_with('\n') && !out.is_empty() {
+                        out.push('\n');
+                    }
+                    lists.push(first);
+                }
+                Event::End(Tag::List(_)) => {
+                    lists.pop();
+                    if lists.is_empty() {
+                        out.push('\n');
+                    }
+                }
+                Event::Start(Tag::Item) => {
+                    start_line(&mut out, quote);
+                    out += &"  ".repeat(lists.len().saturating_sub(1));
+                    match lists.last_mut() {
+                        Some(Some(number)) => {
+                            out += &format!("{}. ", number);
+                            *number += 1;
+                        }
+                        _ => out += "• ",
+                    }
+                }
+                Event::End(Tag::Item) if !out.ends_with('\n') => out.push('\n'),
+                Event::Start(Tag::CodeBlock(kind)) => {
+                    in_code_block = true;
+                    if let CodeBlockKind::Fenced(language) = kind {
+                        if !language.is_empty() {
+                            out += &format!("    \x1b[2m{}{}\n", language, RESET);
+                        }
+                    }
+                }
+                Event::End(Tag::CodeBlock(_)) => {
+                    in_code_block = false;
+                    out.push('\n');
+                }
+                Event::Text(text) if in_code_block => {
+                    for line in text.lines() {
+                        out += &format!("    \x1b[36m{}{}\n", line, RESET);
+                    }
+                }
+                Event::Text(text) => out += &text,
+                Event::Code(code) => {
+                    out += &format!("\x1b[36m{}{}", code, RESET);
+                    out += &style(&styles);
+                }
+                Event::TaskListMarker(done) => out += if done { "[x] " } else { "[ ] " },
+                Event::SoftBreak | Event::HardBreak => {
+                    out.push('\n');
+                    start_line(&mut out, quote);
+                }
+                Event::Rule => out += "────────────────────────────────\n\n",
+                Event::End(Tag::TableCell) => out += " │ ",
+                Event::End(Tag::TableHead | Tag::TableRow) => {
+                    out.truncate(out.trim_end_matches(" │ ").len());
+                    out.push('\n');
+                }
+                Event::End(Tag::Table(_)) => out.push('\n'),
+                Event::Html(html) => out += &html,
+                _ => {}
+            }
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pulldown_cmark::Parser;
+
+    #[test]
+    fn test_builtin_html() {
+        let events =
+            Parser::new_ext("# 1. Use Postgres\n\n| a |\n|---|\n| b |\n", options()).collect();
+        assert_eq!(
+            BuiltinRenderer.html(events).unwrap(),
+            "<h1>1. Use Postgres</h1>\n<table><thead><tr><th>a</th></tr></thead><tbody>\n<tr><td>b</td></tr>\n</tbody></table>\n"
+        );
+    }
+
+    #[cfg(feature = "ansi")]
+    #[test]
+    fn test_ansi() {
+        assert_eq!(
+            BuiltinRenderer
+                .terminal("# 1. A\n\nUse **B**, see [C](c.md).\n\n* one\n* `two`\n\n> quoted\n")
+                .unwrap(),
+            "\x1b[1;4m1. A\x1b[0m\n\nUse \x1b[1mB\x1b[0m, see \x1b[4mC\x1b[0m \x1b[2m(c.md)\x1b[0m.\n\n• one\n• \x1b[36mtwo\x1b[0m\n\n│ quoted\n"
+        );
+    }
+}
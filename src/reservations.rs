@@ -0,0 +1,137 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::state::scoped_path;
+
+// where reserved ADR numbers are recorded, relative to the project root
+pub(crate) static RESERVATIONS_FILE: &str = ".adrs/reservations.toml";
+
+// a single reserved ADR number
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct Reservation {
+    pub(crate) number: i32,
+    pub(crate) owner: String,
+    pub(crate) date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) note: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct Reservations {
+    #[serde(default, rename = "reservation")]
+    pub(crate) reservations: Vec<Reservation>,
+}
+
+impl Reservations {
+    // load the reservations, which may not have been created yet
+    pub(crate) fn load() -> Result<Self> {
+        let Some(path) = Self::path().filter(|path| path.exists()) else {
+            return Ok(Self::default());
+        };
+        let contents = std::fs::read_to_string(&path)?;
+        toml::from_str(&contents)
+            .with_context(|| format!("Invalid reservations file: {}", path.display()))
+    }
+
+    // the contents of the reservations file
+    pub(crate) fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).context("Unable to serialize reservations")
+    }
+
+    pub(crate) fn path() -> Option<PathBuf> {
+        scoped_path(RESERVATIONS_FILE)
+    }
+
+    pub(crate) fn is_reserved(&self, number: i32) -> bool {
+        self.reservations.iter().any(|r| r.number == number)
+    }
+
+    pub(crate) fn highest(&self) -> Option<i32> {
+        self.reservations.iter().map(|r| r.number).max()
+    }
+
+    // reserve the next `count` numbers after both the existing ADRs and earlier reservations,
+    // failing rather than wrapping if they run past the largest ADR number
+    pub(crate) fn reserve(
+        &mut self,
+        after: i32,
+        count: i32,
+        owner: &str,
+        date: &str,
+        note: Option<&str>,
+    ) -> Result<Vec<i32>> {
+        let highest = after.max(self.highest().unwrap_or(0));
+        let (Some(start), Some(last)) = (highest.checked_add(1), highest.checked_add(count)) else {
+            anyhow::bail!(
+                "Unable to reserve {} ADR numbers after {}: the numbers would overflow",
+                count,
+                highest
+            );
+        };
+        let numbers = (start..=last).collect::<Vec<_>>();
+        for number in &numbers {
+            self.reservations.push(Reservation {
+                number: *number,
+                owner: owner.to_string(),
+                date: date.to_string(),
+                note: note.map(String::from),
+            });
+        }
+        Ok(numbers)
+    }
+
+    // release a reservation once its number has been used
+    pub(crate) fn release(&mut self, number: i32) -> Option<Reservation> {
+        let index = self.reservations.iter().position(|r| r.number == number)?;
+        Some(self.reservations.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve() {
+        let mut reservations = Reservations::default();
+        assert_eq!(
+            reservations
+                .reserve(3, 2, "josh", "2024-01-01", None)
+                .unwrap(),
+            vec![4, 5]
+        );
+        assert_eq!(
+            reservations
+                .reserve(3, 1, "sam", "2024-01-01", Some("auth"))
+                .unwrap(),
+            vec![6]
+        );
+        assert!(reservations.is_reserved(5));
+        assert!(!reservations.is_reserved(3));
+
+        assert_eq!(reservations.release(5).unwrap().owner, "josh");
+        assert!(!reservations.is_reserved(5));
+        assert!(reservations.release(5).is_none());
+
+        let toml = reservations.to_toml().unwrap();
+        let parsed: Reservations = toml::from_str(&toml).unwrap();
+        assert_eq!(parsed.reservations, reservations.reservations);
+    }
+
+    #[test]
+    fn test_reserve_overflow() {
+        let mut reservations = Reservations::default();
+        assert!(reservations
+            .reserve(i32::MAX - 1, 2, "josh", "2024-01-01", None)
+            .is_err());
+        assert!(reservations.reservations.is_empty());
+        assert_eq!(
+            reservations
+                .reserve(i32::MAX - 1, 1, "josh", "2024-01-01", None)
+                .unwrap(),
+            vec![i32::MAX]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::adr::to_forward_slashes;
+use crate::cmd::doctor::Severity;
+
+// the version of SARIF written, which GitHub code scanning accepts
+static SARIF_VERSION: &str = "2.1.0";
+static SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A SARIF log, for code scanning dashboards to annotate the ADRs with the problems found
+#[derive(Debug, Serialize)]
+pub(crate) struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifRun {
+    tool: SarifTool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifDriver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<SarifRule>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifRule {
+    id: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    level: &'static str,
+    message: SarifMessage,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<SarifLocation>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: SarifPhysicalLocation,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    region: SarifRegion,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifArtifactLocation {
+    uri: String,
+}
+
+// the problems are about whole files, so they are reported on the first line
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: usize,
+}
+
+// SARIF has no info level, its closest is note
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+impl SarifLog {
+    // a log of a single run over the findings, as (severity, rule, path, message)
+    pub(crate) fn new<'a>(
+        findings: impl IntoIterator<Item = (Severity, &'a str, Option<&'a Path>, &'a str)>,
+    ) -> Self {
+        let mut rules = BTreeSet::new();
+        let results = findings
+            .into_iter()
+            .map(|(severity, rule, path, message)| {
+                rules.insert(rule.to_string());
+                SarifResult {
+                    rule_id: rule.to_string(),
+                    level: level(severity),
+                    message: SarifMessage {
+                        text: message.to_string(),
+                    },
+                    locations: path
+                        .map(|path| SarifLocation {
+                            physical_location: SarifPhysicalLocation {
+                                artifact_location: SarifArtifactLocation {
+                                    uri: to_forward_slashes(path),
+                                },
+                                region: SarifRegion { start_line: 1 },
+                            },
+                        })
+                        .into_iter()
+                        .collect(),
+                }
+            })
+            .collect();
+        Self {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: env!("CARGO_PKG_NAME"),
+                        version: env!("CARGO_PKG_VERSION"),
+                        information_uri: env!("CARGO_PKG_HOMEPAGE"),
+                        rules: rules.into_iter().map(|id| SarifRule { id }).collect(),
+                    },
+                },
+                results,
+            }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sarif_log() {
+        let log = SarifLog::new([
+            (
+                Severity::Info,
+                "links",
+                Some(Path::new("doc/adr/0002-x.md")),
+                "not linked",
+            ),
+            (Severity::Error, "numbering", None, "duplicate number 2"),
+        ]);
+        let json = serde_json::to_value(&log).unwrap();
+        let run = &json["runs"][0];
+        assert_eq!(json["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "links");
+        assert_eq!(run["results"][0]["level"], "note");
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "doc/adr/0002-x.md"
+        );
+        assert_eq!(run["results"][1]["ruleId"], "numbering");
+        assert!(run["results"][1].get("locations").is_none());
+    }
+}
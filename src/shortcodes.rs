@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Result;
+use regex::Regex;
+
+use crate::adr::{get_number, get_title, list_adrs, list_archived_adrs, to_forward_slashes};
+
+// the title and path relative to the ADR directory of every ADR, archived or not, by number,
+// for expanding shortcodes
+#[derive(Debug, Default)]
+pub(crate) struct ShortcodeIndex {
+    adrs: BTreeMap<i32, (String, PathBuf)>,
+}
+
+impl ShortcodeIndex {
+    pub(crate) fn load(adr_dir: &Path) -> Result<Self> {
+        let mut paths = list_adrs(adr_dir)?;
+        paths.extend(list_archived_adrs(adr_dir)?);
+        let mut index = Self::default();
+        for path in paths {
+            if let Some(number) = get_number(&path) {
+                let relative = path.strip_prefix(adr_dir).unwrap_or(&path).to_path_buf();
+                index.insert(number, get_title(&path)?, relative);
+            }
+        }
+        Ok(index)
+    }
+
+    fn insert(&mut self, number: i32, title: String, path: PathBuf) {
+        self.adrs.insert(number, (title, path));
+    }
+
+    // expand the shortcodes in the markdown of the ADR at `from`, relative to the ADR directory,
+    // into links titled like "5. Use Postgres". Shortcodes to ADRs that don't exist are left as
+    // they are
+    pub(crate) fn expand(&self, markdown: &str, from: &Path) -> String {
+        // the link climbs out of any subdirectory the ADR is in, e.g. the archive
+        let depth = from
+            .parent()
+            .map(|parent| {
+                parent
+                    .components()
+                    .filter(|c| matches!(c, Component::Normal(_)))
+                    .count()
+            })
+            .unwrap_or(0);
+        // `{{adr 5}}`, also written `{{ adr 0005 }}` or `{{adr:5}}`
+        let shortcode = Regex::new(r"\{\{\s*adr(?:\s+|:\s*)(\d+)\s*\}\}").unwrap();
+        shortcode
+            .replace_all(markdown, |captures: &regex::Captures| {
+                let number = captures[1].parse::<i32>().ok();
+                match number.and_then(|number| self.adrs.get(&number)) {
+                    Some((title, path)) => format!(
+                        "[{}]({}{})",
+                        title,
+                        "../".repeat(depth),
+                        to_forward_slashes(path)
+                    ),
+                    None => captures[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let mut index = ShortcodeIndex::default();
+        index.insert(
+            5,
+            "5. Use Postgres".to_string(),
+            PathBuf::from("0005-use-postgres.md"),
+        );
+        index.insert(
+            2,
+            "2. Use MySQL".to_string(),
+            PathBuf::from("archive/0002-use-mysql.md"),
+        );
+        assert_eq!(
+            index.expand(
+                "Replaces {{adr 2}}, see {{ adr 0005 }} and {{adr:9}}.",
+                Path::new("0006-tune-postgres.md")
+            ),
+            "Replaces [2. Use MySQL](archive/0002-use-mysql.md), see [5. Use Postgres](0005-use-postgres.md) and {{adr:9}}."
+        );
+        assert_eq!(
+            index.expand("{{adr:5}}", Path::new("archive/0002-use-mysql.md")),
+            "[5. Use Postgres](../0005-use-postgres.md)"
+        );
+    }
+}
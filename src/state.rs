@@ -0,0 +1,84 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::adr::current_scope;
+use crate::config::CONFIG_FILE;
+
+// project state that isn't configuration, relative to the project root
+pub(crate) static STATE_FILE: &str = ".adrs/state.toml";
+// where each scope keeps its own state and reservations, as scopes number their ADRs
+// independently
+pub(crate) static SCOPES_DIR: &str = ".adrs/scopes";
+
+// the files and directories found at the root of a project
+static PROJECT_MARKERS: &[&str] = &[".adr-dir", CONFIG_FILE, ".adrs", ".git"];
+
+// the root of the project that owns the directory given with --dir, if it is in one
+static DIR_PROJECT: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+// keep the state with the project that owns the directory given with --dir rather than in the
+// current directory
+pub(crate) fn set_dir_project(dir: &Path) {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let root = dir
+        .ancestors()
+        .find(|ancestor| {
+            PROJECT_MARKERS
+                .iter()
+                .any(|marker| ancestor.join(marker).exists())
+        })
+        .map(Path::to_path_buf);
+    let _ = DIR_PROJECT.set(root);
+}
+
+// the state file of the current scope, or the project's outside of scopes. A directory given
+// with --dir that isn't in a project has no state
+pub(crate) fn scoped_path(file: &str) -> Option<PathBuf> {
+    let root = match DIR_PROJECT.get() {
+        Some(root) => root.clone()?,
+        None => PathBuf::new(),
+    };
+    Some(match current_scope() {
+        Some(scope) => root
+            .join(SCOPES_DIR)
+            .join(scope)
+            .join(Path::new(file).file_name().unwrap()),
+        None => root.join(file),
+    })
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct State {
+    // the highest ADR number ever allocated, so that deleting the newest ADR doesn't recycle its
+    // number
+    pub(crate) high_water_mark: i32,
+}
+
+impl State {
+    // load the state, which may not have been created yet
+    pub(crate) fn load() -> Result<Self> {
+        let Some(path) = Self::path().filter(|path| path.exists()) else {
+            return Ok(Self::default());
+        };
+        let contents = std::fs::read_to_string(&path)?;
+        toml::from_str(&contents).with_context(|| format!("Invalid state file: {}", path.display()))
+    }
+
+    // the contents of the state file
+    pub(crate) fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).context("Unable to serialize state")
+    }
+
+    pub(crate) fn path() -> Option<PathBuf> {
+        scoped_path(STATE_FILE)
+    }
+
+    // record that a number has been allocated
+    pub(crate) fn allocate(&mut self, number: i32) {
+        self.high_water_mark = self.high_water_mark.max(number);
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::adr::parse_date;
+use crate::model::Adr;
+
+// the status reported for ADRs that don't have one
+static NO_STATUS: &str = "None";
+// the confirmation reported for ADRs that say how they're confirmed but never have been
+static UNCONFIRMED: &str = "unconfirmed";
+
+// summary of how many days ADRs spent in a status
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Durations {
+    pub(crate) count: usize,
+    pub(crate) min: i64,
+    pub(crate) median: i64,
+    pub(crate) p90: i64,
+    pub(crate) max: i64,
+}
+
+// the nearest-rank percentile of sorted values
+fn percentile(sorted: &[i64], percent: usize) -> i64 {
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+impl Durations {
+    fn from_days(mut days: Vec<i64>) -> Option<Self> {
+        days.sort_unstable();
+        Some(Self {
+            count: days.len(),
+            min: *days.first()?,
+            median: percentile(&days, 50),
+            p90: percentile(&days, 90),
+            max: *days.last()?,
+        })
+    }
+}
+
+// aggregates over a set of ADRs, computed in one place so that every report agrees
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Stats {
+    pub(crate) total: usize,
+    pub(crate) by_status: BTreeMap<String, usize>,
+    pub(crate) by_tag: BTreeMap<String, usize>,
+    // by the YYYY-MM of the ADR's date
+    pub(crate) by_month: BTreeMap<String, usize>,
+    // by month and current status, including the months without any ADRs
+    pub(crate) activity: BTreeMap<String, BTreeMap<String, usize>>,
+    // how many ADRs are linked to each number of other ADRs, in either direction
+    pub(crate) link_degrees: BTreeMap<usize, usize>,
+    // how long ADRs spent in each status before moving on
+    pub(crate) time_in_status: BTreeMap<String, Durations>,
+    // by the outcome of the last confirmation, for the ADRs in force that say how they're
+    // confirmed
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) by_confirmation: BTreeMap<String, usize>,
+}
+
+// the YYYY-MM of a date, if it is valid
+fn month(date: &str) -> Option<String> {
+    let date = parse_date(date).ok()?;
+    Some(format!("{:04}-{:02}", date.year(), u8::from(date.month())))
+}
+
+// every YYYY-MM from the first month to the last
+fn months_between(first: &str, last: &str) -> Vec<String> {
+    let parse = |month: &str| -> Option<(i32, u32)> {
+        let (year, month) = month.split_once('-')?;
+        Some((year.parse().ok()?, month.parse().ok()?))
+    };
+    let (Some((mut year, mut month)), Some(last)) = (parse(first), parse(last)) else {
+        return Vec::new();
+    };
+    let mut months = Vec::new();
+    while (year, month) <= last {
+        months.push(format!("{:04}-{:02}", year, month));
+        (year, month) = if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        };
+    }
+    months
+}
+
+impl Stats {
+    // the statistics for the ADRs, with the days each spent in the statuses it has left
+    pub(crate) fn from_adrs(
+        adrs: &[Adr],
+        time_in_status: impl Fn(&Adr) -> BTreeMap<String, i64>,
+    ) -> Self {
+        let mut stats = Stats {
+            total: adrs.len(),
+            ..Default::default()
+        };
+
+        let mut neighbours: BTreeMap<i32, BTreeSet<i32>> = adrs
+            .iter()
+            .map(|adr| (adr.number, BTreeSet::new()))
+            .collect();
+        let mut days: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+        for adr in adrs {
+            let status = adr
+                .current_status()
+                .unwrap_or_else(|| NO_STATUS.to_string());
+            for tag in &adr.tags {
+                *stats.by_tag.entry(tag.clone()).or_default() += 1;
+            }
+            if let Some(month) = adr.date.as_deref().and_then(month) {
+                *stats.by_month.entry(month.clone()).or_default() += 1;
+                let statuses = stats.activity.entry(month).or_default();
+                *statuses.entry(status.clone()).or_default() += 1;
+            }
+            *stats.by_status.entry(status).or_default() += 1;
+            if adr.is_unconfirmed() {
+                *stats
+                    .by_confirmation
+                    .entry(UNCONFIRMED.to_string())
+                    .or_default() += 1;
+            } else if let Some(result) = adr.confirmation_result {
+                *stats
+                    .by_confirmation
+                    .entry(result.as_str().to_string())
+                    .or_default() += 1;
+            }
+
+            for link in adr.links.iter().filter(|link| link.target != adr.number) {
+                neighbours
+                    .entry(adr.number)
+                    .or_default()
+                    .insert(link.target);
+                neighbours
+                    .entry(link.target)
+                    .or_default()
+                    .insert(adr.number);
+            }
+            for (status, spent) in time_in_status(adr) {
+                days.entry(status).or_default().push(spent);
+            }
+        }
+
+        // links to ADRs that aren't in the set don't get an entry of their own
+        for (number, linked) in &neighbours {
+            if adrs.iter().any(|adr| adr.number == *number) {
+                *stats.link_degrees.entry(linked.len()).or_default() += 1;
+            }
+        }
+        if let (Some(first), Some(last)) = (
+            stats.by_month.keys().next(),
+            stats.by_month.keys().next_back(),
+        ) {
+            for month in months_between(first, last) {
+                stats.activity.entry(month).or_default();
+            }
+        }
+        for (status, days) in days {
+            if let Some(durations) = Durations::from_days(days) {
+                stats.time_in_status.insert(status, durations);
+            }
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::history::time_in_status;
+
+    fn adr(path: &str, markdown: &str) -> Adr {
+        Adr::parse(Path::new(path), markdown).unwrap()
+    }
+
+    #[test]
+    fn test_stats() {
+        let adrs = [
+            adr("0001-use-mysql.md", "---\ntags: [database]\nproposed_date: 2024-01-01\ndecided_date: 2024-01-11\n---\n\n# 1. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nAmended by [2. Tune MySQL](0002-tune-mysql.md)\n"),
+            adr("0002-tune-mysql.md", "---\ntags: [database, performance]\nproposed_date: 2024-02-01\ndecided_date: 2024-02-03\nimplemented_date: 2024-03-03\n---\n\n# 2. Tune MySQL\n\nDate: 2024-02-01\n\n## Status\n\nImplemented\n\nAmends [1. Use MySQL](0001-use-mysql.md)\n"),
+            adr("0003-write-docs.md", "# 3. Write docs\n\nDate: 2024-02-20\n\n## Status\n\nProposed\n"),
+        ];
+        let stats = Stats::from_adrs(&adrs, |adr| time_in_status(Path::new("."), adr));
+        assert_eq!(stats.total, 3);
+        assert_eq!(
+            stats.by_status,
+            BTreeMap::from([
+                ("Accepted".to_string(), 1),
+                ("Implemented".to_string(), 1),
+                ("Proposed".to_string(), 1)
+            ])
+        );
+        assert_eq!(stats.by_tag["database"], 2);
+        assert_eq!(stats.by_month["2024-02"], 2);
+        assert_eq!(
+            stats.activity["2024-02"],
+            BTreeMap::from([("Implemented".to_string(), 1), ("Proposed".to_string(), 1)])
+        );
+        assert_eq!(stats.link_degrees, BTreeMap::from([(0, 1), (1, 2)]));
+        assert_eq!(
+            stats.time_in_status["Proposed"],
+            Durations {
+                count: 2,
+                min: 2,
+                median: 2,
+                p90: 10,
+                max: 10
+            }
+        );
+        assert_eq!(stats.time_in_status["Accepted"].max, 29);
+    }
+
+    #[test]
+    fn test_months_between() {
+        assert_eq!(
+            months_between("2023-11", "2024-02"),
+            vec!["2023-11", "2023-12", "2024-01", "2024-02"]
+        );
+        assert_eq!(months_between("2024-02", "2024-02"), vec!["2024-02"]);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let days = (1..=10).collect::<Vec<_>>();
+        assert_eq!(percentile(&days, 50), 5);
+        assert_eq!(percentile(&days, 90), 9);
+        assert_eq!(percentile(&[7], 90), 7);
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::adr::{is_read_only, is_writable};
+use crate::profile;
+
+// a set of file writes that are staged in memory and committed together, so that an operation
+// touching several ADRs either updates all of them or none of them
+#[derive(Debug, Default)]
+pub(crate) struct Transaction {
+    writes: BTreeMap<PathBuf, String>,
+}
+
+// the temporary file a staged write goes to before being renamed into place
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap().to_str().unwrap();
+    path.with_file_name(format!(".{}.adrs-tmp", name))
+}
+
+impl Transaction {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    // read a file as it will be once the transaction commits
+    pub(crate) fn read(&self, path: &Path) -> Result<String> {
+        match self.writes.get(path) {
+            Some(contents) => Ok(contents.clone()),
+            None => std::fs::read_to_string(path)
+                .with_context(|| format!("Unable to read {}", path.display())),
+        }
+    }
+
+    // stage a write, replacing any earlier write to the same path
+    pub(crate) fn write(&mut self, path: impl AsRef<Path>, contents: impl Into<String>) {
+        self.writes
+            .insert(path.as_ref().to_path_buf(), contents.into());
+    }
+
+    // stage an update of a file's contents
+    pub(crate) fn update(
+        &mut self,
+        path: &Path,
+        f: impl FnOnce(&str) -> Result<String>,
+    ) -> Result<()> {
+        let contents = f(&self.read(path)?)?;
+        self.write(path, contents);
+        Ok(())
+    }
+
+    // write every staged file to a temporary file, then rename them all into place. Directories
+    // that don't exist yet are created. If anything fails the files that were already replaced are
+    // restored, and the temporary files and created directories removed.
+    pub(crate) fn commit(self) -> Result<()> {
+        let _span = profile::span("writing");
+        // commands that only write for some arguments are stopped here, before anything is written
+        if let Some(path) = self.writes.keys().next() {
+            if is_read_only() {
+                anyhow::bail!(
+                    "Unable to write {}: adrs is running with --read-only",
+                    path.display()
+                );
+            }
+        }
+        for dir in self.writes.keys().filter_map(|path| path.parent()) {
+            if !is_writable(dir) {
+                anyhow::bail!("Unable to write to {}: it is read-only", dir.display());
+            }
+        }
+        let mut created = Vec::new();
+        let result = self
+            .create_dirs(&mut created)
+            .and_then(|_| self.write_all());
+        if result.is_err() {
+            for dir in created.iter().rev() {
+                let _ = std::fs::remove_dir(dir);
+            }
+        }
+        result
+    }
+
+    // create the missing directories the files are written to, outermost first
+    fn create_dirs(&self, created: &mut Vec<PathBuf>) -> Result<()> {
+        for dir in self.writes.keys().filter_map(|path| path.parent()) {
+            let mut missing = dir
+                .ancestors()
+                .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+                .collect::<Vec<_>>();
+            missing.reverse();
+            for dir in missing {
+                std::fs::create_dir(dir)
+                    .with_context(|| format!("Unable to create {}", dir.display()))?;
+                created.push(dir.to_path_buf());
+            }
+        }
+        Ok(())
+    }
+
+    fn write_all(&self) -> Result<()> {
+        let mut staged = Vec::new();
+        for (path, contents) in &self.writes {
+            let temp = temp_path(path);
+            if let Err(e) = std::fs::write(&temp, contents) {
+                staged.push(temp);
+                for temp in &staged {
+                    let _ = std::fs::remove_file(temp);
+                }
+                return Err(e).with_context(|| format!("Unable to write {}", path.display()));
+            }
+            staged.push(temp);
+        }
+
+        let originals = self
+            .writes
+            .keys()
+            .map(|path| (path, std::fs::read_to_string(path).ok()))
+            .collect::<Vec<_>>();
+
+        for (i, (path, temp)) in self.writes.keys().zip(&staged).enumerate() {
+            if let Err(e) = std::fs::rename(temp, path) {
+                for (path, original) in &originals[..i] {
+                    let _ = match original {
+                        Some(contents) => std::fs::write(path, contents),
+                        None => std::fs::remove_file(path),
+                    };
+                }
+                for temp in &staged[i..] {
+                    let _ = std::fs::remove_file(temp);
+                }
+                return Err(e).with_context(|| format!("Unable to write {}", path.display()));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use predicates::prelude::*;
+
+    #[test]
+    #[serial_test::serial]
+    fn test_commit() {
+        let temp = TempDir::new().unwrap();
+        std::env::set_current_dir(temp.path()).unwrap();
+
+        temp.child("doc/adr/0001-first.md")
+            .write_str("first")
+            .unwrap();
+
+        let mut tx = Transaction::new();
+        tx.write("doc/adr/0002-second.md", "second");
+        tx.update(Path::new("doc/adr/0001-first.md"), |s| {
+            Ok(format!("{} updated", s))
+        })
+        .unwrap();
+        assert_eq!(
+            tx.read(Path::new("doc/adr/0002-second.md")).unwrap(),
+            "second"
+        );
+        tx.commit().unwrap();
+
+        temp.child("doc/adr/0001-first.md").assert("first updated");
+        temp.child("doc/adr/0002-second.md").assert("second");
+        temp.child("doc/adr/.0002-second.md.adrs-tmp")
+            .assert(predicate::path::missing());
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_commit_failure() {
+        let temp = TempDir::new().unwrap();
+        std::env::set_current_dir(temp.path()).unwrap();
+
+        temp.child("doc/adr/0001-first.md")
+            .write_str("first")
+            .unwrap();
+        // a file can't be renamed over a directory
+        temp.child("doc/adr/0003-third.md")
+            .create_dir_all()
+            .unwrap();
+
+        let mut tx = Transaction::new();
+        tx.write("doc/adr/0001-first.md", "changed");
+        tx.write("doc/missing/0002-second.md", "second");
+        tx.write("doc/adr/0003-third.md", "third");
+        assert!(tx.commit().is_err());
+
+        temp.child("doc/adr/0001-first.md").assert("first");
+        temp.child("doc/adr/.0001-first.md.adrs-tmp")
+            .assert(predicate::path::missing());
+        temp.child("doc/missing").assert(predicate::path::missing());
+    }
+}
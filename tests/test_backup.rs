@@ -0,0 +1,112 @@
+use assert_cmd::Command;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_backup_and_restore() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Use Postgres"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["backup", "--output", "backups"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Backed up 2 ADR(s) to backups/adrs-backup-",
+        ));
+    let backup = std::fs::read_dir(temp.path().join("backups"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    assert!(backup.to_string_lossy().ends_with(".tar.gz"));
+
+    let adr = std::fs::read_to_string(temp.path().join("doc/adr/0002-use-postgres.md")).unwrap();
+    std::fs::remove_dir_all(temp.path().join("doc")).unwrap();
+    std::fs::remove_file(temp.path().join(".adr-dir")).unwrap();
+    std::fs::remove_dir_all(temp.path().join(".adrs")).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("restore-backup")
+        .arg(&backup)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored"));
+    assert_eq!(
+        std::fs::read_to_string(temp.path().join("doc/adr/0002-use-postgres.md")).unwrap(),
+        adr
+    );
+    assert!(temp.path().join(".adr-dir").exists());
+    // the export is for other tools, it isn't restored
+    assert!(!temp.path().join("adrs.json").exists());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("restore-backup")
+        .arg(&backup)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Use the --overwrite flag"));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["restore-backup", "--overwrite"])
+        .arg(&backup)
+        .assert()
+        .success();
+}
+
+#[test]
+#[serial_test::serial]
+fn test_backup_deterministic() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    let mut backups = Vec::new();
+    for output in ["first", "second"] {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .env_remove("SOURCE_DATE_EPOCH")
+            .args(["--deterministic", "backup", "--output", output])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "to {}/adrs-backup.tar.gz",
+                output
+            )));
+        backups.push(std::fs::read(temp.path().join(output).join("adrs-backup.tar.gz")).unwrap());
+    }
+    assert_eq!(backups[0], backups[1]);
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .env("SOURCE_DATE_EPOCH", "1700000000")
+        .args(["backup", "--output", "pinned"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "to pinned/adrs-backup-20231114-221320.tar.gz",
+        ));
+}
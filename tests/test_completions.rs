@@ -0,0 +1,49 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_completions() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("completions")
+        .arg("bash")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("_adrs()"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("completions")
+        .arg("nushell")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("export extern \"adrs new\""));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_completions_aliases() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    temp.child(".adrs.toml")
+        .write_str("[alias]\nsupersede = \"new --superseded\"\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("completions")
+        .arg("nushell")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("export extern \"adrs supersede\"")
+                .and(predicate::str::contains("Alias for `new --superseded`")),
+        );
+}
@@ -1,4 +1,5 @@
 use assert_cmd::Command;
+use assert_fs::prelude::*;
 use assert_fs::TempDir;
 use predicates::prelude::*;
 
@@ -17,3 +18,98 @@ fn test_config() {
                 .and(predicate::str::contains("adrs_template_dir=embedded")),
         );
 }
+
+#[test]
+#[serial_test::serial]
+fn test_config_alias() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child(".adrs.toml")
+        .write_str("[alias]\nls = \"list\"\nsupersede = \"new --superseded {}\"\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("supersede")
+        .arg("1")
+        .arg("Replace the first decision")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("ls")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("0001-record-architecture-decisions.md").and(
+                predicate::str::contains("0002-replace-the-first-decision.md"),
+            ),
+        );
+
+    // global options may come before the alias
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--profile", "--dir", "doc/adr", "ls"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "0002-replace-the-first-decision.md",
+        ));
+
+    // a broken configuration doesn't stop the commands that don't need it
+    temp.child(".adrs.toml").write_str("mode = \n").unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("--help")
+        .assert()
+        .success()
+        .stderr(predicate::str::starts_with(
+            "Warning: aliases are unavailable: Invalid configuration",
+        ));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["completions", "bash"])
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("ls")
+        .assert()
+        .failure();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+#[serial_test::serial]
+fn test_config_paths() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("config")
+        .arg("--paths")
+        .env("XDG_CONFIG_HOME", temp.path().join("config"))
+        .env("XDG_STATE_HOME", temp.path().join("state"))
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains(format!(
+                "adrs_global_config={}",
+                temp.path().join("config/adrs/config.toml").display()
+            ))
+            .and(predicate::str::contains(format!(
+                "adrs_journal={}",
+                temp.path().join("state/adrs/journal.log").display()
+            ))),
+        );
+}
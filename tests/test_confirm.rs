@@ -0,0 +1,152 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_confirm() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child(".adrs.toml")
+        .write_str("mode = \"nextgen\"\n")
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-postgres.md")
+        .write_str("---\nid: 2\n---\n\n# 2. Use Postgres\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nAmends [1. Record architecture decisions](0001-record-architecture-decisions.md)\n\n## Confirmation\n\nThe migration check passes in CI.\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .stdout(predicate::str::contains(
+            "has a Confirmation section but has never been confirmed",
+        ));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "By confirmation:\n  unconfirmed: 1\n",
+        ));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "confirm",
+            "2",
+            "--result",
+            "fail",
+            "--note",
+            "job \"migrate\" failed",
+        ])
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-use-postgres.md: confirmation=fail\n");
+    temp.child("doc/adr/0002-use-postgres.md").assert(
+        predicate::str::contains("confirmation_result: fail\nconfirmed_date: ").and(
+            predicate::str::contains("confirmation_note: \"job 'migrate' failed\"\n"),
+        ),
+    );
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .stdout(predicate::str::contains("the last confirmation failed on "));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["confirm", "2", "--result", "pass"])
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-postgres.md").assert(
+        predicate::str::contains("confirmation_result: pass\n")
+            .and(predicate::str::contains("confirmation_note").not()),
+    );
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"confirmation_result\": \"pass\"",
+        ));
+
+    // ADRs without frontmatter have nowhere to keep the result
+    temp.child("doc/adr/0003-use-redis.md")
+        .write_str("# 3. Use Redis\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n")
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["confirm", "3", "--result", "pass"])
+        .assert()
+        .failure();
+}
+
+#[test]
+#[serial_test::serial]
+#[cfg(unix)]
+fn test_confirm_run() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child(".adrs.toml")
+        .write_str("mode = \"nextgen\"\n")
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-postgres.md")
+        .write_str("---\nid: 2\n---\n\n# 2. Use Postgres\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\n## Confirmation\n\n```sh\ntest -f migrations.sql\n```\n")
+        .unwrap();
+    temp.child("doc/adr/0003-use-redis.md")
+        .write_str("---\nid: 3\nconfirmation_command: \"true\"\n---\n\n# 3. Use Redis\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n")
+        .unwrap();
+    // rejected decisions aren't checked
+    temp.child("doc/adr/0004-use-mongodb.md")
+        .write_str("---\nid: 4\nconfirmation_command: \"false\"\n---\n\n# 4. Use MongoDB\n\nDate: 2024-01-01\n\n## Status\n\nRejected\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["confirm", "--all", "--run"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "doc/adr/0002-use-postgres.md: confirmation=fail (`test -f migrations.sql` exit status: 1)\n",
+        ))
+        .stdout(predicate::str::contains(
+            "doc/adr/0003-use-redis.md: confirmation=pass",
+        ))
+        .stdout(predicate::str::contains("0004").not())
+        .stderr(predicate::str::contains("1 confirmation(s) failed"));
+    temp.child("doc/adr/0002-use-postgres.md")
+        .assert(predicate::str::contains("confirmation_result: fail\n"));
+
+    temp.child("migrations.sql").write_str("").unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["confirm", "2", "--run"])
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-postgres.md")
+        .assert(predicate::str::contains("confirmation_result: pass\n"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["confirm", "1", "--run"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("doesn't declare a check command"));
+}
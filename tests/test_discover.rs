@@ -0,0 +1,68 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_discover() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Use Postgres"])
+        .assert()
+        .success();
+    for file in [
+        "services/payments/doc/adr/0001-use-stripe.md",
+        "services/payments/doc/adr/0002-retry-webhooks.md",
+        "services/payments/doc/adr/archive/0003-use-paypal.md",
+        "services/payments/doc/adr/archive/0004-batch-payouts.md",
+        "services/search/docs/decisions/001-use-elasticsearch.md",
+        "services/search/docs/decisions/003-shard-by-tenant.md",
+        "services/search/node_modules/pkg/docs/0001-vendored.md",
+        "services/search/node_modules/pkg/docs/0002-vendored.md",
+        "notes/0001-only-one.md",
+    ] {
+        temp.child(file).write_str("# A decision\n").unwrap();
+    }
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("discover")
+        .assert()
+        .success()
+        .stdout("doc/adr: 2 ADR(s), 0001 to 0002, the ADR directory\nservices/payments/doc/adr: 2 ADR(s), 0001 to 0002, not registered, as scope payments\nservices/search/docs/decisions: 2 ADR(s), 0001 to 0003, not registered, as scope search\nRun again with --register to add them to [scopes] in .adrs.toml\n");
+    temp.child(".adrs.toml").assert(predicate::path::missing());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["discover", "--register"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("Registered scope payments for services/payments/doc/adr\nRegistered scope search for services/search/docs/decisions\n"));
+    temp.child(".adrs.toml").assert(predicate::str::contains(
+        "[scopes]\npayments = \"services/payments/doc/adr\"\nsearch = \"services/search/docs/decisions\"\n",
+    ));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--scope", "payments", "list"])
+        .assert()
+        .success()
+        .stdout("services/payments/doc/adr/0001-use-stripe.md\nservices/payments/doc/adr/0002-retry-webhooks.md\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["discover", "--min", "1"])
+        .assert()
+        .success()
+        .stdout("doc/adr: 2 ADR(s), 0001 to 0002, the ADR directory\nnotes: 1 ADR(s), 0001 to 0001, not registered, as scope notes\nservices/payments/doc/adr: 2 ADR(s), 0001 to 0002, scope payments\nservices/search/docs/decisions: 2 ADR(s), 0001 to 0003, scope search\nRun again with --register to add them to [scopes] in .adrs.toml\n");
+}
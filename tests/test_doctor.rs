@@ -0,0 +1,310 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_doctor_dates() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout("No problems found\n");
+
+    temp.child("doc/adr/0002-undated.md")
+        .write_str("# 2. Undated\n\n## Status\n\nAccepted\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout("warning[date]: doc/adr/0002-undated.md: missing date\n0 error(s), 1 warning(s)\n");
+
+    let output = Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["doctor", "--format", "sarif"])
+        .output()
+        .unwrap();
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let result = &sarif["runs"][0]["results"][0];
+    assert_eq!(result["ruleId"], "date");
+    assert_eq!(result["level"], "warning");
+    assert_eq!(result["message"]["text"], "missing date");
+    assert_eq!(
+        result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "doc/adr/0002-undated.md"
+    );
+
+    temp.child("doc/adr/0003-misdated.md")
+        .write_str("# 3. Misdated\n\nDate: 31/12/2024\n\n## Status\n\nAccepted\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "error[date]: doc/adr/0003-misdated.md: invalid date '31/12/2024', expected YYYY-MM-DD",
+        ));
+
+    // only the configured rules are checked
+    temp.child(".adrs.toml")
+        .write_str("[lint]\nrules = [\"title\"]\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout("No problems found\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_doctor_connectivity() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    for (title, link) in [
+        ("Second", "1:Amends"),
+        ("Third", "2:Extends"),
+        ("Fourth", ""),
+        ("Fifth", ""),
+        ("Sixth", "5:Depends on"),
+    ] {
+        let mut command = Command::cargo_bin("adrs").unwrap();
+        command.arg("new");
+        if !link.is_empty() {
+            command.arg("--link").arg(link);
+        }
+        command.arg(title).assert().success();
+    }
+
+    // the rules are opt-in
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout("No problems found\n");
+
+    temp.child(".adrs.toml")
+        .write_str("[lint]\nrules = [\"islands\", \"clusters\"]\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout(
+            "info[islands]: doc/adr/0004-fourth.md: not linked to or from any other ADR\ninfo[clusters]: doc/adr/0005-fifth.md: ADRs 5, 6 are linked to each other but not to the other ADRs\n0 error(s), 0 warning(s), 2 info\n",
+        );
+
+    // the severity is opt-in too
+    temp.child(".adrs.toml")
+        .write_str("[lint]\nrules = [\"islands\"]\n\n[lint.severity]\nislands = \"error\"\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .failure()
+        .stdout(predicate::str::starts_with(
+            "error[islands]: doc/adr/0004-fourth.md: not linked to or from any other ADR\n",
+        ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_doctor_adr_tools() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--link", "1:Amends", "Second"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["doctor", "--adr-tools"])
+        .assert()
+        .success()
+        .stdout("No problems found\n");
+
+    temp.child("doc/adr/0003-third.md")
+        .write_str("---\ntags: []\n---\n\n# Third\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nAmends [Second](archive/0002-second.md)\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["doctor", "--adr-tools"])
+        .assert()
+        .failure()
+        .stdout(
+            predicate::str::contains("error[adr-tools]: doc/adr/0003-third.md: frontmatter in compatible mode")
+                .and(predicate::str::contains(
+                    "warning[adr-tools]: doc/adr/0003-third.md: the title line doesn't start with '# 3. '",
+                ))
+                .and(predicate::str::contains(
+                    "'Amends [Second](archive/0002-second.md)' isn't a link adr-tools can follow",
+                )),
+        );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_doctor_manifest() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    for repo in ["payments", "search"] {
+        temp.child(repo).create_dir_all().unwrap();
+        std::env::set_current_dir(temp.child(repo).path()).unwrap();
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .arg("init")
+            .assert()
+            .success();
+    }
+    temp.child("search/doc/adr/0002-untitled.md")
+        .write_str("Date: 2024-01-01\n\n## Status\n\nAccepted\n")
+        .unwrap();
+    temp.child("empty").create_dir_all().unwrap();
+    temp.child("repos.toml")
+        .write_str("[[repos]]\npath = \"payments\"\n\n[[repos]]\npath = \"search\"\nname = \"Search\"\n\n[[repos]]\npath = \"empty\"\n")
+        .unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["doctor", "--manifest", "repos.toml"])
+        .assert()
+        .failure()
+        .stdout(
+            predicate::str::contains("== payments (payments) ==\nNo problems found\n")
+                .and(predicate::str::contains(
+                    "== Search (search) ==\nerror[title]: doc/adr/0002-untitled.md: missing title\n1 error(s), 0 warning(s)\n",
+                ))
+                .and(predicate::str::contains(
+                    "== empty (empty) ==\nUnable to check: no ADR directory found\n",
+                ))
+                .and(predicate::str::ends_with(
+                    "3 repositories: 1 error(s), 0 warning(s), 1 unable to check\n",
+                )),
+        );
+    temp.child("empty/doc").assert(predicate::path::missing());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["doctor", "--manifest", "repos.toml", "--format", "json"])
+        .assert()
+        .failure()
+        .stdout(
+            predicate::str::contains("\"name\": \"Search\"")
+                .and(predicate::str::contains("\"rule\": \"title\""))
+                .and(predicate::str::contains("\"failures\": 1")),
+        );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_doctor_status_links() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-mysql.md")
+        .write_str("# 2. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nSuperseded by [3. Use Postgres](0003-use-postgres.md)\n\n## Context\n\nWhy.\n")
+        .unwrap();
+    temp.child("doc/adr/0003-use-postgres.md")
+        .write_str("# 3. Use Postgres\n\nDate: 2024-01-02\n\n## Status\n\nAccepted\n\nSupersedes [2. Use MySQL](0002-use-mysql.md)\n")
+        .unwrap();
+    temp.child("doc/adr/0004-use-redis.md")
+        .write_str("# 4. Use Redis\n\nDate: 2024-01-03\n\n## Status\n\nSuperseded\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "warning[status-links]: doc/adr/0002-use-mysql.md: status is Accepted but a 'Superseded by' link says it has been superseded\n",
+            "warning[status-links]: doc/adr/0004-use-redis.md: status is Superseded but no 'Superseded by' link says which ADR supersedes it\n",
+            "0 error(s), 2 warning(s)\n",
+        ));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["doctor", "--fix"])
+        .assert()
+        .success()
+        .stdout(concat!(
+            "Fixed doc/adr/0002-use-mysql.md: removed status Accepted, as it has been superseded\n",
+            "Unable to fix doc/adr/0004-use-redis.md: no earlier status in its history\n",
+            "warning[status-links]: doc/adr/0004-use-redis.md: status is Superseded but no 'Superseded by' link says which ADR supersedes it\n",
+            "0 error(s), 1 warning(s)\n",
+        ));
+    assert_eq!(
+        std::fs::read_to_string(temp.path().join("doc/adr/0002-use-mysql.md")).unwrap(),
+        "# 2. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nSuperseded by [3. Use Postgres](0003-use-postgres.md)\n\n## Context\n\nWhy.\n"
+    );
+
+    // trusting the status removes the link instead
+    temp.child("doc/adr/0002-use-mysql.md")
+        .write_str("# 2. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nSuperseded by [3. Use Postgres](0003-use-postgres.md)\n\n## Context\n\nWhy.\n")
+        .unwrap();
+    temp.child(".adrs.toml")
+        .write_str("[lint]\nreconcile = \"status\"\n")
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["doctor", "--fix"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "Fixed doc/adr/0002-use-mysql.md: removed the 'Superseded by' link, as its status is Accepted\n",
+        ));
+    assert_eq!(
+        std::fs::read_to_string(temp.path().join("doc/adr/0002-use-mysql.md")).unwrap(),
+        "# 2. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\n## Context\n\nWhy.\n"
+    );
+}
@@ -0,0 +1,66 @@
+use assert_cmd::Command;
+use assert_fs::TempDir;
+
+#[test]
+#[serial_test::serial]
+fn test_drivers() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Use Postgres"])
+        .assert()
+        .success();
+
+    for driver in ["Team expertise", "Cost"] {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .args(["drivers", "add", "2", driver])
+            .assert()
+            .success()
+            .stdout(format!(
+                "doc/adr/0002-use-postgres.md: added decision driver {}\n",
+                driver
+            ));
+    }
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["drivers", "add", "2", "cost"])
+        .assert()
+        .failure();
+
+    let adr = std::fs::read_to_string(temp.path().join("doc/adr/0002-use-postgres.md")).unwrap();
+    assert!(adr.contains("## Decision Drivers\n\n* Team expertise\n* Cost\n\n## Decision\n"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["drivers", "remove", "2", "Team expertise"])
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["drivers", "list", "2"])
+        .assert()
+        .success()
+        .stdout("Cost\n");
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["drivers", "remove", "2", "Team expertise"])
+        .assert()
+        .failure();
+
+    let output = Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "json"])
+        .output()
+        .unwrap();
+    let export: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(export["adrs"][1]["decision_drivers"][0], "Cost");
+}
@@ -25,3 +25,47 @@ fn test_edit() {
         .assert()
         .success();
 }
+
+#[test]
+#[serial_test::serial]
+fn test_edit_section_madr() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    // an editor that replaces whatever it is given
+    let editor = temp.child("editor.sh");
+    editor
+        .write_str("#!/bin/sh\nprintf 'Chosen option: \"Postgres\".\\n' > \"$1\"\n")
+        .unwrap();
+    std::process::Command::new("chmod")
+        .args(["+x", editor.path().to_str().unwrap()])
+        .status()
+        .unwrap();
+    std::env::set_var("EDITOR", editor.path());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-postgres.md")
+        .write_str("---\nstatus: proposed\n---\n# Use Postgres\n\n## Context and Problem Statement\n\nWe need a database.\n\n## Decision Outcome\n\nUndecided.\n\n### Consequences\n\n* Good\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["edit", "--section", "decision", "2"])
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-postgres.md").assert(
+        "---\nstatus: proposed\n---\n# Use Postgres\n\n## Context and Problem Statement\n\nWe need a database.\n\n## Decision Outcome\n\nChosen option: \"Postgres\".\n",
+    );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["edit", "--section", "drivers", "2"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("No drivers section found"));
+
+    std::env::set_var("EDITOR", "cat");
+}
@@ -0,0 +1,1262 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_export_import_json() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("--link")
+        .arg("1:Amends")
+        .arg("Use Postgres")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("export")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\"version\": \"1.1.0\"")
+                .and(predicate::str::contains("\"title\": \"Use Postgres\""))
+                .and(predicate::str::contains("\"type\": \"Amended by\""))
+                .and(predicate::str::contains(
+                    "\"path\": \"0002-use-postgres.md\"",
+                )),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("export")
+        .arg("json")
+        .arg("--output")
+        .arg("export.json")
+        .assert()
+        .success();
+
+    // import into a fresh project
+    std::fs::create_dir("imported").unwrap();
+    std::env::set_current_dir(temp.path().join("imported")).unwrap();
+    temp.child("imported/.adr-dir")
+        .write_str("doc/adr")
+        .unwrap();
+    temp.child("imported/doc/adr").create_dir_all().unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("import")
+        .arg("json")
+        .arg("../export.json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("doc/adr/0002-use-postgres.md"));
+
+    temp.child("imported/doc/adr/0002-use-postgres.md").assert(
+        predicate::str::starts_with("# 2. Use Postgres\n").and(predicate::str::contains(
+            "Amends [1. Record architecture decisions](0001-record-architecture-decisions.md)",
+        )),
+    );
+
+    // an export that doesn't match its checksum isn't imported
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "../export.json", "--sha256", "0123abcd"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Checksum mismatch for ../export.json: expected 0123abcd",
+        ));
+
+    // importing again would overwrite the ADRs
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("import")
+        .arg("json")
+        .arg("../export.json")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+
+    // sync into a project that already has ADR 1's decision under another number
+    std::fs::create_dir(temp.path().join("synced")).unwrap();
+    std::env::set_current_dir(temp.path().join("synced")).unwrap();
+    temp.child("synced/.adr-dir").write_str("doc/adr").unwrap();
+    temp.child("synced/doc/adr/0007-record-architecture-decisions.md")
+        .write_str(
+            "# 7. Record architecture decisions\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n",
+        )
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "--merge-links", "../export.json"])
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-use-postgres.md\n");
+
+    temp.child("synced/doc/adr/0001-record-architecture-decisions.md")
+        .assert(predicate::path::missing());
+    temp.child("synced/doc/adr/0002-use-postgres.md")
+        .assert(predicate::str::contains(
+            "Amends [7. Record architecture decisions](0007-record-architecture-decisions.md)",
+        ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_import_json_update() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Use Postgres"])
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "json", "--output", "export.json"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "--update", "export.json"])
+        .assert()
+        .success()
+        .stdout("0 changed, 0 added, 2 unchanged\n");
+
+    let postgres = temp.child("doc/adr/0002-use-postgres.md");
+    let original = std::fs::read_to_string(postgres.path()).unwrap();
+    postgres
+        .write_str(&original.replace("The issue motivating", "Whatever motivated"))
+        .unwrap();
+    std::fs::remove_file(
+        temp.path()
+            .join("doc/adr/0001-record-architecture-decisions.md"),
+    )
+    .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "--update", "export.json"])
+        .assert()
+        .success()
+        .stdout("changed: doc/adr/0002-use-postgres.md\nadded: doc/adr/0001-record-architecture-decisions.md\n1 changed, 1 added, 0 unchanged\n");
+    postgres.assert(predicate::str::contains("The issue motivating"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "--update", "export.json"])
+        .assert()
+        .success()
+        .stdout("0 changed, 0 added, 2 unchanged\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_import_json_dry_run() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Use Postgres"])
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "json", "--output", "export.json"])
+        .assert()
+        .success();
+
+    let postgres = temp.child("doc/adr/0002-use-postgres.md");
+    let original = std::fs::read_to_string(postgres.path()).unwrap();
+    let edited = original.replace("The issue motivating", "Whatever motivated");
+    postgres.write_str(&edited).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "--update", "--dry-run", "export.json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "export.json is a valid JSON-ADR export of 2 ADR(s)\nchange: doc/adr/0002-use-postgres.md\n--- doc/adr/0002-use-postgres.md\n+++ doc/adr/0002-use-postgres.md\n@@ ",
+        ))
+        .stdout(predicate::str::contains("\n-Whatever motivated"))
+        .stdout(predicate::str::contains("\n+The issue motivating"))
+        .stdout(predicate::str::ends_with(
+            "1 to change, 0 to add, 1 unchanged\n",
+        ));
+    postgres.assert(edited.as_str());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "--dry-run", "export.json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "exists: doc/adr/0001-record-architecture-decisions.md (ADR 1)\n",
+        ))
+        .stderr(predicate::str::contains("2 file(s) already exist"));
+
+    temp.child("elsewhere").create_dir_all().unwrap();
+    std::env::set_current_dir(temp.path().join("elsewhere")).unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    std::fs::remove_file("doc/adr/0001-record-architecture-decisions.md").unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "--dry-run", "../export.json"])
+        .assert()
+        .success()
+        .stdout("../export.json is a valid JSON-ADR export of 2 ADR(s)\ncreate: doc/adr/0001-record-architecture-decisions.md (ADR 1)\ncreate: doc/adr/0002-use-postgres.md (ADR 2)\n2 to create\n");
+    temp.child("elsewhere/doc/adr/0002-use-postgres.md")
+        .assert(predicate::path::missing());
+
+    temp.child("invalid.json")
+        .write_str(r#"{"version": "1.0.0", "adrs": [{"number": "one"}]}"#)
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "--dry-run", "../invalid.json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid JSON-ADR export:\n  /adrs/0",
+        ))
+        .stdout("");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_import_json_taken_number() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child("export.json")
+        .write_str(r#"{"version": "1.0.0", "adrs": [{"number": 1, "title": "Use OIDC", "status": "Accepted"}]}"#)
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    // ADR 1 is taken by a file with another name
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "export.json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "ADR 1 is doc/adr/0001-record-architecture-decisions.md already taken, use --update or --interactive",
+        ));
+    temp.child("doc/adr/0001-use-oidc.md")
+        .assert(predicate::path::missing());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "--dry-run", "export.json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "taken: doc/adr/0001-use-oidc.md (ADR 1 is doc/adr/0001-record-architecture-decisions.md)\n",
+        ))
+        .stderr(predicate::str::contains("1 file(s) already exist or have a number that is taken"));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_import_json_interactive() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Use Postgres"])
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "json", "--output", "export.json"])
+        .assert()
+        .success();
+    let postgres = temp.child("doc/adr/0002-use-postgres.md");
+    let original = std::fs::read_to_string(postgres.path()).unwrap();
+    postgres
+        .write_str(&original.replace("The issue motivating", "Whatever motivated"))
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "export.json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "--interactive", "export.json"])
+        .write_stdin("skip\ndiff\noverwrite\n")
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-use-postgres.md\n")
+        .stderr(predicate::str::contains("Skipped ADR 1"))
+        .stderr(predicate::str::contains("  - Whatever motivated"))
+        .stderr(predicate::str::contains("  + The issue motivating"));
+    postgres.assert(predicate::str::contains("The issue motivating"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "--interactive", "export.json"])
+        .write_stdin("renumber\nrenumber\n")
+        .assert()
+        .success()
+        .stdout("doc/adr/0003-record-architecture-decisions.md\ndoc/adr/0004-use-postgres.md\n");
+    temp.child("doc/adr/0004-use-postgres.md")
+        .assert(predicate::str::starts_with("# 4. Use Postgres"));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_import_json_status_map() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "json", "--output", "export.json"])
+        .assert()
+        .success();
+    let export = std::fs::read_to_string(temp.path().join("export.json")).unwrap();
+    temp.child("export.json")
+        .write_str(&export.replace("\"status\": \"Accepted\"", "\"status\": \"Approved\""))
+        .unwrap();
+    temp.child("status-map.toml")
+        .write_str("approved = \"accepted\"\n\"In Review\" = \"Proposed\"\n")
+        .unwrap();
+    let adr = temp.child("doc/adr/0001-record-architecture-decisions.md");
+    std::fs::remove_file(adr.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "import",
+            "json",
+            "--status-map",
+            "status-map.toml",
+            "export.json",
+        ])
+        .assert()
+        .success();
+    adr.assert(predicate::str::contains("## Status\n\nAccepted\n"));
+
+    temp.child("status-map.toml")
+        .write_str("approved = [\"accepted\"]\n")
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "import",
+            "json",
+            "--status-map",
+            "status-map.toml",
+            "export.json",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid status map status-map.toml",
+        ));
+}
+
+#[cfg(feature = "asciidoc")]
+#[test]
+#[serial_test::serial]
+fn test_import_asciidoc() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("legacy/decisions/adr-012-use-postgres.adoc")
+        .write_str("= ADR-012: Use Postgres\n:status: accepted\n:revdate: 2024-01-01\n\n== Background\n\nWe need *one* database.\n\n== Decision\n\nUse Postgres, see https://postgresql.org[the docs].\n")
+        .unwrap();
+    temp.child("legacy/README.adoc")
+        .write_str("= Decisions\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "asciidoc", "legacy"])
+        .assert()
+        .success()
+        .stdout("legacy/decisions/adr-012-use-postgres.adoc -> doc/adr/0002-use-postgres.md\n");
+    temp.child("doc/adr/0002-use-postgres.md").assert(
+        predicate::str::starts_with(
+            "# 2. Use Postgres\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n",
+        )
+        .and(predicate::str::contains(
+            "## Context\n\nWe need **one** database.",
+        ))
+        .and(predicate::str::contains(
+            "[the docs](https://postgresql.org)",
+        )),
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_import_adr_manager() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child(".adrs.toml")
+        .write_str("mode = \"nextgen\"\n")
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("adr-manager.json")
+        .write_str(
+            r#"[{
+                "title": "Use Postgres",
+                "status": "accepted",
+                "deciders": "Ada, Grace",
+                "date": "2024-01-01",
+                "contextAndProblemStatement": "We need a database.",
+                "consideredOptions": [
+                    {"title": "Postgres", "description": "", "pros": ["mature"], "cons": []},
+                    {"title": "MySQL", "description": "", "pros": [], "cons": ["licensing"]}
+                ],
+                "decisionOutcome": {"chosenOption": "Postgres", "explanation": "it is mature"}
+            }, {
+                "title": "Use Redis"
+            }]"#,
+        )
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "adr-manager", "adr-manager.json"])
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-use-postgres.md\ndoc/adr/0003-use-redis.md\n");
+
+    temp.child("doc/adr/0002-use-postgres.md").assert(
+        predicate::str::contains("deciders: [Ada, Grace]\n")
+            .and(predicate::str::contains(
+                "## Considered Options\n\n* Postgres\n* MySQL\n\n## Decision Outcome\n\nChosen option: \"Postgres\", because it is mature.\n\n## Pros and Cons of the Options\n\n### Postgres\n\n* Good, because mature\n\n### MySQL\n\n* Bad, because licensing\n",
+            )),
+    );
+    temp.child("doc/adr/0003-use-redis.md")
+        .assert(predicate::str::contains("## Status\n\nProposed\n"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["list", "--long"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "doc/adr/0002-use-postgres.md\tAccepted date=2024-01-01 format=madr\n",
+        ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_import_markdown() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("wiki/README.md")
+        .write_str("# Decisions\n")
+        .unwrap();
+    temp.child("wiki/Database choice.md")
+        .write_str("# ADR-12: Use Postgres\n\nStatus: accepted\nDate: 2024-01-01\n\n## Background\n\nWe need a database.\n\n## Resolution\n\nPostgres.\n")
+        .unwrap();
+    temp.child("wiki/infra/caching.md")
+        .write_str("Notes on caching.\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "markdown", "--dry-run", "wiki"])
+        .assert()
+        .success()
+        .stdout("wiki/Database choice.md -> doc/adr/0002-use-postgres.md\nwiki/infra/caching.md -> doc/adr/0003-caching.md\n");
+    temp.child("doc/adr/0002-use-postgres.md")
+        .assert(predicate::path::missing());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "markdown", "wiki"])
+        .assert()
+        .success()
+        .stderr(
+            predicate::str::contains("Warning: wiki/infra/caching.md: no title heading")
+                .and(predicate::str::contains(
+                    "Warning: wiki/infra/caching.md: no status found, imported as Proposed",
+                ))
+                .and(predicate::str::contains("Database choice.md").not()),
+        );
+    temp.child("doc/adr/0002-use-postgres.md").assert(
+        "# 2. Use Postgres\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\n## Context\n\nWe need a database.\n\n## Decision\n\nPostgres.\n",
+    );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["status", "3"])
+        .assert()
+        .success()
+        .stdout("Proposed\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_verify_export() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("--superseded")
+        .arg("1")
+        .arg("Second")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("verify-export")
+        .assert()
+        .success()
+        .stdout("2 ADR(s) round-tripped without loss\n");
+
+    temp.child("doc/adr/0003-custom.md")
+        .write_str("# 3. Custom\n\n## Status\n\nAccepted\n\n## Security Review\n\nApproved.\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("export")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"custom_sections\": [\n        {\n          \"heading\": \"Security Review\",\n          \"content\": \"Approved.\"",
+        ));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("verify-export")
+        .assert()
+        .success()
+        .stdout("3 ADR(s) round-tripped without loss\n");
+
+    // only the current status is exported, not its history
+    temp.child("doc/adr/0004-history.md")
+        .write_str("# 4. History\n\n## Status\n\nProposed\n\nAccepted\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("verify-export")
+        .assert()
+        .failure()
+        .stdout("doc/adr/0004-history.md: section 'Status' changed\n")
+        .stderr(predicate::str::contains(
+            "lost information from 1 of 4 ADR(s)",
+        ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_export_base_url() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("export")
+        .arg("json")
+        .arg("--base-url")
+        .arg("https://example.com/repo/blob/main/")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"source_uri\": \"https://example.com/repo/blob/main/doc/adr/0001-record-architecture-decisions.md\"",
+        ));
+
+    // a {sha} placeholder needs a commit to fill it in
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("export")
+        .arg("json")
+        .arg("--base-url")
+        .arg("https://example.com/repo/blob/{sha}")
+        .assert()
+        .failure();
+
+    for args in [
+        vec!["init", "-q"],
+        vec!["add", "-A"],
+        vec![
+            "-c",
+            "user.name=adrs",
+            "-c",
+            "user.email=adrs@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "Record decisions",
+        ],
+    ] {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+    let sha = std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .unwrap()
+        .stdout;
+    let sha = String::from_utf8(sha).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("export")
+        .arg("json")
+        .arg("--base-url")
+        .arg("https://example.com/repo/blob/{sha}")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "\"source_uri\": \"https://example.com/repo/blob/{}/doc/adr/0001-record-architecture-decisions.md\"",
+            sha.trim()
+        )));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_export_schema() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "export",
+            "json",
+            "--schema-embed",
+            "--output",
+            "out/adrs.json",
+        ])
+        .assert()
+        .failure();
+
+    std::fs::create_dir(temp.path().join("out")).unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "export",
+            "json",
+            "--schema-embed",
+            "--schema-sidecar",
+            "--output",
+            "out/adrs.json",
+        ])
+        .assert()
+        .success();
+    temp.child("out/adrs.json").assert(predicate::str::contains(
+        "\"title\": \"JSON-ADR bulk export\"",
+    ));
+    temp.child("out/schema.json")
+        .assert(predicate::str::contains("\"$id\""));
+
+    // the embedded schema doesn't get in the way of an import
+    temp.child("other").create_dir_all().unwrap();
+    std::env::set_current_dir(temp.path().join("other")).unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "../out/adrs.json"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "json", "--schema-sidecar"])
+        .assert()
+        .failure();
+}
+
+#[cfg(unix)]
+#[test]
+#[serial_test::serial]
+fn test_export_provenance() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    for args in [
+        vec!["init", "-q"],
+        vec!["config", "user.name", "Ada"],
+        vec!["add", "-A"],
+        vec![
+            "-c",
+            "user.email=adrs@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "Record decisions",
+        ],
+    ] {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    // a stand-in for gpg that records what it signed, outside the repository
+    let tools = TempDir::new().unwrap();
+    let bin = tools.child("bin");
+    bin.create_dir_all().unwrap();
+    let script = bin.child("gpg");
+    script
+        .write_str("#!/bin/sh\nwhile [ $# -gt 1 ]; do\n  if [ \"$1\" = --output ]; then out=\"$2\"; fi\n  shift\ndone\necho \"signature of $1\" > \"$out\"\n")
+        .unwrap();
+    std::fs::set_permissions(script.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!(
+        "{}:{}",
+        bin.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .env("PATH", &path)
+        .args([
+            "export",
+            "json",
+            "--provenance",
+            "--sign",
+            "--output",
+            "adrs.json",
+        ])
+        .assert()
+        .success();
+
+    temp.child("adrs.json").assert(
+        predicate::str::is_match(
+            "\"provenance\": \\{\n    \"commit\": \"[0-9a-f]{40}\",\n    \"dirty\": false,\n    \"exported_by\": \"Ada\",\n    \"exported_at\": \"\\d{4}-\\d{2}-\\d{2}\"\n  \\}",
+        )
+        .unwrap(),
+    );
+    temp.child("adrs.json.asc")
+        .assert("signature of adrs.json\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_export_json_deterministic() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .env("SOURCE_DATE_EPOCH", "1700000000")
+        .args(["export", "json", "--provenance"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"exported_at\": \"2023-11-14\""));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .env_remove("SOURCE_DATE_EPOCH")
+        .args(["--deterministic", "export", "json", "--provenance"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\"provenance\"")
+                .and(predicate::str::contains("exported_at").not()),
+        );
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .env("SOURCE_DATE_EPOCH", "yesterday")
+        .args(["export", "json", "--provenance"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid SOURCE_DATE_EPOCH 'yesterday'",
+        ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_export_markdown_standalone() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    // the link was written before ADR 1 was renamed
+    temp.child("doc/adr/0002-use-postgres.md")
+        .write_str("# 2. Use Postgres\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\nAmends [1. Record decisions](0001-record-architecture-decisions.md)\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "markdown", "2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Amends [1. Record decisions](0001-record-architecture-decisions.md)",
+        ));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "markdown", "2", "--standalone"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Amends 1. Record architecture decisions\n",
+        ));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "export",
+            "markdown",
+            "2",
+            "--standalone",
+            "--base-url",
+            "https://example.com/blob/main",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Amends [1. Record architecture decisions](https://example.com/blob/main/doc/adr/0001-record-architecture-decisions.md)",
+        ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_export_csv() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "new",
+            "--tag",
+            "api",
+            "--link",
+            "1:Amends",
+            "Version the API",
+        ])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "csv"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                "^number,title,status,date,tags,deciders,links\n1,Record architecture decisions,Accepted,\\d{4}-\\d{2}-\\d{2},,,Amended by 2\n2,Version the API,Accepted,\\d{4}-\\d{2}-\\d{2},api,,Amends 1\n$",
+            )
+            .unwrap(),
+        );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_export_yaml() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--link", "1:Amends", "Version the API"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "yaml"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("version: 1.1.0\ntool:\n  name: adrs\n")
+                .and(predicate::str::contains(
+                    "adrs:\n- number: 1\n  title: Record architecture decisions\n",
+                ))
+                .and(predicate::str::contains(
+                    "- number: 2\n  title: Version the API\n",
+                )),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "yaml", "2"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("- number: 2\n")
+                .and(predicate::str::contains("- number: 1\n").not()),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "json", "1"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\"number\": 1,")
+                .and(predicate::str::contains("\"number\": 2,").not()),
+        );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_export_html() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "new",
+            "--tag",
+            "api",
+            "--link",
+            "1:Amends",
+            "Version the API",
+        ])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "html", "--output", "site"])
+        .assert()
+        .success()
+        .stdout("Wrote 2 ADR page(s) to site\n");
+
+    let read = |path: &str| std::fs::read_to_string(temp.path().join(path)).unwrap();
+    assert!(read("site/index.html").contains(
+        "<td><a href=\"0002-version-the-api.html\">2. Version the API</a></td><td><span class=\"badge status-accepted\">Accepted</span></td>"
+    ));
+    assert!(read("site/0002-version-the-api.html").contains(
+        "<p>Amends <a href=\"0001-record-architecture-decisions.html\">1. Record architecture decisions</a></p>"
+    ));
+    assert!(read("site/tags/api.html").contains("<h1>Tagged api</h1>"));
+    assert!(temp.path().join("site/style.css").is_file());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "html", "--output", "site"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Directory already exists: site"));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_export_asciidoc() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--link", "1:Amends", "Version the API"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "asciidoc", "--output", "adoc"])
+        .assert()
+        .success()
+        .stdout("Wrote 2 ADR(s) to adoc\n");
+
+    let read = |path: &str| std::fs::read_to_string(temp.path().join(path)).unwrap();
+    assert_eq!(
+        read("adoc/index.adoc"),
+        "= Architecture Decision Records\n\n* xref:0001-record-architecture-decisions.adoc[1. Record architecture decisions] (Accepted)\n* xref:0002-version-the-api.adoc[2. Version the API] (Accepted)\n"
+    );
+    let adoc = read("adoc/0002-version-the-api.adoc");
+    assert!(adoc.starts_with("= 2. Version the API\n:adr-number: 2\n:adr-status: Accepted\n"));
+    assert!(adoc.contains(
+        "== Status\n\nAccepted\n\nAmends xref:0001-record-architecture-decisions.adoc[1. Record architecture decisions]\n"
+    ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_export_confluence() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--link", "1:Amends", "Version the API"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "confluence", "--output", "wiki"])
+        .assert()
+        .success()
+        .stdout("Wrote 2 page(s) to wiki\n");
+
+    let read = |path: &str| std::fs::read_to_string(temp.path().join(path)).unwrap();
+    let manifest: serde_json::Value = serde_json::from_str(&read("wiki/manifest.json")).unwrap();
+    assert_eq!(manifest["pages"][1]["title"], "2. Version the API");
+    assert_eq!(manifest["pages"][1]["file"], "0002-version-the-api.xhtml");
+    assert_eq!(manifest["pages"][1]["status"], "Accepted");
+
+    let page = read("wiki/0002-version-the-api.xhtml");
+    assert!(page.contains("<ac:parameter ac:name=\"colour\">Green</ac:parameter><ac:parameter ac:name=\"title\">Accepted</ac:parameter>"));
+    assert!(page.contains("<ri:page ri:content-title=\"1. Record architecture decisions\" />"));
+    assert!(page.contains("<h2>Context</h2>"));
+    assert!(!page.contains("<h2>Status</h2>"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "confluence", "--output", "wiki"])
+        .assert()
+        .failure();
+}
+
+#[test]
+#[serial_test::serial]
+fn test_export_hugo() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--link", "1:Amends", "Version the API"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "hugo", "--output", "content/decisions"])
+        .assert()
+        .success()
+        .stdout("Wrote 2 ADR(s) to content/decisions\n");
+
+    let read = |path: &str| std::fs::read_to_string(temp.path().join(path)).unwrap();
+    assert_eq!(
+        read("content/decisions/_index.md"),
+        "---\ntitle: Architecture Decision Records\n---\n"
+    );
+    let page = read("content/decisions/0002-version-the-api/index.md");
+    assert!(page.starts_with("---\ntitle: 2. Version the API\ndate: "));
+    assert!(page.contains("weight: 2\nstatus:\n- Accepted\nadr_number: 2\n---\n\n## Status\n"));
+    assert!(page.contains(
+        "Amends [1. Record architecture decisions]({{< relref \"0001-record-architecture-decisions\" >}})"
+    ));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "hugo", "--output", "content/decisions"])
+        .assert()
+        .failure();
+}
+
+#[test]
+#[serial_test::serial]
+fn test_export_backstage() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--link", "1:Amends", "Version the API"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "export",
+            "backstage",
+            "--output",
+            "backstage",
+            "--name",
+            "payments",
+            "--owner",
+            "team-a",
+        ])
+        .assert()
+        .success()
+        .stdout("Wrote 2 ADR(s) to backstage/docs/adrs\n");
+
+    let read = |path: &str| std::fs::read_to_string(temp.path().join(path)).unwrap();
+    assert_eq!(
+        read("backstage/catalog-info.yaml"),
+        "apiVersion: backstage.io/v1alpha1\nkind: Component\nmetadata:\n  name: payments\n  annotations:\n    backstage.io/adr-location: docs/adrs\nspec:\n  type: service\n  lifecycle: production\n  owner: team-a\n"
+    );
+    let page = read("backstage/docs/adrs/0002-version-the-api.md");
+    assert!(page.starts_with("---\nstatus: accepted\ndate: "));
+    assert!(page.contains("---\n\n# 2. Version the API\n"));
+
+    // a date the plugin can't parse stops the export
+    let path = temp.path().join("doc/adr/0002-version-the-api.md");
+    let adr = std::fs::read_to_string(&path).unwrap();
+    let date = adr.lines().find(|line| line.starts_with("Date: ")).unwrap();
+    std::fs::write(&path, adr.replace(date, "Date: last week")).unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "backstage", "--output", "other"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "doc/adr/0002-version-the-api.md: the date 'last week' isn't in YYYY-MM-DD form",
+        ));
+    assert!(!temp.path().join("other").exists());
+}
@@ -0,0 +1,103 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_fix_links() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("--link")
+        .arg("1:Amends")
+        .arg("Use Postgres")
+        .assert()
+        .success();
+
+    // rename and retitle the first ADR by hand
+    std::fs::rename(
+        "doc/adr/0001-record-architecture-decisions.md",
+        "doc/adr/0001-record-decisions.md",
+    )
+    .unwrap();
+    let first = std::fs::read_to_string("doc/adr/0001-record-decisions.md").unwrap();
+    std::fs::write(
+        "doc/adr/0001-record-decisions.md",
+        first.replace(
+            "# 1. Record architecture decisions",
+            "# 1. Record decisions",
+        ),
+    )
+    .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("fix-links")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "0001-record-architecture-decisions.md -> 0001-record-decisions.md",
+        ));
+    temp.child("doc/adr/0002-use-postgres.md")
+        .assert(predicate::str::contains(
+            "0001-record-architecture-decisions.md",
+        ));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("fix-links")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fixed 1 ADR"));
+    temp.child("doc/adr/0002-use-postgres.md")
+        .assert(predicate::str::contains(
+            "Amends [1. Record decisions](0001-record-decisions.md)",
+        ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_fix_links_outside_adr_dir() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/runbooks/0003-restart.md")
+        .write_str("# Restart\n")
+        .unwrap();
+    temp.child("doc/adr/0002-use-postgres.md")
+        .write_str(
+            "# 2. Use Postgres\n\n## Status\n\nAccepted\n\n## Context\n\nSee [the runbook](../runbooks/0003-restart.md), [a missing one](../runbooks/0009-failover.md) and [1. Old title](../adr/0001-old-title.md).\n",
+        )
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("fix-links")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Fixed 1 ADR")
+                .and(predicate::str::contains("unresolved").not()),
+        );
+    temp.child("doc/adr/0002-use-postgres.md").assert(
+        "# 2. Use Postgres\n\n## Status\n\nAccepted\n\n## Context\n\nSee [the runbook](../runbooks/0003-restart.md), [a missing one](../runbooks/0009-failover.md) and [1. Record architecture decisions](../adr/0001-record-architecture-decisions.md).\n",
+    );
+}
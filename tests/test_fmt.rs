@@ -0,0 +1,148 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_fmt() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child(".adrs.toml")
+        .write_str("mode = \"nextgen\"\n")
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--link", "1:Amends", "Use Postgres"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("fmt")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["fmt", "--check"])
+        .assert()
+        .success()
+        .stdout("");
+
+    temp.child("doc/adr/0003-use-redis.md")
+        .write_str("---\ntags: [cache]\nid: 3\n---\n# 3. Use Redis   \n\n\n# Status\nAccepted\nAmends: [Postgres](0002-use-postgres.md)\n## Context\nWe need a cache.\n")
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["fmt", "--check"])
+        .assert()
+        .failure()
+        .stdout("doc/adr/0003-use-redis.md needs formatting\n")
+        .stderr(predicate::str::contains("1 ADR(s) need formatting"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["fmt", "3"])
+        .assert()
+        .success()
+        .stdout("doc/adr/0003-use-redis.md\n");
+    temp.child("doc/adr/0003-use-redis.md").assert(
+        "---\nid: 3\ntags: [cache]\n---\n\n# 3. Use Redis\n\n## Status\n\nAccepted\n\nAmends [2. Use Postgres](0002-use-postgres.md)\n\n## Context\n\nWe need a cache.\n",
+    );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["fmt", "--check"])
+        .assert()
+        .success();
+}
+
+#[test]
+#[serial_test::serial]
+fn test_fmt_check_written_adrs() {
+    for format in ["nygard", "madr"] {
+        let temp = TempDir::new().unwrap();
+        std::env::set_current_dir(temp.path()).unwrap();
+        std::env::set_var("EDITOR", "cat");
+
+        temp.child(".adrs.toml")
+            .write_str(&format!("[templates]\nformat = \"{}\"\n", format))
+            .unwrap();
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .arg("init")
+            .assert()
+            .success();
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .args(["new", "--tag", "db", "Use MySQL"])
+            .assert()
+            .success();
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .args(["new", "-l", "1:Amends:Amended by", "Use Postgres"])
+            .assert()
+            .success();
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .args(["new", "-s", "2", "Use Redis"])
+            .assert()
+            .success();
+
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .args(["fmt", "--check"])
+            .assert()
+            .success()
+            .stdout("");
+    }
+}
+
+#[test]
+#[serial_test::serial]
+fn test_fmt_check_updated_adrs() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child(".adrs.toml")
+        .write_str("mode = \"nextgen\"\n")
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    for title in ["Use Postgres", "Use MongoDB"] {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .args(["new", title])
+            .assert()
+            .success();
+    }
+    for args in [
+        vec!["implement", "2", "--link", "https://example.com/pull/1"],
+        vec!["confirm", "2", "--result", "pass"],
+        vec!["retire", "3", "--reason", "posted by mistake"],
+    ] {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .args(args)
+            .assert()
+            .success();
+    }
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["fmt", "--check"])
+        .assert()
+        .success()
+        .stdout("");
+}
@@ -1,8 +1,9 @@
 use assert_cmd::Command;
 use assert_fs::{
-    fixture::{FileWriteStr, PathChild},
+    fixture::{FileWriteBin, FileWriteStr, PathChild},
     TempDir,
 };
+use predicates::prelude::*;
 
 #[test]
 #[serial_test::serial]
@@ -101,6 +102,186 @@ fn test_generate_graph() {
         .stdout(graph);
 }
 
+#[test]
+#[serial_test::serial]
+fn test_generate_graph_unnumbered_file() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    // adopted directories can keep files named only by their number
+    temp.child("doc/adr/3.md")
+        .write_str("# 3. Use Postgres\n\n## Status\n\nAccepted\n")
+        .unwrap();
+
+    let graph = "digraph {\n  node [shape=plaintext]\n  subgraph {\n\t_1 [label=\"1. Record architecture decisions\"; URL=\"0001-record-architecture-decisions.html\"];\n  }\n}\n";
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "graph"])
+        .assert()
+        .success()
+        .stdout(graph);
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_graph_plantuml() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--link", "1:Amends", "Test new"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "graph", "--format", "plantuml"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("@startuml\nskinparam rectangle {\n")
+                .and(predicate::str::contains("  BackgroundColor<<Accepted>> #C8E6C9\n"))
+                .and(predicate::str::contains(
+                    "}\nrectangle \"1. Record architecture decisions\" <<Accepted>> as _1 [[0001-record-architecture-decisions.html]]\nrectangle \"2. Test new\" <<Accepted>> as _2 [[0002-test-new.html]]\n_1 .[#999999].> _2\n_1 --> _2 : Amended by\n_2 --> _1 : Amends\n@enduml\n",
+                )),
+        );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_graph_graphml_and_cytoscape() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--link", "1:Amends", "Use Postgres"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "graph", "--format", "graphml"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("<graph edgedefault=\"directed\">\n")
+                .and(predicate::str::contains(
+                    "    <node id=\"_2\">\n      <data key=\"number\">2</data>\n      <data key=\"title\">2. Use Postgres</data>\n      <data key=\"status\">Accepted</data>\n",
+                ))
+                .and(predicate::str::contains(
+                    "    <edge source=\"_2\" target=\"_1\">\n      <data key=\"kind\">Amends</data>\n    </edge>\n",
+                ))
+                .and(predicate::str::ends_with("  </graph>\n</graphml>\n")),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "graph", "--format", "cytoscape-json"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\"name\": \"2. Use Postgres\"").and(
+                predicate::str::contains(
+                    "\"id\": \"2-1-0\",\n          \"source\": \"2\",\n          \"target\": \"1\",\n          \"kind\": \"Amends\"",
+                ),
+            ),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "graph", "--format", "html"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("<!DOCTYPE html>")
+                .and(predicate::str::contains(
+                    "<title>Architectural Decision Records</title>",
+                ))
+                .and(predicate::str::contains(
+                    r#"{"source":"2","target":"1","kind":"Amends"}"#,
+                )),
+        );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_graph_theme() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child(".adrs.toml")
+        .write_str(
+            "[generate.graph]\ncolors = { Accepted = \"#AAFFAA\" }\nfont = \"Inter\"\nrankdir = \"lr\"\n",
+        )
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "graph", "--title", "Decisions", "--legend"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with(
+                "digraph {\n  label=\"Decisions\";\n  labelloc=\"t\";\n  rankdir=LR;\n  fontname=\"Inter\";\n",
+            )
+            .and(predicate::str::contains(
+                "\t_1 [label=\"1. Record architecture decisions\"; URL=\"0001-record-architecture-decisions.html\"; shape=box; style=filled; fillcolor=\"#AAFFAA\"];\n",
+            ))
+            .and(predicate::str::contains(
+                "  subgraph cluster_legend {\n    label=\"Legend\";\n    legend_0 [label=\"Accepted\"; shape=box; style=filled; fillcolor=\"#AAFFAA\"];\n  }\n}\n",
+            )),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "generate",
+            "graph",
+            "--format",
+            "plantuml",
+            "--rankdir",
+            "tb",
+            "--legend",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("@startuml\nskinparam defaultFontName Inter\n")
+                .and(predicate::str::contains(
+                    "  BackgroundColor<<Accepted>> #AAFFAA\n",
+                ))
+                .and(predicate::str::contains(
+                    "legend right\n|= Status |\n|<#AAFFAA> Accepted |\nendlegend\n@enduml\n",
+                )),
+        );
+}
+
 #[test]
 #[serial_test::serial]
 fn test_generate_book() {
@@ -153,3 +334,729 @@ fn test_generate_book() {
         .join("0003-test-another.md")
         .exists());
 }
+
+#[test]
+#[serial_test::serial]
+fn test_generate_book_multi_repo() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    for (repo, title) in [("billing", "Use Stripe"), ("web", "Bill through billing")] {
+        std::fs::create_dir(temp.child(repo).path()).unwrap();
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .current_dir(temp.child(repo).path())
+            .arg("init")
+            .assert()
+            .success();
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .current_dir(temp.child(repo).path())
+            .args(["new", title])
+            .assert()
+            .success();
+    }
+    let web_adr = temp.child("web/doc/adr/0002-bill-through-billing.md");
+    let markdown = std::fs::read_to_string(web_adr.path()).unwrap();
+    web_adr
+        .write_str(&format!(
+            "{}\nSee [Use Stripe](../../../billing/doc/adr/0002-use-stripe.md#decision)\n",
+            markdown
+        ))
+        .unwrap();
+    temp.child("adrs.toml")
+        .write_str("[[repos]]\npath = \"billing\"\nname = \"Billing Service\"\n\n[[repos]]\npath = \"web\"\n")
+        .unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "generate",
+            "book",
+            "--multi-repo",
+            "adrs.toml",
+            "--author",
+            "Ada",
+        ])
+        .assert()
+        .success();
+
+    let read = |path: &str| std::fs::read_to_string(temp.child(path).path()).unwrap();
+    assert!(read("book/src/SUMMARY.md").contains(
+        "* [Billing Service](billing-service/README.md)\n  * [Record architecture decisions](billing-service/0001-record-architecture-decisions.md)\n  * [Use Stripe](billing-service/0002-use-stripe.md)\n\n* [web](web/README.md)\n",
+    ));
+    assert_eq!(
+        read("book/src/billing-service/README.md"),
+        "# Billing Service\n"
+    );
+    assert!(read("book/src/web/0002-bill-through-billing.md")
+        .ends_with("See [Use Stripe](../billing-service/0002-use-stripe.md#decision)\n"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "generate",
+            "book",
+            "--multi-repo",
+            "adrs.toml",
+            "--author",
+            "Ada",
+            "--check",
+        ])
+        .assert()
+        .success()
+        .stdout("book is up to date\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_filters() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0002-rejected-idea.md")
+        .write_str("# 2. Rejected idea\n\n## Status\n\nRejected\n")
+        .unwrap();
+    temp.child("doc/adr/archive/0003-old-idea.md")
+        .write_str("# 3. Old idea\n\n## Status\n\nAccepted\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("toc")
+        .assert()
+        .stdout("# Architecture Decision Records\n\n* [1. Record architecture decisions](0001-record-architecture-decisions.md)\n")
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("toc")
+        .arg("--all")
+        .assert()
+        .stdout("# Architecture Decision Records\n\n* [1. Record architecture decisions](0001-record-architecture-decisions.md)\n* [2. Rejected idea](0002-rejected-idea.md)\n* [3. Old idea](archive/0003-old-idea.md)\n")
+        .success();
+
+    let graph = "digraph {\n  node [shape=plaintext]\n  subgraph {\n\t_1 [label=\"1. Record architecture decisions\"; URL=\"0001-record-architecture-decisions.html\"];\n\t_3 [label=\"3. Old idea\"; URL=\"archive/0003-old-idea.html\"];\n\t_1 -> _3 [style=\"dotted\", weight=1];\n  }\n}\n";
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("graph")
+        .arg("--include-archived")
+        .assert()
+        .success()
+        .stdout(graph);
+
+    temp.child(".adrs.toml")
+        .write_str("[generate]\nexclude_status = []\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("book")
+        .assert()
+        .success();
+
+    assert!(temp.child("book/src/0002-rejected-idea.md").exists());
+    assert!(!temp.child("book/src/archive").exists());
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_check() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    let toc = Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("toc")
+        .output()
+        .unwrap()
+        .stdout;
+    temp.child("toc.md").write_binary(&toc).unwrap();
+    let graph = Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("graph")
+        .output()
+        .unwrap()
+        .stdout;
+    temp.child("graph.dot").write_binary(&graph).unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("book")
+        .arg("--author")
+        .arg("someone")
+        .assert()
+        .success();
+
+    for (command, file) in [("toc", "toc.md"), ("graph", "graph.dot")] {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .arg("generate")
+            .arg(command)
+            .arg("--check")
+            .arg(file)
+            .assert()
+            .success()
+            .stdout(format!("{} is up to date\n", file));
+    }
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("book")
+        .arg("--author")
+        .arg("someone")
+        .arg("--check")
+        .assert()
+        .success()
+        .stdout("book is up to date\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("Test new")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("toc")
+        .arg("--check")
+        .arg("toc.md")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "toc.md is out of date, first difference at line 4",
+        ));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("graph")
+        .arg("--check")
+        .arg("graph.dot")
+        .assert()
+        .failure();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("book")
+        .arg("--author")
+        .arg("someone")
+        .arg("--check")
+        .assert()
+        .failure()
+        .stdout(
+            predicates::str::contains("book: src/SUMMARY.md is out of date").and(
+                predicates::str::contains("book: src/0002-test-new.md is missing"),
+            ),
+        );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_graph_why() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("--link")
+        .arg("1:Amends")
+        .arg("Second")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("--link")
+        .arg("2:Depends on")
+        .arg("Third")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("Unrelated")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("graph")
+        .arg("--why")
+        .arg("3")
+        .arg("1")
+        .assert()
+        .success()
+        .stdout("3. Third\n  Depends on 2. Second\n  Amends 1. Record architecture decisions\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("graph")
+        .arg("--why")
+        .arg("1")
+        .arg("4")
+        .assert()
+        .success()
+        .stdout("No chain of links connects 1. Record architecture decisions and 4. Unrelated\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_book_shortcodes() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Use shortcodes"])
+        .assert()
+        .success();
+    let adr = temp.child("doc/adr/0002-use-shortcodes.md");
+    let markdown = std::fs::read_to_string(adr.path()).unwrap();
+    adr.write_str(&format!(
+        "{}\nFollows {{{{adr 1}}}}, not {{{{adr 9}}}}.\n",
+        markdown
+    ))
+    .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "book", "--author", "Ada"])
+        .assert()
+        .success();
+
+    let book =
+        std::fs::read_to_string(temp.child("book/src/0002-use-shortcodes.md").path()).unwrap();
+    assert!(book.ends_with(
+        "Follows [1. Record architecture decisions](0001-record-architecture-decisions.md), not {{adr 9}}.\n"
+    ));
+    // the source keeps the shortcode
+    assert!(std::fs::read_to_string(adr.path())
+        .unwrap()
+        .contains("Follows {{adr 1}}"));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_toc_markers() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Use markers"])
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["status", "2", "proposed"])
+        .assert()
+        .success();
+    temp.child(".adrs.toml")
+        .write_str("[markers]\nstyle = \"emoji\"\nemoji = { Proposed = \"🤔\" }\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "toc"])
+        .assert()
+        .success()
+        .stdout(
+            "# Architecture Decision Records\n\n* [✅ 1. Record architecture decisions](0001-record-architecture-decisions.md)\n* [🤔 2. Use markers](0002-use-markers.md)\n",
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "toc", "--ordered", "--markers", "ascii"])
+        .assert()
+        .success()
+        .stdout(
+            "# Architecture Decision Records\n\n1. [[+] Record architecture decisions](0001-record-architecture-decisions.md)\n1. [[?] Use markers](0002-use-markers.md)\n",
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["list", "--markers", "ascii"])
+        .assert()
+        .success()
+        .stdout(
+            "[+] doc/adr/0001-record-architecture-decisions.md\n[?] doc/adr/0002-use-markers.md\n",
+        );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_toc_grouped() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-postgres.md")
+        .write_str(
+            "---\ntags: [database, ops]\n---\n\n# 2. Use Postgres\n\n## Status\n\nProposed\n",
+        )
+        .unwrap();
+    temp.child("doc/adr/0003-use-kafka.md")
+        .write_str("# 3. Use Kafka\n\n## Status\n\nAccepted\n")
+        .unwrap();
+    temp.child("doc/adr/0004-use-redis.md")
+        .write_str("---\ntags: [database]\n---\n\n# 4. Use Redis\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "toc", "--group-by", "status"])
+        .assert()
+        .success()
+        .stdout("# Architecture Decision Records\n\n## Proposed (1)\n\n* [2. Use Postgres](0002-use-postgres.md)\n\n## Accepted (2)\n\n* [1. Record architecture decisions](0001-record-architecture-decisions.md)\n* [3. Use Kafka](0003-use-kafka.md)\n\n## No status (1)\n\n* [4. Use Redis](0004-use-redis.md)\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "toc", "--group-by", "tag"])
+        .assert()
+        .success()
+        .stdout("# Architecture Decision Records\n\n## database (2)\n\n* [2. Use Postgres](0002-use-postgres.md)\n* [4. Use Redis](0004-use-redis.md)\n\n## ops (1)\n\n* [2. Use Postgres](0002-use-postgres.md)\n\n## Untagged (2)\n\n* [1. Record architecture decisions](0001-record-architecture-decisions.md)\n* [3. Use Kafka](0003-use-kafka.md)\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "toc", "--group-by", "tag", "--ordered"])
+        .assert()
+        .failure();
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_feed() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/0001-record-architecture-decisions.md")
+        .write_str(
+            "# 1. Record architecture decisions\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n",
+        )
+        .unwrap();
+    temp.child("doc/adr/0002-use-postgres.md")
+        .write_str("---\ntags: [database]\ndecided_date: 2024-03-01\n---\n\n# 2. Use Postgres\n\nDate: 2024-02-01\n\n## Status\n\nAccepted\n")
+        .unwrap();
+    temp.child("doc/adr/0003-use-kafka.md")
+        .write_str("# 3. Use Kafka\n\nDate: 2024-02-15\n\n## Status\n\nProposed\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "generate",
+            "feed",
+            "--base-url",
+            "https://example.com/adr/",
+            "--limit",
+            "2",
+        ])
+        .assert()
+        .success()
+        .stdout(concat!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n",
+            "<feed xmlns=\"http://www.w3.org/2005/Atom\">\n",
+            "  <title>Architecture Decision Records</title>\n",
+            "  <id>https://example.com/adr</id>\n",
+            "  <link href=\"https://example.com/adr\"/>\n",
+            "  <updated>2024-03-01T00:00:00Z</updated>\n",
+            "  <entry>\n",
+            "    <title>2. Use Postgres</title>\n",
+            "    <id>https://example.com/adr/0002-use-postgres.md</id>\n",
+            "    <link href=\"https://example.com/adr/0002-use-postgres.md\"/>\n",
+            "    <updated>2024-03-01T00:00:00Z</updated>\n",
+            "    <summary>Status changed to Accepted</summary>\n",
+            "    <category term=\"database\"/>\n",
+            "  </entry>\n",
+            "  <entry>\n",
+            "    <title>3. Use Kafka</title>\n",
+            "    <id>https://example.com/adr/0003-use-kafka.md</id>\n",
+            "    <link href=\"https://example.com/adr/0003-use-kafka.md\"/>\n",
+            "    <updated>2024-02-15T00:00:00Z</updated>\n",
+            "    <summary>Created with status Proposed</summary>\n",
+            "  </entry>\n",
+            "</feed>\n",
+        ));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "generate",
+            "feed",
+            "--base-url",
+            "https://example.com/adr",
+            "--format",
+            "rss",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("<rss version=\"2.0\">")
+                .and(predicate::str::contains(
+                    "<pubDate>Mon, 01 Jan 2024 00:00:00 +0000</pubDate>",
+                ))
+                .and(predicate::str::contains(
+                    "<guid>https://example.com/adr/0001-record-architecture-decisions.md</guid>",
+                )),
+        );
+}
+
+// run git, failing the test if it fails
+fn git(args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(["-c", "user.name=adrs", "-c", "user.email=adrs@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_changelog() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    git(&["init", "-q"]);
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-mysql.md")
+        .write_str("# 2. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n")
+        .unwrap();
+    temp.child("doc/adr/0003-use-kafka.md")
+        .write_str("# 3. Use Kafka\n\nDate: 2024-01-01\n\n## Status\n\nProposed\n")
+        .unwrap();
+    git(&["add", "-A"]);
+    git(&["commit", "-q", "-m", "first"]);
+    git(&["tag", "v1"]);
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "changelog", "--since", "v1"])
+        .assert()
+        .success()
+        .stdout(concat!(
+            "# Decision Changelog\n\n",
+            "Changes to the ADRs between v1 and HEAD.\n\n",
+            "No ADRs were created, changed status or superseded.\n",
+        ));
+
+    temp.child("doc/adr/0002-use-mysql.md")
+        .write_str("# 2. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nSuperseded by [4. Use Postgres](0004-use-postgres.md)\n")
+        .unwrap();
+    temp.child("doc/adr/0003-use-kafka.md")
+        .write_str("# 3. Use Kafka\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n")
+        .unwrap();
+    temp.child("doc/adr/0004-use-postgres.md")
+        .write_str("# 4. Use Postgres\n\nDate: 2024-02-01\n\n## Status\n\nAccepted\n\nSupersedes [2. Use MySQL](0002-use-mysql.md)\n")
+        .unwrap();
+    git(&["add", "-A"]);
+    git(&["commit", "-q", "-m", "second"]);
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "changelog", "--since", "v1"])
+        .assert()
+        .success()
+        .stdout(concat!(
+            "# Decision Changelog\n\n",
+            "Changes to the ADRs between v1 and HEAD.\n\n",
+            "## Created (1)\n\n",
+            "* [4. Use Postgres](0004-use-postgres.md), Accepted\n\n",
+            "## Status changed (1)\n\n",
+            "* [3. Use Kafka](0003-use-kafka.md): Proposed to Accepted\n\n",
+            "## Superseded (1)\n\n",
+            "* [2. Use MySQL](0002-use-mysql.md), by [4. Use Postgres](0004-use-postgres.md)\n",
+        ));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "changelog", "--since", "v0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown git revision v0"));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_excludes_withdrawn() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    // written by hand, so not in the standard spelling
+    temp.child("doc/adr/0002-withdrawn-idea.md")
+        .write_str("# 2. Withdrawn idea\n\n## Status\n\nwithdrawn\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "toc"])
+        .assert()
+        .success()
+        .stdout("# Architecture Decision Records\n\n* [1. Record architecture decisions](0001-record-architecture-decisions.md)\n");
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["status", "2"])
+        .assert()
+        .success()
+        .stdout("Withdrawn\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_toc_ordered_with_gaps() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-mysql.md")
+        .write_str("# 2. Use MySQL\n\nDate: 2024-01-01\n\n## Status\n\nRejected\n")
+        .unwrap();
+    temp.child("doc/adr/0003-use-postgres.md")
+        .write_str("# 3. Use Postgres\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n")
+        .unwrap();
+
+    // the rejected ADR is left out, so the numbers are written out
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "toc", "--ordered"])
+        .assert()
+        .success()
+        .stdout("# Architecture Decision Records\n\n1. [Record architecture decisions](0001-record-architecture-decisions.md)\n3. [Use Postgres](0003-use-postgres.md)\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "toc", "--ordered", "--all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1. [Use MySQL](0002-use-mysql.md)\n1. [Use Postgres](0003-use-postgres.md)\n",
+        ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_generate_commit_msg_and_branch_name() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Use PostgreSQL"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "commit-msg", "2"])
+        .assert()
+        .success()
+        .stdout("docs(adr): accept 0002 use postgresql\n");
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "commit-msg", "--status", "superseded", "2"])
+        .assert()
+        .success()
+        .stdout("docs(adr): supersede 0002 use postgresql\n");
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "branch-name", "2"])
+        .assert()
+        .success()
+        .stdout("adr/0002-use-postgresql\n");
+
+    temp.child(".adrs.toml")
+        .write_str("[generate]\ncommit_msg = \"ADR-{number}: {status} {title}\"\nbranch_name = \"decisions/{status}/{title}\"\n")
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "commit-msg", "2"])
+        .assert()
+        .success()
+        .stdout("ADR-0002: accepted Use PostgreSQL\n");
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "branch-name", "2"])
+        .assert()
+        .success()
+        .stdout("decisions/accepted/Use-PostgreSQL\n");
+}
@@ -0,0 +1,109 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_implement() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child(".adrs.toml")
+        .write_str("mode = \"nextgen\"\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("Use Postgres")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("implement")
+        .arg("2")
+        .arg("partial")
+        .arg("--link")
+        .arg("https://github.com/example/project/issues/7")
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-use-postgres.md: implemented=partial\n");
+
+    temp.child("doc/adr/0002-use-postgres.md").assert(
+        predicate::str::contains("implemented: partial\n")
+            .and(predicate::str::contains(
+                "implementation_link: https://github.com/example/project/issues/7\n",
+            ))
+            .and(predicate::str::contains("implemented_date").not()),
+    );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("list")
+        .arg("--implemented")
+        .arg("partial")
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-use-postgres.md\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("list")
+        .arg("--implemented")
+        .arg("no")
+        .assert()
+        .success()
+        .stdout("doc/adr/0001-record-architecture-decisions.md\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("implement")
+        .arg("2")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0002-use-postgres.md").assert(
+        predicate::str::contains("implemented: true\n")
+            .and(predicate::str::contains("implemented_date: ")),
+    );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("export")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"implemented\": \"true\""));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_implement_compatible() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("implement")
+        .arg("1")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "implementation tracking needs nextgen mode",
+        ));
+}
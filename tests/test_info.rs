@@ -0,0 +1,38 @@
+use assert_cmd::Command;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_info() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("info")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains(format!("adrs_version={}", env!("CARGO_PKG_VERSION")))
+                .and(predicate::str::contains("adrs_dir=doc/adr\nadrs_count=1"))
+                .and(predicate::str::contains(
+                    "config_file=.adrs.toml (not found)",
+                ))
+                .and(predicate::str::contains("mode=compatible"))
+                .and(predicate::str::contains("# effective configuration")),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("env")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("template_format=nygard"));
+}
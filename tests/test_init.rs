@@ -1,6 +1,7 @@
 use assert_cmd::Command;
 use assert_fs::fixture::TempDir;
 use assert_fs::prelude::*;
+use predicates::prelude::*;
 
 #[test]
 #[serial_test::serial]
@@ -42,7 +43,8 @@ fn test_init_with_directory() {
 
     assert_eq!(
         std::fs::read_to_string(format!("{}/.adr-dir", temp.path().to_str().unwrap())).unwrap(),
-        temp.path().to_str().unwrap()
+        // .adr-dir is always written with forward slashes
+        temp.path().to_str().unwrap().replace('\\', "/")
     );
 }
 
@@ -73,7 +75,8 @@ fn test_init_with_file_already_in_directory() {
 
     assert_eq!(
         std::fs::read_to_string(format!("{}/.adr-dir", temp.path().to_str().unwrap())).unwrap(),
-        temp.path().to_str().unwrap()
+        // .adr-dir is always written with forward slashes
+        temp.path().to_str().unwrap().replace('\\', "/")
     );
 }
 
@@ -106,3 +109,254 @@ fn test_init_issue_4() {
         .assert()
         .success();
 }
+
+#[test]
+#[serial_test::serial]
+fn test_init_interactive() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .arg("--interactive")
+        .write_stdin("decisions\nnextgen\nmadr\nsecurity, api\ny\n")
+        .assert()
+        .stdout("decisions/0001-record-architecture-decisions.md\n")
+        .success();
+
+    temp.child(".adrs.toml").assert(
+        predicates::str::starts_with("version = 1\n")
+            .and(predicates::str::contains("mode = \"nextgen\""))
+            .and(predicates::str::contains("format = \"madr\""))
+            .and(predicates::str::contains("\"security\"")),
+    );
+    temp.child("decisions/0001-record-architecture-decisions.md")
+        .assert(
+            predicates::str::starts_with("---\n").and(predicates::str::contains(
+                "## Context and Problem Statement",
+            )),
+        );
+
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    // empty answers accept the defaults, except for the initial ADR here
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .arg("--interactive")
+        .write_stdin("\n\n\n\nn\n")
+        .assert()
+        .stdout("")
+        .success();
+
+    temp.child(".adrs.toml")
+        .assert(predicates::str::contains("mode = \"compatible\""));
+    temp.child("doc/adr").assert(predicates::path::is_dir());
+    temp.child("doc/adr/0001-record-architecture-decisions.md")
+        .assert(predicates::path::missing());
+}
+
+#[test]
+#[serial_test::serial]
+fn test_init_preset() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .arg("--preset")
+        .arg("madr-ng")
+        .assert()
+        .stdout("doc/adr/0001-record-architecture-decisions.md\n")
+        .success();
+
+    temp.child(".adrs.toml").assert(
+        predicates::str::contains("mode = \"nextgen\"")
+            .and(predicates::str::contains("format = \"madr\""))
+            .and(predicates::str::contains("[lint]").not()),
+    );
+
+    // a preset's rules add to the default ones rather than replacing them
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["init", "--preset", "nygard-compat"])
+        .assert()
+        .success();
+    temp.child(".adrs.toml").assert(predicates::str::contains(
+        "rules = [\n    \"title\",\n    \"status\",\n    \"date\",\n    \"links\",\n    \"status-links\",\n    \"confirmation\",\n    \"adr-tools\",\n]\n",
+    ));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .success();
+
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .arg("--preset")
+        .arg("minimal")
+        .assert()
+        .stdout("")
+        .success();
+
+    temp.child(".adrs.toml")
+        .assert(predicates::str::contains("mode = \"compatible\""));
+    temp.child("doc/adr/0001-record-architecture-decisions.md")
+        .assert(predicates::path::missing());
+}
+
+#[test]
+#[serial_test::serial]
+fn test_init_writes_only_chosen_settings() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    temp.child("config/adrs/config.toml")
+        .write_str("[alias]\nmine = \"list\"\n")
+        .unwrap();
+    temp.child(".adrs.toml")
+        .write_str("[generate]\ninclude_archived = true\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["init", "--interactive"])
+        .env("XDG_CONFIG_HOME", temp.path().join("config"))
+        .write_stdin("\nnextgen\n\napi\nn\n")
+        .assert()
+        .success();
+    // the global aliases and the defaults stay out of the project's configuration
+    temp.child(".adrs.toml").assert(concat!(
+        "mode = \"nextgen\"\n",
+        "tags = [\"api\"]\n\n",
+        "[generate]\n",
+        "include_archived = true\n\n",
+        "[templates]\n",
+        "format = \"nygard\"\n",
+    ));
+
+    std::fs::remove_file(temp.path().join(".adrs.toml")).unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["init", "--preset", "minimal"])
+        .env("XDG_CONFIG_HOME", temp.path().join("config"))
+        .assert()
+        .success();
+    temp.child(".adrs.toml").assert(concat!(
+        "version = 1\n",
+        "mode = \"compatible\"\n\n",
+        "[templates]\n",
+        "format = \"nygard\"\n",
+    ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_init_no_first_adr() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["init", "--no-first-adr"])
+        .assert()
+        .stdout("")
+        .success();
+
+    temp.child("doc/adr").assert(predicates::path::is_dir());
+    temp.child("doc/adr/0001-record-architecture-decisions.md")
+        .assert(predicates::path::missing());
+}
+
+#[test]
+#[serial_test::serial]
+fn test_init_adopt_existing() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    temp.child("docs/decisions/0001-use-rust.md")
+        .write_str("# 1. Use Rust\n")
+        .unwrap();
+    temp.child("docs/decisions/adr-002-use-postgres.md")
+        .write_str("# 2. Use Postgres\n")
+        .unwrap();
+    temp.child("docs/decisions/7.md")
+        .write_str("# 7. Use Redis\n")
+        .unwrap();
+    temp.child("docs/decisions/README.md")
+        .write_str("# Decisions\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["init", "--adopt-existing", "docs/decisions"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Adopted 3 ADR(s) in docs/decisions")
+                .and(predicate::str::contains(
+                    "docs/decisions/adr-002-use-postgres.md -> docs/decisions/0002-use-postgres.md",
+                ))
+                .and(predicate::str::contains("--normalize")),
+        );
+    temp.child("docs/decisions/0001-record-architecture-decisions.md")
+        .assert(predicates::path::missing());
+    temp.child(".adr-dir").assert("docs/decisions");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["init", "--adopt-existing", "--normalize", "docs/decisions"])
+        .assert()
+        .success();
+    temp.child("docs/decisions/0002-use-postgres.md")
+        .assert(predicates::path::exists());
+    temp.child("docs/decisions/adr-002-use-postgres.md")
+        .assert(predicates::path::missing());
+    temp.child("docs/decisions/0007-use-redis.md")
+        .assert(predicates::path::exists());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["init", "--adopt-existing", "missing"])
+        .assert()
+        .failure();
+}
+
+#[test]
+#[serial_test::serial]
+fn test_init_adopt_existing_taken_name() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    temp.child("docs/decisions/adr-001-use-rust.md")
+        .write_str("# 1. Use Rust\n")
+        .unwrap();
+    temp.child("docs/decisions/adr-002-use-postgres.md")
+        .write_str("# 2. Use Postgres\n")
+        .unwrap();
+    temp.child("docs/decisions/0002-use-postgres.md")
+        .create_dir_all()
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["init", "--adopt-existing", "--normalize", "docs/decisions"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "docs/decisions/0002-use-postgres.md already exists",
+        ));
+    temp.child("docs/decisions/adr-001-use-rust.md")
+        .assert(predicates::path::exists());
+    temp.child("docs/decisions/0001-use-rust.md")
+        .assert(predicates::path::missing());
+}
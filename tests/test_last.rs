@@ -0,0 +1,54 @@
+use std::time::{Duration, SystemTime};
+
+use assert_cmd::Command;
+use assert_fs::TempDir;
+
+#[test]
+#[serial_test::serial]
+fn test_last() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("last")
+        .assert()
+        .failure();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("Second")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("last")
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-second.md\n");
+
+    // touch the first ADR so that it is the most recently modified
+    std::fs::File::options()
+        .append(true)
+        .open("doc/adr/0001-record-architecture-decisions.md")
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(60))
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("last")
+        .arg("--modified")
+        .assert()
+        .success()
+        .stdout("doc/adr/0001-record-architecture-decisions.md\n");
+}
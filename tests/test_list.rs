@@ -3,6 +3,7 @@ use std::path::Path;
 use assert_cmd::Command;
 use assert_fs::prelude::*;
 use assert_fs::TempDir;
+use predicates::prelude::*;
 
 #[test]
 #[serial_test::serial]
@@ -81,3 +82,148 @@ fn test_list_alternate_adr_dir() {
         .assert()
         .stdout("docs/ADRs/0001-record-architecture-decisions.md\ndocs/ADRs/0002-another-adr.md\n");
 }
+
+#[test]
+#[serial_test::serial]
+fn test_list_profile() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("list")
+        .arg("--profile")
+        .assert()
+        .success()
+        .stdout("doc/adr/0001-record-architecture-decisions.md\n")
+        .stderr(
+            predicate::str::contains("profile:")
+                .and(predicate::str::contains("discovery"))
+                .and(predicate::str::contains("total")),
+        );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_list_tag() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--tag", "api", "Version the API"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["list", "--tag", "API"])
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-version-the-api.md\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_list_dir() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    temp.child("elsewhere/decisions/0001-use-rust.md")
+        .write_str("# 1. Use Rust\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--dir", "elsewhere/decisions", "list"])
+        .assert()
+        .success()
+        .stdout("elsewhere/decisions/0001-use-rust.md\n");
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["show", "1", "--dir", "elsewhere/decisions"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("# 1. Use Rust\n"));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["doctor", "--dir", "elsewhere/decisions"])
+        .assert()
+        .success()
+        .stdout("No problems found\n");
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "toc", "--dir", "elsewhere/decisions"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Use Rust"));
+
+    // nothing is discovered or created
+    temp.child("doc/adr").assert(predicate::path::missing());
+    temp.child(".adr-dir").assert(predicate::path::missing());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["list", "--dir", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("missing isn't a directory"));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_list_relations() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    for args in [
+        vec!["new", "--link", "1:Amends", "Amend the process"],
+        vec!["new", "Use MySQL"],
+        vec!["new", "Use Postgres"],
+        vec!["link", "4", "Supersedes", "3", "Superseded by"],
+        vec!["new", "Unrelated"],
+    ] {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .args(args)
+            .assert()
+            .success();
+    }
+
+    let list = |args: &[&str], expected: &str| {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .arg("list")
+            .args(args)
+            .assert()
+            .success()
+            .stdout(expected.to_string());
+    };
+    list(&["--orphans"], "doc/adr/0005-unrelated.md\n");
+    list(&["--supersedes"], "doc/adr/0004-use-postgres.md\n");
+    list(&["--superseded"], "doc/adr/0003-use-mysql.md\n");
+    list(&["--amends", "1"], "doc/adr/0002-amend-the-process.md\n");
+    list(&["--by", "3"], "doc/adr/0004-use-postgres.md\n");
+    list(
+        &["--by", "2"],
+        "doc/adr/0001-record-architecture-decisions.md\n",
+    );
+}
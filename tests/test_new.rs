@@ -169,3 +169,433 @@ fn test_new_no_current_dir() {
     temp.child("doc/adr/0001-test-new-without-init.md")
         .assert(predicates::path::exists());
 }
+
+#[test]
+#[serial_test::serial]
+fn test_new_failure_leaves_no_state() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child(".adr-dir").write_str("doc/adr").unwrap();
+    // the ADR can't be written over a directory
+    temp.child("doc/adr/0001-blocked.md")
+        .create_dir_all()
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Blocked"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Unable to write doc/adr/0001-blocked.md",
+        ));
+    temp.child(".adrs").assert(predicate::path::missing());
+}
+
+#[test]
+#[serial_test::serial]
+fn test_new_dir_elsewhere() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child("other").create_dir_all().unwrap();
+    temp.child("here").create_dir_all().unwrap();
+    std::env::set_current_dir(temp.path().join("other")).unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    // the state belongs to the project the directory is in, not the current directory
+    std::env::set_current_dir(temp.path().join("here")).unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--dir", "../other/doc/adr", "new", "Use Postgres"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(
+            "../other/doc/adr/0002-use-postgres.md\n",
+        ));
+    temp.child("other/.adrs/state.toml")
+        .assert(predicate::str::contains("high_water_mark = 2"));
+    temp.child("here/.adrs").assert(predicate::path::missing());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--dir", "../other/doc/adr", "info"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "adrs_dir=../other/doc/adr\nadrs_count=2\n",
+        ));
+
+    // a directory outside of any project has no state
+    temp.child("loose").create_dir_all().unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--dir", "../loose", "new", "Use Kafka"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("../loose/0001-use-kafka.md\n"));
+    temp.child("loose/.adrs").assert(predicate::path::missing());
+    temp.child(".adrs").assert(predicate::path::missing());
+    temp.child("here/.adrs").assert(predicate::path::missing());
+}
+
+#[test]
+#[serial_test::serial]
+fn test_new_format() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("--format")
+        .arg("madr")
+        .arg("Test MADR")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0001-test-madr.md").assert(
+        predicate::str::contains("## Considered Options")
+            .and(predicate::str::contains("## Decision Drivers")),
+    );
+
+    temp.child(".adrs.toml")
+        .write_str("mode = \"nextgen\"\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("Test nextgen")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0002-test-nextgen.md").assert(
+        predicate::str::is_match(
+            "^---\nid: [0-9a-f-]{36}\ntags: \\[\\]\ndecided_date: \\d{4}-\\d{2}-\\d{2}\n---\n\n# 2. Test nextgen",
+        )
+        .unwrap()
+        .and(predicate::str::contains("## Context\n")),
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_new_multiple_links() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("Second")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("--link")
+        .arg("1:Amends")
+        .arg("--link")
+        .arg("2:Depends on:Needed by")
+        .arg("Third")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0003-third.md").assert(
+        predicate::str::contains(
+            "Amends [1. Record architecture decisions](0001-record-architecture-decisions.md)",
+        )
+        .and(predicate::str::contains(
+            "Depends on [2. Second](0002-second.md)",
+        )),
+    );
+    temp.child("doc/adr/0001-record-architecture-decisions.md")
+        .assert(predicate::str::contains(
+            "Amended by [3. Third](0003-third.md)",
+        ));
+    temp.child("doc/adr/0002-second.md")
+        .assert(predicate::str::contains(
+            "Needed by [3. Third](0003-third.md)",
+        ));
+
+    // a missing target fails before anything is written
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("--link")
+        .arg("1:Amends")
+        .arg("--link")
+        .arg("99:Amends")
+        .arg("Fourth")
+        .assert()
+        .failure();
+
+    temp.child("doc/adr/0004-fourth.md")
+        .assert(predicate::path::missing());
+    temp.child("doc/adr/0001-record-architecture-decisions.md")
+        .assert(predicate::str::contains("Fourth").not());
+}
+
+#[test]
+#[serial_test::serial]
+fn test_new_invalid_superseded() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    let original =
+        std::fs::read_to_string("doc/adr/0001-record-architecture-decisions.md").unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("--supersedes")
+        .arg("1")
+        .arg("--supersedes")
+        .arg("99")
+        .arg("Replacement")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unable to find superseded ADR 99"));
+
+    temp.child("doc/adr/0002-replacement.md")
+        .assert(predicate::path::missing());
+    temp.child("doc/adr/0001-record-architecture-decisions.md")
+        .assert(original);
+}
+
+#[test]
+#[serial_test::serial]
+fn test_new_number_not_reused() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("Deleted")
+        .assert()
+        .success();
+
+    std::fs::remove_file("doc/adr/0002-deleted.md").unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("Replacement")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0002-replacement.md")
+        .assert(predicate::path::missing());
+    temp.child("doc/adr/0003-replacement.md")
+        .assert(predicate::path::exists());
+    temp.child(".adrs/state.toml")
+        .assert("high_water_mark = 3\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_new_custom_sections() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    temp.child(".adrs.toml")
+        .write_str("[templates]\ncustom_sections = [\"Security Review\"]\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("Reviewed")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0002-reviewed.md")
+        .assert(predicate::str::ends_with(
+            "mitigated.\n\n## Security Review\n",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+#[serial_test::serial]
+fn test_new_from_clipboard() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    // stand-ins for the platform paste commands
+    let bin = temp.child("bin");
+    bin.create_dir_all().unwrap();
+    for program in ["pbpaste", "wl-paste"] {
+        let script = bin.child(program);
+        script
+            .write_str(
+                "#!/bin/sh\nprintf '# Use Postgres\\n\\n## Context\\n\\nFrom the thread.\\n'\n",
+            )
+            .unwrap();
+        std::fs::set_permissions(script.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let path = format!(
+        "{}:{}",
+        bin.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .env("PATH", &path)
+        .arg("new")
+        .arg("--from-clipboard")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0002-use-postgres.md").assert(
+        predicate::str::starts_with("# 2. Use Postgres\n\nDate: ").and(predicate::str::ends_with(
+            "## Status\n\nAccepted\n\n## Context\n\nFrom the thread.\n",
+        )),
+    );
+
+    // an explicit title wins over the heading
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .env("PATH", &path)
+        .arg("new")
+        .arg("--from-clipboard")
+        .args(["Use", "CockroachDB"])
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0003-use-cockroachdb.md").assert(
+        predicate::str::contains("# 3. Use CockroachDB\n")
+            .and(predicate::str::contains("Postgres").not()),
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_new_tags() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--tag", "api", "--tag", "security", "Tagged"])
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0001-tagged.md").assert(
+        predicate::str::is_match(
+            "\nDate: \\d{4}-\\d{2}-\\d{2}\n\nTags: api, security\n\n## Status",
+        )
+        .unwrap(),
+    );
+
+    temp.child(".adrs.toml")
+        .write_str("mode = \"nextgen\"\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--tag", "api", "Tagged nextgen"])
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0002-tagged-nextgen.md").assert(
+        predicate::str::contains("tags: [api]\n").and(predicate::str::contains("Tags:").not()),
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_new_template_context() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    for args in [
+        &["init", "-q"][..],
+        &["symbolic-ref", "HEAD", "refs/heads/adr-work"],
+        &["config", "user.name", "Ada"],
+    ] {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+    temp.child("adr-template.md")
+        .write_str("# {number}. {title}\n\nDate: {today.year}/{today.month}/{today.day}\nAuthor: {git.author}\nBranch: {git.branch}\nOwner: {vars.owner}\nIn: {repo.adr_dir}\n\n## Status\n\nProposed\n")
+        .unwrap();
+    temp.child(".adrs.toml")
+        .write_str("[templates]\nnew = \"adr-template.md\"\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--var", "owner=platform", "Use Postgres"])
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0001-use-postgres.md").assert(
+        predicate::str::is_match(
+            "^# 1. Use Postgres\n\nDate: \\d{4}/\\d{2}/\\d{2}\nAuthor: Ada\nBranch: adr-work\nOwner: platform\nIn: doc/adr\n",
+        )
+        .unwrap(),
+    );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--var", "owner", "Use MySQL"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid variable 'owner', expected KEY=VALUE",
+        ));
+}
@@ -0,0 +1,38 @@
+use assert_cmd::Command;
+use assert_fs::TempDir;
+
+#[test]
+#[serial_test::serial]
+fn test_next_number() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("next-number")
+        .assert()
+        .success()
+        .stdout("2\n");
+
+    // reserved numbers are skipped, as `new` would
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("reserve")
+        .arg("2")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("next-number")
+        .arg("--padded")
+        .assert()
+        .success()
+        .stdout("0004\n");
+}
@@ -0,0 +1,69 @@
+use assert_cmd::Command;
+use assert_fs::TempDir;
+
+#[test]
+#[serial_test::serial]
+fn test_option() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "--format", "madr", "Choose a database"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "option",
+            "add",
+            "2",
+            "SQLite",
+            "--pro",
+            "simple",
+            "--con",
+            "no concurrency",
+        ])
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-choose-a-database.md: added option SQLite\n");
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["option", "add", "2", "PostgreSQL", "--pro", "mature"])
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["option", "choose", "2", "MySQL"])
+        .assert()
+        .failure();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "option",
+            "choose",
+            "2",
+            "postgresql",
+            "--because",
+            "it scales",
+        ])
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-choose-a-database.md: chose option PostgreSQL\n");
+
+    let adr =
+        std::fs::read_to_string(temp.path().join("doc/adr/0002-choose-a-database.md")).unwrap();
+    assert!(adr.contains(
+        "## Considered Options\n\n* The first option\n* SQLite\n* PostgreSQL\n\n## Decision Outcome\n\nChosen option: \"PostgreSQL\", because it scales.\n\n### Consequences\n"
+    ));
+    assert!(adr.contains(
+        "## Pros and Cons of the Options\n\n### SQLite\n\n* Good, because simple\n* Bad, because no concurrency\n\n### PostgreSQL\n\n* Good, because mature\n\n## More Information\n"
+    ));
+}
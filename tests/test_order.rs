@@ -0,0 +1,65 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+
+#[test]
+#[serial_test::serial]
+fn test_order() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0002-use-kafka.md")
+        .write_str("# 2. Use Kafka\n\n## Status\n\nProposed\n\nDepends on [4. Run our own cluster](0004-run-our-own-cluster.md)\n")
+        .unwrap();
+    temp.child("doc/adr/0003-use-postgres.md")
+        .write_str("# 3. Use Postgres\n\n## Status\n\nAccepted\n\nRequired by [5. Use change data capture](0005-use-change-data-capture.md)\n")
+        .unwrap();
+    temp.child("doc/adr/0004-run-our-own-cluster.md")
+        .write_str("# 4. Run our own cluster\n\n## Status\n\nProposed\n")
+        .unwrap();
+    temp.child("doc/adr/0005-use-change-data-capture.md")
+        .write_str("# 5. Use change data capture\n\n## Status\n\nProposed\n\nDepends on [2. Use Kafka](0002-use-kafka.md)\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("order")
+        .assert()
+        .success()
+        .stdout("1. 4. Run our own cluster\n2. 2. Use Kafka\n   after 4\n3. 5. Use change data capture\n   after 2\n");
+
+    temp.child("doc/adr/0006-use-mysql.md")
+        .write_str("# 6. Use MySQL\n\n## Status\n\nRejected\n\nRequired by [4. Run our own cluster](0004-run-our-own-cluster.md)\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("order")
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains(
+            "1. 4. Run our own cluster\n   ! depends on 6. Use MySQL, which is Rejected\n",
+        ))
+        .stderr(predicates::str::contains(
+            "Found 1 unsatisfied dependencies",
+        ));
+
+    temp.child("doc/adr/0004-run-our-own-cluster.md")
+        .write_str("# 4. Run our own cluster\n\n## Status\n\nProposed\n\nDepends on [5. Use change data capture](0005-use-change-data-capture.md)\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("order")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "The dependencies between ADRs 2, 4, 5 form a cycle",
+        ));
+}
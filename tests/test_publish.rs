@@ -0,0 +1,140 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+fn git(args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+#[serial_test::serial]
+fn test_publish_directory() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("publish")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No publish target"));
+
+    temp.child(".adrs.toml")
+        .write_str(
+            "[publish]\ntarget = \"directory\"\npath = \"site\"\ntitle = \"Platform decisions\"\n",
+        )
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .env("XDG_STATE_HOME", temp.path().join("state"))
+        .arg("publish")
+        .assert()
+        .success()
+        .stdout("Published 1 ADR(s) to site\n");
+
+    temp.child("site/adrs.json")
+        .assert(predicate::str::contains(
+            "\"title\": \"Record architecture decisions\"",
+        ));
+    temp.child("site/index.html")
+        .assert(predicate::str::contains("Platform decisions"));
+    temp.child("site/0001-record-architecture-decisions.html")
+        .assert(predicate::path::exists());
+    temp.child("state/adrs/journal.log")
+        .assert(predicate::str::is_match("^\\S+\tpublish\t1 ADR\\(s\\) to site\n$").unwrap());
+}
+
+#[test]
+#[serial_test::serial]
+fn test_publish_github_pages() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    let remote = temp.path().join("remote.git");
+    git(&["init", "-q", "--bare", remote.to_str().unwrap()]);
+    git(&["init", "-q", "project"]);
+    std::env::set_current_dir(temp.path().join("project")).unwrap();
+    git(&["remote", "add", "origin", remote.to_str().unwrap()]);
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .env("XDG_STATE_HOME", temp.path().join("state"))
+        .args(["publish", "--target", "github-pages"])
+        .assert()
+        .success()
+        .stdout("Published 1 ADR(s) to origin gh-pages\n");
+    let remote = remote.to_str().unwrap();
+    assert!(
+        git(&["--git-dir", remote, "ls-tree", "--name-only", "gh-pages"]).contains("adrs.json\n")
+    );
+
+    // publishing again adds nothing to the branch
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .env("XDG_STATE_HOME", temp.path().join("state"))
+        .args(["publish", "--target", "github-pages"])
+        .assert()
+        .success()
+        .stdout("origin gh-pages is already up to date\n");
+
+    // the site's custom domain is added to the branch by hand, and kept when publishing
+    let pages = temp.path().join("pages");
+    let pages = pages.to_str().unwrap();
+    git(&["clone", "-q", "-b", "gh-pages", remote, pages]);
+    std::fs::write(temp.path().join("pages/CNAME"), "decisions.example.com\n").unwrap();
+    git(&["-C", pages, "add", "CNAME"]);
+    git(&[
+        "-C",
+        pages,
+        "-c",
+        "user.name=adrs",
+        "-c",
+        "user.email=adrs@localhost",
+        "commit",
+        "-q",
+        "-m",
+        "Add CNAME",
+    ]);
+    git(&["-C", pages, "push", "-q", "origin", "gh-pages"]);
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Use Postgres"])
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .env("XDG_STATE_HOME", temp.path().join("state"))
+        .args(["publish", "--target", "github-pages"])
+        .assert()
+        .success()
+        .stdout("Published 2 ADR(s) to origin gh-pages\n");
+    assert_eq!(
+        git(&["--git-dir", remote, "log", "--format=%s", "gh-pages"]),
+        "Publish 2 ADR(s)\nAdd CNAME\nPublish 1 ADR(s)\n"
+    );
+    assert_eq!(
+        git(&["--git-dir", remote, "show", "gh-pages:CNAME"]),
+        "decisions.example.com\n"
+    );
+}
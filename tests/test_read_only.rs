@@ -0,0 +1,117 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_read_only_flag() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    let adr = temp.child("doc/adr/0001-record-architecture-decisions.md");
+    let original = std::fs::read_to_string(adr.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--read-only", "new", "Use Postgres"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "`adrs new` changes the ADRs, which --read-only doesn't allow",
+        ));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--read-only", "status", "1", "deprecated"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("adrs is running with --read-only"));
+    adr.assert(original.as_str());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--read-only", "status", "1"])
+        .assert()
+        .success()
+        .stdout("Accepted\n");
+
+    // a dry run writes nothing, so it's allowed
+    temp.child("export.json")
+        .write_str("{\"version\": \"1.0.0\", \"adrs\": [{\"number\": 2, \"title\": \"Use Postgres\", \"status\": \"Accepted\"}]}")
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--read-only", "import", "json", "--dry-run", "export.json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "valid JSON-ADR export of 1 ADR(s)",
+        ));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--read-only", "import", "json", "export.json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "`adrs import` changes the ADRs, which --read-only doesn't allow",
+        ));
+    temp.child("doc/adr/0002-use-postgres.md")
+        .assert(predicate::path::missing());
+    for args in [
+        vec!["list"],
+        vec!["search", "decisions"],
+        vec!["export", "json"],
+        vec!["doctor"],
+    ] {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .arg("--read-only")
+            .args(args)
+            .assert()
+            .success();
+    }
+}
+
+#[cfg(unix)]
+#[test]
+#[serial_test::serial]
+fn test_read_only_directory() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    let dir = temp.path().join("doc/adr");
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o555)).unwrap();
+    // permissions aren't enforced for root
+    let enforced = std::fs::write(dir.join("probe"), "").is_err();
+    if enforced {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .args(["new", "Use Postgres"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "The ADR directory doc/adr is read-only, so `adrs new` can't change it",
+            ));
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .arg("list")
+            .assert()
+            .success()
+            .stdout("doc/adr/0001-record-architecture-decisions.md\n");
+    }
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
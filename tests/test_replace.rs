@@ -0,0 +1,78 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_replace() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0002-use-kafka.md")
+        .write_str("# 2. Use Kafka\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\n## Context\n\nKafka is popular.\n\n## Decision\n\nWe use Kafka.\n")
+        .unwrap();
+    temp.child("doc/adr/0003-tune-kafka.md")
+        .write_str("# 3. Tune Kafka\n\nDate: 2024-01-02\n\n## Status\n\nProposed\n\n## Decision\n\nTune Kafka.\n")
+        .unwrap();
+
+    // only a preview until --apply is given
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("replace")
+        .arg("--pattern")
+        .arg("Kafka")
+        .arg("--with")
+        .arg("Redpanda")
+        .arg("--section")
+        .arg("decision")
+        .arg("--status")
+        .arg("accepted")
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-use-kafka.md:\n  15: - We use Kafka.\n  15: + We use Redpanda.\n1 line(s) in 1 ADR(s) would change, run again with --apply to write them\n");
+
+    temp.child("doc/adr/0002-use-kafka.md")
+        .assert(predicate::str::contains("We use Kafka."));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("replace")
+        .arg("--pattern")
+        .arg("Kafka")
+        .arg("--with")
+        .arg("Redpanda")
+        .arg("--section")
+        .arg("decision")
+        .arg("--status")
+        .arg("accepted")
+        .arg("--apply")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("Changed 1 line(s) in 1 ADR(s)\n"));
+
+    temp.child("doc/adr/0002-use-kafka.md").assert(
+        predicate::str::contains("We use Redpanda.")
+            .and(predicate::str::contains("Kafka is popular.")),
+    );
+    temp.child("doc/adr/0003-tune-kafka.md")
+        .assert(predicate::str::contains("Tune Kafka.\n"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("replace")
+        .arg("--pattern")
+        .arg("Zookeeper")
+        .arg("--with")
+        .arg("KRaft")
+        .assert()
+        .success()
+        .stdout("No matches found\n");
+}
@@ -0,0 +1,66 @@
+use assert_cmd::Command;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[cfg(target_os = "linux")]
+#[test]
+#[serial_test::serial]
+fn test_report_issue() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("XDG_STATE_HOME", temp.path().join("state"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("report-issue")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains(format!("- adrs: {}\n", env!("CARGO_PKG_VERSION")))
+                .and(predicate::str::contains("- mode: compatible\n"))
+                .and(predicate::str::contains("- customized: nothing\n"))
+                .and(predicate::str::contains(
+                    "- ADRs: 1 (0 archived)\n- statuses: Accepted (1)\n",
+                ))
+                .and(predicate::str::contains(
+                    "### Last error\n\nNone recorded\n",
+                )),
+        )
+        .stderr(predicate::str::contains("Nothing has been sent anywhere"));
+
+    let missing = temp.path().join("missing.json");
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", missing.to_str().unwrap()])
+        .assert()
+        .failure();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("report-issue")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("import: Unable to read <repo>/missing.json")
+                .and(predicate::str::contains(temp.path().to_str().unwrap()).not()),
+        );
+
+    // the journal is shared, but another project's errors aren't reported
+    let other = TempDir::new().unwrap();
+    std::env::set_current_dir(other.path()).unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("report-issue")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "### Last error\n\nNone recorded\n",
+        ));
+    std::env::remove_var("XDG_STATE_HOME");
+}
@@ -0,0 +1,143 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_reserve() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("reserve")
+        .arg("3")
+        .arg("--note")
+        .arg("payments team")
+        .assert()
+        .success()
+        .stdout("Reserved ADR numbers 2-4\n");
+
+    temp.child(".adrs/reservations.toml").assert(
+        predicate::str::contains("number = 4")
+            .and(predicate::str::contains("note = \"payments team\"")),
+    );
+
+    // new ADRs skip the reserved numbers
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("Unreserved")
+        .assert()
+        .success();
+    temp.child("doc/adr/0005-unreserved.md")
+        .assert(predicate::path::exists());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("--reserved")
+        .arg("3")
+        .arg("Reserved")
+        .assert()
+        .success();
+    temp.child("doc/adr/0003-reserved.md")
+        .assert(predicate::path::exists());
+
+    temp.child(".adrs/reservations.toml")
+        .assert(predicate::str::contains("number = 3").not());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("--reserved")
+        .arg("3")
+        .arg("Again")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("ADR number 3 is not reserved"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .success()
+        .stdout("No problems found\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_doctor_reservation_in_use() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("reserve")
+        .arg("1")
+        .assert()
+        .success()
+        .stdout("Reserved ADR number 2\n");
+
+    // another branch created ADR 2 without knowing about the reservation
+    temp.child("doc/adr/0002-collision.md")
+        .write_str("# 2. Collision\n\n## Status\n\nAccepted\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("doctor")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "error[reservations]: doc/adr/0002-collision.md: ADR number 2 is reserved by",
+        ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_reserve_overflow() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/2147483646-last.md")
+        .write_str("# 2147483646. Last\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["reserve", "2"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("the numbers would overflow"));
+    temp.child(".adrs/reservations.toml")
+        .assert(predicate::path::missing());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["reserve", "1"])
+        .assert()
+        .success()
+        .stdout("Reserved ADR number 2147483647\n");
+}
@@ -0,0 +1,100 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_retire() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Use MongoDB"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["retire", "2", "--reason", "posted by mistake"])
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-use-mongodb.md: retired\n");
+    let adr = std::fs::read_to_string(temp.path().join("doc/adr/0002-use-mongodb.md")).unwrap();
+    assert!(adr.starts_with("# 2. Use MongoDB\n"));
+    assert!(adr.contains("## Status\n\nRetired\n"));
+    assert!(adr.contains(": posted by mistake\n"));
+    assert!(!adr.contains("## Context"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"status\": \"Retired\""));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["retire", "2", "--reason", "again"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("has already been retired"));
+
+    // the number stays burned
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["new", "Use Postgres"])
+        .assert()
+        .success();
+    assert!(temp.path().join("doc/adr/0003-use-postgres.md").exists());
+}
+
+#[test]
+#[serial_test::serial]
+fn test_retire_nextgen() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child(".adrs.toml")
+        .write_str("mode = \"nextgen\"\n")
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-mongodb.md")
+        .write_str(
+            "---\nid: 1234\ntags: []\ndecided_date: 2024-01-01\nimplemented_date: 2024-01-15\nimplemented: true\n---\n\n# 2. Use MongoDB\n\nDate: 2024-01-01\n\n## Status\n\nAccepted\n\n## Context\n\nSecret.\n",
+        )
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["retire", "2", "--reason", "posted by mistake"])
+        .assert()
+        .success();
+    let adr = std::fs::read_to_string(temp.path().join("doc/adr/0002-use-mongodb.md")).unwrap();
+    // the original date stays and the retirement is dated separately
+    assert!(adr.contains("\nDate: 2024-01-01\n"));
+    assert!(adr.contains("\nretired_date: "));
+    assert!(!adr.contains("retired_date: 2024-01-01"));
+    // the lifecycle keys no longer apply
+    assert!(adr.starts_with("---\nid: 1234\n"));
+    assert!(!adr.contains("decided_date"));
+    assert!(!adr.contains("implemented"));
+    assert!(!adr.contains("Secret."));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["fmt", "--check"])
+        .assert()
+        .success();
+}
@@ -0,0 +1,141 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_scopes() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child(".adrs.toml")
+        .write_str("[scopes]\npayments = \"services/payments/adr\"\nauth = \"services/auth/adr\"\n")
+        .unwrap();
+    for (scope, title) in [
+        ("payments", "Use Stripe"),
+        ("payments", "Retry webhooks"),
+        ("auth", "Use OAuth"),
+    ] {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .args(["--scope", scope, "new", title])
+            .assert()
+            .success();
+    }
+    temp.child("services/payments/adr/0002-retry-webhooks.md")
+        .assert(predicate::path::exists());
+    temp.child("services/auth/adr/0001-use-oauth.md")
+        .assert(predicate::str::starts_with("# 1. Use OAuth"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--scope", "auth", "list"])
+        .assert()
+        .success()
+        .stdout("services/auth/adr/0001-use-oauth.md\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["list", "--all-scopes"])
+        .assert()
+        .success()
+        .stdout(
+            "auth/0001 services/auth/adr/0001-use-oauth.md\npayments/0001 services/payments/adr/0001-use-stripe.md\npayments/0002 services/payments/adr/0002-retry-webhooks.md\n",
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "json", "--all-scopes"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\"scope\": \"auth\"")
+                .and(predicate::str::contains("\"scope\": \"payments\"").count(2)),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--scope", "billing", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Unknown scope 'billing', the configured scopes are: auth, payments",
+        ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_scopes_combined_graph_and_import() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    let config = "[scopes]\npayments = \"services/payments/adr\"\nauth = \"services/auth/adr\"\n";
+    temp.child(".adrs.toml").write_str(config).unwrap();
+    for (scope, title) in [("payments", "Use Stripe"), ("auth", "Use OAuth")] {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .args(["--scope", scope, "new", title])
+            .assert()
+            .success();
+    }
+    let stripe = temp.child("services/payments/adr/0001-use-stripe.md");
+    let markdown = std::fs::read_to_string(stripe.path()).unwrap();
+    stripe
+        .write_str(&markdown.replace(
+            "Accepted\n",
+            "Accepted\n\nRequires [1. Use OAuth](../../auth/adr/0001-use-oauth.md)\n",
+        ))
+        .unwrap();
+
+    // both scopes have an ADR 1, kept apart by their scopes
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["generate", "graph", "--all-scopes", "--format", "dot"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains(
+                "\t_auth_1 [label=\"auth/1. Use OAuth\"; URL=\"services/auth/adr/0001-use-oauth.html\"];",
+            )
+            .and(predicate::str::contains(
+                "\t_payments_1 [label=\"payments/1. Use Stripe\"",
+            ))
+            .and(predicate::str::contains(
+                "  _payments_1 -> _auth_1 [label=\"Requires\", weight=0];",
+            )),
+        );
+
+    let export = Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "json", "--all-scopes"])
+        .output()
+        .unwrap()
+        .stdout;
+
+    let other = TempDir::new().unwrap();
+    std::env::set_current_dir(other.path()).unwrap();
+    other.child(".adrs.toml").write_str(config).unwrap();
+    other.child("export.json").write_binary(&export).unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "export.json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The export combines the scopes auth, payments, import them one at a time with --scope NAME",
+        ));
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["--scope", "payments", "import", "json", "export.json"])
+        .assert()
+        .success();
+    other
+        .child("services/payments/adr/0001-use-stripe.md")
+        .assert(predicate::path::exists());
+    other
+        .child("services/payments/adr/0001-use-oauth.md")
+        .assert(predicate::path::missing());
+}
@@ -0,0 +1,98 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+fn commit(message: &str, date: &str) {
+    for args in [
+        vec!["add", "-A"],
+        vec![
+            "-c",
+            "user.name=Ada",
+            "-c",
+            "user.email=ada@example.com",
+            "commit",
+            "-q",
+            "-m",
+            message,
+        ],
+    ] {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+}
+
+#[test]
+#[serial_test::serial]
+fn test_search_include_history() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    let status = std::process::Command::new("git")
+        .args(["init", "-q"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-mysql.md")
+        .write_str("# 2. Use MySQL\n\n## Status\n\nAccepted\n\n## Decision\n\nMySQL, for its replication.\n")
+        .unwrap();
+    temp.child("doc/adr/0003-use-redis.md")
+        .write_str("# 3. Use Redis\n\n## Status\n\nAccepted\n\n## Decision\n\nRedis, replicated.\n")
+        .unwrap();
+    commit("Record decisions", "2024-01-01T12:00:00");
+    temp.child("doc/adr/0002-use-mysql.md")
+        .write_str("# 2. Use MySQL\n\n## Status\n\nAccepted\n\n## Decision\n\nMySQL.\n")
+        .unwrap();
+    std::fs::remove_file(temp.path().join("doc/adr/0003-use-redis.md")).unwrap();
+    commit("Rewrite decisions", "2024-06-01T12:00:00");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["search", "REPLICA"])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["search", "--include-history", "REPLICA"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                "^\\[historical [0-9a-f]{7} 2024-01-01\\] doc/adr/0002-use-mysql.md:9: MySQL, for its replication.\n\\[historical [0-9a-f]{7} 2024-01-01, deleted\\] doc/adr/0003-use-redis.md:9: Redis, replicated.\n$",
+            )
+            .unwrap(),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args([
+            "search",
+            "--include-history",
+            "--since",
+            "2024-03-01",
+            "replica",
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["search", "--regex", "^mysql\\.$"])
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-use-mysql.md:9: MySQL.\n");
+}
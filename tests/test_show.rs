@@ -0,0 +1,114 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_show() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("show")
+        .arg("1")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "# 1. Record architecture decisions\n",
+        ));
+
+    temp.child(".adrs.toml")
+        .write_str("mode = \"nextgen\"\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("new")
+        .arg("Use Postgres")
+        .assert()
+        .success();
+
+    let markdown = std::fs::read_to_string("doc/adr/0002-use-postgres.md").unwrap();
+    let id = markdown
+        .lines()
+        .find_map(|line| line.strip_prefix("id: "))
+        .unwrap()
+        .to_string();
+
+    // the id still finds the ADR after it has been renumbered
+    std::fs::rename(
+        "doc/adr/0002-use-postgres.md",
+        "doc/adr/0005-use-postgres.md",
+    )
+    .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("show")
+        .arg(&id)
+        .arg("--path")
+        .assert()
+        .success()
+        .stdout("doc/adr/0005-use-postgres.md\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("export")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("\"id\": \"{}\"", id)));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("show")
+        .arg("00000000-0000-0000-0000-000000000000")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "No ADR found with id 00000000-0000-0000-0000-000000000000",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+#[serial_test::serial]
+fn test_show_render() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child(".adrs.toml")
+        .write_str("[render]\nterminal = \"tr a-z A-Z\"\nhtml = \"sed 's/Record/Keep/'\"\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["show", "1", "--render"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "# 1. RECORD ARCHITECTURE DECISIONS\n",
+        ));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "html", "--output", "site"])
+        .assert()
+        .success();
+    temp.child("site/0001-record-architecture-decisions.html")
+        .assert(predicate::str::contains("# 1. Keep architecture decisions"));
+}
@@ -0,0 +1,168 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_stats() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0002-use-postgres.md")
+        .write_str("# 2. Use Postgres\n\nDate: 2024-03-01\n\n## Status\n\nProposed\n\nAmends [1. Record architecture decisions](0001-record-architecture-decisions.md)\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("ADRs: 2\n\nBy status:\n  Accepted: 1\n  Proposed: 1\n")
+                .and(predicate::str::contains("  2024-03: 1\n"))
+                .and(predicate::str::contains(
+                    "ADRs by number of linked ADRs:\n  1: 2\n",
+                )),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("stats")
+        .arg("--json")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\"total\": 2,").and(predicate::str::contains(
+                "\"link_degrees\": {\n    \"1\": 2\n  }",
+            )),
+        );
+}
+
+// commit everything with the given author date
+fn git_commit(date: &str) {
+    for args in [
+        vec!["add", "-A"],
+        vec![
+            "-c",
+            "user.name=adrs",
+            "-c",
+            "user.email=adrs@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "update",
+        ],
+    ] {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .env("GIT_AUTHOR_DATE", format!("{}T12:00:00Z", date))
+            .env("GIT_COMMITTER_DATE", format!("{}T12:00:00Z", date))
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+}
+
+#[test]
+#[serial_test::serial]
+fn test_stats_time_in_status() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    let status = std::process::Command::new("git")
+        .args(["init", "-q"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-postgres.md")
+        .write_str("# 2. Use Postgres\n\nDate: 2024-03-01\n\n## Status\n\nProposed\n")
+        .unwrap();
+    git_commit("2024-03-01");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("status")
+        .arg("2")
+        .arg("accepted")
+        .assert()
+        .success();
+    git_commit("2024-03-15");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(
+            "Days in status:\n  Proposed: count=1 min=14 median=14 p90=14 max=14\n",
+        ));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("export")
+        .arg("json")
+        .arg("--time-in-status")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"time_in_status\": {\n        \"Proposed\": 14\n      }",
+        ));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_stats_activity() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0001-record-architecture-decisions.md")
+        .write_str(
+            "# 1. Record architecture decisions\n\nDate: 2024-01-10\n\n## Status\n\nAccepted\n",
+        )
+        .unwrap();
+    temp.child("doc/adr/0002-use-postgres.md")
+        .write_str("# 2. Use Postgres\n\nDate: 2024-03-01\n\n## Status\n\nProposed\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("stats")
+        .arg("--activity")
+        .arg("--sparkline")
+        .assert()
+        .success()
+        .stdout(
+            "| Month | Accepted | Proposed | Total |\n|---|---|---|---|\n| 2024-01 | 1 | 0 | 1 |\n| 2024-02 | 0 | 0 | 0 |\n| 2024-03 | 0 | 1 | 1 |\n\n█▁█\n",
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("stats")
+        .arg("--activity")
+        .arg("--json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"2024-02\": {},"));
+}
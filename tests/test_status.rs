@@ -0,0 +1,237 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_status() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("status")
+        .arg("1")
+        .assert()
+        .success()
+        .stdout("Accepted\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("status")
+        .arg("1")
+        .arg("deprecated")
+        .assert()
+        .success()
+        .stdout("doc/adr/0001-record-architecture-decisions.md: Deprecated\n");
+
+    temp.child("doc/adr/0001-record-architecture-decisions.md")
+        .assert(
+            predicate::str::contains("## Status\n\nDeprecated\n\n## Context")
+                .and(predicate::str::contains("Accepted").not()),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("status")
+        .arg("1")
+        .assert()
+        .success()
+        .stdout("Deprecated\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_status_dates() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    temp.child(".adrs.toml")
+        .write_str("mode = \"nextgen\"\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0002-dated.md")
+        .write_str(
+            "---\ntags: []\n---\n\n# 2. Dated\n\nDate: 2024-01-01\n\n## Status\n\nProposed\n",
+        )
+        .unwrap();
+
+    for status in ["Accepted", "Implemented"] {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .arg("status")
+            .arg("2")
+            .arg(status)
+            .assert()
+            .success();
+    }
+
+    temp.child("doc/adr/0002-dated.md").assert(
+        predicate::str::is_match(
+            "^---\ntags: \\[\\]\ndecided_date: \\d{4}-\\d{2}-\\d{2}\nimplemented_date: \\d{4}-\\d{2}-\\d{2}\n---\n",
+        )
+        .unwrap(),
+    );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("list")
+        .arg("--long")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                "doc/adr/0002-dated.md\tImplemented date=2024-01-01 decided=\\d{4}-\\d{2}-\\d{2} implemented=\\d{4}-\\d{2}-\\d{2} format=nygard\n",
+            )
+            .unwrap(),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("export")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"implemented_date\": "));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_status_by() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    for title in ["Use MySQL", "Use Postgres"] {
+        Command::cargo_bin("adrs")
+            .unwrap()
+            .args(["new", title])
+            .assert()
+            .success();
+    }
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["status", "2", "--by", "9"])
+        .assert()
+        .failure();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["status", "2", "accepted", "--by", "3"])
+        .assert()
+        .failure();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["status", "2", "--by", "3"])
+        .assert()
+        .success()
+        .stdout("doc/adr/0002-use-mysql.md: Superseded by 3. Use Postgres\n");
+
+    temp.child("doc/adr/0002-use-mysql.md")
+        .assert(predicate::str::contains(
+            "## Status\n\nSuperseded by [3. Use Postgres](0003-use-postgres.md)\n\n## Context",
+        ));
+    temp.child("doc/adr/0003-use-postgres.md")
+        .assert(predicate::str::contains(
+            "## Status\n\nAccepted\n\nSupersedes [2. Use MySQL](0002-use-mysql.md)\n\n## Context",
+        ));
+
+    // running it again keeps a single link each way
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["status", "2", "superseded", "--by", "3"])
+        .assert()
+        .success();
+    let adr = std::fs::read_to_string(temp.path().join("doc/adr/0003-use-postgres.md")).unwrap();
+    assert_eq!(adr.matches("Supersedes").count(), 1);
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["status", "1", "--by", "2"])
+        .assert()
+        .success()
+        .stderr("Warning: doc/adr/0002-use-mysql.md is itself superseded\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_status_mixed_formats() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-postgres.md")
+        .write_str("---\nstatus: proposed\ndate: 2024-01-01\n---\n# Use Postgres\n\n## Context and Problem Statement\n\nWe need a database.\n\n## Decision Outcome\n\nPostgres.\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["status", "2"])
+        .assert()
+        .success()
+        .stdout("Proposed\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["status", "2", "accepted"])
+        .assert()
+        .success();
+    temp.child("doc/adr/0002-use-postgres.md").assert(
+        predicate::str::contains("status: accepted\n")
+            .and(predicate::str::contains("## Status").not()),
+    );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["list", "--long"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                "0001-record-architecture-decisions.md\tAccepted date=\\S+ format=nygard\n",
+            )
+            .unwrap()
+            .and(
+                predicate::str::is_match(
+                    "0002-use-postgres.md\tAccepted date=2024-01-01 decided=\\S+ format=madr\n",
+                )
+                .unwrap(),
+            ),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["export", "json"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\"format\": \"madr\"")
+                .and(predicate::str::contains("\"format\": \"nygard\"")),
+        );
+}
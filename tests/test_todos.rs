@@ -0,0 +1,48 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+
+#[test]
+#[serial_test::serial]
+fn test_todos() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+
+    temp.child("doc/adr/0002-use-postgres.md")
+        .write_str("---\ntags: [database, ops]\n---\n\n# 2. Use Postgres\n\n## Status\n\nAccepted\n\n## Consequences\n\n- [ ] set up replication\n- [x] pick a host\n")
+        .unwrap();
+    temp.child("doc/adr/0003-use-kafka.md")
+        .write_str("# 3. Use Kafka\n\n## Status\n\nAccepted\n\n## Consequences\n\n- [ ] size the cluster\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("todos")
+        .assert()
+        .success()
+        .stdout("2. Use Postgres (doc/adr/0002-use-postgres.md)\n  - [ ] set up replication\n\n3. Use Kafka (doc/adr/0003-use-kafka.md)\n  - [ ] size the cluster\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("todos")
+        .arg("--all")
+        .arg("--tag")
+        .arg("database")
+        .assert()
+        .success()
+        .stdout("2. Use Postgres (doc/adr/0002-use-postgres.md)\n  - [ ] set up replication\n  - [x] pick a host\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("todos")
+        .arg("--by-tag")
+        .assert()
+        .success()
+        .stdout("database\n  - [ ] set up replication (2. Use Postgres)\n\nops\n  - [ ] set up replication (2. Use Postgres)\n\nuntagged\n  - [ ] size the cluster (3. Use Kafka)\n");
+}
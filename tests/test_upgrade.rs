@@ -0,0 +1,90 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_upgrade() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+    std::env::set_var("EDITOR", "cat");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .assert()
+        .success();
+    temp.child(".adrs.toml")
+        .write_str("format = \"madr\"\n")
+        .unwrap();
+    temp.child("doc/adr/0002-use-mysql.md")
+        .write_str("# 2. Use MySQL\n\n## Status\n\nSuperceded by [3. Use Postgres](0003-use-postgres.md)\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["upgrade", "--adrs", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains(".adrs.toml: moved `format` to `templates.format`")
+                .and(predicate::str::contains(
+                    ".adrs.toml: stamped version 1 (was 0)",
+                ))
+                .and(predicate::str::contains(
+                    "doc/adr/0002-use-mysql.md: spelled `Superceded by` as `Superseded by`",
+                ))
+                .and(predicate::str::contains("Would make 3 change(s)")),
+        );
+    temp.child(".adrs.toml").assert("format = \"madr\"\n");
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["upgrade", "--adrs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Made 3 change(s)"));
+    temp.child(".adrs.toml").assert(
+        predicate::str::starts_with("version = 1\n")
+            .and(predicate::str::contains("[templates]\nformat = \"madr\"")),
+    );
+    temp.child("doc/adr/0002-use-mysql.md")
+        .assert(predicate::str::contains("Superseded by [3. Use Postgres]"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["upgrade", "--adrs"])
+        .assert()
+        .success()
+        .stdout("Already up to date\n");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_upgrade_json() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    temp.child("adrs.json")
+        .write_str(r#"{"version": "1.0.0", "adrs": [{"number": 2, "title": "Use Postgres", "links": [{"type": "Supercedes", "target": 1}]}]}"#)
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["upgrade", "--json", "adrs.json"])
+        .assert()
+        .success()
+        .stdout("adrs.json: ADR 2: spelled `Supercedes` as `Supersedes`\nadrs.json: migrated from JSON-ADR version 1.0.0 to 1.1.0\nMade 2 change(s)\n");
+    temp.child("adrs.json").assert(
+        predicate::str::contains("\"version\": \"1.1.0\"")
+            .and(predicate::str::contains("\"type\": \"Supersedes\"")),
+    );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["upgrade", "--json", "adrs.json"])
+        .assert()
+        .success()
+        .stdout("Already up to date\n");
+}
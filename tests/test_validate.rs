@@ -0,0 +1,107 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_validate() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    // no ADR directory is needed
+    temp.child("proposal.md")
+        .write_str("# 1. Proposal\n\nDate: 2024-01-01\n\n## Status\n\nProposed\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("validate")
+        .arg("proposal.md")
+        .assert()
+        .success()
+        .stdout("proposal.md is valid\n");
+
+    temp.child("broken.md")
+        .write_str("No title\n\nDate: someday\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("validate")
+        .arg("broken.md")
+        .assert()
+        .failure()
+        .stdout(
+            "error[title]: broken.md: missing title\nwarning[status]: broken.md: missing status\nerror[date]: broken.md: invalid date 'someday', expected YYYY-MM-DD\n",
+        )
+        .stderr(predicate::str::contains("Found 2 error(s) in broken.md"));
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("validate")
+        .arg("broken.md")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .failure()
+        .stdout(
+            predicate::str::contains("\"errors\": 2,\n  \"warnings\": 1,").and(
+                predicate::str::contains("\"severity\": \"error\",\n      \"rule\": \"title\""),
+            ),
+        );
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("validate")
+        .arg("missing.md")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No such file: missing.md"));
+}
+
+#[test]
+#[serial_test::serial]
+fn test_validate_json() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    temp.child("export.json")
+        .write_str(r#"{"version": "1.0.0", "adrs": [{"number": 1, "title": "Use MySQL"}]}"#)
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["validate", "json", "export.json"])
+        .assert()
+        .success()
+        .stdout("export.json is valid\n");
+
+    temp.child("broken.json")
+        .write_str(r#"{"version": "1.0.0", "adrs": [{"number": "one", "title": "Use MySQL"}, {"number": 2}]}"#)
+        .unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["validate", "json", "broken.json"])
+        .assert()
+        .failure()
+        .stdout("error[json-schema]: broken.json: /adrs/0/number: \"one\" is not of type \"integer\"\nerror[json-schema]: broken.json: /adrs/1: \"title\" is a required property\n")
+        .stderr(predicate::str::contains("Found 2 error(s) in broken.json"));
+
+    temp.child("garbage.json").write_str("not json").unwrap();
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["validate", "json", "garbage.json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("/: invalid JSON: "));
+
+    // importing reports the same problems
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .args(["import", "json", "broken.json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid JSON-ADR export:\n  /adrs/0/number: \"one\" is not of type \"integer\"\n",
+        ));
+}
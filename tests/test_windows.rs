@@ -0,0 +1,51 @@
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use predicates::prelude::*;
+
+#[test]
+#[serial_test::serial]
+fn test_backslash_adr_dir() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    // an .adr-dir written by a Windows tool
+    temp.child(".adr-dir")
+        .write_str("docs\\decisions\r\n")
+        .unwrap();
+    temp.child("docs/decisions/archive/0001-first.md")
+        .write_str("# 1. First\n\n## Status\n\nAccepted\n")
+        .unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("generate")
+        .arg("toc")
+        .arg("--include-archived")
+        .arg("--prefix")
+        .arg("docs\\decisions")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "* [1. First](docs/decisions/archive/0001-first.md)",
+        ));
+}
+
+#[cfg(windows)]
+#[test]
+#[serial_test::serial]
+fn test_windows_init_writes_forward_slashes() {
+    let temp = TempDir::new().unwrap();
+    std::env::set_current_dir(temp.path()).unwrap();
+
+    Command::cargo_bin("adrs")
+        .unwrap()
+        .arg("init")
+        .arg("docs\\decisions")
+        .assert()
+        .success();
+
+    temp.child(".adr-dir").assert("docs/decisions");
+    temp.child("docs/decisions/0001-record-architecture-decisions.md")
+        .assert(predicate::path::exists());
+}